@@ -0,0 +1,24 @@
+#import bevy_pbr::mesh_view_bindings
+
+struct AtmosphereMaterial {
+    color: vec4<f32>,
+};
+
+@group(1) @binding(0)
+var<uniform> material: AtmosphereMaterial;
+
+@fragment
+fn fragment(
+    #import bevy_pbr::mesh_vertex_output
+) -> @location(0) vec4<f32> {
+    let view_direction = normalize(view.world_position.xyz - world_position.xyz);
+    // 1 facing the camera, 0 at the edge of the shell
+    let facing = abs(dot(normalize(world_normal), view_direction));
+    // thickest just inside the limb, where the line of
+    // sight passes through the most air, and fading out
+    // both towards the middle and right at the edge
+    let alpha = material.color.a
+        * pow(1.0 - facing, 2.0)
+        * smoothstep(0.0, 0.3, facing);
+    return vec4<f32>(material.color.rgb, alpha);
+}
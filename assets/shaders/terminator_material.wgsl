@@ -0,0 +1,26 @@
+struct TerminatorMaterial {
+    shadow: vec4<f32>,
+    sun: vec2<f32>,
+};
+
+@group(1) @binding(0)
+var<uniform> material: TerminatorMaterial;
+
+@fragment
+fn fragment(
+    #import bevy_sprite::mesh2d_vertex_output
+) -> @location(0) vec4<f32> {
+    // the uv is where this point is on the planet, with
+    // the edge at a length of 1, so the sphere's normal
+    // can be worked back out of it
+    let flat = uv;
+    let depth = sqrt(max(1.0 - dot(flat, flat), 0.0));
+    let normal = vec3<f32>(flat, depth);
+    let light = dot(normal, vec3<f32>(material.sun, 0.0));
+    // a soft edge between day and night
+    let night = 1.0 - smoothstep(-0.15, 0.15, light);
+    return vec4<f32>(
+        material.shadow.rgb,
+        material.shadow.a * night,
+    );
+}
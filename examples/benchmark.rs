@@ -0,0 +1,89 @@
+//! Times the headless gravity step with lots of bodies
+//! that all attract each other, to check how it scales.
+//!
+//! `cargo run --release --example benchmark -- 500 200`
+//! steps 500 bodies 200 times. A third argument splits
+//! each step into that many substeps, and `cache` after it
+//! turns on the `GravityCache`, so both can be measured
+//! against the plain solve:
+//! `cargo run --release --example benchmark -- 500 200 4`.
+use bevy::prelude::*;
+use particular::ParticleSet;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use space_golf::{
+    golf_ball::PointMass,
+    gravity::{
+        step_gravity, GravityCache, GravityMode,
+        GravitySettings, Integrator, Precision, SimBody,
+    },
+};
+use std::time::Instant;
+
+const BODIES: usize = 1000;
+const STEPS: usize = 100;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let bodies = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(BODIES);
+    let steps = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(STEPS);
+    let settings = GravitySettings {
+        substeps: args
+            .next()
+            .and_then(|arg| arg.parse().ok())
+            .unwrap_or(1),
+        cache: args.next().as_deref() == Some("cache"),
+        ..default()
+    };
+
+    // a fixed seed so runs are comparable
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut sim: Vec<SimBody> = (0..bodies)
+        .map(|_| {
+            let mass = rng.gen_range(1.0..100.0);
+            SimBody {
+                position: Vec2::new(
+                    rng.gen_range(-2000.0..2000.0),
+                    rng.gen_range(-2000.0..2000.0),
+                ),
+                velocity: Vec2::ZERO,
+                mass,
+                point_mass: PointMass::HasGravity { mass },
+                golf_ball: false,
+            }
+        })
+        .collect();
+
+    let mut particle_set = ParticleSet::new();
+    let mut cache = GravityCache::default();
+    let start = Instant::now();
+    for _ in 0..steps {
+        step_gravity(
+            &mut particle_set,
+            &mut cache,
+            &mut sim,
+            1.0 / 60.0,
+            Integrator::SemiImplicitEuler,
+            GravityMode::All,
+            &settings,
+            Precision::F32,
+        );
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{} bodies, {} steps, {} substeps{}: {:?} total, \
+         {:?} per step",
+        bodies,
+        steps,
+        settings.substeps,
+        if settings.cache { ", cached" } else { "" },
+        elapsed,
+        elapsed / steps.max(1) as u32
+    );
+}
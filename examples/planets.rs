@@ -1,21 +1,275 @@
-use bevy::prelude::*;
+//! A grid of cubesphere planets at a spread of mesh
+//! resolutions, each with its own bumpy surface and some
+//! with an atmosphere, for checking what the mesh
+//! generator puts out. Drag with the left mouse button to
+//! orbit the camera and scroll to zoom.
+//!
+//! `cargo run --example planets -- 12 7` shows 12 planets
+//! with their surfaces seeded from 7.
+//!
+//! `cargo run --example planets -- orbit` shows two planets
+//! going round each other instead, pulled along by their
+//! own gravity. Their masses and how far apart they are
+//! can follow, as in `orbit 0.02 0.01 5`.
+use bevy::{
+    pbr::wireframe::Wireframe, prelude::*,
+    render::mesh::VertexAttributeValues,
+};
 use bevy_inspector_egui::WorldInspectorPlugin;
-use space_golf::planet::PlanetPlugin;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use space_golf::{
+    golf_ball::PointMass,
+    orbit_camera::{OrbitCamera, OrbitCameraPlugin},
+    planet::{
+        AtmosphereShell, Planet3dMaterial, PlanetMesh,
+        PlanetPlugin,
+    },
+    self_gravity::{Binary, SelfGravityPlugin},
+    spin::Spin,
+};
+use std::f32::consts::TAU;
+
+const PLANETS: usize = 9;
+const SEED: u64 = 0;
+/// handed out to the planets in turn
+const RESOLUTIONS: [u32; 4] = [5, 10, 20, 40];
+/// distance between planet centers
+const SPACING: f32 = 3.0;
+/// how far the tallest bump can rise above the surface
+const MAX_DISPLACEMENT: f32 = 0.15;
+/// sine waves summed up for each surface
+const WAVES: usize = 4;
+
+/// Which planets to show, from the command line.
+struct PlanetGrid {
+    count: usize,
+    seed: u64,
+}
 
 fn main() {
-    App::new()
-        .insert_resource(WindowDescriptor {
-            title: "Planet Examples".to_string(),
-            width: 1280.0,
-            height: 720.0,
-            ..Default::default()
-        })
-        .insert_resource(ClearColor(Color::BLACK))
-        .add_plugins(DefaultPlugins)
-        .add_plugin(WorldInspectorPlugin::new())
-        .add_plugin(PlanetPlugin)
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("orbit") {
+        args.next();
+        let defaults = Binary::default();
+        let mut number = |default: f32| {
+            args.next()
+                .and_then(|arg| arg.parse().ok())
+                .unwrap_or(default)
+        };
+        let binary = Binary {
+            masses: [
+                number(defaults.masses[0]),
+                number(defaults.masses[1]),
+            ],
+            separation: number(defaults.separation),
+            ..defaults
+        };
+        app()
+            .insert_resource(binary)
+            .add_plugin(SelfGravityPlugin)
+            .add_startup_system(setup_binary)
+            .run();
+        return;
+    }
+    let count = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(PLANETS);
+    let seed = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(SEED);
+
+    app()
+        .insert_resource(PlanetGrid { count, seed })
         .add_startup_system(setup)
         .run();
 }
 
-fn setup() {}
+fn app() -> App {
+    let mut app = App::new();
+    app.insert_resource(WindowDescriptor {
+        title: "Planet Examples".to_string(),
+        width: 1280.0,
+        height: 720.0,
+        ..Default::default()
+    })
+    .insert_resource(ClearColor(Color::BLACK))
+    .add_plugins(DefaultPlugins)
+    .add_plugin(WorldInspectorPlugin::new())
+    .add_plugin(PlanetPlugin)
+    .add_plugin(OrbitCameraPlugin);
+    app
+}
+
+/// Pushes every vertex of a unit cubesphere in or out
+/// along its direction by a few random sine waves, so
+/// neighbouring vertices move together and the surface
+/// stays smooth.
+fn displace(mesh: &mut Mesh, rng: &mut StdRng) {
+    let waves: Vec<(Vec3, f32, f32)> = (0..WAVES)
+        .map(|_| {
+            let axis = Vec3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            )
+            .normalize_or_zero();
+            (
+                axis,
+                rng.gen_range(2.0..8.0),
+                rng.gen_range(0.0..TAU),
+            )
+        })
+        .collect();
+    let amplitude =
+        rng.gen_range(0.0..MAX_DISPLACEMENT) / WAVES as f32;
+    if let Some(VertexAttributeValues::Float32x3(
+        positions,
+    )) = mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for position in positions.iter_mut() {
+            let direction = Vec3::from(*position);
+            let height: f32 = waves
+                .iter()
+                .map(|(axis, frequency, phase)| {
+                    (direction.dot(*axis) * frequency
+                        + phase)
+                        .sin()
+                })
+                .sum();
+            *position = (direction
+                * (1.0 + height * amplitude))
+                .into();
+        }
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    grid: Res<PlanetGrid>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<Planet3dMaterial>>,
+) {
+    let mut rng = StdRng::seed_from_u64(grid.seed);
+    let columns =
+        (grid.count as f32).sqrt().ceil() as usize;
+    let rows = grid.count.div_ceil(columns.max(1));
+    // centered on the origin so the camera can orbit it
+    let offset = Vec3::new(
+        (columns as f32 - 1.0) * SPACING / 2.0,
+        0.0,
+        (rows as f32 - 1.0) * SPACING / 2.0,
+    );
+    for i in 0..grid.count {
+        let resolution = RESOLUTIONS[i % RESOLUTIONS.len()];
+        let mut mesh =
+            Mesh::from(PlanetMesh { resolution });
+        displace(&mut mesh, &mut rng);
+        let position = Vec3::new(
+            (i % columns) as f32 * SPACING,
+            0.0,
+            (i / columns) as f32 * SPACING,
+        ) - offset;
+        let planet = commands
+            .spawn()
+            .insert_bundle(MaterialMeshBundle {
+                mesh: meshes.add(mesh),
+                transform: Transform::from_translation(
+                    position,
+                ),
+                material: materials.add(Planet3dMaterial {
+                    color: Color::hsl(
+                        rng.gen_range(0.0..360.0),
+                        0.6,
+                        0.5,
+                    ),
+                }),
+                ..default()
+            })
+            .insert(Wireframe)
+            .insert(Spin {
+                axis: Vec3::Y,
+                rate: 0.5,
+            })
+            .id();
+        // about half of them get some air
+        if rng.gen_bool(0.5) {
+            commands.entity(planet).insert(
+                AtmosphereShell {
+                    color: Color::hsla(
+                        rng.gen_range(180.0..240.0),
+                        0.7,
+                        0.7,
+                        0.8,
+                    ),
+                    thickness: rng.gen_range(0.05..0.25),
+                },
+            );
+        }
+    }
+    let distance =
+        columns.max(rows) as f32 * SPACING * 1.5 + 2.0;
+    spawn_camera(&mut commands, distance);
+}
+
+/// Two planets sized by their mass, so the lighter one is
+/// the smaller one, already on their way round.
+fn setup_binary(
+    mut commands: Commands,
+    binary: Res<Binary>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<Planet3dMaterial>>,
+) {
+    let heaviest = binary.masses[0].max(binary.masses[1]);
+    let hues = [30.0, 210.0];
+    for (i, (position, orbiting)) in
+        binary.bodies().into_iter().enumerate()
+    {
+        let mass = binary.masses[i];
+        let radius = if heaviest > 0.0 {
+            (mass / heaviest).cbrt()
+        } else {
+            1.0
+        };
+        commands
+            .spawn_bundle(MaterialMeshBundle {
+                mesh: meshes.add(Mesh::from(PlanetMesh {
+                    resolution: 20,
+                })),
+                transform: Transform::from_translation(
+                    position,
+                )
+                .with_scale(Vec3::splat(radius)),
+                material: materials.add(Planet3dMaterial {
+                    color: Color::hsl(hues[i], 0.6, 0.5),
+                }),
+                ..default()
+            })
+            .insert(PointMass::HasGravity { mass })
+            .insert(orbiting)
+            .insert(Spin {
+                axis: Vec3::Y,
+                rate: 0.5,
+            });
+    }
+    spawn_camera(
+        &mut commands,
+        binary.separation * 2.0 + 2.0,
+    );
+}
+
+fn spawn_camera(commands: &mut Commands, distance: f32) {
+    commands
+        .spawn_bundle(Camera3dBundle {
+            transform: Transform::from_xyz(
+                0.0, distance, distance,
+            )
+            .looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        })
+        .insert(OrbitCamera {
+            distance,
+            ..default()
+        });
+}
@@ -0,0 +1,112 @@
+//! Times one frame's gravity for lots of balls around a
+//! few planets, the usual game, both ways the game can
+//! work it out: through a `ParticleSet` the way
+//! `GravityMode::All` does, and by summing the planets'
+//! pulls directly the way `GravityMode::PlanetsOnly` does.
+//! Both have to agree.
+//!
+//! `cargo run --release --example planets_only -- 500 3`
+//! times 500 balls and 3 planets.
+use bevy::prelude::*;
+use particular::ParticleSet;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use space_golf::{
+    gravity::{gravity_at, GravitySource, G},
+    Body,
+};
+use std::time::Instant;
+
+const BALLS: usize = 500;
+const PLANETS: usize = 3;
+const FRAMES: u32 = 200;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let balls = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(BALLS);
+    let planets = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(PLANETS);
+
+    // a fixed seed so runs are comparable
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut position = || {
+        Vec2::new(
+            rng.gen_range(-2000.0..2000.0),
+            rng.gen_range(-2000.0..2000.0),
+        )
+    };
+    let sources: Vec<GravitySource> = (0..planets)
+        .map(|_| GravitySource {
+            position: position(),
+            mu: 1E5 * G,
+        })
+        .collect();
+    let positions: Vec<Vec2> =
+        (0..balls).map(|_| position()).collect();
+
+    let start = Instant::now();
+    let mut through_set = Vec::new();
+    for _ in 0..FRAMES {
+        // rebuilt every frame, like `sync_particle_set`
+        let mut particle_set = ParticleSet::new();
+        for (i, source) in sources.iter().enumerate() {
+            particle_set.add(Body::new(
+                source.position.extend(0.0),
+                source.mu,
+                Entity::from_raw(i as u32),
+            ));
+        }
+        for (i, position) in positions.iter().enumerate() {
+            particle_set.add(Body::new(
+                position.extend(0.0),
+                0.0,
+                Entity::from_raw((planets + i) as u32),
+            ));
+        }
+        let mut forces: Vec<(u32, Vec2)> = particle_set
+            .result()
+            .filter(|(body, _)| {
+                body.entity.id() as usize >= planets
+            })
+            .map(|(body, gravity)| {
+                (body.entity.id(), gravity.truncate())
+            })
+            .collect();
+        // particular hands results back in its own order
+        forces.sort_by_key(|(id, _)| *id);
+        through_set = forces
+            .into_iter()
+            .map(|(_, force)| force)
+            .collect();
+    }
+    let set_time = start.elapsed() / FRAMES;
+
+    let start = Instant::now();
+    let mut direct = Vec::new();
+    for _ in 0..FRAMES {
+        direct = positions
+            .iter()
+            .map(|position| gravity_at(&sources, *position))
+            .collect();
+    }
+    let direct_time = start.elapsed() / FRAMES;
+
+    println!(
+        "{} balls, {} planets: ParticleSet {:?}, \
+         planets only {:?} per frame",
+        balls, planets, set_time, direct_time
+    );
+    assert_eq!(through_set.len(), direct.len());
+    for (a, b) in through_set.iter().zip(&direct) {
+        assert!(
+            a.distance(*b) <= b.length() * 1e-4,
+            "the two paths disagree: {} vs {}",
+            a,
+            b
+        );
+    }
+}
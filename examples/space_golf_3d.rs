@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::WorldInspectorPlugin;
+use space_golf::{
+    golf_ball::{BallPalette, GolfBallSettings},
+    orbit_camera::{OrbitCamera, OrbitCameraPlugin},
+    planet::Planet3dMaterial,
+    three_d::{
+        spawn_ball_3d, spawn_planet_3d, ThreeDPlugin,
+    },
+};
+
+/// where every shot starts from
+const TEE: Vec3 = Vec3::new(0.0, 0.0, 400.0);
+
+fn main() {
+    App::new()
+        .insert_resource(WindowDescriptor {
+            title: "Space Golf 3D".to_string(),
+            width: 1280.0,
+            height: 720.0,
+            ..Default::default()
+        })
+        .insert_resource(ClearColor(Color::BLACK))
+        .init_resource::<GolfBallSettings>()
+        .init_resource::<BallPalette>()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WorldInspectorPlugin::new())
+        .add_plugin(ThreeDPlugin)
+        .add_plugin(OrbitCameraPlugin)
+        .add_startup_system(setup)
+        .add_system(launch_ball)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<Planet3dMaterial>>,
+) {
+    spawn_planet_3d(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        Vec3::new(-300.0, -50.0, 0.0),
+        60.0,
+        10E5,
+        Color::BLUE,
+    );
+    spawn_planet_3d(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        Vec3::new(200.0, 10.0, -100.0),
+        50.0,
+        8E5,
+        Color::RED,
+    );
+    commands.spawn_bundle(PointLightBundle {
+        transform: Transform::from_xyz(0.0, 800.0, 800.0),
+        point_light: PointLight {
+            range: 4000.0,
+            intensity: 4_000_000.0,
+            ..default()
+        },
+        ..default()
+    });
+    let eye = Vec3::new(0.0, 400.0, 900.0);
+    commands
+        .spawn_bundle(Camera3dBundle {
+            transform: Transform::from_translation(eye)
+                .looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        })
+        .insert(OrbitCamera::looking_from(eye, Vec3::ZERO));
+}
+
+/// `B` launches a ball from the tee towards the middle of
+/// the system.
+fn launch_ball(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    settings: Res<GolfBallSettings>,
+    mut palette: ResMut<BallPalette>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !keyboard.just_pressed(KeyCode::B) {
+        return;
+    }
+    let color = palette.next_color();
+    spawn_ball_3d(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        TEE,
+        Vec3::new(40.0, 30.0, -150.0),
+        color,
+        &settings,
+    );
+}
@@ -0,0 +1,223 @@
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    golf_ball::{BallAssets, GolfBall},
+    menu::playing,
+    time_scale::TimeScale,
+};
+
+/// Afterimages: every `interval` seconds each moving ball
+/// leaves a see-through copy of itself behind, which fades
+/// away over `lifetime` seconds. Unlike a trail it's the
+/// ball itself that's copied, so faster balls leave their
+/// copies further apart. Toggled with `F2`.
+///
+/// Both times are simulated time, so afterimages hang in
+/// the air while the game is paused.
+pub struct Afterimages {
+    pub enabled: bool,
+    pub interval: f32,
+    pub lifetime: f32,
+    /// how see-through a fresh afterimage is
+    pub alpha: f32,
+    /// No new afterimages are left while this many are
+    /// still fading.
+    pub max_live: usize,
+}
+
+impl Default for Afterimages {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: 0.05,
+            lifetime: 0.75,
+            alpha: 0.5,
+            max_live: 400,
+        }
+    }
+}
+
+/// A fading copy of a ball.
+#[derive(Component)]
+pub struct Afterimage {
+    age: Timer,
+    color: Color,
+    texture: Option<Handle<Image>>,
+    /// which of `FADE_STEPS` it's faded to so far
+    step: u32,
+}
+
+/// Afterimages fade in this many steps rather than
+/// smoothly, so they share `BallAssets`' materials instead
+/// of each needing its own.
+const FADE_STEPS: u32 = 8;
+/// balls slower than this are resting, not leaving a path
+const MIN_SPEED: f32 = 1.0;
+
+pub struct AfterimagePlugin;
+
+impl Plugin for AfterimagePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Afterimages>()
+            .add_system(
+                toggle_afterimages
+                    .with_run_criteria(playing),
+            )
+            .add_system(
+                spawn_afterimages.after(toggle_afterimages),
+            )
+            .add_system(
+                fade_afterimages.after(spawn_afterimages),
+            );
+    }
+}
+
+fn toggle_afterimages(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    mut afterimages: ResMut<Afterimages>,
+    live: Query<Entity, With<Afterimage>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F2) {
+        return;
+    }
+    afterimages.enabled = !afterimages.enabled;
+    if !afterimages.enabled {
+        for entity in live.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// The faded copy of `color` an afterimage shows at
+/// `step`.
+fn faded(color: Color, alpha: f32, step: u32) -> Color {
+    let left = 1.0 - step as f32 / FADE_STEPS as f32;
+    let mut color = color;
+    color.set_a(color.a() * alpha * left);
+    color
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_afterimages(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    afterimages: Res<Afterimages>,
+    mut since_last: Local<f32>,
+    mut ball_assets: ResMut<BallAssets>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    balls: Query<
+        (
+            &GlobalTransform,
+            &Velocity,
+            &Mesh2dHandle,
+            &Handle<ColorMaterial>,
+        ),
+        With<GolfBall>,
+    >,
+    live: Query<(), With<Afterimage>>,
+) {
+    if !afterimages.enabled {
+        *since_last = 0.0;
+        return;
+    }
+    *since_last += time.delta_seconds() * time_scale.0;
+    if *since_last < afterimages.interval {
+        return;
+    }
+    *since_last = 0.0;
+
+    let mut count = live.iter().count();
+    for (transform, velocity, mesh, material) in
+        balls.iter()
+    {
+        if count >= afterimages.max_live {
+            return;
+        }
+        if velocity.linvel.length() < MIN_SPEED {
+            continue;
+        }
+        let (color, texture) = match materials.get(material)
+        {
+            Some(material) => {
+                (material.color, material.texture.clone())
+            }
+            None => continue,
+        };
+        let material = ball_assets.material(
+            &mut materials,
+            faded(color, afterimages.alpha, 0),
+            texture.clone(),
+        );
+        // just behind the balls themselves
+        let mut translation = transform.translation();
+        translation.z -= 0.5;
+        commands
+            .spawn_bundle(MaterialMesh2dBundle {
+                mesh: mesh.clone(),
+                material,
+                transform: Transform {
+                    translation,
+                    rotation: transform
+                        .to_scale_rotation_translation()
+                        .1,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(Afterimage {
+                age: Timer::from_seconds(
+                    afterimages.lifetime,
+                    false,
+                ),
+                color,
+                texture,
+                step: 0,
+            });
+        count += 1;
+    }
+}
+
+fn fade_afterimages(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    afterimages: Res<Afterimages>,
+    mut ball_assets: ResMut<BallAssets>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut live: Query<(
+        Entity,
+        &mut Afterimage,
+        &mut Handle<ColorMaterial>,
+    )>,
+) {
+    let delta = time.delta().mul_f32(time_scale.0);
+    for (entity, mut afterimage, mut material) in
+        live.iter_mut()
+    {
+        if afterimage.age.tick(delta).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let step = (afterimage.age.percent()
+            * FADE_STEPS as f32) as u32;
+        if step == afterimage.step {
+            continue;
+        }
+        afterimage.step = step;
+        *material = ball_assets.material(
+            &mut materials,
+            faded(
+                afterimage.color,
+                afterimages.alpha,
+                step,
+            ),
+            afterimage.texture.clone(),
+        );
+    }
+}
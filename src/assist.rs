@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+
+use crate::{
+    course::{Assists, Sunk},
+    golf_ball::GolfBall,
+    gravity::{
+        dominant_body, softened_potential_at,
+        GravityBodies, GravitySettings, GravitySource,
+    },
+    planet_spec::Planet,
+    sim_state::sim_running,
+    Body,
+};
+
+/// A flyby counts as a close one inside this many planet
+/// radii of the planet's center.
+pub const FLYBY_RADII: f32 = 3.0;
+/// How much faster a ball has to leave a flyby than it
+/// came in for it to count as a gravity assist, measured
+/// at the distance it came in at. Small changes from the
+/// other planets' pull along the way don't count.
+pub const ASSIST_MIN_DELTA_V: f32 = 100.0;
+
+/// Sent when a ball leaves a close flyby of a planet at
+/// least [`ASSIST_MIN_DELTA_V`] faster than it came in.
+/// Each one takes a stroke off the level's score.
+pub struct GravityAssist {
+    pub entity: Entity,
+    pub planet: Entity,
+    pub delta_v: f32,
+}
+
+/// A ball that's currently flying close past `planet`.
+#[derive(Component)]
+struct Flyby {
+    planet: Entity,
+    entry_speed: f32,
+    /// the ball's energy in the planet's well on the way
+    /// in, see `track_flybys`
+    entry_energy: f32,
+}
+
+pub struct GravityAssistPlugin;
+
+impl Plugin for GravityAssistPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GravityAssist>()
+            .add_system(
+                track_flybys.with_run_criteria(sim_running),
+            )
+            .add_system(count_assists.after(track_flybys));
+    }
+}
+
+/// A fixed planet hands back on the way out exactly the
+/// speed it gave on the way in, so comparing speeds would
+/// only measure how far apart the ball was seen going in
+/// and coming out. The ball's energy in the planet's well,
+/// kinetic plus potential per unit of mass, stays the same
+/// through the whole flyby unless something else, like the
+/// planet moving, adds to it, so the assist is worked out
+/// from that instead.
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn track_flybys(
+    mut commands: Commands,
+    mut assists: EventWriter<GravityAssist>,
+    particle_set: Res<ParticleSet<Body>>,
+    gravity_settings: Res<GravitySettings>,
+    bodies: GravityBodies,
+    balls: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Velocity,
+            Option<&ColliderMassProperties>,
+            Option<&Flyby>,
+        ),
+        (With<GolfBall>, Without<Sunk>),
+    >,
+    planets: Query<
+        (&GlobalTransform, &Collider),
+        With<Planet>,
+    >,
+) {
+    let near = |planet: Entity, position: Vec3| {
+        planets.get(planet).is_ok_and(
+            |(transform, collider)| {
+                collider.as_ball().is_some_and(|ball| {
+                    transform
+                        .translation()
+                        .distance(position)
+                        < ball.radius() * FLYBY_RADII
+                })
+            },
+        )
+    };
+    let energy = |planet: Entity,
+                  position: Vec3,
+                  speed: f32,
+                  mass: f32| {
+        let (center, mu) = bodies.get(planet)?;
+        let well = softened_potential_at(
+            &[GravitySource {
+                position: center.truncate(),
+                mu: mu * gravity_settings.g_scale(),
+            }],
+            position.truncate(),
+            gravity_settings.softening,
+        );
+        // gravity reaches balls as a force, so the ball's
+        // own mass divides the planet's pull
+        Some(0.5 * speed * speed + well / mass)
+    };
+    for (ball, transform, velocity, mass_props, flyby) in
+        balls.iter()
+    {
+        let position = transform.translation();
+        let speed = velocity.linvel.length();
+        let mass = match mass_props {
+            Some(ColliderMassProperties::Mass(mass)) => {
+                *mass
+            }
+            _ => 1.0,
+        };
+        match flyby {
+            Some(flyby) if near(flyby.planet, position) => {
+            }
+            Some(flyby) => {
+                // the speed the ball would have back where
+                // it came in
+                let delta_v = energy(
+                    flyby.planet,
+                    position,
+                    speed,
+                    mass,
+                )
+                .map_or(
+                    0.0,
+                    |exit_energy| {
+                        let gained = exit_energy
+                            - flyby.entry_energy;
+                        (flyby.entry_speed
+                            * flyby.entry_speed
+                            + 2.0 * gained)
+                            .max(0.0)
+                            .sqrt()
+                            - flyby.entry_speed
+                    },
+                );
+                if delta_v >= ASSIST_MIN_DELTA_V {
+                    assists.send(GravityAssist {
+                        entity: ball,
+                        planet: flyby.planet,
+                        delta_v,
+                    });
+                }
+                commands.entity(ball).remove::<Flyby>();
+            }
+            None => {
+                let entry =
+                    dominant_body(&particle_set, position)
+                        .filter(|planet| {
+                            near(*planet, position)
+                        })
+                        .and_then(|planet| {
+                            energy(
+                                planet, position, speed,
+                                mass,
+                            )
+                            .map(|energy| (planet, energy))
+                        });
+                if let Some((planet, entry_energy)) = entry
+                {
+                    commands.entity(ball).insert(Flyby {
+                        planet,
+                        entry_speed: speed,
+                        entry_energy,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn count_assists(
+    mut events: EventReader<GravityAssist>,
+    mut assists: ResMut<Assists>,
+) {
+    assists.0 += events.iter().count() as u32;
+}
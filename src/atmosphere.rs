@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::forces::{
+    AddForceModifier, BallState, ForceModifier,
+};
+
+/// A shell around a planet that slows down any golf
+/// ball passing through it. `radius` is measured from
+/// the planet's center, so it should be larger than
+/// the planet itself.
+#[derive(
+    Component,
+    Reflect,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[reflect(Component)]
+pub struct Atmosphere {
+    pub radius: f32,
+    pub drag: f32,
+}
+
+/// Slows down balls inside any planet's atmosphere.
+#[derive(Default)]
+pub struct AtmosphericDrag {
+    atmospheres: Vec<(Vec2, Atmosphere)>,
+}
+
+impl ForceModifier for AtmosphericDrag {
+    fn prepare(&mut self, world: &mut World) {
+        self.atmospheres.clear();
+        let mut atmospheres = world
+            .query::<(&GlobalTransform, &Atmosphere)>();
+        self.atmospheres.extend(
+            atmospheres.iter(world).map(
+                |(transform, atmosphere)| {
+                    (
+                        transform.translation().truncate(),
+                        *atmosphere,
+                    )
+                },
+            ),
+        );
+    }
+
+    fn force(&self, ball: &BallState) -> Vec2 {
+        self.atmospheres
+            .iter()
+            .filter(|(center, atmosphere)| {
+                ball.position.distance(*center)
+                    < atmosphere.radius
+            })
+            .fold(Vec2::ZERO, |total, (_, atmosphere)| {
+                total - ball.velocity * atmosphere.drag
+            })
+    }
+}
+
+pub struct AtmospherePlugin;
+
+impl Plugin for AtmospherePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Atmosphere>()
+            .add_force_modifier(AtmosphericDrag::default());
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_rapier2d::prelude::*;
+use std::time::Duration;
+
+use crate::{
+    course::{Course, Filled},
+    golf_ball::GolfBallSettings,
+    gravity::{
+        GravityBodies, GravityMode, GravitySettings,
+    },
+    hole::Hole,
+    menu::playing,
+    planet_spec::Planet,
+    prediction::{
+        find_shot, Obstacle, PredictionSettings, ShotSearch,
+    },
+};
+
+/// Asks for a ball to be launched as if the player had
+/// aimed it, stroke and all.
+pub struct LaunchShot {
+    pub position: Vec3,
+    pub velocity: Vec2,
+}
+
+/// Press `F` to have the game look for a shot from the
+/// tee that sinks an open hole, and take it if it finds
+/// one. The shot is found with the trajectory predictor,
+/// so it only knows about the planets, not other balls.
+pub struct AutoAim {
+    pub search: ShotSearch,
+}
+
+impl Default for AutoAim {
+    fn default() -> Self {
+        Self {
+            search: ShotSearch {
+                directions: 180,
+                speeds: 16,
+                speed_step: 25.0,
+                // a frame or so of hitching at most
+                time_limit: Some(Duration::from_millis(
+                    250,
+                )),
+            },
+        }
+    }
+}
+
+pub struct AutoAimPlugin;
+
+impl Plugin for AutoAimPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AutoAim>()
+            .add_event::<LaunchShot>()
+            .add_system(
+                auto_aim.with_run_criteria(playing),
+            );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn auto_aim(
+    keyboard: Res<Input<KeyCode>>,
+    auto_aim: Res<AutoAim>,
+    course: Res<Course>,
+    ball: Res<GolfBallSettings>,
+    prediction: Res<PredictionSettings>,
+    gravity_mode: Res<GravityMode>,
+    gravity_settings: Res<GravitySettings>,
+    mut launches: EventWriter<LaunchShot>,
+    bodies: GravityBodies,
+    planets: Query<
+        (&GlobalTransform, &Collider),
+        With<Planet>,
+    >,
+    holes: Query<
+        (&GlobalTransform, &Hole),
+        Without<Filled>,
+    >,
+) {
+    if !keyboard.just_pressed(KeyCode::F) {
+        return;
+    }
+    let tee = match course
+        .current_level()
+        .and_then(|level| level.tee)
+    {
+        Some(tee) => tee,
+        None => return,
+    };
+    let sources = bodies
+        .sources(*gravity_mode, gravity_settings.g_scale());
+    let obstacles: Vec<Obstacle> = planets
+        .iter()
+        .map(|(transform, collider)| {
+            Obstacle::from_collider(transform, collider)
+        })
+        .collect();
+
+    for (transform, hole) in holes.iter() {
+        if let Some(velocity) = find_shot(
+            &sources,
+            &obstacles,
+            tee,
+            transform.translation().xy(),
+            hole.radius,
+            &ball,
+            &prediction,
+            &auto_aim.search,
+        ) {
+            info!(
+                "auto aim: power {:.0} at {:.1} degrees",
+                velocity.length(),
+                velocity.y.atan2(velocity.x).to_degrees()
+            );
+            launches.send(LaunchShot {
+                position: tee.extend(0.0),
+                velocity,
+            });
+            return;
+        }
+    }
+    info!("auto aim: no shot found");
+}
@@ -0,0 +1,136 @@
+use bevy::{app::AppExit, audio::AudioSink, prelude::*};
+
+use crate::course::Course;
+
+/// The background color used by levels that don't set
+/// their own.
+pub const DEFAULT_BACKGROUND: Color = Color::BLACK;
+/// seconds the old track takes to fade out, and the new
+/// one to fade in, when the music changes
+const CROSSFADE_SECONDS: f32 = 2.0;
+
+/// A looping track and how far it's faded in, from 0.0 to
+/// 1.0.
+struct Track {
+    path: String,
+    sink: Handle<AudioSink>,
+    volume: f32,
+}
+
+/// What's playing: the current level's music, fading in,
+/// and whatever tracks are still fading out.
+#[derive(Default)]
+struct Music {
+    current: Option<Track>,
+    fading_out: Vec<Track>,
+}
+
+/// Sets the background color and music of each level as
+/// it loads, from `Level::background` and `Level::music`.
+pub struct BackdropPlugin;
+
+impl Plugin for BackdropPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Music>()
+            .add_system(apply_backdrop)
+            .add_system(
+                crossfade_music.after(apply_backdrop),
+            )
+            .add_system_to_stage(
+                CoreStage::Last,
+                stop_music_on_exit,
+            );
+    }
+}
+
+fn apply_backdrop(
+    course: Res<Course>,
+    mut clear_color: ResMut<ClearColor>,
+    mut music: ResMut<Music>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+) {
+    if !course.is_changed() {
+        return;
+    }
+    let level = course.current_level();
+    clear_color.0 = level
+        .and_then(|level| level.background)
+        .unwrap_or(DEFAULT_BACKGROUND);
+
+    let wanted =
+        level.and_then(|level| level.music.as_ref());
+    // the same track carries on across levels that share it
+    if music.current.as_ref().map(|track| &track.path)
+        == wanted
+    {
+        return;
+    }
+    if let Some(track) = music.current.take() {
+        music.fading_out.push(track);
+    }
+    music.current = wanted.map(|path| {
+        let sink = audio.play_with_settings(
+            asset_server.load(path.as_str()),
+            PlaybackSettings::LOOP.with_volume(0.0),
+        );
+        Track {
+            path: path.clone(),
+            sink: audio_sinks.get_handle(sink),
+            volume: 0.0,
+        }
+    });
+}
+
+fn crossfade_music(
+    time: Res<Time>,
+    mut music: ResMut<Music>,
+    audio_sinks: Res<Assets<AudioSink>>,
+) {
+    let step = time.delta_seconds() / CROSSFADE_SECONDS;
+    // a sink only shows up once its track has loaded and
+    // started, so until then there's nothing to fade
+    if let Some(track) = music.current.as_mut() {
+        if let Some(sink) = audio_sinks.get(&track.sink) {
+            track.volume = (track.volume + step).min(1.0);
+            sink.set_volume(track.volume);
+        }
+    }
+    music.fading_out.retain_mut(|track| {
+        track.volume = (track.volume - step).max(0.0);
+        match audio_sinks.get(&track.sink) {
+            Some(sink) if track.volume > 0.0 => {
+                sink.set_volume(track.volume);
+                true
+            }
+            Some(sink) => {
+                sink.stop();
+                false
+            }
+            // still loading, so hang on to it until it can
+            // be stopped
+            None => true,
+        }
+    });
+}
+
+fn stop_music_on_exit(
+    mut exit: EventReader<AppExit>,
+    mut music: ResMut<Music>,
+    audio_sinks: Res<Assets<AudioSink>>,
+) {
+    if exit.iter().last().is_none() {
+        return;
+    }
+    let tracks = music
+        .current
+        .take()
+        .into_iter()
+        .chain(std::mem::take(&mut music.fading_out));
+    for track in tracks {
+        if let Some(sink) = audio_sinks.get(&track.sink) {
+            sink.stop();
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::{golf_ball::GolfBall, menu::playing};
+
+/// A sandbox tool for scattering clustered balls: `X`
+/// kicks every dynamic ball within `radius` of the cursor
+/// straight away from it, harder the closer it is.
+///
+/// It's a one-shot `ExternalImpulse`, unlike gravity which
+/// is a force applied every step.
+pub struct BlastSettings {
+    /// impulse at one world unit from the cursor, falling
+    /// off with the inverse of the distance
+    pub strength: f32,
+    pub radius: f32,
+    /// closer balls are pushed as if they were this far
+    /// away, so one right under the cursor isn't launched
+    /// off to infinity
+    pub min_distance: f32,
+}
+
+impl Default for BlastSettings {
+    fn default() -> Self {
+        Self {
+            strength: 600_000.0,
+            radius: 150.0,
+            min_distance: 10.0,
+        }
+    }
+}
+
+/// How long the blast radius stays drawn, in seconds.
+const BLAST_SECONDS: f32 = 0.3;
+
+pub struct BlastPlugin;
+
+impl Plugin for BlastPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BlastSettings>()
+            .add_system(blast.with_run_criteria(playing));
+    }
+}
+
+fn blast(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    settings: Res<BlastSettings>,
+    mouse_pos: Res<MousePosWorld>,
+    mut lines: ResMut<DebugLines>,
+    mut balls: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &RigidBody,
+            Option<&mut ExternalImpulse>,
+        ),
+        With<GolfBall>,
+    >,
+) {
+    if !keyboard.just_pressed(KeyCode::X) {
+        return;
+    }
+    let center = mouse_pos.truncate();
+    for (entity, transform, rigidbody, impulse) in
+        balls.iter_mut()
+    {
+        if *rigidbody != RigidBody::Dynamic {
+            continue;
+        }
+        let offset =
+            transform.translation().truncate() - center;
+        let distance = offset.length();
+        if distance > settings.radius {
+            continue;
+        }
+        let kick = offset.normalize_or_zero()
+            * settings.strength
+            / distance.max(settings.min_distance);
+        let kick = ExternalImpulse {
+            impulse: kick,
+            ..default()
+        };
+        // the impulse is applied once when it changes, so
+        // overwrite whatever was left from a previous kick
+        match impulse {
+            Some(mut impulse) => *impulse = kick,
+            None => {
+                commands.entity(entity).insert(kick);
+            }
+        }
+    }
+
+    let segments = 32;
+    for i in 0..segments {
+        let point = |i: usize| {
+            let angle = i as f32 / segments as f32 * TAU;
+            (center
+                + Vec2::new(angle.cos(), angle.sin())
+                    * settings.radius)
+                .extend(0.0)
+        };
+        lines.line_colored(
+            point(i),
+            point(i + 1),
+            BLAST_SECONDS,
+            Color::ORANGE,
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use bevy::{prelude::*, sprite::Rect};
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    course::{BallLost, GameMode, Sunk},
+    golf_ball::GolfBall,
+    groups::{WALL_FILTER, WALL_GROUP},
+    GravitySystem,
+};
+
+/// What happens to a ball at the edge of the world.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundsMode {
+    /// nothing, balls fly off forever
+    Open,
+    /// solid walls balls bounce off of
+    Bounce,
+    /// balls leaving one side come back in on the other,
+    /// keeping their velocity
+    Wrap,
+}
+
+/// The edges of the world, for levels that keep the ball
+/// in a box.
+pub struct WorldBounds {
+    pub rect: Rect,
+    pub mode: BoundsMode,
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        Self {
+            rect: Rect {
+                min: Vec2::new(-640.0, -360.0),
+                max: Vec2::new(640.0, 360.0),
+            },
+            mode: BoundsMode::Open,
+        }
+    }
+}
+
+const WALL_THICKNESS: f32 = 20.0;
+
+#[derive(Component)]
+struct Wall;
+
+pub struct WorldBoundsPlugin;
+
+impl Plugin for WorldBoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldBounds>()
+            .add_system(update_walls)
+            .add_system(draw_bounds)
+            .add_system(lose_balls)
+            // before the particle set is synced, so the
+            // gravity for this frame is worked out from
+            // where the ball ends up
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                wrap_balls.before(GravitySystem::Sync),
+            );
+    }
+}
+
+/// Rebuilds the walls whenever `WorldBounds` changes.
+fn update_walls(
+    mut commands: Commands,
+    bounds: Res<WorldBounds>,
+    walls: Query<Entity, With<Wall>>,
+) {
+    if !bounds.is_changed() {
+        return;
+    }
+    for wall in walls.iter() {
+        commands.entity(wall).despawn();
+    }
+    if bounds.mode != BoundsMode::Bounce {
+        return;
+    }
+    let rect = bounds.rect;
+    let center = (rect.min + rect.max) / 2.0;
+    let half_size = (rect.max - rect.min) / 2.0;
+    let half_thickness = WALL_THICKNESS / 2.0;
+    // the walls sit just outside of the rect and overlap
+    // at the corners
+    let sides = [
+        (
+            Vec2::new(
+                rect.min.x - half_thickness,
+                center.y,
+            ),
+            Vec2::new(
+                half_thickness,
+                half_size.y + WALL_THICKNESS,
+            ),
+        ),
+        (
+            Vec2::new(
+                rect.max.x + half_thickness,
+                center.y,
+            ),
+            Vec2::new(
+                half_thickness,
+                half_size.y + WALL_THICKNESS,
+            ),
+        ),
+        (
+            Vec2::new(
+                center.x,
+                rect.min.y - half_thickness,
+            ),
+            Vec2::new(
+                half_size.x + WALL_THICKNESS,
+                half_thickness,
+            ),
+        ),
+        (
+            Vec2::new(
+                center.x,
+                rect.max.y + half_thickness,
+            ),
+            Vec2::new(
+                half_size.x + WALL_THICKNESS,
+                half_thickness,
+            ),
+        ),
+    ];
+    for (position, half_extents) in sides {
+        commands
+            .spawn_bundle(TransformBundle::from(
+                Transform::from_translation(
+                    position.extend(0.0),
+                ),
+            ))
+            .insert(RigidBody::Fixed)
+            .insert(Collider::cuboid(
+                half_extents.x,
+                half_extents.y,
+            ))
+            // balls don't bounce at all off of planets, so
+            // the wall decides
+            .insert(Restitution {
+                coefficient: 1.0,
+                combine_rule: CoefficientCombineRule::Max,
+            })
+            .insert(CollisionGroups::new(
+                WALL_GROUP,
+                WALL_FILTER,
+            ))
+            .insert(Wall);
+    }
+}
+
+fn draw_bounds(
+    mut lines: ResMut<DebugLines>,
+    bounds: Res<WorldBounds>,
+) {
+    let color = match bounds.mode {
+        BoundsMode::Open => return,
+        BoundsMode::Bounce => Color::GRAY,
+        BoundsMode::Wrap => Color::DARK_GRAY,
+    };
+    let Rect { min, max } = bounds.rect;
+    let corners = [
+        Vec3::new(min.x, min.y, 0.0),
+        Vec3::new(max.x, min.y, 0.0),
+        Vec3::new(max.x, max.y, 0.0),
+        Vec3::new(min.x, max.y, 0.0),
+    ];
+    for i in 0..corners.len() {
+        lines.line_colored(
+            corners[i],
+            corners[(i + 1) % corners.len()],
+            0.0,
+            color,
+        );
+    }
+}
+
+/// Moves balls that left the rect back in on the other
+/// side. `GlobalTransform` is updated too, since it's what
+/// the particle set is synced from and it otherwise
+/// wouldn't catch up until the end of the frame.
+fn wrap_balls(
+    bounds: Res<WorldBounds>,
+    mut balls: Query<
+        (&mut Transform, &mut GlobalTransform),
+        With<GolfBall>,
+    >,
+) {
+    if bounds.mode != BoundsMode::Wrap {
+        return;
+    }
+    let Rect { min, max } = bounds.rect;
+    let size = max - min;
+    for (mut transform, mut global) in balls.iter_mut() {
+        let position = transform.translation.truncate();
+        if position.cmpge(min).all()
+            && position.cmplt(max).all()
+        {
+            continue;
+        }
+        let wrapped = min
+            + Vec2::new(
+                (position.x - min.x).rem_euclid(size.x),
+                (position.y - min.y).rem_euclid(size.y),
+            );
+        transform.translation.x = wrapped.x;
+        transform.translation.y = wrapped.y;
+        *global = GlobalTransform::from(*transform);
+    }
+}
+
+/// How far past the edge of the world a ball can get in
+/// `GameMode::Challenge` before it's lost, so one that's
+/// only just gone off screen isn't.
+const LOST_MARGIN: f32 = 100.0;
+
+#[allow(clippy::type_complexity)]
+fn lose_balls(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    bounds: Res<WorldBounds>,
+    mut lost: EventWriter<BallLost>,
+    balls: Query<
+        (Entity, &GlobalTransform),
+        (With<GolfBall>, Without<Sunk>),
+    >,
+) {
+    // walls and wrapping already keep balls in
+    if *mode != GameMode::Challenge
+        || bounds.mode != BoundsMode::Open
+    {
+        return;
+    }
+    let Rect { min, max } = bounds.rect;
+    for (ball, transform) in balls.iter() {
+        let position = transform.translation().truncate();
+        if position.cmplt(min - LOST_MARGIN).any()
+            || position.cmpgt(max + LOST_MARGIN).any()
+        {
+            commands.entity(ball).despawn_recursive();
+            lost.send(BallLost { ball });
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+
+use crate::{menu::playing, PIXELS_PER_METER};
+
+/// Marks every contact rapier's solver is working with: a
+/// cross at each contact point and a line along the
+/// contact normal, pointing from the first collider of the
+/// pair into the second. Contacts made this step are drawn
+/// in `NEW_COLOR`, ones carried over from the last step in
+/// `KEPT_COLOR`, which makes a ball that keeps losing and
+/// regaining contact on a bounce easy to spot. Toggled
+/// with `F9`.
+///
+/// The collider outlines are `RapierDebugRenderPlugin`'s
+/// job, this is only the contacts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShowContactPoints(pub bool);
+
+const NEW_COLOR: Color = Color::YELLOW;
+const KEPT_COLOR: Color = Color::LIME_GREEN;
+/// half the width of the cross marking a contact point
+const CROSS_SIZE: f32 = 3.0;
+const NORMAL_LENGTH: f32 = 20.0;
+
+pub struct ContactPointsPlugin;
+
+impl Plugin for ContactPointsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShowContactPoints>()
+            .add_system(
+                toggle_contact_points
+                    .with_run_criteria(playing),
+            )
+            .add_system(
+                draw_contact_points
+                    .after(toggle_contact_points),
+            );
+    }
+}
+
+fn toggle_contact_points(
+    keyboard: Res<Input<KeyCode>>,
+    mut show: ResMut<ShowContactPoints>,
+) {
+    if keyboard.just_pressed(KeyCode::F9) {
+        show.0 = !show.0;
+    }
+}
+
+fn draw_contact_points(
+    show: Res<ShowContactPoints>,
+    context: Res<RapierContext>,
+    mut lines: ResMut<DebugLines>,
+) {
+    if !show.0 {
+        return;
+    }
+    for pair in context.narrow_phase.contact_pairs() {
+        if !pair.has_any_active_contact {
+            continue;
+        }
+        for manifold in pair.manifolds.iter() {
+            let normal = Vec2::new(
+                manifold.data.normal.x,
+                manifold.data.normal.y,
+            );
+            for contact in
+                manifold.data.solver_contacts.iter()
+            {
+                let point = Vec2::new(
+                    contact.point.x,
+                    contact.point.y,
+                ) * PIXELS_PER_METER;
+                let color = if contact.is_new {
+                    NEW_COLOR
+                } else {
+                    KEPT_COLOR
+                };
+                for arm in [
+                    Vec2::new(CROSS_SIZE, CROSS_SIZE),
+                    Vec2::new(CROSS_SIZE, -CROSS_SIZE),
+                ] {
+                    lines.line_colored(
+                        (point - arm).extend(10.0),
+                        (point + arm).extend(10.0),
+                        0.0,
+                        color,
+                    );
+                }
+                lines.line_colored(
+                    point.extend(10.0),
+                    (point + normal * NORMAL_LENGTH)
+                        .extend(10.0),
+                    0.0,
+                    color,
+                );
+            }
+        }
+    }
+}
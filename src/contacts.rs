@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::{
+    fragile::{track_velocity, LastVelocity},
+    golf_ball::GolfBall,
+    menu::playing,
+};
+
+/// A spark, and optionally a click, where two balls hit
+/// each other. Harder hits get bigger sparks and louder
+/// clicks. Toggled with `C`.
+pub struct BallContactEffects {
+    pub enabled: bool,
+    /// hits slower than this are ignored
+    pub min_speed: f32,
+    /// played on every hit, if set
+    pub sound: Option<Handle<AudioSource>>,
+}
+
+impl Default for BallContactEffects {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_speed: 20.0,
+            sound: None,
+        }
+    }
+}
+
+/// world units of spark per unit of impact speed
+const SPARK_SCALE: f32 = 0.05;
+const MAX_SPARK_LENGTH: f32 = 30.0;
+const SPARK_RAYS: usize = 6;
+/// seconds a spark stays on screen
+const SPARK_DURATION: f32 = 0.15;
+/// impact speed the click reaches full volume at
+const FULL_VOLUME_SPEED: f32 = 400.0;
+
+pub struct BallContactPlugin;
+
+impl Plugin for BallContactPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BallContactEffects>()
+            .add_system(
+                toggle_contact_effects
+                    .with_run_criteria(playing),
+            )
+            .add_system(
+                ball_contacts
+                    .after(toggle_contact_effects)
+                    .before(track_velocity),
+            );
+    }
+}
+
+fn toggle_contact_effects(
+    keyboard: Res<Input<KeyCode>>,
+    mut effects: ResMut<BallContactEffects>,
+) {
+    if keyboard.just_pressed(KeyCode::C) {
+        effects.enabled = !effects.enabled;
+    }
+}
+
+fn ball_contacts(
+    mut collisions: EventReader<CollisionEvent>,
+    mut lines: ResMut<DebugLines>,
+    effects: Res<BallContactEffects>,
+    audio: Res<Audio>,
+    balls: Query<
+        (&GlobalTransform, &Collider, &LastVelocity),
+        With<GolfBall>,
+    >,
+) {
+    for event in collisions.iter() {
+        let (a, b) = match event {
+            CollisionEvent::Started(a, b, _) => (*a, *b),
+            CollisionEvent::Stopped(..) => continue,
+        };
+        if !effects.enabled {
+            continue;
+        }
+        let (
+            (a, a_collider, a_velocity),
+            (b, b_collider, b_velocity),
+        ) = match (balls.get(a), balls.get(b)) {
+            (Ok(a), Ok(b)) => (a, b),
+            _ => continue,
+        };
+        let (a_radius, b_radius) = match (
+            a_collider.as_ball(),
+            b_collider.as_ball(),
+        ) {
+            (Some(a), Some(b)) => (a.radius(), b.radius()),
+            _ => continue,
+        };
+        let a = a.translation().truncate();
+        let b = b.translation().truncate();
+        let normal = (b - a).normalize_or_zero();
+        // the velocities from before rapier resolved the
+        // contact, since that's what the hit was made with
+        let speed =
+            (a_velocity.0 - b_velocity.0).dot(normal);
+        if speed < effects.min_speed {
+            continue;
+        }
+        // two circles touch on the line between their
+        // centers
+        let contact =
+            a + (b - a) * a_radius / (a_radius + b_radius);
+
+        let length =
+            (speed * SPARK_SCALE).min(MAX_SPARK_LENGTH);
+        for i in 0..SPARK_RAYS {
+            let angle = i as f32 / SPARK_RAYS as f32 * TAU;
+            let ray = Vec2::new(angle.cos(), angle.sin())
+                * length;
+            lines.line_colored(
+                contact.extend(5.0),
+                (contact + ray).extend(5.0),
+                SPARK_DURATION,
+                Color::YELLOW,
+            );
+        }
+        if let Some(sound) = &effects.sound {
+            audio.play_with_settings(
+                sound.clone(),
+                PlaybackSettings::ONCE.with_volume(
+                    (speed / FULL_VOLUME_SPEED).min(1.0),
+                ),
+            );
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::Indices, render_resource::PrimitiveTopology,
+    },
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use particular::ParticleSet;
+
+use crate::{
+    bounds::WorldBounds,
+    gravity::{
+        softened_potential_at, GravitySettings,
+        GravitySource,
+    },
+    menu::playing,
+    planet_spec::Planet,
+    Body,
+};
+
+/// Filled bands of equal gravitational potential drawn
+/// behind the level, toggled with `K`. It reads like a
+/// height map of the gravity wells: the more bands packed
+/// together, the steeper the well.
+pub struct PotentialContours {
+    pub enabled: bool,
+    /// how many bands the potential is split into
+    pub bands: u32,
+    /// the color of the shallowest band and of the
+    /// deepest, with the rest blended in between
+    pub ramp: (Color, Color),
+    /// size of the grid cells the potential is sampled on,
+    /// in world units
+    pub cell_size: f32,
+}
+
+impl Default for PotentialContours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bands: 12,
+            ramp: (
+                Color::rgba(0.1, 0.1, 0.4, 0.3),
+                Color::rgba(0.9, 0.3, 0.1, 0.3),
+            ),
+            cell_size: 10.0,
+        }
+    }
+}
+
+#[derive(Component)]
+struct ContourMesh;
+
+pub struct PotentialContoursPlugin;
+
+impl Plugin for PotentialContoursPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PotentialContours>()
+            .add_startup_system(spawn_contour_mesh)
+            .add_system(
+                toggle_contours.with_run_criteria(playing),
+            )
+            .add_system(
+                update_contours.after(toggle_contours),
+            );
+    }
+}
+
+fn toggle_contours(
+    keyboard: Res<Input<KeyCode>>,
+    mut contours: ResMut<PotentialContours>,
+) {
+    if keyboard.just_pressed(KeyCode::K) {
+        contours.enabled = !contours.enabled;
+    }
+}
+
+fn spawn_contour_mesh(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        .spawn_bundle(MaterialMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::new(
+                    PrimitiveTopology::TriangleList,
+                ))
+                .into(),
+            // the vertex colors do the coloring
+            material: materials
+                .add(ColorMaterial::from(Color::WHITE)),
+            // behind everything else
+            transform: Transform::from_xyz(0.0, 0.0, -2.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(ContourMesh);
+}
+
+/// Builds a quad per grid cell over `bounds`, each one
+/// flat colored by the band its center's potential falls
+/// in. Bands are spaced evenly in the log of the
+/// potential, since it runs off to minus infinity at
+/// every planet. The potential is softened by `softening`,
+/// the same as the energy diagnostic's.
+fn contour_mesh(
+    sources: &[GravitySource],
+    softening: f32,
+    bounds: &WorldBounds,
+    contours: &PotentialContours,
+) -> Mesh {
+    let min = bounds.rect.min;
+    let size = bounds.rect.max - min;
+    let cell = contours.cell_size.max(1.0);
+    let columns = (size.x / cell).ceil().max(1.0) as u32;
+    let rows = (size.y / cell).ceil().max(1.0) as u32;
+
+    let mut cells =
+        Vec::with_capacity((columns * rows) as usize);
+    for row in 0..rows {
+        for column in 0..columns {
+            let corner = min
+                + Vec2::new(column as f32, row as f32)
+                    * cell;
+            let depth = -softened_potential_at(
+                sources,
+                corner + Vec2::splat(cell / 2.0),
+                softening,
+            );
+            cells.push((
+                corner,
+                depth.max(f32::MIN_POSITIVE).ln(),
+            ));
+        }
+    }
+    let (lowest, highest) = cells.iter().fold(
+        (f32::INFINITY, f32::NEG_INFINITY),
+        |(lowest, highest), (_, depth)| {
+            (lowest.min(*depth), highest.max(*depth))
+        },
+    );
+    let range = (highest - lowest).max(f32::EPSILON);
+    let bands = contours.bands.max(1);
+    let (shallow, deep) = (
+        contours.ramp.0.as_linear_rgba_f32(),
+        contours.ramp.1.as_linear_rgba_f32(),
+    );
+
+    let mut positions = Vec::with_capacity(cells.len() * 4);
+    let mut colors = Vec::with_capacity(cells.len() * 4);
+    let mut indices = Vec::with_capacity(cells.len() * 6);
+    for (corner, depth) in cells {
+        let band = (((depth - lowest) / range
+            * bands as f32) as u32)
+            .min(bands - 1);
+        let t = if bands > 1 {
+            band as f32 / (bands - 1) as f32
+        } else {
+            0.0
+        };
+        let color = [
+            shallow[0] + (deep[0] - shallow[0]) * t,
+            shallow[1] + (deep[1] - shallow[1]) * t,
+            shallow[2] + (deep[2] - shallow[2]) * t,
+            shallow[3] + (deep[3] - shallow[3]) * t,
+        ];
+        let first = positions.len() as u32;
+        for offset in [
+            Vec2::ZERO,
+            Vec2::new(cell, 0.0),
+            Vec2::new(cell, cell),
+            Vec2::new(0.0, cell),
+        ] {
+            let vertex = corner + offset;
+            positions.push([vertex.x, vertex.y, 0.0]);
+            colors.push(color);
+        }
+        indices.extend_from_slice(&[
+            first,
+            first + 1,
+            first + 2,
+            first,
+            first + 2,
+            first + 3,
+        ]);
+    }
+
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+    let uvs = vec![[0.0, 0.0]; positions.len()];
+    let mut mesh =
+        Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        positions,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh
+}
+
+/// Only the planets are used, and the mesh is only rebuilt
+/// when they or the settings change, since balls barely
+/// dent the potential but move every frame.
+#[allow(clippy::too_many_arguments)]
+fn update_contours(
+    contours: Res<PotentialContours>,
+    gravity_settings: Res<GravitySettings>,
+    bounds: Res<WorldBounds>,
+    particle_set: Res<ParticleSet<Body>>,
+    planets: Query<(), With<Planet>>,
+    moved: Query<
+        (),
+        (With<Planet>, Changed<GlobalTransform>),
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut contour_meshes: Query<
+        (&Mesh2dHandle, &mut Visibility),
+        With<ContourMesh>,
+    >,
+    mut planet_count: Local<usize>,
+) {
+    let count = planets.iter().count();
+    let stale = contours.is_changed()
+        || gravity_settings.is_changed()
+        || bounds.is_changed()
+        || !moved.is_empty()
+        || count != *planet_count;
+    *planet_count = count;
+    for (handle, mut visibility) in
+        contour_meshes.iter_mut()
+    {
+        if visibility.is_visible != contours.enabled {
+            visibility.is_visible = contours.enabled;
+        }
+        if !contours.enabled || !stale {
+            continue;
+        }
+        let sources: Vec<GravitySource> = particle_set
+            .iter()
+            .filter(|body| planets.contains(body.entity))
+            .map(|body| GravitySource {
+                position: body.position.truncate(),
+                mu: body.mu,
+            })
+            .collect();
+        if let Some(mesh) = meshes.get_mut(&handle.0) {
+            *mesh = contour_mesh(
+                &sources,
+                gravity_settings.softening,
+                &bounds,
+                &contours,
+            );
+        }
+    }
+}
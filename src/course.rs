@@ -0,0 +1,602 @@
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::{
+    prelude::*, rapier::geometry::CollisionEventFlags,
+};
+
+use crate::{
+    cup::{CupCaptured, CupSettings},
+    golf_ball::{GolfBall, GolfBallSettings},
+    gravity::GravitySettings,
+    hole::Hole,
+    level::{Level, TeeBox},
+    menu::playing,
+    planet_spec::Planet,
+    practice::Practice,
+    sim_state::sim_running,
+    time_scale::TimeScale,
+};
+
+/// Every level being played through, in order.
+#[derive(Default)]
+pub struct Course {
+    pub levels: Vec<Level>,
+    pub current: usize,
+}
+
+impl Course {
+    pub fn current_level(&self) -> Option<&Level> {
+        self.levels.get(self.current)
+    }
+
+    /// The strokes the current level allows in
+    /// `GameMode::Challenge`.
+    pub fn stroke_limit(&self) -> u32 {
+        self.current_level().map_or(0, |level| level.par)
+            + CHALLENGE_STROKES_OVER_PAR
+    }
+
+    /// The current level's tee box, if `mode` enforces it.
+    pub fn tee_box(
+        &self,
+        mode: GameMode,
+    ) -> Option<TeeBox> {
+        match mode {
+            GameMode::Sandbox => None,
+            GameMode::Challenge => self
+                .current_level()
+                .and_then(|level| level.tee_box),
+        }
+    }
+}
+
+/// The rules being played by, switched with `M`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum GameMode {
+    /// launch as many balls as you like, nothing is lost
+    /// and nothing is enforced
+    #[default]
+    Sandbox,
+    /// Each level allows its par plus
+    /// [`CHALLENGE_STROKES_OVER_PAR`] strokes, and a ball
+    /// that leaves the `WorldBounds` is lost. Running out
+    /// of strokes with holes still open restarts the level.
+    Challenge,
+}
+
+/// How one level follows on from the last, switched with
+/// `Shift+M`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CourseMode {
+    /// every level starts from scratch once the last one
+    /// is finished
+    #[default]
+    Discrete,
+    /// The ball that fills a level's last hole keeps
+    /// flying: it's moved to the next level's tee with its
+    /// velocity intact, so the whole course is one long
+    /// shot. The cup is bypassed so the ball sinks as
+    /// soon as it's over the hole, at full speed.
+    Continuous,
+}
+
+/// How many strokes over par a level allows in
+/// `GameMode::Challenge`.
+pub const CHALLENGE_STROKES_OVER_PAR: u32 = 3;
+
+/// Shots taken on the current level.
+#[derive(Default)]
+pub struct Strokes(pub u32);
+
+/// Gravity assists pulled off on the current level. Each
+/// one takes a stroke off the score the level is finished
+/// with, see [`GravityAssist`].
+///
+/// [`GravityAssist`]: crate::assist::GravityAssist
+#[derive(Default)]
+pub struct Assists(pub u32);
+
+impl Assists {
+    /// The score for a level finished in `strokes`, never
+    /// less than one.
+    pub fn effective_strokes(&self, strokes: u32) -> u32 {
+        strokes.saturating_sub(self.0).max(1)
+    }
+}
+
+/// Sent the first time a ball rolls into a hole.
+pub struct BallSunk {
+    pub ball: Entity,
+    pub hole: Entity,
+    /// strokes taken when it went in
+    pub strokes: u32,
+}
+
+/// Sent when a shot's ball has been in play for
+/// `max_shot_time` without sinking, usually because it
+/// settled into an orbit, or has bounced more than
+/// `max_bounces` times in `GameMode::Challenge`. The ball
+/// is despawned so the next stroke can be taken.
+pub struct ShotTimedOut {
+    pub ball: Entity,
+}
+
+/// Sent when a ball leaves the world in
+/// `GameMode::Challenge`. The ball is despawned.
+pub struct BallLost {
+    pub ball: Entity,
+}
+
+/// Sent when a `GameMode::Challenge` level runs out of
+/// strokes with holes still open, just before it's
+/// restarted.
+pub struct LevelFailed {
+    pub level: usize,
+}
+
+/// Sent when the last open hole of a level is filled,
+/// just before the next level is loaded.
+pub struct LevelComplete {
+    pub level: usize,
+    pub strokes: u32,
+}
+
+/// Sent when the last level of the course is complete.
+pub struct CourseComplete;
+
+/// Marks a ball that has already been sunk so it isn't
+/// counted twice.
+#[derive(Component)]
+pub struct Sunk;
+
+/// How long a launched ball has left before its shot
+/// times out. Counts simulated time, so it stops while
+/// paused and follows the `TimeScale`.
+#[derive(Component)]
+pub struct ShotTimer(pub Timer);
+
+impl ShotTimer {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, false))
+    }
+}
+
+/// How many times a launched ball has hit something since
+/// it was launched. In `GameMode::Challenge` a ball that
+/// goes past `GolfBallSettings::max_bounces` times out, so
+/// one rattling around a gravity pocket can't hold up the
+/// level.
+#[derive(Component, Default)]
+pub struct Bounces(pub u32);
+
+/// Marks a hole that already has a ball in it. Filled
+/// holes don't take any more balls.
+#[derive(Component)]
+pub struct Filled;
+
+const FILLED_COLOR: Color = Color::GREEN;
+
+pub struct CoursePlugin;
+
+impl Plugin for CoursePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Course>()
+            .init_resource::<Strokes>()
+            .init_resource::<Assists>()
+            .init_resource::<GameMode>()
+            .init_resource::<CourseMode>()
+            .add_event::<BallSunk>()
+            .add_event::<LevelComplete>()
+            .add_event::<CourseComplete>()
+            .add_event::<ShotTimedOut>()
+            .add_event::<BallLost>()
+            .add_event::<LevelFailed>()
+            .add_system(
+                toggle_game_mode.with_run_criteria(playing),
+            )
+            .add_system(apply_level_gravity)
+            .add_system(draw_tee_box)
+            .add_system(detect_sunk_balls)
+            .add_system(
+                time_out_shots
+                    .with_run_criteria(sim_running)
+                    .after(detect_sunk_balls),
+            )
+            .add_system(
+                retire_bouncing_balls
+                    .with_run_criteria(sim_running)
+                    .after(detect_sunk_balls),
+            )
+            .add_system(
+                check_level_complete
+                    .after(detect_sunk_balls),
+            )
+            .add_system(
+                advance_level.after(check_level_complete),
+            )
+            .add_system(
+                restart_failed_level
+                    .after(time_out_shots)
+                    .after(retire_bouncing_balls)
+                    .after(check_level_complete),
+            );
+    }
+}
+
+/// Hands the current level's `Level::g` to
+/// `GravitySettings` as each level loads, or clears it so
+/// the global `g` is back in effect.
+fn apply_level_gravity(
+    course: Res<Course>,
+    mut gravity_settings: ResMut<GravitySettings>,
+) {
+    if !course.is_changed() {
+        return;
+    }
+    let level_g =
+        course.current_level().and_then(|level| level.g);
+    if gravity_settings.level_g != level_g {
+        gravity_settings.level_g = level_g;
+    }
+}
+
+fn toggle_game_mode(
+    keyboard: Res<Input<KeyCode>>,
+    mut mode: ResMut<GameMode>,
+    mut course_mode: ResMut<CourseMode>,
+) {
+    if !keyboard.just_pressed(KeyCode::M) {
+        return;
+    }
+    if keyboard
+        .any_pressed([KeyCode::LShift, KeyCode::RShift])
+    {
+        *course_mode = match *course_mode {
+            CourseMode::Discrete => CourseMode::Continuous,
+            CourseMode::Continuous => CourseMode::Discrete,
+        };
+    } else {
+        *mode = match *mode {
+            GameMode::Sandbox => GameMode::Challenge,
+            GameMode::Challenge => GameMode::Sandbox,
+        };
+    }
+}
+
+fn draw_tee_box(
+    mut lines: ResMut<DebugLines>,
+    course: Res<Course>,
+    mode: Res<GameMode>,
+) {
+    let tee_box = match course.tee_box(*mode) {
+        Some(tee_box) => tee_box,
+        None => return,
+    };
+    let corners = [
+        tee_box.min,
+        Vec2::new(tee_box.max.x, tee_box.min.y),
+        tee_box.max,
+        Vec2::new(tee_box.min.x, tee_box.max.y),
+    ];
+    for i in 0..corners.len() {
+        lines.line_colored(
+            corners[i].extend(0.0),
+            corners[(i + 1) % corners.len()].extend(0.0),
+            0.0,
+            Color::YELLOW_GREEN,
+        );
+    }
+}
+
+/// With the cup in play a ball is only sunk once it's
+/// settled into the hole, see [`CupSettings::active`];
+/// otherwise it sinks as soon as enough of it is over the
+/// hole, see [`Hole::captures`]. Practice balls never
+/// sink.
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn detect_sunk_balls(
+    mut commands: Commands,
+    cup: Res<CupSettings>,
+    course_mode: Res<CourseMode>,
+    mut captured: EventReader<CupCaptured>,
+    mut sunk_events: EventWriter<BallSunk>,
+    strokes: Res<Strokes>,
+    balls: Query<
+        (Entity, &GlobalTransform, &Collider),
+        (With<GolfBall>, Without<Sunk>, Without<Practice>),
+    >,
+    holes: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Hole,
+            &Handle<ColorMaterial>,
+        ),
+        Without<Filled>,
+    >,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // the markers aren't inserted until the end of the
+    // frame, so keep track of what was used up this frame
+    let mut used = Vec::new();
+    let mut candidates: Vec<(Entity, Entity)> = captured
+        .iter()
+        .map(|event| (event.ball, event.hole))
+        .collect();
+    if !cup.active(*course_mode) {
+        candidates.clear();
+        for (ball, transform, collider) in balls.iter() {
+            let position =
+                transform.translation().truncate();
+            let radius = collider
+                .as_ball()
+                .map_or(0.0, |ball| ball.radius());
+            for (hole, hole_transform, spec, _) in
+                holes.iter()
+            {
+                let distance = position.distance(
+                    hole_transform.translation().truncate(),
+                );
+                if spec.captures(
+                    distance,
+                    radius,
+                    cup.capture_fraction,
+                ) {
+                    candidates.push((ball, hole));
+                }
+            }
+        }
+    }
+    for (ball, hole) in candidates {
+        if !balls.contains(ball)
+            || used.contains(&ball)
+            || used.contains(&hole)
+        {
+            continue;
+        }
+        let material = match holes.get(hole) {
+            Ok((_, _, _, material)) => material,
+            Err(_) => continue,
+        };
+        if let Some(material) = materials.get_mut(material)
+        {
+            material.color = FILLED_COLOR;
+        }
+        commands.entity(ball).insert(Sunk);
+        commands.entity(hole).insert(Filled);
+        used.push(ball);
+        used.push(hole);
+        sunk_events.send(BallSunk {
+            ball,
+            hole,
+            strokes: strokes.0,
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn time_out_shots(
+    mut commands: Commands,
+    mut timed_out: EventWriter<ShotTimedOut>,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut balls: Query<
+        (Entity, &mut ShotTimer),
+        (With<GolfBall>, Without<Sunk>),
+    >,
+) {
+    let delta = time.delta().mul_f32(time_scale.0);
+    for (ball, mut timer) in balls.iter_mut() {
+        if timer.0.tick(delta).just_finished() {
+            commands.entity(ball).despawn_recursive();
+            timed_out.send(ShotTimedOut { ball });
+        }
+    }
+}
+
+/// Hole sensors don't count as bounces, only hitting
+/// something solid does.
+#[allow(clippy::type_complexity)]
+fn retire_bouncing_balls(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    mut timed_out: EventWriter<ShotTimedOut>,
+    mode: Res<GameMode>,
+    settings: Res<GolfBallSettings>,
+    mut balls: Query<
+        (Entity, &mut Bounces),
+        (With<GolfBall>, Without<Sunk>),
+    >,
+) {
+    for event in collisions.iter() {
+        let (a, b) = match event {
+            CollisionEvent::Started(a, b, flags)
+                if !flags.contains(
+                    CollisionEventFlags::SENSOR,
+                ) =>
+            {
+                (*a, *b)
+            }
+            _ => continue,
+        };
+        for ball in [a, b] {
+            if let Ok((_, mut bounces)) =
+                balls.get_mut(ball)
+            {
+                bounces.0 += 1;
+            }
+        }
+    }
+    let max_bounces = match settings.max_bounces {
+        Some(max_bounces)
+            if *mode == GameMode::Challenge =>
+        {
+            max_bounces
+        }
+        _ => return,
+    };
+    for (ball, bounces) in balls.iter() {
+        if bounces.0 > max_bounces {
+            commands.entity(ball).despawn_recursive();
+            timed_out.send(ShotTimedOut { ball });
+        }
+    }
+}
+
+fn check_level_complete(
+    mut sunk_events: EventReader<BallSunk>,
+    mut complete_events: EventWriter<LevelComplete>,
+    course: Res<Course>,
+    strokes: Res<Strokes>,
+    assists: Res<Assists>,
+    holes: Query<Entity, (With<Hole>, Without<Filled>)>,
+) {
+    let sunk: Vec<Entity> = sunk_events
+        .iter()
+        .map(|event| event.hole)
+        .collect();
+    if sunk.is_empty() {
+        return;
+    }
+    // holes filled this frame don't have `Filled` yet
+    if holes.iter().all(|hole| sunk.contains(&hole)) {
+        complete_events.send(LevelComplete {
+            level: course.current,
+            strokes: assists.effective_strokes(strokes.0),
+        });
+    }
+}
+
+/// In `CourseMode::Continuous` the ball that finished the
+/// level is kept and moved to the next level's tee, or
+/// left where it is if that level has no tee. It's still
+/// the same shot, so its `ShotTimer`, `Bounces` and place
+/// in the `ShotHistory` carry on with it.
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn advance_level(
+    mut commands: Commands,
+    course_mode: Res<CourseMode>,
+    mut sunk_events: EventReader<BallSunk>,
+    mut complete_events: EventReader<LevelComplete>,
+    mut course_complete: EventWriter<CourseComplete>,
+    mut course: ResMut<Course>,
+    mut strokes: ResMut<Strokes>,
+    mut assists: ResMut<Assists>,
+    old: Query<
+        Entity,
+        Or<(With<Planet>, With<Hole>, With<GolfBall>)>,
+    >,
+    mut balls: Query<&mut Transform, With<GolfBall>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // the last ball sunk is the one that filled the last
+    // hole
+    let last_sunk =
+        sunk_events.iter().last().map(|event| event.ball);
+    if complete_events.iter().last().is_none() {
+        return;
+    }
+    if course.current + 1 >= course.levels.len() {
+        course_complete.send(CourseComplete);
+        return;
+    }
+    let carried = match *course_mode {
+        CourseMode::Discrete => None,
+        CourseMode::Continuous => last_sunk,
+    };
+    for entity in old.iter() {
+        if Some(entity) != carried {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    course.current += 1;
+    if let Some(level) = course.current_level() {
+        level.spawn(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+        );
+        if let Some(ball) = carried {
+            // `Velocity` is left alone so the ball flies on
+            if let (Some(tee), Ok(mut transform)) =
+                (level.tee, balls.get_mut(ball))
+            {
+                transform.translation.x = tee.x;
+                transform.translation.y = tee.y;
+            }
+            commands.entity(ball).remove::<Sunk>();
+        }
+    }
+    strokes.0 = 0;
+    assists.0 = 0;
+}
+
+/// Only checked when a shot ends, so a ball that's still
+/// in play can always finish the level.
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn restart_failed_level(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    mut sunk_events: EventReader<BallSunk>,
+    mut timed_out: EventReader<ShotTimedOut>,
+    mut lost: EventReader<BallLost>,
+    mut failed: EventWriter<LevelFailed>,
+    course: Res<Course>,
+    mut strokes: ResMut<Strokes>,
+    mut assists: ResMut<Assists>,
+    balls: Query<
+        Entity,
+        (With<GolfBall>, Without<Sunk>, Without<Practice>),
+    >,
+    holes: Query<Entity, (With<Hole>, Without<Filled>)>,
+    old: Query<
+        Entity,
+        Or<(With<Planet>, With<Hole>, With<GolfBall>)>,
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // despawns and markers from this frame haven't been
+    // applied yet, so leave out what the events mention
+    let mut ended = Vec::new();
+    let mut filled = Vec::new();
+    for event in sunk_events.iter() {
+        ended.push(event.ball);
+        filled.push(event.hole);
+    }
+    ended.extend(timed_out.iter().map(|event| event.ball));
+    ended.extend(lost.iter().map(|event| event.ball));
+    if *mode != GameMode::Challenge
+        || ended.is_empty()
+        || strokes.0 < course.stroke_limit()
+        || balls.iter().any(|ball| !ended.contains(&ball))
+        || holes.iter().all(|hole| filled.contains(&hole))
+    {
+        return;
+    }
+    for entity in old.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if let Some(level) = course.current_level() {
+        level.spawn(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+        );
+    }
+    strokes.0 = 0;
+    assists.0 = 0;
+    failed.send(LevelFailed {
+        level: course.current,
+    });
+}
@@ -0,0 +1,200 @@
+use bevy::{
+    prelude::*,
+    render::mesh::{Indices, VertexAttributeValues},
+};
+use bevy_rapier3d::prelude::*;
+use std::collections::HashSet;
+
+use crate::{golf_ball::GolfBall, planet_spec::Planet};
+
+/// Dents the 3D planets where balls hit them hard.
+pub struct CraterSettings {
+    /// slowest impact that still leaves a crater
+    pub impact_speed: f32,
+    /// crater size on the unit planet mesh
+    pub radius: f32,
+    /// how far one impact pushes the surface in, as a
+    /// fraction of the planet radius
+    pub depth: f32,
+    /// the surface never gets pushed deeper than this
+    pub max_depth: f32,
+    /// impacts past this many on one planet are ignored
+    pub max_craters: u32,
+}
+
+impl Default for CraterSettings {
+    fn default() -> Self {
+        Self {
+            impact_speed: 200.0,
+            radius: 0.25,
+            depth: 0.05,
+            max_depth: 0.2,
+            max_craters: 20,
+        }
+    }
+}
+
+/// How many craters a planet has taken.
+#[derive(Component, Default)]
+pub struct Craters(pub u32);
+
+/// A ball's velocity from the previous frame, since by the
+/// time the collision event shows up rapier has already
+/// bounced it.
+#[derive(Component, Default)]
+struct LastVelocity(Vec3);
+
+pub struct CraterPlugin;
+
+impl Plugin for CraterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CraterSettings>()
+            .add_system(dent_planets)
+            .add_system(track_velocity.after(dent_planets));
+    }
+}
+
+fn track_velocity(
+    mut commands: Commands,
+    balls: Query<(Entity, &Velocity), With<GolfBall>>,
+) {
+    for (entity, velocity) in balls.iter() {
+        commands
+            .entity(entity)
+            .insert(LastVelocity(velocity.linvel));
+    }
+}
+
+fn dent_planets(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    settings: Res<CraterSettings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    balls: Query<
+        (&GlobalTransform, &LastVelocity),
+        With<GolfBall>,
+    >,
+    planets: Query<
+        (&GlobalTransform, &Handle<Mesh>, Option<&Craters>),
+        With<Planet>,
+    >,
+) {
+    for event in collision_events.iter() {
+        let (a, b) = match event {
+            CollisionEvent::Started(a, b, _) => (*a, *b),
+            CollisionEvent::Stopped(..) => continue,
+        };
+        let (ball, planet) =
+            if balls.contains(a) { (a, b) } else { (b, a) };
+        let (
+            (ball_transform, velocity),
+            (planet_transform, mesh_handle, craters),
+        ) = match (balls.get(ball), planets.get(planet)) {
+            (Ok(ball), Ok(planet)) => (ball, planet),
+            _ => continue,
+        };
+        let craters =
+            craters.map_or(0, |craters| craters.0);
+        if velocity.0.length() < settings.impact_speed
+            || craters >= settings.max_craters
+        {
+            continue;
+        }
+
+        // where the ball hit, on the unit sphere the mesh
+        // was built on
+        let (_, rotation, center) = planet_transform
+            .to_scale_rotation_translation();
+        let direction = rotation.inverse()
+            * (ball_transform.translation() - center);
+        let mesh = match meshes.get_mut(mesh_handle) {
+            Some(mesh) => mesh,
+            None => continue,
+        };
+        dent(mesh, direction.normalize(), &settings);
+        commands
+            .entity(planet)
+            .insert(Craters(craters + 1));
+    }
+}
+
+/// Pushes the vertices around `impact` in along their
+/// normals, deepest in the middle, then recomputes the
+/// normals of the triangles that moved.
+fn dent(
+    mesh: &mut Mesh,
+    impact: Vec3,
+    settings: &CraterSettings,
+) {
+    let mut moved = HashSet::new();
+    let positions: Vec<Vec3> = match mesh
+        .attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        Some(VertexAttributeValues::Float32x3(
+            positions,
+        )) => {
+            for (i, position) in
+                positions.iter_mut().enumerate()
+            {
+                let vertex = Vec3::from(*position);
+                let distance =
+                    vertex.normalize().distance(impact);
+                if distance >= settings.radius {
+                    continue;
+                }
+                let falloff =
+                    1.0 - distance / settings.radius;
+                let length = (vertex.length()
+                    - settings.depth * falloff)
+                    .max(1.0 - settings.max_depth);
+                *position =
+                    (vertex.normalize() * length).into();
+                moved.insert(i as u32);
+            }
+            positions
+                .iter()
+                .map(|p| Vec3::from(*p))
+                .collect()
+        }
+        _ => return,
+    };
+    if moved.is_empty() {
+        return;
+    }
+
+    let triangles: Vec<u32> = match mesh.indices() {
+        Some(Indices::U32(indices)) => indices.clone(),
+        _ => return,
+    };
+    let mut sums = vec![Vec3::ZERO; positions.len()];
+    for triangle in triangles.chunks_exact(3) {
+        if !triangle.iter().any(|i| moved.contains(i)) {
+            continue;
+        }
+        let [a, b, c] = [
+            positions[triangle[0] as usize],
+            positions[triangle[1] as usize],
+            positions[triangle[2] as usize],
+        ];
+        let mut normal = (b - a).cross(c - a);
+        // keep every normal pointing out of the planet
+        // whatever the winding
+        if normal.dot(a) < 0.0 {
+            normal = -normal;
+        }
+        for i in triangle {
+            sums[*i as usize] += normal;
+        }
+    }
+    if let Some(VertexAttributeValues::Float32x3(normals)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+    {
+        for i in moved {
+            let sum = sums[i as usize];
+            if sum != Vec3::ZERO {
+                normals[i as usize] =
+                    sum.normalize().into();
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    course::{CourseMode, Filled, Sunk},
+    golf_ball::GolfBall,
+    hole::Hole,
+    practice::Practice,
+    sim_state::sim_running,
+    GravitySystem,
+};
+
+/// Makes sinking feel like dropping into a cup. A ball
+/// that rolls into a hole slowly enough is caught and
+/// pulled to the middle, and only counts as sunk once it's
+/// settled there. One that comes in too fast lips out and
+/// carries on over the hole. With `enabled` off, a ball
+/// sinks as soon as enough of it is over a hole.
+///
+/// The cup is bypassed in `CourseMode::Continuous`, where
+/// a ball has to sink at full speed to carry anything on
+/// to the next level.
+pub struct CupSettings {
+    pub enabled: bool,
+    /// balls entering a hole faster than this lip out
+    pub max_entry_speed: f32,
+    /// how hard a caught ball is pulled to the middle, as
+    /// acceleration per unit of distance from it
+    pub pull: f32,
+    /// slows a caught ball so it settles instead of
+    /// swinging through the middle
+    pub damping: f32,
+    /// how close to the middle, as a fraction of the
+    /// hole's radius, a ball has to settle to be sunk.
+    /// Holes float just above the ground, so a ball
+    /// resting under one can't reach the middle; at 1.0 it
+    /// only has to settle somewhere over the hole.
+    pub sink_radius: f32,
+    /// and how slowly it has to be moving there
+    pub sink_speed: f32,
+    /// How much of a ball has to be over a hole for it to
+    /// sink, see [`Hole::captures`]. Applies with the cup
+    /// turned off too. A ball resting on the ground under
+    /// a hole never has all of itself over it, so anything
+    /// above 0.5 can leave it stuck at the bottom.
+    pub capture_fraction: f32,
+}
+
+impl CupSettings {
+    /// Whether balls are caught by the cup in
+    /// `course_mode`, rather than sinking straight away.
+    pub fn active(&self, course_mode: CourseMode) -> bool {
+        self.enabled && course_mode == CourseMode::Discrete
+    }
+}
+
+impl Default for CupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entry_speed: 150.0,
+            pull: 300.0,
+            damping: 35.0,
+            sink_radius: 1.0,
+            sink_speed: 20.0,
+            capture_fraction: 0.5,
+        }
+    }
+}
+
+/// A ball that's been caught by a hole and is being pulled
+/// in.
+#[derive(Component)]
+pub struct InCup {
+    pub hole: Entity,
+}
+
+/// Sent when a caught ball settles in the middle of its
+/// hole, which is what sinks it.
+pub struct CupCaptured {
+    pub ball: Entity,
+    pub hole: Entity,
+}
+
+pub struct CupPlugin;
+
+impl Plugin for CupPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CupSettings>()
+            .add_event::<CupCaptured>()
+            .add_system(catch_balls)
+            .add_system(
+                pull_into_cups
+                    .with_run_criteria(sim_running)
+                    .after(GravitySystem::Accelerate),
+            );
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn catch_balls(
+    mut commands: Commands,
+    settings: Res<CupSettings>,
+    course_mode: Res<CourseMode>,
+    mut collision_events: EventReader<CollisionEvent>,
+    balls: Query<
+        (&Velocity, Option<&InCup>),
+        (With<GolfBall>, Without<Sunk>, Without<Practice>),
+    >,
+    holes: Query<(), (With<Hole>, Without<Filled>)>,
+) {
+    if !settings.active(*course_mode) {
+        return;
+    }
+    for event in collision_events.iter() {
+        let (a, b, started) = match event {
+            CollisionEvent::Started(a, b, _) => {
+                (*a, *b, true)
+            }
+            CollisionEvent::Stopped(a, b, _) => {
+                (*a, *b, false)
+            }
+        };
+        let (ball, hole) =
+            if balls.contains(a) { (a, b) } else { (b, a) };
+        let (velocity, in_cup) = match balls.get(ball) {
+            Ok(ball) => ball,
+            Err(_) => continue,
+        };
+        if !holes.contains(hole) {
+            continue;
+        }
+        if started {
+            if in_cup.is_none()
+                && velocity.linvel.length()
+                    <= settings.max_entry_speed
+            {
+                commands
+                    .entity(ball)
+                    .insert(InCup { hole });
+            }
+        } else if in_cup
+            .is_some_and(|in_cup| in_cup.hole == hole)
+        {
+            // knocked back out before it settled
+            commands.entity(ball).remove::<InCup>();
+        }
+    }
+}
+
+// accelerate_particles overwrites the force every frame,
+// so the pull has to be added on top of it afterwards.
+#[allow(clippy::type_complexity)]
+fn pull_into_cups(
+    mut commands: Commands,
+    settings: Res<CupSettings>,
+    mut captured: EventWriter<CupCaptured>,
+    mut balls: Query<
+        (
+            Entity,
+            &InCup,
+            &GlobalTransform,
+            &Velocity,
+            &ColliderMassProperties,
+            &Collider,
+            &mut ExternalForce,
+        ),
+        Without<Sunk>,
+    >,
+    holes: Query<
+        (&GlobalTransform, &Hole),
+        Without<Filled>,
+    >,
+) {
+    for (
+        ball,
+        in_cup,
+        transform,
+        velocity,
+        mass_props,
+        collider,
+        mut force,
+    ) in balls.iter_mut()
+    {
+        let (hole_transform, hole) =
+            match holes.get(in_cup.hole) {
+                Ok(hole) => hole,
+                // someone else got there first
+                Err(_) => {
+                    commands.entity(ball).remove::<InCup>();
+                    continue;
+                }
+            };
+        let offset =
+            hole_transform.translation().truncate()
+                - transform.translation().truncate();
+        let ball_radius = collider
+            .as_ball()
+            .map_or(0.0, |ball| ball.radius());
+        if offset.length()
+            <= hole.radius * settings.sink_radius
+            && velocity.linvel.length()
+                <= settings.sink_speed
+            && hole.captures(
+                offset.length(),
+                ball_radius,
+                settings.capture_fraction,
+            )
+        {
+            commands.entity(ball).remove::<InCup>();
+            captured.send(CupCaptured {
+                ball,
+                hole: in_cup.hole,
+            });
+            continue;
+        }
+        let mass = match mass_props {
+            ColliderMassProperties::Mass(mass) => *mass,
+            _ => 1.0,
+        };
+        force.force += (offset * settings.pull
+            - velocity.linvel * settings.damping)
+            * mass;
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::{
+    prelude::*, render::mesh::VertexAttributeValues,
+    sprite::Mesh2dHandle,
+};
+use bevy_rapier2d::prelude::*;
+use std::f32::consts::PI;
+
+/// How much mass a body packs into each unit of area,
+/// which is what decides its size. Two planets of the same
+/// mass can be very different sizes because of it.
+///
+/// Changing it, or the body's `ColliderMassProperties`,
+/// resizes a round body to match.
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component)]
+pub struct Density(pub f32);
+
+impl Default for Density {
+    fn default() -> Self {
+        Density(1.0)
+    }
+}
+
+impl Density {
+    /// The radius of a disc of `mass` at this density.
+    pub fn radius(&self, mass: f32) -> f32 {
+        (mass / (self.0 * PI)).sqrt()
+    }
+}
+
+/// How much bigger a body's mesh is drawn than the body
+/// really is, from `PlanetSpec::visual_scale`. Bodies
+/// without one are drawn at their true size.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct VisualScale(pub f32);
+
+/// How far the furthest vertex of a flat mesh is from its
+/// center, which for a round body is the radius it's drawn
+/// at.
+pub fn mesh_radius(mesh: &Mesh) -> Option<f32> {
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION)? {
+        VertexAttributeValues::Float32x3(positions) => {
+            Some(
+                positions
+                    .iter()
+                    .map(|position| {
+                        Vec2::new(position[0], position[1])
+                            .length()
+                    })
+                    .fold(0.0, f32::max),
+            )
+        }
+        _ => None,
+    }
+}
+
+pub struct DensityPlugin;
+
+impl Plugin for DensityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Density>()
+            .add_system(resize_bodies);
+    }
+}
+
+/// Only circles are resized. Other shapes keep the size
+/// they were spawned with. The mesh, less any
+/// [`VisualScale`], tells how big the body currently is,
+/// and the collider is scaled along with it, so a planet
+/// with a smaller solid core keeps it.
+#[allow(clippy::type_complexity)]
+fn resize_bodies(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut bodies: Query<
+        (
+            &Density,
+            &ColliderMassProperties,
+            &mut Collider,
+            &mut Mesh2dHandle,
+            Option<&VisualScale>,
+        ),
+        Or<(
+            Changed<Density>,
+            Changed<ColliderMassProperties>,
+        )>,
+    >,
+) {
+    for (
+        density,
+        mass_props,
+        mut collider,
+        mut mesh,
+        visual_scale,
+    ) in bodies.iter_mut()
+    {
+        let mass = match mass_props {
+            ColliderMassProperties::Mass(mass) => *mass,
+            _ => continue,
+        };
+        let collider_radius = match collider.as_ball() {
+            Some(ball) => ball.radius(),
+            None => continue,
+        };
+        let visual_scale =
+            visual_scale.map_or(1.0, |scale| scale.0);
+        let old_radius =
+            match meshes.get(&mesh.0).and_then(mesh_radius)
+            {
+                Some(drawn) if visual_scale > 0.0 => {
+                    drawn / visual_scale
+                }
+                _ => continue,
+            };
+        let radius = density.radius(mass);
+        if radius <= 0.0
+            || old_radius <= 0.0
+            || (radius - old_radius).abs() < 1e-3
+        {
+            continue;
+        }
+        // balls of the same size share a mesh, so this
+        // body gets a copy of its own. Scaling the
+        // vertices keeps anything else on the mesh, like a
+        // planet's vertex colors.
+        let mut resized = match meshes.get(&mesh.0) {
+            Some(old) => old.clone(),
+            None => continue,
+        };
+        let scale = radius / old_radius;
+        if let Some(VertexAttributeValues::Float32x3(
+            positions,
+        )) =
+            resized.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            for position in positions.iter_mut() {
+                position[0] *= scale;
+                position[1] *= scale;
+            }
+        }
+        mesh.0 = meshes.add(resized);
+        *collider = Collider::ball(collider_radius * scale);
+    }
+}
@@ -0,0 +1,245 @@
+use bevy::{
+    input::{mouse::MouseButtonInput, ButtonState},
+    prelude::*,
+};
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::{
+    menu::playing,
+    planet_spec::{spawn_planet, Planet, PlanetSpec},
+};
+
+/// Lets courses be built while the game is running,
+/// toggled with `E`. Right-drag spawns a planet at the
+/// press position whose mass grows with the length of the
+/// drag, the same way left-drag sets a ball's velocity.
+///
+/// `1` to `9` pick a preset mass instead, which every
+/// planet placed after it uses whatever the drag length,
+/// until `0` goes back to sizing by drag.
+pub struct EditorMode {
+    pub enabled: bool,
+    /// planet mass added per world unit of drag
+    pub mass_per_unit: f32,
+    /// the mass of preset `1`, with preset `n` being `n`
+    /// times as heavy
+    pub preset_step: f32,
+    /// the preset placed planets use, if any
+    pub preset: Option<u32>,
+    drag_start: Option<Vec2>,
+}
+
+impl Default for EditorMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mass_per_unit: 10_000.0,
+            preset_step: 100_000.0,
+            preset: None,
+            drag_start: None,
+        }
+    }
+}
+
+impl EditorMode {
+    /// The mass of the selected preset, if there is one.
+    pub fn preset_mass(&self) -> Option<f32> {
+        self.preset
+            .map(|preset| preset as f32 * self.preset_step)
+    }
+
+    fn spec(&self, start: Vec2, end: Vec2) -> PlanetSpec {
+        let mass =
+            self.preset_mass().unwrap_or_else(|| {
+                start.distance(end) * self.mass_per_unit
+            });
+        PlanetSpec {
+            position: start.extend(1.0),
+            mass: mass.max(1.0),
+            ..default()
+        }
+    }
+}
+
+const PRESET_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorMode>()
+            .add_system(
+                toggle_editor.with_run_criteria(playing),
+            )
+            .add_system(
+                select_preset
+                    .with_run_criteria(playing)
+                    .after(toggle_editor),
+            )
+            .add_system(
+                place_planet
+                    .with_run_criteria(playing)
+                    .after(select_preset),
+            )
+            .add_system(highlight_overlaps);
+    }
+}
+
+fn toggle_editor(
+    keyboard: Res<Input<KeyCode>>,
+    mut editor: ResMut<EditorMode>,
+) {
+    if keyboard.just_pressed(KeyCode::E) {
+        editor.enabled = !editor.enabled;
+        editor.drag_start = None;
+    }
+}
+
+fn select_preset(
+    keyboard: Res<Input<KeyCode>>,
+    mut editor: ResMut<EditorMode>,
+) {
+    if !editor.enabled {
+        return;
+    }
+    if keyboard.just_pressed(KeyCode::Key0) {
+        editor.preset = None;
+    }
+    for (i, key) in PRESET_KEYS.iter().enumerate() {
+        if keyboard.just_pressed(*key) {
+            editor.preset = Some(i as u32 + 1);
+        }
+    }
+}
+
+fn place_planet(
+    mut commands: Commands,
+    mut click_event: EventReader<MouseButtonInput>,
+    mut editor: ResMut<EditorMode>,
+    mut lines: ResMut<DebugLines>,
+    mouse_pos: Res<MousePosWorld>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if !editor.enabled {
+        return;
+    }
+    let mouse_pos = mouse_pos.truncate();
+
+    for event in click_event.iter() {
+        if event.button != MouseButton::Right {
+            continue;
+        }
+        match event.state {
+            ButtonState::Pressed => {
+                editor.drag_start = Some(mouse_pos)
+            }
+            ButtonState::Released => {
+                if let Some(start) =
+                    editor.drag_start.take()
+                {
+                    spawn_planet(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        &editor.spec(start, mouse_pos),
+                    );
+                }
+            }
+        }
+    }
+
+    // outline the planet that releasing would spawn
+    if let Some(start) = editor.drag_start {
+        let radius = editor.spec(start, mouse_pos).radius();
+        let segments = 32;
+        for i in 0..segments {
+            let point = |i: usize| {
+                let angle =
+                    i as f32 / segments as f32 * TAU;
+                (start
+                    + Vec2::new(angle.cos(), angle.sin())
+                        * radius)
+                    .extend(0.0)
+            };
+            lines.line_colored(
+                point(i),
+                point(i + 1),
+                0.0,
+                Color::BLUE,
+            );
+        }
+        lines.line_colored(
+            start.extend(0.0),
+            mouse_pos.extend(0.0),
+            0.0,
+            Color::GRAY,
+        );
+    }
+}
+
+/// Rings every round planet that overlaps another one in
+/// red, wherever it came from, since overlapping planets
+/// break both the gravity and the collisions.
+fn highlight_overlaps(
+    mut lines: ResMut<DebugLines>,
+    planets: Query<
+        (&GlobalTransform, &Collider),
+        With<Planet>,
+    >,
+) {
+    let circles: Vec<(Vec2, f32)> = planets
+        .iter()
+        .filter_map(|(transform, collider)| {
+            collider.as_ball().map(|ball| {
+                (
+                    transform.translation().truncate(),
+                    ball.radius(),
+                )
+            })
+        })
+        .collect();
+    for (index, (center, radius)) in
+        circles.iter().enumerate()
+    {
+        let overlaps = circles.iter().enumerate().any(
+            |(j, (other, other_radius))| {
+                index != j
+                    && center.distance(*other)
+                        < radius + other_radius
+            },
+        );
+        if !overlaps {
+            continue;
+        }
+        let segments = 32;
+        let point = |i: usize| {
+            let angle = i as f32 / segments as f32 * TAU;
+            (*center
+                + Vec2::new(angle.cos(), angle.sin())
+                    * (*radius + 2.0))
+                .extend(5.0)
+        };
+        for i in 0..segments {
+            lines.line_colored(
+                point(i),
+                point(i + 1),
+                0.0,
+                Color::RED,
+            );
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::{
+    input::{mouse::MouseButtonInput, ButtonState},
+    prelude::*,
+};
+use bevy_inspector_egui::bevy_egui::{egui, EguiContext};
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+
+use crate::{
+    golf_ball::GolfBall,
+    gravity::{
+        dominant_body, GravityBodies, GravitySettings,
+    },
+    menu::playing,
+    Body,
+};
+
+/// The shape of a two-body orbit at one instant, as if the
+/// dominant planet were the only thing pulling.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitalElements {
+    /// negative for a hyperbolic orbit, infinite for an
+    /// exactly parabolic one
+    pub semi_major_axis: f32,
+    /// 0 is circular, under 1 elliptical, 1 parabolic or
+    /// falling straight in, over 1 hyperbolic
+    pub eccentricity: f32,
+    /// kinetic plus potential energy per unit of mass
+    pub specific_energy: f32,
+}
+
+impl OrbitalElements {
+    /// Whether the body will keep coming back rather than
+    /// escape.
+    pub fn is_bound(&self) -> bool {
+        self.specific_energy < 0.0
+    }
+}
+
+/// The elements of a body at `position` moving at
+/// `velocity`, both relative to a body with gravitational
+/// parameter `mu`. `None` when there's no orbit to speak
+/// of: no gravity, or sitting right on the center.
+///
+/// A radial orbit, with no sideways velocity at all, comes
+/// out with an eccentricity of one whatever its energy.
+pub fn orbital_elements(
+    position: Vec2,
+    velocity: Vec2,
+    mu: f32,
+) -> Option<OrbitalElements> {
+    let radius = position.length();
+    if mu <= 0.0 || radius <= f32::EPSILON {
+        return None;
+    }
+    let speed_squared = velocity.length_squared();
+    let specific_energy = speed_squared / 2.0 - mu / radius;
+    let eccentricity = (((speed_squared - mu / radius)
+        * position
+        - position.dot(velocity) * velocity)
+        / mu)
+        .length();
+    let semi_major_axis = if specific_energy.abs()
+        <= f32::EPSILON * speed_squared.max(1.0)
+    {
+        f32::INFINITY
+    } else {
+        -mu / (2.0 * specific_energy)
+    };
+    Some(OrbitalElements {
+        semi_major_axis,
+        eccentricity,
+        specific_energy,
+    })
+}
+
+/// The ball whose orbit is shown, picked with a
+/// shift-click. Shift-clicking anything else clears it.
+#[derive(Default)]
+pub struct SelectedBall(pub Option<Entity>);
+
+pub struct OrbitalElementsPlugin;
+
+impl Plugin for OrbitalElementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedBall>()
+            .add_system(
+                select_ball.with_run_criteria(playing),
+            )
+            .add_system(
+                show_orbital_elements.after(select_ball),
+            );
+    }
+}
+
+fn select_ball(
+    mut click_event: EventReader<MouseButtonInput>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse_pos: Res<MousePosWorld>,
+    mut selected: ResMut<SelectedBall>,
+    balls: Query<
+        (Entity, &GlobalTransform, &Collider),
+        With<GolfBall>,
+    >,
+) {
+    let clicked = click_event.iter().any(|event| {
+        event.button == MouseButton::Left
+            && event.state == ButtonState::Pressed
+    });
+    if !clicked
+        || !keyboard
+            .any_pressed([KeyCode::LShift, KeyCode::RShift])
+    {
+        return;
+    }
+    let mouse_pos = mouse_pos.truncate();
+    selected.0 = balls
+        .iter()
+        .find(|(_, transform, collider)| {
+            collider.as_ball().is_some_and(|ball| {
+                transform
+                    .translation()
+                    .truncate()
+                    .distance(mouse_pos)
+                    <= ball.radius()
+            })
+        })
+        .map(|(entity, ..)| entity);
+}
+
+fn show_orbital_elements(
+    mut egui_context: ResMut<EguiContext>,
+    mut selected: ResMut<SelectedBall>,
+    particle_set: Res<ParticleSet<Body>>,
+    gravity_settings: Res<GravitySettings>,
+    balls: Query<
+        (
+            &GlobalTransform,
+            &Velocity,
+            &ColliderMassProperties,
+        ),
+        With<GolfBall>,
+    >,
+    bodies: GravityBodies,
+    velocities: Query<&Velocity>,
+) {
+    let ball = match selected.0 {
+        Some(ball) => ball,
+        None => return,
+    };
+    let (transform, velocity, mass_props) =
+        match balls.get(ball) {
+            Ok(ball) => ball,
+            // sunk, lost or timed out
+            Err(_) => {
+                selected.0 = None;
+                return;
+            }
+        };
+    let position = transform.translation();
+    let elements = dominant_body(&particle_set, position)
+        .filter(|entity| *entity != ball)
+        .and_then(|entity| {
+            let (planet, mu) = bodies.get(entity)?;
+            Some((planet, mu, velocities.get(entity).ok()))
+        })
+        .and_then(|(planet, mu, planet_velocity)| {
+            // gravity reaches balls as a force, so the
+            // ball's own mass divides the planet's pull
+            let mass = match mass_props {
+                ColliderMassProperties::Mass(mass) => *mass,
+                _ => 1.0,
+            };
+            orbital_elements(
+                (position - planet).truncate(),
+                velocity.linvel
+                    - planet_velocity
+                        .map_or(Vec2::ZERO, |velocity| {
+                            velocity.linvel
+                        }),
+                mu * gravity_settings.g_scale() / mass,
+            )
+        });
+
+    egui::Window::new("Orbit").show(
+        egui_context.ctx_mut(),
+        |ui| match elements {
+            Some(elements) => {
+                ui.label(if elements.is_bound() {
+                    "bound"
+                } else {
+                    "hyperbolic, escaping"
+                });
+                if elements.semi_major_axis.is_finite() {
+                    ui.label(format!(
+                        "semi-major axis: {:.0}",
+                        elements.semi_major_axis
+                    ));
+                } else {
+                    ui.label("semi-major axis: infinite");
+                }
+                ui.label(format!(
+                    "eccentricity: {:.3}",
+                    elements.eccentricity
+                ));
+                ui.label(format!(
+                    "specific energy: {:.0}",
+                    elements.specific_energy
+                ));
+            }
+            None => {
+                ui.label("not orbiting anything");
+            }
+        },
+    );
+}
@@ -0,0 +1,177 @@
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+};
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+
+use crate::{
+    gravity::{
+        softened_potential_at, GravitySettings,
+        GravitySource,
+    },
+    sim_state::sim_running,
+    Body, GravitySystem,
+};
+
+/// Tracks the total mechanical energy of everything in the
+/// `ParticleSet`. For an isolated system it should stay
+/// roughly constant; a steady drift means the integration
+/// or the units are off.
+///
+/// The measurements go into Bevy's `Diagnostics`. Every
+/// pair of bodies adds to the potential, so they start
+/// disabled and are only measured while one of them is
+/// enabled: the `F3` stats overlay turns them on while
+/// it's up, see [`EnergyDiagnosticPlugin::enable`], and
+/// `LogDiagnosticsPlugin` prints them once they are.
+pub struct EnergyDiagnosticPlugin;
+
+impl EnergyDiagnosticPlugin {
+    pub const KINETIC: DiagnosticId =
+        DiagnosticId::from_u128(
+            0x5c1f5e07_7a1e_4f3b_9a0e_1c0de0000001,
+        );
+    pub const POTENTIAL: DiagnosticId =
+        DiagnosticId::from_u128(
+            0x5c1f5e07_7a1e_4f3b_9a0e_1c0de0000002,
+        );
+    pub const TOTAL: DiagnosticId = DiagnosticId::from_u128(
+        0x5c1f5e07_7a1e_4f3b_9a0e_1c0de0000003,
+    );
+
+    const ALL: [DiagnosticId; 3] =
+        [Self::KINETIC, Self::POTENTIAL, Self::TOTAL];
+
+    /// Turns the energy measurements on or off.
+    pub fn enable(
+        diagnostics: &mut Diagnostics,
+        enabled: bool,
+    ) {
+        for id in Self::ALL {
+            if let Some(diagnostic) =
+                diagnostics.get_mut(id)
+            {
+                diagnostic.is_enabled = enabled;
+            }
+        }
+    }
+}
+
+impl Plugin for EnergyDiagnosticPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_diagnostics)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                measure_energy
+                    .with_run_criteria(sim_running)
+                    .after(GravitySystem::Sync),
+            );
+    }
+}
+
+fn setup_diagnostics(mut diagnostics: ResMut<Diagnostics>) {
+    for (id, name) in [
+        (EnergyDiagnosticPlugin::KINETIC, "kinetic_energy"),
+        (
+            EnergyDiagnosticPlugin::POTENTIAL,
+            "potential_energy",
+        ),
+        (EnergyDiagnosticPlugin::TOTAL, "total_energy"),
+    ] {
+        let mut diagnostic = Diagnostic::new(id, name, 20);
+        diagnostic.is_enabled = false;
+        diagnostics.add(diagnostic);
+    }
+}
+
+fn measure_energy(
+    mut diagnostics: ResMut<Diagnostics>,
+    particle_set: Res<ParticleSet<Body>>,
+    gravity_settings: Res<GravitySettings>,
+    bodies: Query<(&Velocity, &ColliderMassProperties)>,
+) {
+    let enabled =
+        EnergyDiagnosticPlugin::ALL.iter().any(|id| {
+            diagnostics.get(*id).is_some_and(|diagnostic| {
+                diagnostic.is_enabled
+            })
+        });
+    if !enabled {
+        return;
+    }
+    let g = gravity_settings.g();
+    // (position, mu, mass, velocity) for every body
+    let state: Vec<(Vec3, f32, f32, Vec2)> = particle_set
+        .iter()
+        .map(|body| {
+            let (mass, velocity) =
+                match bodies.get(body.entity) {
+                    Ok((
+                        velocity,
+                        ColliderMassProperties::Mass(mass),
+                    )) => (*mass, velocity.linvel),
+                    Ok((velocity, _)) => {
+                        (body.mu / g, velocity.linvel)
+                    }
+                    Err(_) => (body.mu / g, Vec2::ZERO),
+                };
+            (body.position, body.mu, mass, velocity)
+        })
+        .collect();
+
+    let kinetic: f32 = state
+        .iter()
+        .map(|(_, _, mass, velocity)| {
+            0.5 * mass * velocity.length_squared()
+        })
+        .sum();
+
+    // a pair only counts the pull of the side that
+    // actually attracts, so massless balls still have
+    // potential energy relative to the planets. Softened
+    // the same way as the pull itself.
+    let softening = gravity_settings.softening;
+    let well = |position: Vec3, mu, point: Vec3| {
+        softened_potential_at(
+            &[GravitySource {
+                position: position.truncate(),
+                mu,
+            }],
+            point.truncate(),
+            softening,
+        )
+    };
+    let mut potential = 0.0;
+    for (i, (a_position, a_mu, a_mass, _)) in
+        state.iter().enumerate()
+    {
+        for (b_position, b_mu, b_mass, _) in
+            state.iter().skip(i + 1)
+        {
+            potential +=
+                (well(*a_position, *a_mu, *b_position)
+                    * b_mass)
+                    .min(
+                        well(
+                            *b_position,
+                            *b_mu,
+                            *a_position,
+                        ) * a_mass,
+                    );
+        }
+    }
+
+    diagnostics.add_measurement(
+        EnergyDiagnosticPlugin::KINETIC,
+        || kinetic as f64,
+    );
+    diagnostics.add_measurement(
+        EnergyDiagnosticPlugin::POTENTIAL,
+        || potential as f64,
+    );
+    diagnostics.add_measurement(
+        EnergyDiagnosticPlugin::TOTAL,
+        || (kinetic + potential) as f64,
+    );
+}
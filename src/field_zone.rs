@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::forces::{
+    AddForceModifier, BallState, ForceModifier,
+};
+
+/// An axis-aligned box that pushes every ball inside of
+/// it with a constant acceleration, like a solar wind or a
+/// conveyor. Overlapping zones add up.
+#[derive(
+    Component, Reflect, Clone, Copy, Debug, Default,
+)]
+#[reflect(Component)]
+pub struct FieldZone {
+    pub min: Vec2,
+    pub max: Vec2,
+    pub accel: Vec2,
+}
+
+impl FieldZone {
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all()
+            && point.cmple(self.max).all()
+    }
+}
+
+pub struct FieldZonePlugin;
+
+impl Plugin for FieldZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<FieldZone>()
+            .add_force_modifier(FieldZones::default());
+    }
+}
+
+pub fn spawn_field_zone(
+    commands: &mut Commands,
+    zone: FieldZone,
+) -> Entity {
+    let size = zone.max - zone.min;
+    let center = (zone.min + zone.max) / 2.0;
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.4, 0.8, 1.0, 0.1),
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(
+                center.extend(0.0),
+            ),
+            ..default()
+        })
+        .insert(zone)
+        .id()
+}
+
+/// Pushes balls inside of any `FieldZone`.
+#[derive(Default)]
+pub struct FieldZones {
+    zones: Vec<FieldZone>,
+}
+
+impl ForceModifier for FieldZones {
+    fn prepare(&mut self, world: &mut World) {
+        self.zones.clear();
+        let mut zones = world.query::<&FieldZone>();
+        self.zones.extend(zones.iter(world).copied());
+    }
+
+    fn force(&self, ball: &BallState) -> Vec2 {
+        self.zones
+            .iter()
+            .filter(|zone| zone.contains(ball.position))
+            .fold(Vec2::ZERO, |total, zone| {
+                total + zone.accel * ball.mass
+            })
+    }
+}
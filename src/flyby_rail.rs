@@ -0,0 +1,412 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+use std::{
+    f32::consts::TAU,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    assist::FLYBY_RADII,
+    course::{Course, Filled},
+    golf_ball::GolfBallSettings,
+    gravity::{
+        dominant_body, sphere_of_influence, Attractor,
+        GravityBodies, GravityMode, GravitySettings,
+        GravitySource,
+    },
+    hole::Hole,
+    menu::playing,
+    planet_spec::Planet,
+    prediction::{
+        predict_trajectory, Obstacle, PredictionSettings,
+    },
+    Body,
+};
+
+/// The planet a flyby is planned around.
+#[derive(Clone, Copy, Debug)]
+pub struct FlybyPlanet {
+    pub center: Vec2,
+    pub radius: f32,
+    pub mu: f32,
+    /// the furthest out a pass still counts as a flyby,
+    /// usually the edge of the planet's sphere of influence
+    pub reach: f32,
+}
+
+/// The flybys [`find_flyby`] tries: `periapses` distances
+/// evenly spread from just above the surface out to the
+/// planet's `reach`, passing it on either side, each at
+/// `speeds` launch speeds going up in steps of
+/// `speed_step`.
+#[derive(Clone, Debug)]
+pub struct FlybySearch {
+    pub periapses: u32,
+    pub speeds: u32,
+    pub speed_step: f32,
+    /// stop looking after this long
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for FlybySearch {
+    fn default() -> Self {
+        Self {
+            periapses: 24,
+            speeds: 16,
+            speed_step: 25.0,
+            // a frame or so of hitching at most
+            time_limit: Some(Duration::from_millis(250)),
+        }
+    }
+}
+
+/// A shot that swings past a planet on its way to a
+/// target.
+#[derive(Clone, Debug)]
+pub struct Flyby {
+    pub velocity: Vec2,
+    /// how close the predicted path passes the planet's
+    /// center
+    pub periapsis: f32,
+    /// how close it then gets to the target
+    pub miss: f32,
+    /// the whole predicted path, from the launch on
+    pub path: Vec<Vec2>,
+}
+
+impl Flyby {
+    /// The stretch of the path within the planet's
+    /// `reach`: the flyby itself.
+    pub fn rail(&self, planet: &FlybyPlanet) -> &[Vec2] {
+        let inside = |point: &Vec2| {
+            point.distance(planet.center) <= planet.reach
+        };
+        let start = match self.path.iter().position(inside)
+        {
+            Some(start) => start,
+            None => return &[],
+        };
+        let length = self.path[start..]
+            .iter()
+            .position(|point| !inside(point))
+            .unwrap_or(self.path.len() - start);
+        &self.path[start..start + length]
+    }
+}
+
+/// Plans a shot from `start` that swings past `planet` and
+/// on to `target`. Every flyby in `search` is aimed by
+/// where a lone planet would bend it, then checked with
+/// the trajectory predictor, which has every planet
+/// pulling. The one that ends up closest to `target` wins.
+///
+/// `None` if no launch gets within the planet's `reach`
+/// before the time limit.
+#[allow(clippy::too_many_arguments)]
+pub fn find_flyby(
+    sources: &[GravitySource],
+    obstacles: &[Obstacle],
+    start: Vec2,
+    planet: &FlybyPlanet,
+    target: Vec2,
+    ball: &GolfBallSettings,
+    settings: &PredictionSettings,
+    search: &FlybySearch,
+) -> Option<Flyby> {
+    let started = Instant::now();
+    let toward = (planet.center - start).try_normalize()?;
+    // gravity reaches balls as a force
+    let mu = planet.mu / ball.mass;
+    // just clear of the surface, so the closest pass
+    // doesn't bounce
+    let lowest = planet.radius + ball.radius() + 1.0;
+    if lowest >= planet.reach {
+        return None;
+    }
+    let step = (planet.reach - lowest)
+        / search.periapses.max(1) as f32;
+
+    let mut best: Option<Flyby> = None;
+    for speed in 1..=search.speeds {
+        let speed = speed as f32 * search.speed_step;
+        for i in 0..search.periapses {
+            if search.time_limit.is_some_and(|limit| {
+                started.elapsed() > limit
+            }) {
+                return best;
+            }
+            let periapsis =
+                lowest + step * (i as f32 + 0.5);
+            // a hyperbola passing at `periapsis` comes in
+            // aimed this far off the planet's center
+            let aim_offset = periapsis
+                * (1.0
+                    + 2.0 * mu
+                        / (periapsis * speed * speed))
+                    .sqrt();
+            for side in [1.0, -1.0] {
+                let aim = planet.center
+                    + toward.perp() * side * aim_offset;
+                let velocity = match (aim - start)
+                    .try_normalize()
+                {
+                    Some(direction) => direction * speed,
+                    None => continue,
+                };
+                let path = predict_trajectory(
+                    sources, obstacles, start, velocity,
+                    ball, settings,
+                );
+                let periapsis =
+                    closest_distance(&path, planet.center);
+                if periapsis > planet.reach {
+                    continue;
+                }
+                let miss = closest_distance(&path, target);
+                if best
+                    .as_ref()
+                    .is_none_or(|best| miss < best.miss)
+                {
+                    best = Some(Flyby {
+                        velocity,
+                        periapsis,
+                        miss,
+                        path,
+                    });
+                }
+            }
+        }
+    }
+    best
+}
+
+/// How close `path` gets to `point`, between its points
+/// as well as at them.
+fn closest_distance(path: &[Vec2], point: Vec2) -> f32 {
+    path.windows(2).fold(
+        path.first().map_or(f32::INFINITY, |first| {
+            first.distance(point)
+        }),
+        |closest, segment| {
+            let along = segment[1] - segment[0];
+            let t = if along == Vec2::ZERO {
+                0.0
+            } else {
+                ((point - segment[0]).dot(along)
+                    / along.length_squared())
+                .clamp(0.0, 1.0)
+            };
+            closest.min(
+                (segment[0] + along * t).distance(point),
+            )
+        },
+    )
+}
+
+/// Press `F11` with the mouse near a planet to plan a shot
+/// from the tee that swings past it into an open hole. The
+/// flyby is drawn as a rail around the planet, with the
+/// periapsis marked, and the launch it needs is logged.
+/// Pressing it away from any planet clears the rail.
+///
+/// Only passes inside [`FLYBY_RADII`] planet radii are
+/// tried, so a rail that speeds the ball up enough also
+/// earns a `GravityAssist`.
+#[derive(Default)]
+pub struct FlybyRail {
+    pub search: FlybySearch,
+    rail: Option<(Entity, FlybyPlanet, Flyby)>,
+}
+
+const RAIL_COLOR: Color = Color::ORANGE;
+/// the rest of the path, before and after the flyby
+const PATH_COLOR: Color = Color::rgba(1.0, 0.65, 0.0, 0.25);
+const PERIAPSIS_SEGMENTS: usize = 16;
+const PERIAPSIS_MARKER: f32 = 4.0;
+
+pub struct FlybyRailPlugin;
+
+impl Plugin for FlybyRailPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlybyRail>()
+            .add_system(
+                plan_flyby.with_run_criteria(playing),
+            )
+            .add_system(draw_flyby_rail.after(plan_flyby));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn plan_flyby(
+    keyboard: Res<Input<KeyCode>>,
+    mut flyby_rail: ResMut<FlybyRail>,
+    course: Res<Course>,
+    ball: Res<GolfBallSettings>,
+    prediction: Res<PredictionSettings>,
+    gravity_mode: Res<GravityMode>,
+    gravity_settings: Res<GravitySettings>,
+    particle_set: Res<ParticleSet<Body>>,
+    mouse_pos: Res<MousePosWorld>,
+    bodies: GravityBodies,
+    planets: Query<
+        (&GlobalTransform, &Collider),
+        With<Planet>,
+    >,
+    holes: Query<
+        (&GlobalTransform, &Hole),
+        Without<Filled>,
+    >,
+) {
+    // a new level has new planets
+    if course.is_changed() {
+        flyby_rail.rail = None;
+    }
+    if !keyboard.just_pressed(KeyCode::F11) {
+        return;
+    }
+    flyby_rail.rail = None;
+    let tee = match course
+        .current_level()
+        .and_then(|level| level.tee)
+    {
+        Some(tee) => tee,
+        None => return,
+    };
+    let (entity, (center, mu)) = match dominant_body(
+        &particle_set,
+        mouse_pos.truncate().extend(0.0),
+    )
+    .and_then(|entity| {
+        bodies.get(entity).map(|body| (entity, body))
+    }) {
+        Some(planet) => planet,
+        None => return,
+    };
+    // an `Attractor` has no surface to measure its reach
+    // in, so passes out as far as the tee are all fair game
+    let radius = planets
+        .get(entity)
+        .ok()
+        .and_then(|(_, collider)| collider.as_ball())
+        .map_or(0.0, |ball| ball.radius());
+    let mu = mu * gravity_settings.g_scale();
+    let mut reach = if radius > 0.0 {
+        radius * FLYBY_RADII
+    } else {
+        center.xy().distance(tee)
+    };
+    if let Some(soi) =
+        sphere_of_influence(&particle_set, center, mu)
+    {
+        reach = reach.min(soi);
+    }
+    let planet = FlybyPlanet {
+        center: center.xy(),
+        radius,
+        mu,
+        reach,
+    };
+
+    let sources = bodies
+        .sources(*gravity_mode, gravity_settings.g_scale());
+    let obstacles: Vec<Obstacle> = planets
+        .iter()
+        .map(|(transform, collider)| {
+            Obstacle::from_collider(transform, collider)
+        })
+        .collect();
+
+    // the hole the best flyby gets closest to
+    let best = holes
+        .iter()
+        .filter_map(|(transform, _)| {
+            find_flyby(
+                &sources,
+                &obstacles,
+                tee,
+                &planet,
+                transform.translation().xy(),
+                &ball,
+                &prediction,
+                &flyby_rail.search,
+            )
+        })
+        .min_by(|a, b| a.miss.total_cmp(&b.miss));
+    match best {
+        Some(flyby) => {
+            info!(
+                "flyby: power {:.0} at {:.1} degrees",
+                flyby.velocity.length(),
+                flyby
+                    .velocity
+                    .y
+                    .atan2(flyby.velocity.x)
+                    .to_degrees()
+            );
+            info!(
+                "flyby: periapsis {:.0}, misses by {:.0}",
+                flyby.periapsis, flyby.miss
+            );
+            flyby_rail.rail = Some((entity, planet, flyby));
+        }
+        None => info!("flyby: none found"),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn draw_flyby_rail(
+    mut lines: ResMut<DebugLines>,
+    flyby_rail: Res<FlybyRail>,
+    planets: Query<(), Or<(With<Planet>, With<Attractor>)>>,
+) {
+    let (entity, planet, flyby) = match &flyby_rail.rail {
+        Some(rail) => rail,
+        None => return,
+    };
+    // the planet was cleared away with its level
+    if !planets.contains(*entity) {
+        return;
+    }
+    for segment in flyby.path.windows(2) {
+        lines.line_colored(
+            segment[0].extend(5.0),
+            segment[1].extend(5.0),
+            0.0,
+            PATH_COLOR,
+        );
+    }
+    let rail = flyby.rail(planet);
+    for segment in rail.windows(2) {
+        lines.line_colored(
+            segment[0].extend(6.0),
+            segment[1].extend(6.0),
+            0.0,
+            RAIL_COLOR,
+        );
+    }
+    let periapsis = rail.iter().copied().min_by(|a, b| {
+        a.distance(planet.center)
+            .total_cmp(&b.distance(planet.center))
+    });
+    if let Some(periapsis) = periapsis {
+        let point = |i: usize| {
+            let angle =
+                i as f32 / PERIAPSIS_SEGMENTS as f32 * TAU;
+            (periapsis
+                + Vec2::new(angle.cos(), angle.sin())
+                    * PERIAPSIS_MARKER)
+                .extend(6.0)
+        };
+        for i in 0..PERIAPSIS_SEGMENTS {
+            lines.line_colored(
+                point(i),
+                point(i + 1),
+                0.0,
+                RAIL_COLOR,
+            );
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::{
+    golf_ball::{BallColor, GolfBall},
+    menu::playing,
+    split_screen::PlayerTwoCamera,
+    thumbnail::ThumbnailCamera,
+    undo::ShotHistory,
+};
+
+/// The ball the camera follows. `Tab` moves on to the next
+/// shot in [`ShotHistory`] whose ball is still in flight,
+/// and `Shift+Tab` back to the one before; going past
+/// either end lets go, and the camera goes back to where
+/// it started. Pinned balls are skipped, and so are
+/// practice shots and shots too old to be undone, which
+/// the history doesn't keep.
+///
+/// The ball being followed has a ring pulsing around it,
+/// in its own color, so it can be picked out of a crowd.
+#[derive(Default)]
+pub struct CameraFocus {
+    pub target: Option<Entity>,
+}
+
+pub struct CameraFocusPlugin;
+
+impl Plugin for CameraFocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraFocus>()
+            .add_system(
+                cycle_focus.with_run_criteria(playing),
+            )
+            .add_system(follow_focus.after(cycle_focus))
+            .add_system(
+                highlight_focus.after(follow_focus),
+            );
+    }
+}
+
+const HIGHLIGHT_SEGMENTS: usize = 32;
+/// pulses per second
+const HIGHLIGHT_RATE: f32 = 1.5;
+/// how far out the ring sits, in ball radii
+const HIGHLIGHT_SCALE: f32 = 1.8;
+
+fn cycle_focus(
+    keyboard: Res<Input<KeyCode>>,
+    history: Res<ShotHistory>,
+    mut focus: ResMut<CameraFocus>,
+    rigidbodies: Query<&RigidBody, With<GolfBall>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    // pinned balls are still balls, but not ones in flight
+    let balls: Vec<Entity> = history
+        .iter()
+        .filter(|ball| {
+            matches!(
+                rigidbodies.get(*ball),
+                Ok(RigidBody::Dynamic)
+            )
+        })
+        .collect();
+    let backwards = keyboard
+        .any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let current = focus.target.and_then(|target| {
+        balls.iter().position(|ball| *ball == target)
+    });
+    let next = match (current, backwards) {
+        (None, false) => Some(0),
+        (None, true) => balls.len().checked_sub(1),
+        (Some(i), false) => Some(i + 1),
+        (Some(i), true) => i.checked_sub(1),
+    };
+    focus.target = next.and_then(|i| balls.get(i)).copied();
+}
+
+#[allow(clippy::type_complexity)]
+fn follow_focus(
+    mut focus: ResMut<CameraFocus>,
+    balls: Query<&GlobalTransform, With<GolfBall>>,
+    mut cameras: Query<
+        &mut Transform,
+        (
+            With<Camera2d>,
+            Without<ThumbnailCamera>,
+            Without<PlayerTwoCamera>,
+        ),
+    >,
+) {
+    let position = match focus.target {
+        Some(target) => match balls.get(target) {
+            Ok(transform) => {
+                transform.translation().truncate()
+            }
+            // sunk, lost or timed out, so stay where the
+            // ball was last seen
+            Err(_) => {
+                focus.target = None;
+                return;
+            }
+        },
+        None if focus.is_changed() => Vec2::ZERO,
+        None => return,
+    };
+    for mut transform in cameras.iter_mut() {
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+/// Drawn anew every frame, so there's nothing to clean up
+/// once the ball is gone: `follow_focus` has already let it
+/// go by then.
+fn highlight_focus(
+    time: Res<Time>,
+    focus: Res<CameraFocus>,
+    mut lines: ResMut<DebugLines>,
+    balls: Query<
+        (&GlobalTransform, &Collider, Option<&BallColor>),
+        With<GolfBall>,
+    >,
+) {
+    let (transform, collider, color) = match focus
+        .target
+        .map(|target| balls.get(target))
+    {
+        Some(Ok(ball)) => ball,
+        _ => return,
+    };
+    let radius = collider
+        .as_ball()
+        .map_or(1.0, |ball| ball.radius());
+    let pulse = (time.seconds_since_startup() as f32
+        * HIGHLIGHT_RATE
+        * TAU)
+        .sin();
+    let ring = radius * (HIGHLIGHT_SCALE + 0.3 * pulse);
+    let color = color.map_or(Color::WHITE, |color| color.0);
+    let center = transform.translation().truncate();
+    let point = |i: usize| {
+        let angle =
+            i as f32 / HIGHLIGHT_SEGMENTS as f32 * TAU;
+        (center
+            + Vec2::new(angle.cos(), angle.sin()) * ring)
+            .extend(5.0)
+    };
+    for i in 0..HIGHLIGHT_SEGMENTS {
+        lines.line_colored(
+            point(i),
+            point(i + 1),
+            0.0,
+            color,
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{golf_ball::GolfBall, sim_state::sim_running};
+
+/// What a [`ForceModifier`] gets to know about each ball.
+#[derive(Clone, Copy, Debug)]
+pub struct BallState {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub mass: f32,
+    /// The force on the ball so far this frame: its
+    /// gravity, anything other systems have added, and the
+    /// forces of every modifier added before this one.
+    pub force: Vec2,
+}
+
+/// Pushes balls around on top of gravity, like a wind
+/// that changes over time. Added with
+/// [`AddForceModifier::add_force_modifier`].
+///
+/// Every frame the simulation runs, once all of `Update`'s
+/// systems are done and so after gravity has been worked
+/// out:
+///
+/// 1. every modifier is `prepare`d, in the order they were
+///    added
+/// 2. for each ball, every modifier's `force` is asked for
+///    in that same order and added to its `ExternalForce`
+///
+/// Nothing runs while the simulation is paused.
+///
+/// Atmospheric drag, `FieldZone`s and the `HoleAssist` are
+/// all modifiers, added by their plugins. See the `wind`
+/// example for one of your own.
+pub trait ForceModifier: Send + Sync + 'static {
+    /// Picks up whatever the modifier needs out of the
+    /// world, before any forces are asked for.
+    fn prepare(&mut self, _world: &mut World) {}
+
+    /// The extra force on `ball`.
+    fn force(&self, ball: &BallState) -> Vec2;
+}
+
+/// Every [`ForceModifier`], in the order they're applied.
+#[derive(Default)]
+pub struct ForceModifiers {
+    modifiers: Vec<Box<dyn ForceModifier>>,
+}
+
+impl ForceModifiers {
+    pub fn push(&mut self, modifier: impl ForceModifier) {
+        self.modifiers.push(Box::new(modifier));
+    }
+
+    pub fn len(&self) -> usize {
+        self.modifiers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modifiers.is_empty()
+    }
+}
+
+pub trait AddForceModifier {
+    /// Adds `modifier` after every modifier added so far.
+    fn add_force_modifier(
+        &mut self,
+        modifier: impl ForceModifier,
+    ) -> &mut Self;
+}
+
+impl AddForceModifier for App {
+    fn add_force_modifier(
+        &mut self,
+        modifier: impl ForceModifier,
+    ) -> &mut Self {
+        self.init_resource::<ForceModifiers>();
+        self.world
+            .resource_mut::<ForceModifiers>()
+            .push(modifier);
+        self
+    }
+}
+
+/// Applies the [`ForceModifiers`]. Modifiers can be added
+/// before or after this plugin.
+pub struct ForceModifierPlugin;
+
+impl Plugin for ForceModifierPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ForceModifiers>().add_system(
+            apply_force_modifiers
+                .exclusive_system()
+                .at_end()
+                .with_run_criteria(sim_running),
+        );
+    }
+}
+
+fn apply_force_modifiers(world: &mut World) {
+    // taken out so the modifiers can have the world to
+    // themselves while they prepare
+    let mut modifiers =
+        match world.remove_resource::<ForceModifiers>() {
+            Some(modifiers) => modifiers,
+            None => return,
+        };
+    for modifier in modifiers.modifiers.iter_mut() {
+        modifier.prepare(world);
+    }
+    let mut balls = world.query_filtered::<(
+        Entity,
+        &GlobalTransform,
+        Option<&Velocity>,
+        Option<&ColliderMassProperties>,
+        &mut ExternalForce,
+    ), With<GolfBall>>();
+    for (
+        entity,
+        transform,
+        velocity,
+        mass_props,
+        mut force,
+    ) in balls.iter_mut(world)
+    {
+        let mut ball = BallState {
+            entity,
+            position: transform.translation().truncate(),
+            velocity: velocity
+                .map_or(Vec2::ZERO, |velocity| {
+                    velocity.linvel
+                }),
+            mass: match mass_props {
+                Some(ColliderMassProperties::Mass(
+                    mass,
+                )) => *mass,
+                _ => 1.0,
+            },
+            force: force.force,
+        };
+        for modifier in modifiers.modifiers.iter() {
+            ball.force += modifier.force(&ball);
+        }
+        if force.force != ball.force {
+            force.force = ball.force;
+        }
+    }
+    world.insert_resource(modifiers);
+}
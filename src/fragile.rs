@@ -0,0 +1,173 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use std::f32::consts::FRAC_PI_4;
+
+use crate::{
+    golf_ball::{
+        spawn_ball, BallAssets, BallColor, GolfBall,
+        GolfBallSettings,
+    },
+    planet_spec::Planet,
+    rng::GameRng,
+};
+
+/// Balls that hit a planet faster than `impact_speed`
+/// break apart into smaller balls.
+pub struct FragileBalls {
+    pub enabled: bool,
+    pub impact_speed: f32,
+    /// fragments lighter than this aren't spawned at all
+    pub min_fragment_mass: f32,
+}
+
+impl Default for FragileBalls {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            impact_speed: 300.0,
+            min_fragment_mass: 10.0,
+        }
+    }
+}
+
+/// The velocity a ball had going into the last physics
+/// step. By the time we see a `CollisionEvent` rapier
+/// has already resolved the contact, so `Velocity` no
+/// longer tells us how hard the ball hit.
+#[derive(Component, Default)]
+pub(crate) struct LastVelocity(pub(crate) Vec2);
+
+pub struct FragileBallsPlugin;
+
+impl Plugin for FragileBallsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FragileBalls>()
+            .add_system(shatter_balls)
+            .add_system(
+                track_velocity.after(shatter_balls),
+            );
+    }
+}
+
+pub(crate) fn track_velocity(
+    mut commands: Commands,
+    mut balls: Query<
+        (Entity, &Velocity, Option<&mut LastVelocity>),
+        With<GolfBall>,
+    >,
+) {
+    for (entity, velocity, last) in balls.iter_mut() {
+        match last {
+            Some(mut last) => last.0 = velocity.linvel,
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(LastVelocity(velocity.linvel));
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn shatter_balls(
+    mut commands: Commands,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    fragile: Res<FragileBalls>,
+    settings: Res<GolfBallSettings>,
+    mut rng: ResMut<GameRng>,
+    mut collisions: EventReader<CollisionEvent>,
+    balls: Query<
+        (
+            &GlobalTransform,
+            &LastVelocity,
+            &ColliderMassProperties,
+            &BallColor,
+        ),
+        With<GolfBall>,
+    >,
+    planets: Query<&GlobalTransform, With<Planet>>,
+) {
+    let mut shattered = HashSet::default();
+    for event in collisions.iter() {
+        let (a, b) = match event {
+            CollisionEvent::Started(a, b, _) => (*a, *b),
+            CollisionEvent::Stopped(..) => continue,
+        };
+        if !fragile.enabled {
+            continue;
+        }
+        let (ball, planet) = if planets.contains(b) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let (transform, velocity, mass, color) =
+            match balls.get(ball) {
+                Ok((
+                    transform,
+                    velocity,
+                    ColliderMassProperties::Mass(mass),
+                    color,
+                )) => (transform, velocity, *mass, color),
+                _ => continue,
+            };
+        let planet_transform = match planets.get(planet) {
+            Ok(planet_transform) => planet_transform,
+            Err(_) => continue,
+        };
+
+        let speed = velocity.0.length();
+        if speed < fragile.impact_speed
+            || !shattered.insert(ball)
+        {
+            continue;
+        }
+        commands.entity(ball).despawn_recursive();
+
+        // harder hits break into more pieces
+        let count = 3
+            + ((speed / fragile.impact_speed) as usize)
+                .saturating_sub(1)
+                .min(2);
+        let fragment_mass = mass / count as f32;
+        if fragment_mass < fragile.min_fragment_mass {
+            continue;
+        }
+        let fragment = GolfBallSettings {
+            mass: fragment_mass,
+            ..(*settings).clone()
+        };
+
+        let position = transform.translation();
+        let normal = (position
+            - planet_transform.translation())
+        .truncate()
+        .normalize_or_zero();
+        let reflected = velocity.0
+            - 2.0 * velocity.0.dot(normal) * normal;
+        // fan the fragments out around the reflected
+        // direction, losing some energy in the crash.
+        for i in 0..count {
+            let t = i as f32 / (count - 1) as f32;
+            let angle = (t - 0.5) * 2.0 * FRAC_PI_4
+                + rng.gen_range(-0.15..0.15);
+            let direction =
+                Mat2::from_angle(angle) * reflected * 0.5;
+            let offset = direction.normalize_or_zero()
+                * fragment.radius();
+            spawn_ball(
+                &mut commands,
+                &mut ball_assets,
+                &mut meshes,
+                &mut materials,
+                position + offset.extend(0.0),
+                direction,
+                color.0,
+                &fragment,
+            );
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    course::{Course, Sunk},
+    golf_ball::GolfBall,
+    hole::Hole,
+    level::Level,
+    menu::playing,
+    planet_spec::Planet,
+    thumbnail::ThumbnailCamera,
+};
+
+/// Fits the camera around the whole level whenever one is
+/// loaded, and again on `Home`.
+pub struct Framing {
+    /// how much room is left around the bodies, as a
+    /// fraction of their extent on each side
+    pub padding: f32,
+    /// Never zoom in past this. Levels are laid out for a
+    /// scale of 1.0, so a small one isn't blown up.
+    pub min_scale: f32,
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Self {
+            padding: 0.1,
+            min_scale: 1.0,
+        }
+    }
+}
+
+/// Where a camera looking at `size` pixels of screen has
+/// to sit, and the orthographic scale it needs, to show
+/// every circle in `bodies` with `padding` to spare. Only
+/// the bounding box of the bodies matters for an upright
+/// view, so that's all that's worked out of their hull.
+/// Returns `None` with no bodies or no screen.
+pub fn frame_bodies(
+    bodies: &[(Vec2, f32)],
+    size: Vec2,
+    padding: f32,
+) -> Option<(Vec2, f32)> {
+    if bodies.is_empty() || size.min_element() <= 0.0 {
+        return None;
+    }
+    let mut min = Vec2::splat(f32::INFINITY);
+    let mut max = Vec2::splat(f32::NEG_INFINITY);
+    for (center, radius) in bodies.iter() {
+        min = min.min(*center - Vec2::splat(*radius));
+        max = max.max(*center + Vec2::splat(*radius));
+    }
+    let extent =
+        (max - min) * (1.0 + 2.0 * padding.max(0.0));
+    // the tighter of the two axes sets the scale, the other
+    // gets the leftover room
+    let scale = (extent / size).max_element();
+    Some(((min + max) / 2.0, scale))
+}
+
+/// Everything in a level that should be on screen when it
+/// starts: its planets, holes and tee.
+pub fn level_bodies(level: &Level) -> Vec<(Vec2, f32)> {
+    let mut bodies: Vec<(Vec2, f32)> = level
+        .planets
+        .iter()
+        .map(|planet| {
+            (
+                planet.position.xy(),
+                planet.semi_axes().max_element(),
+            )
+        })
+        .collect();
+    bodies.extend(
+        level.sized_holes().map(|(position, hole)| {
+            (position, hole.radius)
+        }),
+    );
+    bodies.extend(level.tee.map(|tee| (tee, 0.0)));
+    bodies
+}
+
+pub struct FramingPlugin;
+
+impl Plugin for FramingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Framing>()
+            .add_system(frame_level)
+            .add_system(
+                frame_all.with_run_criteria(playing),
+            );
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn fit_camera(
+    framing: &Framing,
+    windows: &Windows,
+    bodies: &[(Vec2, f32)],
+    cameras: &mut Query<
+        (&mut Transform, &mut OrthographicProjection),
+        (With<Camera2d>, Without<ThumbnailCamera>),
+    >,
+) {
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let size = Vec2::new(window.width(), window.height());
+    let (center, scale) =
+        match frame_bodies(bodies, size, framing.padding) {
+            Some(frame) => frame,
+            None => return,
+        };
+    for (mut transform, mut projection) in
+        cameras.iter_mut()
+    {
+        transform.translation.x = center.x;
+        transform.translation.y = center.y;
+        projection.scale = scale.max(framing.min_scale);
+    }
+}
+
+/// Runs off the level itself rather than its bodies, which
+/// aren't spawned until the end of the frame it's loaded
+/// in.
+#[allow(clippy::type_complexity)]
+fn frame_level(
+    framing: Res<Framing>,
+    windows: Res<Windows>,
+    course: Res<Course>,
+    mut cameras: Query<
+        (&mut Transform, &mut OrthographicProjection),
+        (With<Camera2d>, Without<ThumbnailCamera>),
+    >,
+) {
+    if !course.is_changed() {
+        return;
+    }
+    let level = match course.current_level() {
+        Some(level) => level,
+        None => return,
+    };
+    fit_camera(
+        &framing,
+        &windows,
+        &level_bodies(level),
+        &mut cameras,
+    );
+}
+
+/// Frames the planets, holes and balls still in play. A
+/// ball the camera is following keeps the camera on it,
+/// but still gets the zoom.
+#[allow(clippy::type_complexity)]
+fn frame_all(
+    keyboard: Res<Input<KeyCode>>,
+    framing: Res<Framing>,
+    windows: Res<Windows>,
+    bodies: Query<
+        (&GlobalTransform, &Collider, Option<&Sunk>),
+        Or<(With<Planet>, With<GolfBall>)>,
+    >,
+    holes: Query<(&GlobalTransform, &Hole)>,
+    mut cameras: Query<
+        (&mut Transform, &mut OrthographicProjection),
+        (With<Camera2d>, Without<ThumbnailCamera>),
+    >,
+) {
+    if !keyboard.just_pressed(KeyCode::Home) {
+        return;
+    }
+    let mut circles: Vec<(Vec2, f32)> = bodies
+        .iter()
+        .filter(|(_, _, sunk)| sunk.is_none())
+        .map(|(transform, collider, _)| {
+            (
+                transform.translation().xy(),
+                collider
+                    .raw
+                    .compute_local_bounding_sphere()
+                    .radius,
+            )
+        })
+        .collect();
+    circles.extend(holes.iter().map(
+        |(transform, hole)| {
+            (transform.translation().xy(), hole.radius)
+        },
+    ));
+    fit_camera(&framing, &windows, &circles, &mut cameras);
+}
@@ -0,0 +1,135 @@
+use bevy::{
+    input::{mouse::MouseButtonInput, ButtonState},
+    prelude::*,
+    sprite::Mesh2dHandle,
+};
+use bevy_mouse_tracking_plugin::MousePosWorld;
+
+use crate::{
+    density::mesh_radius, golf_ball::PointMass,
+    measure::MeasureTool, menu::playing,
+    planet_spec::Planet,
+};
+
+/// How much of its color a planet keeps while its gravity
+/// is turned off.
+const DIM: f32 = 0.35;
+
+/// Marks a planet whose gravity has been turned off with a
+/// shift middle click. It stays where it is and can still
+/// be hit, it just doesn't pull on anything until it's
+/// clicked again, which makes it easy to see how much each
+/// planet bends a shot. A plain middle click pins balls
+/// instead, see [`Pinned`](crate::pin::Pinned).
+#[derive(Component)]
+pub struct GravityFrozen {
+    mass: f32,
+    color: Color,
+}
+
+pub struct FreezeGravityPlugin;
+
+impl Plugin for FreezeGravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            toggle_planet_gravity
+                .with_run_criteria(playing),
+        );
+    }
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn toggle_planet_gravity(
+    mut commands: Commands,
+    mut click_event: EventReader<MouseButtonInput>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse_pos: Res<MousePosWorld>,
+    measure: Res<MeasureTool>,
+    meshes: Res<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut planets: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Mesh2dHandle,
+            &Handle<ColorMaterial>,
+            &mut PointMass,
+            Option<&GravityFrozen>,
+        ),
+        With<Planet>,
+    >,
+) {
+    let clicked = click_event.iter().any(|event| {
+        event.button == MouseButton::Middle
+            && event.state == ButtonState::Pressed
+    });
+    let shift = keyboard
+        .any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    // middle clicks pick points while measuring
+    if !clicked || !shift || measure.enabled {
+        return;
+    }
+    let mouse_pos = mouse_pos.truncate();
+    // the collider can be just the planet's solid core, so
+    // clicks are checked against what's drawn instead
+    let hit = planets.iter_mut().find(
+        |(_, transform, mesh, ..)| {
+            meshes
+                .get(&mesh.0)
+                .and_then(mesh_radius)
+                .is_some_and(|radius| {
+                    transform
+                        .translation()
+                        .truncate()
+                        .distance(mouse_pos)
+                        <= radius
+                })
+        },
+    );
+    let (entity, _, _, material, mut point_mass, frozen) =
+        match hit {
+            Some(hit) => hit,
+            None => return,
+        };
+    let material = materials.get_mut(material);
+    // `sync_particle_set` rereads every `PointMass` each
+    // frame, so the change is picked up on the next one
+    match frozen {
+        Some(frozen) => {
+            *point_mass =
+                PointMass::HasGravity { mass: frozen.mass };
+            if let Some(material) = material {
+                material.color = frozen.color;
+            }
+            commands
+                .entity(entity)
+                .remove::<GravityFrozen>();
+        }
+        None => {
+            let mass = match *point_mass {
+                PointMass::HasGravity { mass } => mass,
+                PointMass::AffectedByGravity => return,
+            };
+            *point_mass = PointMass::AffectedByGravity;
+            let color = match material {
+                Some(material) => {
+                    let color = material.color;
+                    material.color = Color::rgba(
+                        color.r() * DIM,
+                        color.g() * DIM,
+                        color.b() * DIM,
+                        color.a(),
+                    );
+                    color
+                }
+                None => Color::WHITE,
+            };
+            commands
+                .entity(entity)
+                .insert(GravityFrozen { mass, color });
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::f32::consts::TAU;
+
+use crate::{
+    course::{Assists, Course, Strokes},
+    golf_ball::GolfBall,
+    hole::Hole,
+    level::Level,
+    menu::playing,
+    planet_spec::{Planet, PlanetPreset, PlanetSpec},
+    prediction::ShotSearch,
+    rng::GameRng,
+    solve::solve_hole,
+};
+
+/// Replaces the current level with a freshly generated
+/// one.
+pub struct GenerateLevel {
+    pub seed: u64,
+    pub difficulty: u32,
+}
+
+/// How hard the levels `N` generates are. Their seeds come
+/// from the `GameRng`.
+pub struct LevelGenerator {
+    pub difficulty: u32,
+}
+
+impl Default for LevelGenerator {
+    fn default() -> Self {
+        Self { difficulty: 1 }
+    }
+}
+
+pub struct GeneratePlugin;
+
+impl Plugin for GeneratePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GenerateLevel>()
+            .init_resource::<LevelGenerator>()
+            .add_system(
+                generate_controls
+                    .with_run_criteria(playing),
+            )
+            .add_system(
+                replace_level.after(generate_controls),
+            );
+    }
+}
+
+/// Half the width and height of the area levels are
+/// generated in.
+const BOUNDS: Vec2 = Vec2::new(500.0, 300.0);
+/// Levels that fail the solvability check are thrown away
+/// and regenerated this many times before giving up.
+const MAX_ATTEMPTS: u32 = 20;
+/// space kept clear around the tee and between planets
+const CLEARANCE: f32 = 30.0;
+
+/// Makes a level with a tee on the left and planets
+/// scattered to the right of it, with the hole on the
+/// surface of the furthest one. Higher difficulties get
+/// more planets and a smaller hole.
+///
+/// Every level is checked by predicting a fan of shots
+/// from the tee, and is only returned if one of them
+/// reaches the hole. If nothing solvable turns up after a
+/// few attempts the planet count is reduced, and the last
+/// attempt is returned regardless.
+pub fn generate_level(seed: u64, difficulty: u32) -> Level {
+    // a generator of its own, so the same seed always
+    // makes the same level
+    let mut rng = StdRng::seed_from_u64(seed);
+    let planet_count = (1 + difficulty).min(8);
+    let mut level = Level::new();
+    for attempt in 0..MAX_ATTEMPTS {
+        let count =
+            planet_count.saturating_sub(attempt / 4).max(1);
+        level = random_level(&mut rng, count, difficulty);
+        if level.validate().is_ok() && is_solvable(&level) {
+            break;
+        }
+    }
+    level.set_name(format!("Generated #{}", seed));
+    level
+}
+
+/// The hole radius levels of `difficulty` are generated
+/// with, shrinking from a forgiving 14.0 at difficulty 1
+/// down to 7.0, which still leaves room around a default
+/// ball.
+pub fn hole_radius_for(difficulty: u32) -> f32 {
+    (16.0 - 2.0 * difficulty as f32).max(7.0)
+}
+
+fn random_level(
+    rng: &mut StdRng,
+    planet_count: u32,
+    difficulty: u32,
+) -> Level {
+    let tee = Vec2::new(
+        -BOUNDS.x + 50.0,
+        rng.gen_range(-BOUNDS.y..BOUNDS.y),
+    );
+    let hole = Hole::default();
+
+    let mut level = Level::new();
+    level
+        .set_tee(tee)
+        .set_par(2 + difficulty / 2)
+        .set_hole_radius(hole_radius_for(difficulty));
+
+    // planets that don't fit after a few tries are
+    // dropped rather than squeezed in
+    for _ in 0..planet_count {
+        for _ in 0..10 {
+            let planet = PlanetSpec {
+                position: Vec3::new(
+                    rng.gen_range(
+                        -BOUNDS.x + 150.0..BOUNDS.x - 150.0,
+                    ),
+                    rng.gen_range(-BOUNDS.y..BOUNDS.y),
+                    1.0,
+                ),
+                mass: rng.gen_range(3E5..12E5),
+                ..default()
+            };
+            let preset = preset_for_mass(planet.mass);
+            let planet = planet.with_preset(preset);
+            let fits = !planet.contains(tee, CLEARANCE)
+                && level.planets.iter().all(|other| {
+                    other.position.truncate().distance(
+                        planet.position.truncate(),
+                    ) > other.radius()
+                        + planet.radius()
+                        + CLEARANCE
+                });
+            if fits {
+                level.add_planet(planet);
+                break;
+            }
+        }
+    }
+
+    // the hole sits on the planet furthest from the tee,
+    // anywhere around it. Spots another planet covers fail
+    // validation and the level is thrown away.
+    let furthest = level
+        .planets
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| {
+            a.position.truncate().distance(tee).total_cmp(
+                &b.position.truncate().distance(tee),
+            )
+        })
+        .map(|(i, _)| i);
+    if let Some(planet) = furthest {
+        level.place_hole_on_surface(
+            planet,
+            rng.gen_range(0.0..TAU),
+            hole,
+        );
+    }
+    level
+}
+
+/// Heavier planets get the hotter looking presets, so
+/// their size isn't the only hint at how hard they pull.
+fn preset_for_mass(mass: f32) -> PlanetPreset {
+    let presets = PlanetPreset::ALL;
+    let t = ((mass - 3E5) / 9E5).clamp(0.0, 0.999);
+    presets[(t * presets.len() as f32) as usize]
+}
+
+/// Whether every hole can be reached by one of a fan of
+/// shots from the tee.
+fn is_solvable(level: &Level) -> bool {
+    level.tee.is_some()
+        && (0..level.holes.len()).all(|hole| {
+            solve_hole(level, hole, &ShotSearch::default())
+                .is_some()
+        })
+}
+
+fn generate_controls(
+    keyboard: Res<Input<KeyCode>>,
+    generator: Res<LevelGenerator>,
+    mut rng: ResMut<GameRng>,
+    mut events: EventWriter<GenerateLevel>,
+) {
+    if keyboard.just_pressed(KeyCode::N) {
+        events.send(GenerateLevel {
+            seed: rng.gen(),
+            difficulty: generator.difficulty,
+        });
+    }
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn replace_level(
+    mut commands: Commands,
+    mut events: EventReader<GenerateLevel>,
+    old: Query<
+        Entity,
+        Or<(With<Planet>, With<Hole>, With<GolfBall>)>,
+    >,
+    mut course: ResMut<Course>,
+    mut strokes: ResMut<Strokes>,
+    mut assists: ResMut<Assists>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // only the newest request matters if several came in
+    // on the same frame
+    let event = match events.iter().last() {
+        Some(event) => event,
+        None => return,
+    };
+    for entity in old.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let level =
+        generate_level(event.seed, event.difficulty);
+    level.spawn(&mut commands, &mut meshes, &mut materials);
+    *course = Course {
+        levels: vec![level],
+        current: 0,
+    };
+    strokes.0 = 0;
+    assists.0 = 0;
+}
@@ -0,0 +1,70 @@
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+
+use crate::golf_ball::{BallPalette, GolfBallSettings};
+
+/// A see-through copy of the ball that is about to be
+/// launched, sitting exactly where it will spawn.
+#[derive(Component)]
+pub struct GhostBall;
+
+pub struct GhostBallPlugin;
+
+impl Plugin for GhostBallPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(update_ghost_ball);
+    }
+}
+
+/// There's at most one ghost. It appears when aiming
+/// starts, follows the ball's radius while the settings
+/// change, and goes away when the shot is launched or
+/// cancelled.
+fn update_ghost_ball(
+    mut commands: Commands,
+    settings: Res<GolfBallSettings>,
+    palette: Res<BallPalette>,
+    mut ghosts: Query<
+        (Entity, &mut Transform),
+        With<GhostBall>,
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let position = match settings.position {
+        Some(position) => position,
+        None => {
+            for (entity, _) in ghosts.iter() {
+                commands.entity(entity).despawn();
+            }
+            return;
+        }
+    };
+    let radius = settings.radius();
+    let transform = Transform::from_translation(position)
+        .with_scale(Vec3::new(radius, radius, 1.0));
+
+    match ghosts.get_single_mut() {
+        Ok((_, mut ghost_transform)) => {
+            *ghost_transform = transform;
+        }
+        Err(_) => {
+            let mut color = palette.peek_color();
+            color.set_a(0.3);
+            commands
+                .spawn_bundle(MaterialMesh2dBundle {
+                    // a unit circle scaled up to the
+                    // ball's radius
+                    mesh: meshes
+                        .add(Mesh::from(
+                            shape::Circle::new(1.0),
+                        ))
+                        .into(),
+                    material: materials
+                        .add(ColorMaterial::from(color)),
+                    transform,
+                    ..default()
+                })
+                .insert(GhostBall);
+        }
+    }
+}
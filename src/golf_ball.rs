@@ -1,17 +1,71 @@
 use bevy::{
     prelude::*,
+    reflect::ReflectDeserialize,
     sprite::{Material2d, MaterialMesh2dBundle},
 };
 use bevy_rapier2d::prelude::*;
-use particular::prelude::*;
-use std::f32::consts::PI;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Component)]
+use crate::{
+    density::Density,
+    gravity::G,
+    groups::{BALL_FILTER, BALL_GROUP},
+    time_scale::BASE_MAX_DT,
+};
+
+// bevy_reflect can't look inside of enums yet, so
+// PointMass is reflected as an opaque value that gets
+// (de)serialized as a whole.
+#[derive(
+    Component,
+    Reflect,
+    Clone,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Default,
+)]
+#[reflect_value(
+    Component,
+    PartialEq,
+    Serialize,
+    Deserialize
+)]
 pub enum PointMass {
     HasGravity { mass: f32 },
+    /// Pulled on like anything else, but pulls on nothing.
+    /// Its `mu` is zero, which keeps it out of the sources
+    /// particular sums up and is skipped by
+    /// `gravity::falloff_gravity_at`, so any number of
+    /// them, wherever they sit, add no force and can't
+    /// turn a pull into a NaN.
+    #[default]
     AffectedByGravity,
 }
 
+impl PointMass {
+    pub fn mu(&self) -> f32 {
+        match self {
+            PointMass::HasGravity { mass } => *mass * G,
+            PointMass::AffectedByGravity => 0.0,
+        }
+    }
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct GolfBall;
+
+/// The color a ball was given when it was launched, kept
+/// around so anything drawn for the ball can match it.
+#[derive(
+    Component, Reflect, Clone, Copy, Debug, Default,
+)]
+#[reflect(Component)]
+pub struct BallColor(pub Color);
+
 #[derive(Bundle)]
 pub struct CircleWithGravity<M: Material2d> {
     #[bundle]
@@ -26,18 +80,492 @@ pub struct CircleWithGravity<M: Material2d> {
     pub point_mass: PointMass,
 }
 
+/// The meshes and materials balls are drawn with, shared
+/// between every ball of the same size and look instead of
+/// adding a new pair of assets per shot.
+#[derive(Default)]
+pub struct BallAssets {
+    /// keyed by radius in hundredths
+    meshes: HashMap<i32, Handle<Mesh>>,
+    materials: HashMap<
+        (u32, Option<Handle<Image>>),
+        Handle<ColorMaterial>,
+    >,
+}
+
+impl BallAssets {
+    pub fn mesh(
+        &mut self,
+        meshes: &mut Assets<Mesh>,
+        radius: f32,
+    ) -> Handle<Mesh> {
+        let key = (radius * 100.0).round() as i32;
+        self.meshes
+            .entry(key)
+            .or_insert_with(|| {
+                meshes.add(Mesh::from(shape::Circle {
+                    radius,
+                    ..default()
+                }))
+            })
+            .clone()
+    }
+
+    pub fn material(
+        &mut self,
+        materials: &mut Assets<ColorMaterial>,
+        color: Color,
+        texture: Option<Handle<Image>>,
+    ) -> Handle<ColorMaterial> {
+        self.materials
+            .entry((color.as_rgba_u32(), texture.clone()))
+            .or_insert_with(|| {
+                materials
+                    .add(ColorMaterial { color, texture })
+            })
+            .clone()
+    }
+
+    /// How many distinct meshes and materials balls are
+    /// using between them.
+    pub fn len(&self) -> (usize, usize) {
+        (self.meshes.len(), self.materials.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.meshes.is_empty() && self.materials.is_empty()
+    }
+}
+
+/// Spawn a dynamic golf ball. Everything that launches a
+/// ball should go through here so they all behave the same.
+/// A ball lighter or smaller than `settings` allows is
+/// spawned at the floor instead, see
+/// [`GolfBallSettings::clamped`].
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_ball(
+    commands: &mut Commands,
+    ball_assets: &mut BallAssets,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    position: Vec3,
+    velocity: Vec2,
+    color: Color,
+    settings: &GolfBallSettings,
+) -> Entity {
+    let settings = &settings.clamped();
+    let radius = settings.radius();
+    let texture = match &settings.visual {
+        BallVisual::Circle => None,
+        BallVisual::Sprite(image) => Some(image.clone()),
+    };
+    commands
+        .spawn_bundle(CircleWithGravity {
+            shape_bundle: MaterialMesh2dBundle {
+                mesh: ball_assets
+                    .mesh(meshes, radius)
+                    .into(),
+                transform: Transform::from_translation(
+                    position,
+                ),
+                material: ball_assets
+                    .material(materials, color, texture),
+                ..default()
+            },
+            collider: Collider::ball(radius),
+            friction: Friction {
+                coefficient: settings.friction,
+                ..default()
+            },
+            mass: ColliderMassProperties::Mass(
+                settings.mass,
+            ),
+            restitution: Restitution {
+                coefficient: settings.restitution,
+                ..default()
+            },
+            rigidbody: RigidBody::Dynamic,
+            velocity: Velocity::linear(velocity),
+            acceleration: ExternalForce::default(),
+            // balls only pull on each other when
+            // `BallGravity` is turned on
+            point_mass: PointMass::AffectedByGravity,
+        })
+        .insert(settings.damping.component())
+        .insert(GolfBall)
+        .insert(BallColor(color))
+        .insert(Density(settings.density))
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(CollisionGroups::new(
+            BALL_GROUP,
+            BALL_FILTER,
+        ))
+        .insert(Ccd {
+            enabled: settings.ccd,
+        })
+        .id()
+}
+
+/// Asks for a ball at exactly `position` moving at
+/// exactly `velocity`, for scripted demos and for
+/// reproducing a reported orbit. Unlike a shot it doesn't
+/// take a stroke.
+#[derive(Clone, Copy, Debug)]
+pub struct SpawnBall {
+    pub position: Vec3,
+    pub velocity: Vec2,
+    /// overrides `GolfBallSettings::mass` when set
+    pub mass: Option<f32>,
+}
+
+pub struct SpawnBallPlugin;
+
+impl Plugin for SpawnBallPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnBall>()
+            .init_resource::<BallAssets>()
+            .add_system(spawn_requested_balls);
+    }
+}
+
+fn spawn_requested_balls(
+    mut commands: Commands,
+    mut events: EventReader<SpawnBall>,
+    settings: Res<GolfBallSettings>,
+    mut palette: ResMut<BallPalette>,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for event in events.iter() {
+        let settings = GolfBallSettings {
+            mass: event.mass.unwrap_or(settings.mass),
+            ..settings.clone()
+        };
+        let color = palette.next_color();
+        spawn_ball(
+            &mut commands,
+            &mut ball_assets,
+            &mut meshes,
+            &mut materials,
+            event.position,
+            event.velocity,
+            color,
+            &settings,
+        );
+    }
+}
+
+/// How quickly a ball's motion dies away on its own.
+/// Rapier scales damping by each step's length, so the
+/// same settings slow a ball the same way at any frame
+/// rate or `TimeScale`.
+#[derive(Reflect, Clone, Copy, Debug)]
+pub struct BallDamping {
+    /// Bleeds off orbital energy so shots eventually
+    /// settle. Zero by default, which keeps space
+    /// frictionless and lets balls orbit forever.
+    pub linear: f32,
+    pub angular: f32,
+}
+
+impl Default for BallDamping {
+    fn default() -> Self {
+        Self {
+            linear: 0.0,
+            angular: 1.5,
+        }
+    }
+}
+
+impl BallDamping {
+    /// rapier2d's `Damping` for these settings. The
+    /// `three_d` balls build rapier3d's own.
+    pub fn component(&self) -> Damping {
+        Damping {
+            linear_damping: self.linear,
+            angular_damping: self.angular,
+        }
+    }
+}
+
+/// What a ball looks like. Rapier turns the ball's
+/// transform as it rolls, so an image makes spin visible
+/// where a flat colored circle can't.
+#[derive(Clone, Debug, Default)]
+pub enum BallVisual {
+    #[default]
+    Circle,
+    /// drawn on the ball and tinted with its color
+    Sprite(Handle<Image>),
+}
+
+#[derive(Reflect, Clone)]
+#[reflect(Resource)]
 pub struct GolfBallSettings {
     pub position: Option<Vec3>,
     pub mass: f32,
+    pub density: f32,
+    pub restitution: f32,
+    /// Friction is what lets a ball roll along a planet
+    /// instead of sliding, and angular damping is what
+    /// eventually brings that roll to a stop.
+    pub friction: f32,
+    pub damping: BallDamping,
+    /// seconds of steerable thrust given to each launched
+    /// ball, or 0.0 for none
+    pub thrust_fuel: f32,
+    pub thrust_power: f32,
     pub trail: bool,
+    /// seconds a launched ball can stay in play without
+    /// sinking before the shot times out
+    pub max_shot_time: f32,
+    /// times a launched ball can hit something in
+    /// `GameMode::Challenge` before the shot times out,
+    /// `None` for no limit
+    pub max_bounces: Option<u32>,
+    /// How far the aim has been swept around while
+    /// dragging, in radians, counter clockwise positive.
+    pub aim_curl: f32,
+    /// Spin given to a launched ball for each radian of
+    /// `aim_curl`, in radians per second. Sweeping the aim
+    /// counter clockwise during the drag spins the ball
+    /// counter clockwise, which friction turns into a roll
+    /// once it lands.
+    pub spin_per_curl: f32,
+    /// The lightest a ball can be spawned, 1.0 by default.
+    /// Anything asking for less gets this instead.
+    pub min_mass: f32,
+    /// The smallest a ball can be spawned, 2.0 by default.
+    /// Balls much smaller than a pixel tunnel through
+    /// everything, so a ball whose mass and density would
+    /// make it smaller is spawned at this radius instead,
+    /// with a lower density so its mass stays the same.
+    pub min_radius: f32,
+    /// Continuous collision detection for launched balls.
+    /// Without it a ball that moves further than a
+    /// planet's width in a single step can jump clean over
+    /// it, which `max_speed` and substeps only make less
+    /// likely. rapier then sweeps the ball along its whole
+    /// step instead, at a cost every step for every ball,
+    /// so it's off by default.
+    pub ccd: bool,
+    /// How many steps the trajectory predictor follows a
+    /// shot for, the aiming preview included. The path
+    /// covers `prediction_steps * prediction_dt` seconds,
+    /// so the same lookahead can be had from fewer, bigger
+    /// steps at the cost of accuracy near planets.
+    pub prediction_steps: usize,
+    /// Seconds per predicted step, `BASE_MAX_DT` to match
+    /// the simulation. The defaults follow two seconds of
+    /// flight, which is exact for putts. For a quick
+    /// preview of long interplanetary shots, something
+    /// like `1.0 / 20.0` with the same steps looks six
+    /// seconds ahead.
+    pub prediction_dt: f32,
+    #[reflect(ignore)]
+    pub visual: BallVisual,
 }
 
 impl Default for GolfBallSettings {
     fn default() -> Self {
         Self {
             position: None,
-            mass: 20.0,
+            mass: 100.0,
+            density: 1.0,
+            restitution: 0.0,
+            friction: 1.0,
+            damping: BallDamping::default(),
+            thrust_fuel: 0.0,
+            thrust_power: 20000.0,
             trail: false,
+            max_shot_time: 30.0,
+            max_bounces: Some(25),
+            aim_curl: 0.0,
+            spin_per_curl: 5.0,
+            min_mass: 1.0,
+            min_radius: 2.0,
+            ccd: false,
+            prediction_steps: 120,
+            prediction_dt: BASE_MAX_DT,
+            visual: BallVisual::Circle,
+        }
+    }
+}
+
+impl GolfBallSettings {
+    pub fn radius(&self) -> f32 {
+        Density(self.density)
+            .radius(self.mass.max(self.min_mass))
+            .max(self.min_radius)
+    }
+
+    /// These settings with `min_mass` and `min_radius`
+    /// enforced, the way [`spawn_ball`] spawns them.
+    pub fn clamped(&self) -> Self {
+        let mut settings = self.clone();
+        settings.mass =
+            settings.mass.max(settings.min_mass);
+        let min_radius = settings.min_radius.max(0.0);
+        if Density(settings.density).radius(settings.mass)
+            < min_radius
+        {
+            settings.density = settings.mass
+                / (std::f32::consts::PI
+                    * min_radius.powi(2));
+        }
+        settings
+    }
+}
+
+/// Colors handed out to launched balls, in order.
+pub struct BallPalette {
+    pub colors: Vec<Color>,
+    next: usize,
+}
+
+impl Default for BallPalette {
+    fn default() -> Self {
+        Self {
+            colors: vec![
+                Color::WHITE,
+                Color::ORANGE,
+                Color::CYAN,
+                Color::PINK,
+                Color::YELLOW_GREEN,
+                Color::GOLD,
+                Color::VIOLET,
+                Color::TOMATO,
+            ],
+            next: 0,
         }
     }
 }
+
+impl BallPalette {
+    /// The color the next ball will get, without using it
+    /// up.
+    pub fn peek_color(&self) -> Color {
+        if self.colors.is_empty() {
+            return Color::WHITE;
+        }
+        self.colors[self.next % self.colors.len()]
+    }
+
+    pub fn next_color(&mut self) -> Color {
+        if self.colors.is_empty() {
+            return Color::WHITE;
+        }
+        let color =
+            self.colors[self.next % self.colors.len()];
+        self.next = self.next.wrapping_add(1);
+        color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PIXELS_PER_METER;
+    use bevy::{
+        asset::AssetPlugin, hierarchy::HierarchyPlugin,
+        transform::TransformPlugin,
+    };
+
+    const DT: f32 = 1.0 / 60.0;
+    const GROUND_HALF_HEIGHT: f32 = 10.0;
+
+    /// the angular damping a ball rolls with
+    struct Rolling(f32);
+
+    fn roll(
+        mut commands: Commands,
+        rolling: Res<Rolling>,
+        mut ball_assets: ResMut<BallAssets>,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<ColorMaterial>>,
+    ) {
+        commands
+            .spawn_bundle(TransformBundle::default())
+            .insert(RigidBody::Fixed)
+            .insert(Collider::cuboid(
+                10000.0,
+                GROUND_HALF_HEIGHT,
+            ));
+        let settings = GolfBallSettings {
+            damping: BallDamping {
+                angular: rolling.0,
+                ..default()
+            },
+            ..default()
+        };
+        // resting on the ground, already moving along it
+        spawn_ball(
+            &mut commands,
+            &mut ball_assets,
+            &mut meshes,
+            &mut materials,
+            Vec3::new(
+                0.0,
+                GROUND_HALF_HEIGHT + settings.radius(),
+                0.0,
+            ),
+            Vec2::new(100.0, 0.0),
+            Color::WHITE,
+            &settings,
+        );
+    }
+
+    /// How fast the ball is going after ten seconds on
+    /// the ground.
+    fn speed_after_rolling(angular_damping: f32) -> f32 {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_asset::<ColorMaterial>()
+            .init_resource::<BallAssets>()
+            .insert_resource(Rolling(angular_damping))
+            .insert_resource(RapierConfiguration {
+                gravity: Vec2::new(0.0, -500.0),
+                timestep_mode: TimestepMode::Fixed {
+                    dt: DT,
+                    substeps: 1,
+                },
+                ..default()
+            })
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(
+                PIXELS_PER_METER,
+            ))
+            .add_startup_system(roll);
+        for _ in 0..600 {
+            app.update();
+        }
+        let mut balls = app
+            .world
+            .query_filtered::<&Velocity, With<GolfBall>>();
+        balls.single(&app.world).linvel.length()
+    }
+
+    #[test]
+    fn balls_roll_to_rest() {
+        let settled = speed_after_rolling(
+            BallDamping::default().angular,
+        );
+        assert!(
+            settled < 1.0,
+            "still rolling at {}",
+            settled
+        );
+
+        // without it nothing slows a rolling ball down
+        let undamped = speed_after_rolling(0.0);
+        assert!(
+            undamped > 10.0,
+            "slowed to {} without angular damping",
+            undamped
+        );
+    }
+}
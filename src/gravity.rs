@@ -0,0 +1,1798 @@
+use bevy::{
+    ecs::system::SystemParam, math::DVec2, prelude::*,
+};
+use bevy_rapier2d::prelude::*;
+
+use particular::ParticleSet;
+use std::time::{Duration, Instant};
+
+use crate::{
+    golf_ball::{GolfBall, PointMass},
+    gravity_budget::GravityBudget,
+    pin::Pinned,
+    undo::ShotHistory,
+    Body,
+};
+
+pub const G: f32 = 1000.0;
+
+/// A gravity source that's nothing else: no collider, no
+/// mesh, and never pulled on itself, like an invisible
+/// focal point or a Lagrange-point attractor in a level.
+/// Bodies that should both pull and be pulled keep using
+/// `PointMass::HasGravity`; an entity with both is only
+/// counted once, as its `PointMass`. A negative mass
+/// pushes instead of pulling.
+#[derive(
+    Component, Reflect, Clone, Copy, Debug, Default,
+)]
+#[reflect(Component)]
+pub struct Attractor {
+    pub mass: f32,
+}
+
+impl Attractor {
+    pub fn mu(&self) -> f32 {
+        self.mass * G
+    }
+}
+
+/// Every entity that pulls, for systems that look gravity
+/// up themselves instead of going through the
+/// `ParticleSet`: each `PointMass`, and each `Attractor`
+/// that isn't also one.
+#[derive(SystemParam)]
+pub struct GravityBodies<'w, 's> {
+    point_masses: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GlobalTransform,
+            &'static PointMass,
+            Option<&'static GolfBall>,
+        ),
+    >,
+    attractors: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GlobalTransform,
+            &'static Attractor,
+        ),
+        Without<PointMass>,
+    >,
+}
+
+impl<'w, 's> GravityBodies<'w, 's> {
+    /// Each body's entity, position, `mu` at [`G`] and
+    /// whether it's a golf ball. Attractors never are.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (Entity, Vec3, f32, bool)> + '_
+    {
+        self.point_masses
+            .iter()
+            .map(|(entity, transform, point_mass, ball)| {
+                (
+                    entity,
+                    transform.translation(),
+                    point_mass.mu(),
+                    ball.is_some(),
+                )
+            })
+            .chain(self.attractors.iter().map(
+                |(entity, transform, attractor)| {
+                    (
+                        entity,
+                        transform.translation(),
+                        attractor.mu(),
+                        false,
+                    )
+                },
+            ))
+    }
+
+    /// Where `entity` is and its `mu` at [`G`], if it
+    /// pulls.
+    pub fn get(
+        &self,
+        entity: Entity,
+    ) -> Option<(Vec3, f32)> {
+        if let Ok((_, transform, point_mass, _)) =
+            self.point_masses.get(entity)
+        {
+            return Some((
+                transform.translation(),
+                point_mass.mu(),
+            ));
+        }
+        self.attractors.get(entity).ok().map(
+            |(_, transform, attractor)| {
+                (transform.translation(), attractor.mu())
+            },
+        )
+    }
+
+    /// The sources pulling in `mode`, scaled by `g_scale`,
+    /// see [`GravitySettings::g_scale`]. Bodies that don't
+    /// pull at all are left out.
+    pub fn sources(
+        &self,
+        mode: GravityMode,
+        g_scale: f32,
+    ) -> Vec<GravitySource> {
+        self.iter()
+            .filter(|(.., ball)| mode.attracts(*ball))
+            .map(|(_, position, mu, _)| GravitySource {
+                position: position.truncate(),
+                mu: mu * g_scale,
+            })
+            .filter(|source| source.mu != 0.0)
+            .collect()
+    }
+}
+
+/// Which bodies pull on the others. Every `PointMass` is
+/// still pulled on either way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum GravityMode {
+    /// every `PointMass::HasGravity` attracts every other
+    /// body
+    #[default]
+    All,
+    /// planets attract balls but balls don't attract each
+    /// other (or the planets). The few planets' pulls are
+    /// summed up for each body directly, skipping
+    /// particular, which is much cheaper with lots of
+    /// balls in play.
+    PlanetsOnly,
+}
+
+/// Whether balls attract each other. Off by default, so
+/// balls are only pulled around by planets and launching
+/// lots of them stays cheap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BallGravity(pub bool);
+
+/// Tuning for how gravity is integrated.
+pub struct GravitySettings {
+    /// The gravitational constant every mass is multiplied
+    /// by. This is the global value, used by every level
+    /// that doesn't set a `g` of its own; see
+    /// [`GravitySettings::g`] for the one in effect.
+    pub g: f32,
+    /// The current level's `Level::g`, set as each level
+    /// loads and cleared again by one without. While set
+    /// it takes precedence over `g`.
+    pub level_g: Option<f32>,
+    /// How many pieces each frame's gravity is split
+    /// into. Bodies are moved through each piece in turn
+    /// with the pull worked out again at every one, which
+    /// keeps fast close flybys from gaining energy. In the
+    /// game [`accelerate_particles`] does the substeps and
+    /// rapier carries each body to where they end up.
+    pub substeps: u32,
+    /// Picks the substeps each frame from how close any
+    /// ball is to a planet instead, see
+    /// [`AdaptiveSubsteps`]. `substeps` is ignored while
+    /// this is set.
+    pub adaptive_substeps: Option<AdaptiveSubsteps>,
+    /// Reuse last frame's gravity while nothing has moved,
+    /// see [`GravityCache`]. Only used without substeps.
+    pub cache: bool,
+    /// The fastest anything can move, `None` for no limit.
+    /// A ball falling straight down a deep well can
+    /// otherwise get fast enough to pass through a planet
+    /// in a single step.
+    pub max_speed: Option<f32>,
+    /// Plummer softening length. Gravity falls off with
+    /// `r² + softening²` instead of `r²`, so it stays
+    /// finite right down to a body's center. 0.0 is plain
+    /// inverse square gravity.
+    pub softening: f32,
+    /// Gravity falls off with `r^falloff_exponent`. 2.0 is
+    /// plain inverse square gravity; 1.0 lets wells reach
+    /// much further out and 3.0 keeps them tight around
+    /// their planets. particular only does inverse square,
+    /// so anything else takes the slower path too.
+    pub falloff_exponent: f32,
+    /// Sources further than this from a body don't pull on
+    /// it at all, `None` for no cutoff. Far off planets
+    /// barely pull anyway, so in sprawling levels this
+    /// bounds what each ball costs for little error.
+    /// Particular can't leave sources out, so it only
+    /// applies in `GravityMode::PlanetsOnly`, where every
+    /// path sums the pulls itself; see
+    /// [`GravitySettings::cutoff`].
+    pub gravity_cutoff: Option<f32>,
+    /// How gravity is handed to rapier, see
+    /// [`GravityPipeline`].
+    pub pipeline: GravityPipeline,
+}
+
+impl Default for GravitySettings {
+    fn default() -> Self {
+        Self {
+            g: G,
+            level_g: None,
+            substeps: 1,
+            adaptive_substeps: None,
+            cache: false,
+            max_speed: Some(3000.0),
+            softening: 0.0,
+            falloff_exponent: 2.0,
+            gravity_cutoff: None,
+            pipeline: GravityPipeline::default(),
+        }
+    }
+}
+
+impl GravitySettings {
+    /// The gravitational constant in effect: the current
+    /// level's if it sets one, otherwise the global `g`.
+    pub fn g(&self) -> f32 {
+        self.level_g.unwrap_or(self.g)
+    }
+
+    /// How much stronger gravity is than [`G`], which
+    /// `PointMass::mu` and `Attractor::mu` are worked out
+    /// with. Anything summing up pulls from those scales
+    /// them by this.
+    pub fn g_scale(&self) -> f32 {
+        self.g() / G
+    }
+
+    /// The [`GravitySettings::gravity_cutoff`] pulls are
+    /// summed up with in `mode`, so the simulation and the
+    /// preview leave out the same sources.
+    pub fn cutoff(&self, mode: GravityMode) -> Option<f32> {
+        match mode {
+            GravityMode::PlanetsOnly => self.gravity_cutoff,
+            GravityMode::All => None,
+        }
+    }
+}
+
+/// Where the pull worked out each frame ends up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum GravityPipeline {
+    /// Every body gets its pull as an `ExternalForce`.
+    #[default]
+    ExternalForce,
+    /// The newest shot's dominant planet pulls it through
+    /// rapier's own gravity, see
+    /// [`hand_gravity_to_rapier`]. Everything else keeps
+    /// its `ExternalForce`.
+    RapierGravity,
+}
+
+/// Moves the pull of the newest shot's dominant planet out
+/// of the ball's `ExternalForce` and into rapier's gravity,
+/// for `GravityPipeline::RapierGravity`. Whatever else
+/// pulls on the ball, the other planets and balls, stays a
+/// force.
+///
+/// Rapier only has the one gravity vector, so only one
+/// ball can ride it. Every other dynamic body gets a
+/// `GravityScale` of zero while it does, and keeps
+/// following its `ExternalForce` alone. Runs after the
+/// forces are worked out each frame.
+#[allow(clippy::too_many_arguments)]
+pub fn hand_gravity_to_rapier(
+    mut commands: Commands,
+    gravity_settings: Res<GravitySettings>,
+    gravity_mode: Res<GravityMode>,
+    particle_set: Res<ParticleSet<Body>>,
+    history: Res<ShotHistory>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    bodies: GravityBodies,
+    mut balls: Query<
+        (
+            &GlobalTransform,
+            &mut ExternalForce,
+            Option<&ColliderMassProperties>,
+        ),
+        With<GolfBall>,
+    >,
+    rigidbodies: Query<(
+        Entity,
+        &RigidBody,
+        Option<&GravityScale>,
+    )>,
+) {
+    let mut global_gravity = Vec2::ZERO;
+    let rider = history.newest().filter(|ball| {
+        gravity_settings.pipeline
+            == GravityPipeline::RapierGravity
+            && matches!(
+                rigidbodies.get(*ball),
+                Ok((_, RigidBody::Dynamic, _))
+            )
+    });
+    if let Some(ball) = rider {
+        let position = balls
+            .get(ball)
+            .map(|(transform, ..)| transform.translation());
+        // another ball can't stand in for the planet
+        let planet = position.ok().and_then(|position| {
+            dominant_body(&particle_set, position)
+                .filter(|planet| !balls.contains(*planet))
+                .and_then(|planet| bodies.get(planet))
+                .map(|planet| (position, planet))
+        });
+        if let Some((position, (center, mu))) = planet {
+            let source = [GravitySource {
+                position: center.truncate(),
+                mu: mu * gravity_settings.g_scale(),
+            }];
+            let pull = cutoff_gravity_at(
+                &source,
+                position.truncate(),
+                gravity_settings.softening,
+                gravity_settings.falloff_exponent,
+                gravity_settings.cutoff(*gravity_mode),
+            );
+            if let Ok((_, mut force, mass_props)) =
+                balls.get_mut(ball)
+            {
+                let mass = match mass_props {
+                    Some(ColliderMassProperties::Mass(
+                        mass,
+                    )) => *mass,
+                    _ => 1.0,
+                };
+                force.force -= pull;
+                global_gravity = pull / mass;
+            }
+        }
+        for (entity, rigidbody, scale) in rigidbodies.iter()
+        {
+            if *rigidbody != RigidBody::Dynamic {
+                continue;
+            }
+            let wanted =
+                if entity == ball { 1.0 } else { 0.0 };
+            if scale.map_or(1.0, |scale| scale.0) != wanted
+            {
+                commands
+                    .entity(entity)
+                    .insert(GravityScale(wanted));
+            }
+        }
+    }
+    if rapier_config.gravity != global_gravity {
+        rapier_config.gravity = global_gravity;
+    }
+}
+
+/// How far a body can drift before the cached gravity is
+/// thrown away.
+const CACHE_EPSILON: f32 = 1e-3;
+
+/// The gravity worked out on the last full solve, along
+/// with the bodies it was worked out for. While every
+/// body is still where it was, for example while aiming
+/// with nothing in flight, the solve can be skipped.
+#[derive(Default)]
+pub struct GravityCache {
+    bodies: Vec<(Entity, Vec3, f32)>,
+    forces: Vec<(Entity, Vec3)>,
+    /// frames that reused the cache
+    pub hits: u64,
+    /// frames that had to solve
+    pub misses: u64,
+    /// how long the last full solve took, which is about
+    /// what each hit saves
+    pub last_solve: Duration,
+}
+
+impl GravityCache {
+    /// Whether `particle_set` holds the same bodies, in
+    /// the same places, as the cached solve.
+    pub fn matches(
+        &self,
+        particle_set: &ParticleSet<Body>,
+    ) -> bool {
+        let mut bodies = self.bodies.iter();
+        particle_set.iter().all(|body| {
+            bodies.next().is_some_and(
+                |(entity, position, mu)| {
+                    *entity == body.entity
+                        && *mu == body.mu
+                        && position.abs_diff_eq(
+                            body.position,
+                            CACHE_EPSILON,
+                        )
+                },
+            )
+        }) && bodies.next().is_none()
+    }
+
+    pub fn store(
+        &mut self,
+        particle_set: &ParticleSet<Body>,
+        forces: Vec<(Entity, Vec3)>,
+        solve_time: Duration,
+    ) {
+        self.bodies = particle_set
+            .iter()
+            .map(|body| {
+                (body.entity, body.position, body.mu)
+            })
+            .collect();
+        self.forces = forces;
+        self.last_solve = solve_time;
+    }
+
+    pub fn forces(&self) -> &[(Entity, Vec3)] {
+        &self.forces
+    }
+}
+
+impl GravityMode {
+    /// Whether a body should act as a gravity source.
+    pub fn attracts(&self, is_ball: bool) -> bool {
+        match self {
+            GravityMode::All => true,
+            GravityMode::PlanetsOnly => !is_ball,
+        }
+    }
+}
+
+/// Something that pulls on everything around it.
+#[derive(Clone, Copy, Debug)]
+pub struct GravitySource {
+    pub position: Vec2,
+    pub mu: f32,
+}
+
+/// The summed gravitational pull at `point`. Sources
+/// sitting exactly on `point` are skipped, which is what
+/// keeps a body from pulling on itself, and so are
+/// massless ones. The pull is always finite, however
+/// close a source is.
+pub fn gravity_at(
+    sources: &[GravitySource],
+    point: Vec2,
+) -> Vec2 {
+    softened_gravity_at(sources, point, 0.0)
+}
+
+/// [`gravity_at`] with a Plummer `softening` length, see
+/// [`GravitySettings::softening`].
+pub fn softened_gravity_at(
+    sources: &[GravitySource],
+    point: Vec2,
+    softening: f32,
+) -> Vec2 {
+    falloff_gravity_at(sources, point, softening, 2.0)
+}
+
+/// [`softened_gravity_at`] falling off with
+/// `r^exponent`, see
+/// [`GravitySettings::falloff_exponent`].
+pub fn falloff_gravity_at(
+    sources: &[GravitySource],
+    point: Vec2,
+    softening: f32,
+    exponent: f32,
+) -> Vec2 {
+    cutoff_gravity_at(
+        sources, point, softening, exponent, None,
+    )
+}
+
+/// [`falloff_gravity_at`] leaving out every source further
+/// than `cutoff` from `point`, see
+/// [`GravitySettings::gravity_cutoff`].
+pub fn cutoff_gravity_at(
+    sources: &[GravitySource],
+    point: Vec2,
+    softening: f32,
+    exponent: f32,
+    cutoff: Option<f32>,
+) -> Vec2 {
+    let cutoff_squared = cutoff
+        .map_or(f32::INFINITY, |cutoff| cutoff * cutoff);
+    sources.iter().fold(Vec2::ZERO, |total, source| {
+        // a massless body pulls on nothing, however close
+        if source.mu == 0.0 {
+            return total;
+        }
+        let offset = source.position - point;
+        let distance_squared = offset.length_squared();
+        if distance_squared == 0.0
+            || distance_squared > cutoff_squared
+        {
+            return total;
+        }
+        let softened =
+            distance_squared + softening * softening;
+        // `offset` carries one power of the distance
+        // already
+        let falloff = softened.powf((exponent + 1.0) / 2.0);
+        // close enough that the falloff underflows, which
+        // would divide by zero
+        if falloff == 0.0 {
+            return total;
+        }
+        total + offset * source.mu / falloff
+    })
+}
+
+/// The gravitational potential at `point` per unit of
+/// mass, `-mu / r`.
+/// Always zero or less, and deepest next to the heaviest
+/// bodies. Sources sitting exactly on `point` are skipped.
+pub fn potential_at(
+    sources: &[GravitySource],
+    point: Vec2,
+) -> f32 {
+    softened_potential_at(sources, point, 0.0)
+}
+
+/// [`potential_at`] with a Plummer `softening` length,
+/// which keeps it finite right at a source's center. The
+/// energy diagnostic and the potential contours both use
+/// it, softened like the pull.
+pub fn softened_potential_at(
+    sources: &[GravitySource],
+    point: Vec2,
+    softening: f32,
+) -> f32 {
+    falloff_potential_at(sources, point, softening, 2.0)
+}
+
+/// The potential behind [`falloff_gravity_at`], so a pull
+/// of `mu / r^n` sits in a well of
+/// `-mu / ((n - 1) r^(n - 1))`. At `n = 1` that's the
+/// `mu ln r` well instead, which is zero a unit away from
+/// the source and climbs without limit past it.
+pub fn falloff_potential_at(
+    sources: &[GravitySource],
+    point: Vec2,
+    softening: f32,
+    exponent: f32,
+) -> f32 {
+    sources
+        .iter()
+        .map(|source| {
+            let softened =
+                (source.position.distance_squared(point)
+                    + softening * softening)
+                    .sqrt();
+            if softened == 0.0 {
+                0.0
+            } else if exponent == 1.0 {
+                source.mu * softened.ln()
+            } else {
+                -source.mu
+                    / ((exponent - 1.0)
+                        * softened.powf(exponent - 1.0))
+            }
+        })
+        .sum()
+}
+
+/// The average pull on a body over the next `dt` seconds,
+/// found by moving it through `substeps` smaller steps.
+/// Gravity is applied as a force, so `mass` scales the
+/// acceleration the same way rapier will. A body without
+/// a positive `mass` isn't moved by it between substeps.
+/// Sources further than `cutoff` are left out, as in
+/// [`cutoff_gravity_at`].
+#[allow(clippy::too_many_arguments)]
+pub fn average_gravity(
+    sources: &[GravitySource],
+    start: Vec2,
+    velocity: Vec2,
+    mass: f32,
+    dt: f32,
+    substeps: u32,
+    softening: f32,
+    falloff_exponent: f32,
+    cutoff: Option<f32>,
+) -> Vec2 {
+    let substeps = substeps.max(1);
+    let step = dt / substeps as f32;
+    let mut position = start;
+    let mut velocity = velocity;
+    let mut total = Vec2::ZERO;
+    for _ in 0..substeps {
+        let gravity = cutoff_gravity_at(
+            sources,
+            position,
+            softening,
+            falloff_exponent,
+            cutoff,
+        );
+        total += gravity;
+        if mass > 0.0 {
+            velocity += gravity / mass * step;
+        }
+        position += velocity * step;
+    }
+    total / substeps as f32
+}
+
+/// Moves a body through `dt` seconds in `substeps` smaller
+/// steps, with the pull worked out again at the start of
+/// each, and returns where it ends up and how fast it's
+/// going then. Each substep kicks the velocity and then
+/// moves the body, as a step of rapier's would. `mass` and
+/// `cutoff` work as in [`average_gravity`].
+///
+/// Stepping like that leaves the velocity half a step
+/// behind the position. The velocity going in and coming
+/// out is half of `dt` behind, as after a single step, so
+/// the number of substeps can change from one frame to the
+/// next without nudging the body off its path.
+#[allow(clippy::too_many_arguments)]
+pub fn substep_gravity(
+    sources: &[GravitySource],
+    start: Vec2,
+    velocity: Vec2,
+    mass: f32,
+    dt: f32,
+    substeps: u32,
+    softening: f32,
+    falloff_exponent: f32,
+    cutoff: Option<f32>,
+) -> (Vec2, Vec2) {
+    let substeps = substeps.max(1);
+    let step = dt / substeps as f32;
+    let acceleration = |position| {
+        if mass > 0.0 {
+            cutoff_gravity_at(
+                sources,
+                position,
+                softening,
+                falloff_exponent,
+                cutoff,
+            ) / mass
+        } else {
+            Vec2::ZERO
+        }
+    };
+    // how much further behind a whole step leaves the
+    // velocity than a substep does
+    let lag = (dt - step) / 2.0;
+    let mut position = start;
+    let mut velocity = velocity + acceleration(start) * lag;
+    for _ in 0..substeps {
+        velocity += acceleration(position) * step;
+        position += velocity * step;
+    }
+    (position, velocity - acceleration(position) * lag)
+}
+
+/// Maps how close the nearest ball is to a planet's center
+/// onto how many substeps a frame's gravity gets. The pull
+/// grows with `1/r²`, so that's what's interpolated
+/// between `far`, where one substep is plenty, and `near`,
+/// which gets all of `max_substeps`. Far-field motion
+/// stays as cheap as without substeps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveSubsteps {
+    pub near: f32,
+    pub far: f32,
+    pub max_substeps: u32,
+}
+
+impl Default for AdaptiveSubsteps {
+    fn default() -> Self {
+        Self {
+            near: 60.0,
+            far: 400.0,
+            max_substeps: 16,
+        }
+    }
+}
+
+impl AdaptiveSubsteps {
+    pub fn substeps(&self, distance: f32) -> u32 {
+        let max = self.max_substeps.max(1);
+        let pull =
+            |r: f32| 1.0 / r.max(f32::EPSILON).powi(2);
+        let (near, far) = (pull(self.near), pull(self.far));
+        if near <= far {
+            return max;
+        }
+        let t = ((pull(distance) - far) / (near - far))
+            .clamp(0.0, 1.0);
+        1 + (t * (max - 1) as f32).round() as u32
+    }
+}
+
+/// The smallest distance from any of `balls` to any of
+/// `planets`, `None` if either is empty.
+pub fn closest_distance(
+    balls: &[Vec2],
+    planets: &[Vec2],
+) -> Option<f32> {
+    balls
+        .iter()
+        .flat_map(|ball| {
+            planets
+                .iter()
+                .map(|planet| ball.distance(*planet))
+        })
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// Works out the pull on every body in the particle set
+/// and hands it to rapier as each body's `ExternalForce`.
+///
+/// With more than one substep, or with softening or a
+/// different falloff, each dynamic body is moved through
+/// the frame's substeps here instead, with the pull worked
+/// out again at each one, see [`substep_gravity`]. Rapier
+/// only steps once a frame and can't work the pull out in
+/// between, so the body's velocity is set to carry it
+/// straight to where the substeps end, and
+/// [`finish_substeps`] gives it the velocity they end with
+/// once rapier has moved it there.
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+pub fn accelerate_particles(
+    mut particle_set: ResMut<ParticleSet<Body>>,
+    gravity_settings: Res<GravitySettings>,
+    mut gravity_cache: ResMut<GravityCache>,
+    time: Res<Time>,
+    rapier_config: Res<RapierConfiguration>,
+    sim_to_render: Res<SimulationToRenderTime>,
+    mut corrections: ResMut<SubstepCorrections>,
+    gravity_mode: Res<GravityMode>,
+    mut gravity_budget: ResMut<GravityBudget>,
+    mut planet_sources: Local<Vec<GravitySource>>,
+    planets: Query<
+        (&GlobalTransform, &PointMass),
+        Without<GolfBall>,
+    >,
+    attractors: Query<
+        (&GlobalTransform, &Attractor),
+        Without<PointMass>,
+    >,
+    mut query: Query<
+        (
+            &mut ExternalForce,
+            Option<&mut Velocity>,
+            Option<&ColliderMassProperties>,
+            Option<&RigidBody>,
+            &GlobalTransform,
+        ),
+        With<PointMass>,
+    >,
+    balls: Query<&GlobalTransform, With<GolfBall>>,
+) {
+    let solve_started = Instant::now();
+    let substeps = match gravity_settings.adaptive_substeps
+    {
+        Some(adaptive) => {
+            let balls: Vec<Vec2> = balls
+                .iter()
+                .map(|ball| ball.translation().truncate())
+                .collect();
+            let planets: Vec<Vec2> = planets
+                .iter()
+                .map(|(planet, _)| {
+                    planet.translation().truncate()
+                })
+                .collect();
+            closest_distance(&balls, &planets)
+                .map_or(1, |distance| {
+                    adaptive.substeps(distance)
+                })
+        }
+        None => gravity_settings.substeps,
+    };
+    // with only a handful of planets pulling, summing their
+    // pulls on each body directly is cheaper than going
+    // through particular
+    if *gravity_mode == GravityMode::PlanetsOnly
+        && substeps <= 1
+    {
+        planet_sources.clear();
+        let g_scale = gravity_settings.g_scale();
+        let source = |transform: &GlobalTransform, mu| {
+            GravitySource {
+                position: transform
+                    .translation()
+                    .truncate(),
+                mu: mu * g_scale,
+            }
+        };
+        planet_sources.extend(
+            planets
+                .iter()
+                .map(|(transform, point_mass)| {
+                    source(transform, point_mass.mu())
+                })
+                .chain(attractors.iter().map(
+                    |(transform, attractor)| {
+                        source(transform, attractor.mu())
+                    },
+                ))
+                // a negative mu pushes, like the gravity
+                // tractor's
+                .filter(|source| source.mu != 0.0),
+        );
+        for (mut acceleration, .., transform) in
+            query.iter_mut()
+        {
+            acceleration.force = cutoff_gravity_at(
+                &planet_sources,
+                transform.translation().truncate(),
+                gravity_settings.softening,
+                gravity_settings.falloff_exponent,
+                gravity_settings.cutoff(*gravity_mode),
+            );
+        }
+    } else if substeps <= 1
+        && gravity_settings.softening <= 0.0
+        && gravity_settings.falloff_exponent == 2.0
+    {
+        // particular can't soften gravity or change how it
+        // falls off, so either always takes the slower path
+        if gravity_settings.cache
+            && gravity_cache.matches(&particle_set)
+        {
+            gravity_cache.hits += 1;
+        } else {
+            let started = Instant::now();
+            let forces = particle_set
+                .result()
+                .map(|(body, gravity)| {
+                    (body.entity, gravity)
+                })
+                .collect();
+            gravity_cache.store(
+                &particle_set,
+                forces,
+                started.elapsed(),
+            );
+            gravity_cache.misses += 1;
+        }
+        for (entity, gravity) in gravity_cache.forces() {
+            if let Ok((mut acceleration, ..)) =
+                query.get_mut(*entity)
+            {
+                acceleration.force = gravity.truncate();
+            }
+        }
+    } else {
+        let dt =
+            frame_dt(&rapier_config, &time, &sim_to_render);
+        let bodies: Vec<(Entity, GravitySource)> =
+            particle_set
+                .iter()
+                .map(|body| {
+                    (
+                        body.entity,
+                        GravitySource {
+                            position: body
+                                .position
+                                .truncate(),
+                            mu: body.mu,
+                        },
+                    )
+                })
+                .collect();
+        for (entity, body) in bodies.iter() {
+            let (
+                mut acceleration,
+                velocity,
+                mass_props,
+                rigidbody,
+                _,
+            ) = match query.get_mut(*entity) {
+                Ok(body) => body,
+                Err(_) => continue,
+            };
+            // the substeps below move the body instead
+            acceleration.force = Vec2::ZERO;
+            let mut velocity = match velocity {
+                Some(velocity)
+                    if dt > 0.0
+                        && rigidbody
+                            == Some(
+                                &RigidBody::Dynamic,
+                            ) =>
+                {
+                    velocity
+                }
+                _ => continue,
+            };
+            // a body moves away from its own entry during
+            // the substeps, so it has to be left out
+            let sources: Vec<GravitySource> = bodies
+                .iter()
+                .filter(|(other, _)| other != entity)
+                .map(|(_, source)| *source)
+                .collect();
+            let mass = match mass_props {
+                Some(ColliderMassProperties::Mass(
+                    mass,
+                )) => *mass,
+                _ => 1.0,
+            };
+            let (end, end_velocity) = substep_gravity(
+                &sources,
+                body.position,
+                velocity.linvel,
+                mass,
+                dt,
+                substeps,
+                gravity_settings.softening,
+                gravity_settings.falloff_exponent,
+                gravity_settings.cutoff(*gravity_mode),
+            );
+            // rapier carries the body along a straight line
+            // this frame, at the speed that lands it where
+            // the substeps did. The velocity they ended on
+            // is put back once it has.
+            let through = (end - body.position) / dt;
+            velocity.linvel = through;
+            corrections
+                .0
+                .push((*entity, end_velocity - through));
+        }
+    }
+    gravity_budget.last_solve = solve_started.elapsed();
+}
+/// The velocity each body moved by [`substep_gravity`]
+/// this frame still has to be given once rapier has
+/// stepped, on top of the one it was carried there with.
+#[derive(Default)]
+pub struct SubstepCorrections(pub Vec<(Entity, Vec2)>);
+
+/// Gives each body [`accelerate_particles`] substepped
+/// the velocity its substeps ended on. Runs after rapier
+/// has stepped. Anything the step itself did to the
+/// velocity, like bouncing off a planet, is kept.
+pub fn finish_substeps(
+    mut corrections: ResMut<SubstepCorrections>,
+    mut velocities: Query<&mut Velocity>,
+) {
+    for (entity, correction) in corrections.0.drain(..) {
+        if let Ok(mut velocity) = velocities.get_mut(entity)
+        {
+            velocity.linvel += correction;
+        }
+    }
+}
+
+/// Gives every ball a gravity of its own while
+/// `BallGravity` is on and takes it away again when it's
+/// turned off. Pinned balls always keep theirs.
+#[allow(clippy::type_complexity)]
+pub fn apply_ball_gravity(
+    ball_gravity: Res<BallGravity>,
+    mut balls: Query<
+        (&mut PointMass, &ColliderMassProperties),
+        (With<GolfBall>, Without<Pinned>),
+    >,
+) {
+    for (mut point_mass, mass_props) in balls.iter_mut() {
+        let wanted = match (ball_gravity.0, mass_props) {
+            (true, ColliderMassProperties::Mass(mass)) => {
+                PointMass::HasGravity { mass: *mass }
+            }
+            _ => PointMass::AffectedByGravity,
+        };
+        // only write on a change so change detection
+        // stays meaningful
+        if *point_mass != wanted {
+            *point_mass = wanted;
+        }
+    }
+}
+
+/// Caps every body's speed at `GravitySettings::max_speed`,
+/// keeping its direction. rapier adds this step's
+/// `ExternalForce` to the velocity before it moves
+/// anything, so the force is cut back too, far enough that
+/// the step it's applied over can't take the body past the
+/// cap either.
+#[allow(clippy::type_complexity)]
+pub fn clamp_speeds(
+    gravity_settings: Res<GravitySettings>,
+    rapier_config: Res<RapierConfiguration>,
+    time: Res<Time>,
+    mut bodies: Query<
+        (
+            &mut Velocity,
+            Option<&mut ExternalForce>,
+            Option<&ColliderMassProperties>,
+        ),
+        With<PointMass>,
+    >,
+) {
+    let max_speed = match gravity_settings.max_speed {
+        Some(max_speed) => max_speed,
+        None => return,
+    };
+    let dt = step_dt(&rapier_config, &time);
+    for (mut velocity, force, mass_props) in
+        bodies.iter_mut()
+    {
+        // only write on a change so change detection
+        // stays meaningful
+        if velocity.linvel.length_squared()
+            > max_speed * max_speed
+        {
+            velocity.linvel =
+                velocity.linvel.clamp_length_max(max_speed);
+        }
+        let mut force = match force {
+            Some(force) if dt > 0.0 => force,
+            _ => continue,
+        };
+        let mass = match mass_props {
+            Some(ColliderMassProperties::Mass(mass)) => {
+                *mass
+            }
+            _ => 1.0,
+        };
+        let after =
+            velocity.linvel + force.force / mass * dt;
+        if after.length_squared() > max_speed * max_speed {
+            force.force = (after
+                .clamp_length_max(max_speed)
+                - velocity.linvel)
+                * mass
+                / dt;
+        }
+    }
+}
+
+/// How far rapier's next step moves the simulation on.
+fn step_dt(
+    rapier_config: &RapierConfiguration,
+    time: &Time,
+) -> f32 {
+    match rapier_config.timestep_mode {
+        TimestepMode::Fixed { dt, .. }
+        | TimestepMode::Interpolated { dt, .. } => dt,
+        TimestepMode::Variable {
+            max_dt,
+            time_scale,
+            ..
+        } => {
+            (time.delta_seconds() * time_scale).min(max_dt)
+        }
+    }
+}
+
+/// How far rapier's steps this frame move the simulation
+/// on altogether. With `TimestepMode::Interpolated` it
+/// takes however many fixed steps it needs to catch up
+/// with the frame, which can be none at all. Only right
+/// before rapier has stepped.
+pub fn frame_dt(
+    rapier_config: &RapierConfiguration,
+    time: &Time,
+    sim_to_render: &SimulationToRenderTime,
+) -> f32 {
+    match rapier_config.timestep_mode {
+        TimestepMode::Interpolated {
+            dt,
+            time_scale,
+            ..
+        } if dt > 0.0 => {
+            // the same catching up rapier's own loop does
+            let mut diff =
+                sim_to_render.diff + time.delta_seconds();
+            let mut steps = 0;
+            while diff > 0.0 {
+                diff -= dt;
+                steps += 1;
+            }
+            steps as f32 * dt * time_scale
+        }
+        _ => step_dt(rapier_config, time),
+    }
+}
+
+/// The body pulling hardest on `position`, ignoring any
+/// body sitting exactly on it.
+pub fn dominant_body(
+    particle_set: &ParticleSet<Body>,
+    position: Vec3,
+) -> Option<Entity> {
+    particle_set
+        .iter()
+        .filter_map(|body| {
+            let distance_squared =
+                body.position.distance_squared(position);
+            (distance_squared > 0.0 && body.mu > 0.0).then(
+                || {
+                    (
+                        body.entity,
+                        body.mu / distance_squared,
+                    )
+                },
+            )
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
+
+/// The strength of a body's gravity at its surface. Balls
+/// feel this divided by their own mass, since gravity is
+/// applied to them as a force.
+pub fn surface_gravity(mu: f32, radius: f32) -> f32 {
+    if radius <= 0.0 {
+        return 0.0;
+    }
+    mu / (radius * radius)
+}
+
+/// A body stepped by [`step_gravity`], for running the
+/// simulation without an `App`.
+#[derive(Clone, Debug)]
+pub struct SimBody {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// the mass rapier would give the body's collider
+    pub mass: f32,
+    pub point_mass: PointMass,
+    /// whether it's a `GolfBall`, which
+    /// `GravityMode::PlanetsOnly` stops pulling on anything
+    pub golf_ball: bool,
+}
+
+/// How [`step_gravity`] moves bodies along.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Integrator {
+    /// What rapier does with an `ExternalForce`: the
+    /// velocity is updated first, then the position with
+    /// the new velocity. Orbits slowly drift.
+    #[default]
+    SemiImplicitEuler,
+    /// Velocity Verlet. Solves gravity twice per step but
+    /// holds on to a long orbit's energy much better.
+    Verlet,
+}
+
+/// What [`step_gravity`] and [`run_gravity`] solve
+/// gravity in. [`SimBody`]s are kept in `f32` either way,
+/// since that's what bevy and rapier use. With
+/// [`Precision::F64`], `step_gravity` converts positions
+/// and masses up for the solve and only the resulting
+/// accelerations come back down, which cuts the rounding
+/// error of summing up many small pulls; `run_gravity`
+/// keeps the bodies in `f64` for the whole run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// through the same `ParticleSet` the game uses
+    #[default]
+    F32,
+    /// summed up directly, since particular only does
+    /// `f32`
+    F64,
+}
+
+/// How many substeps [`step_gravity`] splits a step of
+/// bodies at `positions` into, picked the way
+/// `accelerate_particles` picks them. Each position comes
+/// with whether it's a golf ball's.
+fn sim_substeps(
+    positions: impl Iterator<Item = (Vec2, bool)>,
+    settings: &GravitySettings,
+) -> u32 {
+    let adaptive = match settings.adaptive_substeps {
+        Some(adaptive) => adaptive,
+        None => return settings.substeps.max(1),
+    };
+    let (balls, planets): (Vec<_>, Vec<_>) =
+        positions.partition(|(_, golf_ball)| *golf_ball);
+    let positions = |bodies: Vec<(Vec2, bool)>| {
+        bodies
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect::<Vec<Vec2>>()
+    };
+    closest_distance(&positions(balls), &positions(planets))
+        .map_or(1, |distance| adaptive.substeps(distance))
+}
+
+/// The acceleration gravity gives each of `bodies`, by
+/// index, solved the same way `accelerate_particles`
+/// solves it in the game without substeps: the planets'
+/// pulls summed up directly in `GravityMode::PlanetsOnly`,
+/// or the `ParticleSet` (through `cache` when
+/// [`GravitySettings::cache`] is on). particular has no
+/// softening and only does inverse square gravity, so
+/// either sums the pulls up directly too.
+fn sim_accelerations(
+    particle_set: &mut ParticleSet<Body>,
+    cache: &mut GravityCache,
+    bodies: &[SimBody],
+    mode: GravityMode,
+    settings: &GravitySettings,
+    precision: Precision,
+) -> Vec<Vec2> {
+    if precision == Precision::F64 {
+        return sim_accelerations_f64(
+            bodies, mode, settings,
+        );
+    }
+    let g_scale = settings.g_scale();
+    let sources: Vec<GravitySource> = bodies
+        .iter()
+        .map(|body| GravitySource {
+            position: body.position,
+            mu: if mode.attracts(body.golf_ball) {
+                body.point_mass.mu() * g_scale
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    if mode == GravityMode::PlanetsOnly {
+        let planets: Vec<GravitySource> = sources
+            .iter()
+            .filter(|source| source.mu != 0.0)
+            .copied()
+            .collect();
+        return bodies
+            .iter()
+            .map(|body| {
+                cutoff_gravity_at(
+                    &planets,
+                    body.position,
+                    settings.softening,
+                    settings.falloff_exponent,
+                    settings.cutoff(mode),
+                ) / body.mass
+            })
+            .collect();
+    }
+    if settings.softening <= 0.0
+        && settings.falloff_exponent == 2.0
+    {
+        *particle_set = ParticleSet::new();
+        for (index, source) in sources.iter().enumerate() {
+            particle_set.add(Body::new(
+                source.position.extend(0.0),
+                source.mu,
+                Entity::from_raw(index as u32),
+            ));
+        }
+        if settings.cache && cache.matches(particle_set) {
+            cache.hits += 1;
+        } else {
+            let started = Instant::now();
+            let forces = particle_set
+                .result()
+                .map(|(body, gravity)| {
+                    (body.entity, gravity)
+                })
+                .collect();
+            cache.store(
+                particle_set,
+                forces,
+                started.elapsed(),
+            );
+            cache.misses += 1;
+        }
+        let mut accelerations =
+            vec![Vec2::ZERO; bodies.len()];
+        for (entity, gravity) in cache.forces() {
+            let index = entity.id() as usize;
+            // gravity is applied as a force, same as in
+            // rapier
+            accelerations[index] =
+                gravity.truncate() / bodies[index].mass;
+        }
+        return accelerations;
+    }
+    bodies
+        .iter()
+        .map(|body| {
+            // a body's own entry sits right on it, so it
+            // doesn't pull on itself
+            cutoff_gravity_at(
+                &sources,
+                body.position,
+                settings.softening,
+                settings.falloff_exponent,
+                settings.cutoff(mode),
+            ) / body.mass
+        })
+        .collect()
+}
+
+/// A [`SimBody`] converted up to `f64`, with the `mu` it
+/// pulls with worked out for the `GravityMode`.
+#[derive(Clone, Copy)]
+struct SimBodyF64 {
+    position: DVec2,
+    velocity: DVec2,
+    mass: f64,
+    mu: f64,
+    golf_ball: bool,
+}
+
+impl SimBodyF64 {
+    fn new(
+        body: &SimBody,
+        mode: GravityMode,
+        settings: &GravitySettings,
+    ) -> Self {
+        let mu = match body.point_mass {
+            PointMass::HasGravity { mass }
+                if mode.attracts(body.golf_ball) =>
+            {
+                mass as f64 * settings.g() as f64
+            }
+            _ => 0.0,
+        };
+        Self {
+            position: body.position.as_dvec2(),
+            velocity: body.velocity.as_dvec2(),
+            mass: body.mass as f64,
+            mu,
+            golf_ball: body.golf_ball,
+        }
+    }
+}
+
+/// The acceleration gravity gives each of `bodies`, by
+/// index, summed up directly in `f64`, without the cache.
+fn accelerations_f64(
+    bodies: &[SimBodyF64],
+    mode: GravityMode,
+    settings: &GravitySettings,
+) -> Vec<DVec2> {
+    let softening = settings.softening as f64;
+    let power =
+        (settings.falloff_exponent as f64 + 1.0) / 2.0;
+    let cutoff_squared = settings
+        .cutoff(mode)
+        .map_or(f64::INFINITY, |cutoff| {
+            cutoff as f64 * cutoff as f64
+        });
+    bodies
+        .iter()
+        .map(|body| {
+            let gravity = bodies.iter().fold(
+                DVec2::ZERO,
+                |total, source| {
+                    if source.mu == 0.0 {
+                        return total;
+                    }
+                    let offset =
+                        source.position - body.position;
+                    let distance_squared =
+                        offset.length_squared();
+                    // itself, or something right on top
+                    if distance_squared == 0.0
+                        || distance_squared > cutoff_squared
+                    {
+                        return total;
+                    }
+                    let softened = distance_squared
+                        + softening * softening;
+                    total
+                        + offset * source.mu
+                            / softened.powf(power)
+                },
+            );
+            // gravity is applied as a force, same as in
+            // rapier
+            gravity / body.mass
+        })
+        .collect()
+}
+
+/// [`sim_accelerations`] worked out in `f64`.
+fn sim_accelerations_f64(
+    bodies: &[SimBody],
+    mode: GravityMode,
+    settings: &GravitySettings,
+) -> Vec<Vec2> {
+    let bodies: Vec<SimBodyF64> = bodies
+        .iter()
+        .map(|body| SimBodyF64::new(body, mode, settings))
+        .collect();
+    accelerations_f64(&bodies, mode, settings)
+        .into_iter()
+        .map(|acceleration| acceleration.as_vec2())
+        .collect()
+}
+
+/// Caps `velocity` at [`GravitySettings::max_speed`] the
+/// way [`clamp_speeds`] does in the game.
+fn clamp_sim_speed(
+    velocity: Vec2,
+    settings: &GravitySettings,
+) -> Vec2 {
+    match settings.max_speed {
+        Some(max_speed) => {
+            velocity.clamp_length_max(max_speed)
+        }
+        None => velocity,
+    }
+}
+
+/// Advance `bodies` by `dt` seconds with no window, no
+/// rapier and no collisions. Gravity is solved the same way
+/// the game solves it for `mode` and `settings`, down to
+/// the level's `g`, the cache, softening, falloff, the
+/// cutoff and the speed limit, and is applied as a force.
+/// With [`Integrator::SemiImplicitEuler`] it's then
+/// integrated the way rapier does, so without substeps
+/// this follows the game's own path. With them the step
+/// is split up and bodies are moved through each piece,
+/// see [`GravitySettings::substeps`]. The game itself
+/// always goes through rapier, so [`Integrator::Verlet`]
+/// is only for headless runs. `precision` picks what
+/// gravity is solved in, and `cache` is only used with
+/// [`GravitySettings::cache`] on.
+#[allow(clippy::too_many_arguments)]
+pub fn step_gravity(
+    particle_set: &mut ParticleSet<Body>,
+    cache: &mut GravityCache,
+    bodies: &mut [SimBody],
+    dt: f32,
+    integrator: Integrator,
+    mode: GravityMode,
+    settings: &GravitySettings,
+    precision: Precision,
+) {
+    let substeps = sim_substeps(
+        bodies
+            .iter()
+            .map(|body| (body.position, body.golf_ball)),
+        settings,
+    );
+    let dt = dt / substeps as f32;
+    let mut solve = |bodies: &[SimBody]| {
+        sim_accelerations(
+            particle_set,
+            cache,
+            bodies,
+            mode,
+            settings,
+            precision,
+        )
+    };
+    for _ in 0..substeps {
+        let accelerations = solve(bodies);
+        match integrator {
+            Integrator::SemiImplicitEuler => {
+                for (body, acceleration) in
+                    bodies.iter_mut().zip(accelerations)
+                {
+                    body.velocity = clamp_sim_speed(
+                        body.velocity + acceleration * dt,
+                        settings,
+                    );
+                    body.position += body.velocity * dt;
+                }
+            }
+            Integrator::Verlet => {
+                for (body, acceleration) in
+                    bodies.iter_mut().zip(&accelerations)
+                {
+                    body.position += body.velocity * dt
+                        + 0.5 * *acceleration * dt * dt;
+                }
+                let next = solve(bodies);
+                for ((body, acceleration), next) in bodies
+                    .iter_mut()
+                    .zip(accelerations)
+                    .zip(next)
+                {
+                    body.velocity = clamp_sim_speed(
+                        body.velocity
+                            + 0.5
+                                * (acceleration + next)
+                                * dt,
+                        settings,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// The patched-conic sphere of influence of the body at
+/// `position`: how far out its own gravity matters more
+/// than any heavier body's. Against each heavier body this
+/// is `d * (mu / mu_heavier)^(2/5)`, and the smallest of
+/// those wins. The heaviest body has no heavier neighbour
+/// so its sphere never ends, which comes back as `None`.
+pub fn sphere_of_influence(
+    particle_set: &ParticleSet<Body>,
+    position: Vec3,
+    mu: f32,
+) -> Option<f32> {
+    if mu <= 0.0 {
+        return Some(0.0);
+    }
+    particle_set
+        .iter()
+        .filter(|body| body.mu > mu)
+        .map(|body| {
+            body.position.distance(position)
+                * (mu / body.mu).powf(0.4)
+        })
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// [`step_gravity`] `steps` times over. With
+/// [`Precision::F64`] the bodies are converted up once,
+/// stepped entirely in `f64` and only rounded back down to
+/// `f32` at the end, so a long run doesn't pick up `f32`
+/// rounding on every step the way stepping it one call at
+/// a time does. That matters most far from the origin,
+/// where an `f32` position only has room for a few
+/// decimal places.
+#[allow(clippy::too_many_arguments)]
+pub fn run_gravity(
+    particle_set: &mut ParticleSet<Body>,
+    cache: &mut GravityCache,
+    bodies: &mut [SimBody],
+    dt: f32,
+    steps: usize,
+    integrator: Integrator,
+    mode: GravityMode,
+    settings: &GravitySettings,
+    precision: Precision,
+) {
+    if precision == Precision::F32 {
+        for _ in 0..steps {
+            step_gravity(
+                particle_set,
+                cache,
+                bodies,
+                dt,
+                integrator,
+                mode,
+                settings,
+                precision,
+            );
+        }
+        return;
+    }
+    let mut sim: Vec<SimBodyF64> = bodies
+        .iter()
+        .map(|body| SimBodyF64::new(body, mode, settings))
+        .collect();
+    let dt = dt as f64;
+    let clamp = |velocity: DVec2| match settings.max_speed {
+        Some(max_speed) => {
+            velocity.clamp_length_max(max_speed as f64)
+        }
+        None => velocity,
+    };
+    for _ in 0..steps {
+        let substeps = sim_substeps(
+            sim.iter().map(|body| {
+                (body.position.as_vec2(), body.golf_ball)
+            }),
+            settings,
+        );
+        let dt = dt / substeps as f64;
+        for _ in 0..substeps {
+            let accelerations =
+                accelerations_f64(&sim, mode, settings);
+            match integrator {
+                Integrator::SemiImplicitEuler => {
+                    for (body, acceleration) in
+                        sim.iter_mut().zip(accelerations)
+                    {
+                        body.velocity = clamp(
+                            body.velocity
+                                + acceleration * dt,
+                        );
+                        body.position += body.velocity * dt;
+                    }
+                }
+                Integrator::Verlet => {
+                    for (body, acceleration) in
+                        sim.iter_mut().zip(&accelerations)
+                    {
+                        body.position += body.velocity * dt
+                            + 0.5 * *acceleration * dt * dt;
+                    }
+                    let next = accelerations_f64(
+                        &sim, mode, settings,
+                    );
+                    for ((body, acceleration), next) in sim
+                        .iter_mut()
+                        .zip(accelerations)
+                        .zip(next)
+                    {
+                        body.velocity = clamp(
+                            body.velocity
+                                + 0.5
+                                    * (acceleration + next)
+                                    * dt,
+                        );
+                    }
+                }
+            }
+        }
+    }
+    for (body, sim) in bodies.iter_mut().zip(sim) {
+        body.position = sim.position.as_vec2();
+        body.velocity = sim.velocity.as_vec2();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ball(x: f32, golf_ball: bool) -> SimBody {
+        SimBody {
+            position: Vec2::new(x, 0.0),
+            velocity: Vec2::ZERO,
+            mass: 1.0,
+            point_mass: PointMass::HasGravity {
+                mass: 100.0,
+            },
+            golf_ball,
+        }
+    }
+
+    fn step(
+        bodies: &mut [SimBody],
+        mode: GravityMode,
+        settings: &GravitySettings,
+    ) {
+        step_gravity(
+            &mut ParticleSet::new(),
+            &mut GravityCache::default(),
+            bodies,
+            1.0 / 60.0,
+            Integrator::SemiImplicitEuler,
+            mode,
+            settings,
+            Precision::F32,
+        );
+    }
+
+    #[test]
+    fn planets_only_stops_balls_pulling() {
+        let settings = GravitySettings::default();
+        let mut all = [ball(0.0, true), ball(50.0, true)];
+        step(&mut all, GravityMode::All, &settings);
+        assert!(all[0].velocity.x > 0.0);
+        assert!(all[1].velocity.x < 0.0);
+
+        for settings in [
+            GravitySettings::default(),
+            GravitySettings {
+                substeps: 4,
+                ..default()
+            },
+        ] {
+            let mut balls =
+                [ball(0.0, true), ball(50.0, true)];
+            step(
+                &mut balls,
+                GravityMode::PlanetsOnly,
+                &settings,
+            );
+            assert!(balls
+                .iter()
+                .all(|ball| ball.velocity == Vec2::ZERO));
+        }
+
+        // and planets still pull on balls
+        let mut bodies =
+            [ball(0.0, false), ball(50.0, true)];
+        step(
+            &mut bodies,
+            GravityMode::PlanetsOnly,
+            &settings,
+        );
+        assert_eq!(bodies[0].velocity, Vec2::ZERO);
+        assert!(bodies[1].velocity.x < 0.0);
+    }
+
+    #[test]
+    fn the_level_g_scales_the_step() {
+        let pull = |level_g| {
+            let mut bodies =
+                [ball(0.0, false), ball(50.0, true)];
+            step(
+                &mut bodies,
+                GravityMode::All,
+                &GravitySettings {
+                    level_g,
+                    ..default()
+                },
+            );
+            bodies[1].velocity.x
+        };
+        let doubled = pull(Some(2.0 * G)) / pull(None);
+        assert!(
+            (doubled - 2.0).abs() < 1e-4,
+            "{}",
+            doubled
+        );
+    }
+
+    #[test]
+    fn softening_keeps_gravity_finite_near_a_center() {
+        let sources = [GravitySource {
+            position: Vec2::ZERO,
+            mu: 1000.0,
+        }];
+        let softening = 5.0;
+        let pull = |r: f32, softening| {
+            softened_gravity_at(
+                &sources,
+                Vec2::new(r, 0.0),
+                softening,
+            )
+            .length()
+        };
+        // plain inverse square blows up as r -> 0, softened
+        // gravity never gets past mu / softening²
+        for r in [1.0, 1E-2, 1E-4] {
+            assert!(
+                pull(r, 0.0) >= 1000.0 / (r * r) * 0.99
+            );
+            assert!(
+                pull(r, softening)
+                    <= 1000.0 / (softening * softening)
+            );
+        }
+        assert!(pull(1E-4, 0.0) > 1E10);
+        // and it shrinks to nothing right at the center
+        // instead of flipping direction
+        assert!(
+            pull(1E-4, softening) < pull(1.0, softening)
+        );
+
+        // far out the two agree
+        let far = pull(1000.0, 0.0);
+        assert!(
+            (pull(1000.0, softening) - far).abs() / far
+                < 1E-4
+        );
+    }
+
+    #[test]
+    fn the_cache_reuses_a_solve_while_nothing_moves() {
+        let settings = GravitySettings {
+            cache: true,
+            ..default()
+        };
+        let mut cache = GravityCache::default();
+        let mut particle_set = ParticleSet::new();
+        // two planets sitting still, pulling on each other
+        // through masses too heavy to move
+        let planet = |x: f32| SimBody {
+            mass: f32::MAX,
+            ..ball(x, false)
+        };
+        let mut bodies = [planet(0.0), planet(50.0)];
+        for _ in 0..3 {
+            step_gravity(
+                &mut particle_set,
+                &mut cache,
+                &mut bodies,
+                1.0 / 60.0,
+                Integrator::SemiImplicitEuler,
+                GravityMode::All,
+                &settings,
+                Precision::F32,
+            );
+        }
+        assert_eq!(cache.misses, 1);
+        assert_eq!(cache.hits, 2);
+    }
+}
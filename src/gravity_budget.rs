@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use particular::ParticleSet;
+use std::time::Duration;
+
+use crate::{gravity::GravityMode, Body};
+
+/// Keeps the gravity solve inside a frame budget on slow
+/// machines. When the solve runs over `target` for
+/// `patience` frames in a row with every body pulling, the
+/// `GravityMode` drops to `PlanetsOnly`, which only sums
+/// up the planets' pulls. Once the bodies in play are down
+/// to `restore_fraction` of what there were when it
+/// dropped, `GravityMode::All` comes back. Both changes
+/// are logged.
+///
+/// Off unless `enabled` is set. Turning it off while
+/// degraded puts the mode back straight away.
+pub struct GravityBudget {
+    pub enabled: bool,
+    pub target: Duration,
+    pub patience: u32,
+    pub restore_fraction: f32,
+    /// how long the last frame's gravity took, set by the
+    /// solve itself
+    pub last_solve: Duration,
+    over_budget: u32,
+    /// the bodies in play when the mode was dropped, while
+    /// it's still dropped
+    degraded_at: Option<usize>,
+}
+
+impl Default for GravityBudget {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: Duration::from_millis(4),
+            patience: 5,
+            restore_fraction: 0.75,
+            last_solve: Duration::ZERO,
+            over_budget: 0,
+            degraded_at: None,
+        }
+    }
+}
+
+impl GravityBudget {
+    /// Whether the mode has been dropped to stay in budget.
+    pub fn is_degraded(&self) -> bool {
+        self.degraded_at.is_some()
+    }
+}
+
+pub struct GravityBudgetPlugin;
+
+impl Plugin for GravityBudgetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GravityBudget>()
+            .add_system(keep_gravity_in_budget);
+    }
+}
+
+fn keep_gravity_in_budget(
+    mut budget: ResMut<GravityBudget>,
+    mut gravity_mode: ResMut<GravityMode>,
+    particle_set: Res<ParticleSet<Body>>,
+) {
+    let bodies = particle_set.iter().count();
+    if let Some(degraded_at) = budget.degraded_at {
+        let recovered = bodies as f32
+            <= degraded_at as f32 * budget.restore_fraction;
+        if !budget.enabled || recovered {
+            info!(
+                "gravity budget: back to every body \
+                 pulling, {} bodies",
+                bodies
+            );
+            *gravity_mode = GravityMode::All;
+            budget.degraded_at = None;
+            budget.over_budget = 0;
+        }
+        return;
+    }
+    if !budget.enabled || *gravity_mode != GravityMode::All
+    {
+        budget.over_budget = 0;
+        return;
+    }
+    if budget.last_solve <= budget.target {
+        budget.over_budget = 0;
+        return;
+    }
+    budget.over_budget += 1;
+    if budget.over_budget < budget.patience {
+        return;
+    }
+    info!(
+        "gravity budget: solve took {:.1}ms of {:.1}ms \
+         with {} bodies, planets only",
+        budget.last_solve.as_secs_f32() * 1000.0,
+        budget.target.as_secs_f32() * 1000.0,
+        bodies
+    );
+    *gravity_mode = GravityMode::PlanetsOnly;
+    budget.degraded_at = Some(bodies);
+    budget.over_budget = 0;
+}
@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_prototype_debug_lines::DebugLines;
+
+use crate::{
+    menu::playing, split_screen::PlayerTwoCamera,
+    thumbnail::ThumbnailCamera,
+};
+
+/// A world-space grid for lining up levels, toggled
+/// with `G`.
+pub struct GridOverlay {
+    pub spacing: f32,
+    pub enabled: bool,
+}
+
+impl Default for GridOverlay {
+    fn default() -> Self {
+        Self {
+            spacing: 100.0,
+            enabled: false,
+        }
+    }
+}
+
+#[derive(Component)]
+struct GridLabel;
+
+#[derive(Component)]
+struct MouseReadout;
+
+pub struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GridOverlay>()
+            .add_startup_system(spawn_mouse_readout)
+            .add_system(
+                toggle_grid.with_run_criteria(playing),
+            )
+            .add_system(draw_grid.after(toggle_grid))
+            .add_system(
+                update_mouse_readout.after(toggle_grid),
+            );
+    }
+}
+
+fn toggle_grid(
+    keyboard: Res<Input<KeyCode>>,
+    mut grid: ResMut<GridOverlay>,
+) {
+    if keyboard.just_pressed(KeyCode::G) {
+        grid.enabled = !grid.enabled;
+    }
+}
+
+/// The world-space grid lines that are currently on
+/// screen, as `(min, max)` multiples of the spacing.
+fn visible_cells(
+    windows: &Windows,
+    camera: (&GlobalTransform, &OrthographicProjection),
+    spacing: f32,
+) -> Option<(IVec2, IVec2)> {
+    let window = windows.get_primary()?;
+    let (transform, projection) = camera;
+    let half_size =
+        Vec2::new(window.width(), window.height())
+            * projection.scale
+            / 2.0;
+    let center = transform.translation().truncate();
+    let min = ((center - half_size) / spacing).floor();
+    let max = ((center + half_size) / spacing).ceil();
+    Some((min.as_ivec2(), max.as_ivec2()))
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn draw_grid(
+    mut commands: Commands,
+    mut lines: ResMut<DebugLines>,
+    grid: Res<GridOverlay>,
+    windows: Res<Windows>,
+    asset_server: Res<AssetServer>,
+    cameras: Query<
+        (&GlobalTransform, &OrthographicProjection),
+        (
+            With<Camera2d>,
+            Without<ThumbnailCamera>,
+            Without<PlayerTwoCamera>,
+        ),
+    >,
+    labels: Query<Entity, With<GridLabel>>,
+    mut last_cells: Local<Option<(IVec2, IVec2)>>,
+) {
+    let cells = if grid.enabled {
+        cameras.get_single().ok().and_then(|camera| {
+            visible_cells(&windows, camera, grid.spacing)
+        })
+    } else {
+        None
+    };
+
+    // labels only need rebuilding when the visible part
+    // of the grid changes.
+    if *last_cells != cells || grid.is_changed() {
+        for entity in labels.iter() {
+            commands.entity(entity).despawn();
+        }
+        if let Some((min, max)) = cells {
+            let style = TextStyle {
+                font: asset_server
+                    .load("fonts/DejaVuSansMono.ttf"),
+                font_size: 14.0,
+                color: Color::rgba(1.0, 1.0, 1.0, 0.5),
+            };
+            let mut label = |value: f32, position: Vec2| {
+                commands
+                    .spawn_bundle(Text2dBundle {
+                        text: Text::from_section(
+                            format!("{value}"),
+                            style.clone(),
+                        ),
+                        transform:
+                            Transform::from_translation(
+                                position.extend(5.0),
+                            ),
+                        ..default()
+                    })
+                    .insert(GridLabel);
+            };
+            for x in min.x..=max.x {
+                let x = x as f32 * grid.spacing;
+                label(x, Vec2::new(x, 0.0));
+            }
+            for y in min.y..=max.y {
+                if y == 0 {
+                    continue;
+                }
+                let y = y as f32 * grid.spacing;
+                label(y, Vec2::new(0.0, y));
+            }
+        }
+        *last_cells = cells;
+    }
+
+    let (min, max) = match cells {
+        Some(cells) => cells,
+        None => return,
+    };
+    let min_world = min.as_vec2() * grid.spacing;
+    let max_world = max.as_vec2() * grid.spacing;
+    for x in min.x..=max.x {
+        let color = if x == 0 {
+            Color::rgba(1.0, 1.0, 1.0, 0.5)
+        } else {
+            Color::rgba(1.0, 1.0, 1.0, 0.15)
+        };
+        let x = x as f32 * grid.spacing;
+        lines.line_colored(
+            Vec3::new(x, min_world.y, 0.0),
+            Vec3::new(x, max_world.y, 0.0),
+            0.0,
+            color,
+        );
+    }
+    for y in min.y..=max.y {
+        let color = if y == 0 {
+            Color::rgba(1.0, 1.0, 1.0, 0.5)
+        } else {
+            Color::rgba(1.0, 1.0, 1.0, 0.15)
+        };
+        let y = y as f32 * grid.spacing;
+        lines.line_colored(
+            Vec3::new(min_world.x, y, 0.0),
+            Vec3::new(max_world.x, y, 0.0),
+            0.0,
+            color,
+        );
+    }
+}
+
+fn spawn_mouse_readout(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: asset_server
+                        .load("fonts/DejaVuSansMono.ttf"),
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(MouseReadout);
+}
+
+fn update_mouse_readout(
+    grid: Res<GridOverlay>,
+    mouse_pos: Res<MousePosWorld>,
+    mut readout: Query<
+        (&mut Text, &mut Visibility),
+        With<MouseReadout>,
+    >,
+) {
+    for (mut text, mut visibility) in readout.iter_mut() {
+        visibility.is_visible = grid.enabled;
+        if grid.enabled {
+            text.sections[0].value = format!(
+                "x: {:.1} y: {:.1}",
+                mouse_pos.x, mouse_pos.y
+            );
+        }
+    }
+}
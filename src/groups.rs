@@ -0,0 +1,36 @@
+//! Rapier collision group bits for everything the game
+//! spawns. Override a body's `CollisionGroups` after
+//! spawning it to change what it collides with.
+
+pub const BALL_GROUP: u32 = 1 << 0;
+pub const PLANET_GROUP: u32 = 1 << 1;
+pub const HOLE_GROUP: u32 = 1 << 2;
+/// purely visual bodies that shouldn't touch anything
+pub const DECORATION_GROUP: u32 = 1 << 3;
+/// the edges of the world, see `WorldBounds`
+pub const WALL_GROUP: u32 = 1 << 4;
+/// practice balls, which stay out of the way of the balls
+/// that count, see `Practice`
+pub const PRACTICE_GROUP: u32 = 1 << 5;
+
+/// the lowest of the eight bits kept for `PhaseMask`
+/// layers, which phased planets are in instead of
+/// `PLANET_GROUP`
+pub const PHASE_SHIFT: u32 = 8;
+pub const PHASE_GROUPS: u32 = 0xff << PHASE_SHIFT;
+
+/// balls hit planets, the hole, walls and each other
+pub const BALL_FILTER: u32 = BALL_GROUP
+    | PLANET_GROUP
+    | HOLE_GROUP
+    | WALL_GROUP
+    | PHASE_GROUPS;
+/// practice balls hit planets, walls and each other, but
+/// pass through the holes and the balls that count
+pub const PRACTICE_FILTER: u32 = PRACTICE_GROUP
+    | PLANET_GROUP
+    | WALL_GROUP
+    | PHASE_GROUPS;
+pub const PLANET_FILTER: u32 = BALL_GROUP | PRACTICE_GROUP;
+pub const HOLE_FILTER: u32 = BALL_GROUP;
+pub const WALL_FILTER: u32 = BALL_GROUP | PRACTICE_GROUP;
@@ -0,0 +1,199 @@
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    course::Filled,
+    forces::{AddForceModifier, BallState, ForceModifier},
+    groups::{HOLE_FILTER, HOLE_GROUP},
+    menu::playing,
+};
+
+/// The target every shot is aiming for. It's a sensor, so
+/// balls pass over it instead of bouncing off.
+///
+/// Level authors: a hole only ever detects overlap, it
+/// never pushes back. Anything that should stop a ball,
+/// like a rim around the hole, has to be a separate solid
+/// body such as a planet. Spawn holes with [`spawn_hole`],
+/// which makes them a `Sensor`: a solid hole deflects
+/// balls instead of taking them.
+#[derive(
+    Component,
+    Reflect,
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
+#[reflect(Component)]
+pub struct Hole {
+    pub radius: f32,
+}
+
+impl Default for Hole {
+    fn default() -> Self {
+        Self { radius: 10.0 }
+    }
+}
+
+impl Hole {
+    /// Whether a ball of `ball_radius` whose center is
+    /// `distance` from the hole's is far enough over it to
+    /// drop in. `capture_fraction` is how much of the ball
+    /// has to be over the hole, across its diameter: 1.0
+    /// for all of it, 0.5 for its center and 0.0 for just
+    /// touching. With all of it a ball bigger than the
+    /// hole can never drop in.
+    pub fn captures(
+        &self,
+        distance: f32,
+        ball_radius: f32,
+        capture_fraction: f32,
+    ) -> bool {
+        let fraction = capture_fraction.clamp(0.0, 1.0);
+        distance
+            <= self.radius
+                + ball_radius * (1.0 - 2.0 * fraction)
+    }
+}
+
+pub fn spawn_hole(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    position: Vec2,
+    hole: Hole,
+) -> Entity {
+    commands
+        .spawn_bundle(MaterialMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::from(shape::Circle {
+                    radius: hole.radius,
+                    ..default()
+                }))
+                .into(),
+            transform: Transform::from_translation(
+                position.extend(2.0),
+            ),
+            material: materials
+                .add(ColorMaterial::from(Color::DARK_GRAY)),
+            ..default()
+        })
+        .insert(Collider::ball(hole.radius))
+        .insert(Sensor)
+        .insert(CollisionGroups::new(
+            HOLE_GROUP,
+            HOLE_FILTER,
+        ))
+        .insert(hole)
+        .id()
+}
+
+/// An optional assist for casual play: a ball that comes
+/// within `radius` of an open hole gets pulled towards its
+/// center, strongest right at the hole. Toggled with `H`
+/// and off by default so scores stay comparable.
+#[derive(Clone, Copy, Debug)]
+pub struct HoleAssist {
+    pub enabled: bool,
+    pub radius: f32,
+    /// acceleration towards the hole at its center
+    pub strength: f32,
+}
+
+impl Default for HoleAssist {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 60.0,
+            strength: 200.0,
+        }
+    }
+}
+
+impl HoleAssist {
+    /// The extra force on a ball of `mass` at `position`.
+    pub fn force(
+        &self,
+        holes: &[Vec2],
+        position: Vec2,
+        mass: f32,
+    ) -> Vec2 {
+        holes.iter().fold(Vec2::ZERO, |total, hole| {
+            let offset = *hole - position;
+            let distance = offset.length();
+            if distance >= self.radius || distance == 0.0 {
+                return total;
+            }
+            let falloff = 1.0 - distance / self.radius;
+            total
+                + offset / distance
+                    * self.strength
+                    * falloff
+                    * mass
+        })
+    }
+}
+
+/// Applies the `HoleAssist` while it's turned on.
+#[derive(Default)]
+pub struct HoleAssistForce {
+    assist: Option<HoleAssist>,
+    holes: Vec<Vec2>,
+}
+
+impl ForceModifier for HoleAssistForce {
+    fn prepare(&mut self, world: &mut World) {
+        self.holes.clear();
+        self.assist = world
+            .get_resource::<HoleAssist>()
+            .filter(|assist| assist.enabled)
+            .copied();
+        if self.assist.is_none() {
+            return;
+        }
+        let mut holes = world.query_filtered::<
+            &GlobalTransform,
+            (With<Hole>, Without<Filled>),
+        >();
+        self.holes.extend(
+            holes
+                .iter(world)
+                .map(|hole| hole.translation().truncate()),
+        );
+    }
+
+    fn force(&self, ball: &BallState) -> Vec2 {
+        self.assist.map_or(Vec2::ZERO, |assist| {
+            assist.force(
+                &self.holes,
+                ball.position,
+                ball.mass,
+            )
+        })
+    }
+}
+
+pub struct HoleAssistPlugin;
+
+impl Plugin for HoleAssistPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HoleAssist>()
+            .add_force_modifier(HoleAssistForce::default())
+            .add_system(
+                toggle_hole_assist
+                    .with_run_criteria(playing),
+            );
+    }
+}
+
+fn toggle_hole_assist(
+    keyboard: Res<Input<KeyCode>>,
+    mut assist: ResMut<HoleAssist>,
+) {
+    if keyboard.just_pressed(KeyCode::H) {
+        assist.enabled = !assist.enabled;
+    }
+}
@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+use std::cmp::Ordering;
+
+use crate::{
+    course::{
+        Assists, BallSunk, Course, CourseComplete, Filled,
+        GameMode, LevelComplete, Strokes,
+    },
+    editor::EditorMode,
+    golf_ball::GolfBallSettings,
+    gravity::{
+        dominant_body, surface_gravity, GravityBodies,
+        GravitySettings,
+    },
+    hole::Hole,
+    leaderboard::NewBest,
+    Body,
+};
+
+/// How long the "Sunk!" banner stays up, in seconds.
+const BANNER_SECONDS: f32 = 3.0;
+
+#[derive(Component)]
+struct ScoreText;
+
+#[derive(Component)]
+struct SunkBanner {
+    timer: Timer,
+}
+
+/// The in-game UI: level name, strokes and par in the top
+/// left corner, and a banner whenever a ball is sunk or a
+/// level is finished.
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_hud)
+            .add_system(update_score)
+            .add_system(show_sunk_banner)
+            .add_system(
+                show_new_best.after(show_sunk_banner),
+            )
+            .add_system(
+                hide_sunk_banner.after(show_new_best),
+            );
+    }
+}
+
+fn spawn_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    let font =
+        asset_server.load("fonts/DejaVuSansMono.ttf");
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(ScoreText);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(
+                    Val::Percent(100.0),
+                    Val::Percent(100.0),
+                ),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font,
+                        font_size: 64.0,
+                        color: Color::GOLD,
+                    },
+                ))
+                .insert(Visibility { is_visible: false })
+                .insert(SunkBanner {
+                    timer: Timer::from_seconds(
+                        BANNER_SECONDS,
+                        false,
+                    ),
+                });
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_score(
+    course: Res<Course>,
+    strokes: Res<Strokes>,
+    assists: Res<Assists>,
+    mode: Res<GameMode>,
+    editor: Res<EditorMode>,
+    ball: Res<GolfBallSettings>,
+    particle_set: Res<ParticleSet<Body>>,
+    gravity_settings: Res<GravitySettings>,
+    holes: Query<Option<&Filled>, With<Hole>>,
+    bodies: GravityBodies,
+    colliders: Query<&Collider>,
+    mut texts: Query<&mut Text, With<ScoreText>>,
+) {
+    let (name, par) = match course.current_level() {
+        Some(level) => (level.name.as_str(), level.par),
+        None => ("", 0),
+    };
+    let mut value = format!(
+        "{}\nStrokes: {}\nPar: {}",
+        name, strokes.0, par
+    );
+    if *mode == GameMode::Challenge {
+        value.push_str(&format!(
+            "\nChallenge: {} strokes allowed",
+            course.stroke_limit()
+        ));
+    }
+    if assists.0 > 0 {
+        value
+            .push_str(&format!("\nAssists: {}", assists.0));
+    }
+    if editor.enabled {
+        if let Some(mass) = editor.preset_mass() {
+            value.push_str(&format!(
+                "\nPlanet preset {}: {:E}",
+                editor.preset.unwrap_or_default(),
+                mass
+            ));
+        }
+    }
+    let total = holes.iter().count();
+    if total > 1 {
+        let filled = holes
+            .iter()
+            .filter(|filled| filled.is_some())
+            .count();
+        value.push_str(&format!(
+            "\nHoles: {}/{}",
+            filled, total
+        ));
+    }
+    // while aiming, show how strong gravity is on the
+    // planet that will be pulling the ball the most
+    let dominant = ball.position.and_then(|position| {
+        dominant_body(&particle_set, position)
+    });
+    // an `Attractor` has no collider, and so no surface
+    let surface = dominant.and_then(|entity| {
+        let (_, mu) = bodies.get(entity)?;
+        let radius =
+            colliders.get(entity).ok()?.as_ball()?;
+        Some((mu, radius.radius()))
+    });
+    if let Some((mu, radius)) = surface {
+        value.push_str(&format!(
+            "\nSurface gravity: {:.0}",
+            surface_gravity(mu, radius)
+                * gravity_settings.g_scale()
+        ));
+    }
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn show_sunk_banner(
+    mut sunk_events: EventReader<BallSunk>,
+    mut level_events: EventReader<LevelComplete>,
+    mut course_events: EventReader<CourseComplete>,
+    course: Res<Course>,
+    mut banners: Query<(
+        &mut Text,
+        &mut Visibility,
+        &mut SunkBanner,
+    )>,
+) {
+    let sunk = sunk_events.iter().last().is_some();
+    let mut message = match level_events.iter().last() {
+        // the course has already moved on, so look the
+        // finished level up by index
+        Some(event) => {
+            let par = course
+                .levels
+                .get(event.level)
+                .map_or(0, |level| level.par);
+            let score = match event.strokes.cmp(&par) {
+                Ordering::Greater => {
+                    format!(
+                        "{} over par",
+                        event.strokes - par
+                    )
+                }
+                Ordering::Equal => "par".to_string(),
+                Ordering::Less => format!(
+                    "{} under par",
+                    par - event.strokes
+                ),
+            };
+            format!("Sunk! {}", score)
+        }
+        // more holes left to fill
+        None if sunk => "Sunk!".to_string(),
+        None => return,
+    };
+    if course_events.iter().last().is_some() {
+        message.push_str("\nCourse complete!");
+    }
+    for (mut text, mut visibility, mut banner) in
+        banners.iter_mut()
+    {
+        text.sections[0].value = message.clone();
+        visibility.is_visible = true;
+        banner.timer.reset();
+    }
+}
+
+fn show_new_best(
+    mut events: EventReader<NewBest>,
+    mut banners: Query<&mut Text, With<SunkBanner>>,
+) {
+    if events.iter().last().is_none() {
+        return;
+    }
+    for mut text in banners.iter_mut() {
+        text.sections[0].value.push_str("\nNew best!");
+    }
+}
+
+fn hide_sunk_banner(
+    time: Res<Time>,
+    mut banners: Query<(&mut Visibility, &mut SunkBanner)>,
+) {
+    for (mut visibility, mut banner) in banners.iter_mut() {
+        if visibility.is_visible
+            && banner
+                .timer
+                .tick(time.delta())
+                .just_finished()
+        {
+            visibility.is_visible = false;
+        }
+    }
+}
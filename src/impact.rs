@@ -0,0 +1,361 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    course::{Filled, Sunk},
+    density::Density,
+    golf_ball::{GolfBall, GolfBallSettings, PointMass},
+    gravity::{
+        Attractor, GravityMode, GravitySettings,
+        GravitySource,
+    },
+    hole::Hole,
+    menu::playing,
+    planet_spec::Planet,
+    prediction::{
+        closest_approach, time_to_impact, Obstacle,
+        PredictionSettings,
+    },
+};
+
+/// Floating text next to each ball in flight counting down
+/// to when it's predicted to land, or to drop into a hole.
+/// Toggled with `Comma`.
+///
+/// The prediction starts from where the ball is right now
+/// rather than from the aim, so it keeps up with whatever
+/// has happened to the shot since it was taken.
+pub struct ImpactEstimates {
+    pub enabled: bool,
+    /// how often the estimates are worked out again
+    pub interval: Timer,
+    /// how far ahead to look, in steps of `dt` seconds.
+    /// Coarser than the aim preview, since a long shot is
+    /// the whole point.
+    pub steps: usize,
+    pub dt: f32,
+    /// the estimate ends at the first landing, so the
+    /// predicted path doesn't bounce
+    pub lookahead: PredictionSettings,
+}
+
+impl Default for ImpactEstimates {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: Timer::from_seconds(0.25, true),
+            steps: 600,
+            dt: 1.0 / 30.0,
+            lookahead: PredictionSettings {
+                max_bounces: 0,
+                point_stride: 1,
+            },
+        }
+    }
+}
+
+const LABEL_COLOR: Color = Color::ANTIQUE_WHITE;
+/// how far up and right of the ball the label sits
+const LABEL_OFFSET: Vec2 = Vec2::new(24.0, 24.0);
+
+/// The label showing a ball's estimate. It isn't a child
+/// of the ball so that it stays upright while the ball
+/// spins.
+#[derive(Component)]
+pub struct ImpactLabel {
+    ball: Entity,
+}
+
+/// Marks a ball that already has an `ImpactLabel`.
+#[derive(Component)]
+pub struct ImpactEstimate {
+    label: Entity,
+}
+
+pub struct ImpactEstimatesPlugin;
+
+impl Plugin for ImpactEstimatesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ImpactEstimates>()
+            .add_system(
+                toggle_impact_estimates
+                    .with_run_criteria(playing),
+            )
+            .add_system(add_impact_labels)
+            .add_system(
+                estimate_impacts
+                    .after(toggle_impact_estimates)
+                    .after(add_impact_labels),
+            )
+            .add_system(
+                follow_balls.after(estimate_impacts),
+            );
+    }
+}
+
+fn toggle_impact_estimates(
+    keyboard: Res<Input<KeyCode>>,
+    mut estimates: ResMut<ImpactEstimates>,
+) {
+    if keyboard.just_pressed(KeyCode::Comma) {
+        estimates.enabled = !estimates.enabled;
+    }
+}
+
+fn add_impact_labels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    balls: Query<
+        Entity,
+        (With<GolfBall>, Without<ImpactEstimate>),
+    >,
+) {
+    for ball in balls.iter() {
+        let label = commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load(
+                            "fonts/DejaVuSansMono.ttf",
+                        ),
+                        font_size: 16.0,
+                        color: LABEL_COLOR,
+                    },
+                ),
+                visibility: Visibility {
+                    is_visible: false,
+                },
+                ..default()
+            })
+            .insert(ImpactLabel { ball })
+            .id();
+        commands
+            .entity(ball)
+            .insert(ImpactEstimate { label });
+    }
+}
+
+/// A ball already touching a planet isn't in flight, so it
+/// gets no label.
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn estimate_impacts(
+    time: Res<Time>,
+    mut estimates: ResMut<ImpactEstimates>,
+    ball_settings: Res<GolfBallSettings>,
+    gravity_mode: Res<GravityMode>,
+    gravity_settings: Res<GravitySettings>,
+    balls: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Velocity,
+            &ColliderMassProperties,
+            &Density,
+            &ImpactEstimate,
+        ),
+        (With<GolfBall>, Without<Sunk>),
+    >,
+    bodies: Query<(
+        Entity,
+        &GlobalTransform,
+        &PointMass,
+        Option<&GolfBall>,
+    )>,
+    attractors: Query<
+        (&GlobalTransform, &Attractor),
+        Without<PointMass>,
+    >,
+    planets: Query<
+        (&GlobalTransform, &Collider),
+        With<Planet>,
+    >,
+    holes: Query<
+        (&GlobalTransform, &Hole),
+        Without<Filled>,
+    >,
+    mut labels: Query<
+        (&mut Text, &mut Visibility),
+        With<ImpactLabel>,
+    >,
+) {
+    if !estimates.enabled {
+        for (_, mut visibility) in labels.iter_mut() {
+            if visibility.is_visible {
+                visibility.is_visible = false;
+            }
+        }
+        return;
+    }
+    if !estimates
+        .interval
+        .tick(time.delta())
+        .just_finished()
+    {
+        return;
+    }
+
+    let g_scale = gravity_settings.g_scale();
+    let obstacles: Vec<Obstacle> = planets
+        .iter()
+        .map(|(transform, collider)| {
+            Obstacle::from_collider(transform, collider)
+        })
+        .collect();
+
+    for (
+        entity,
+        transform,
+        velocity,
+        mass,
+        density,
+        estimate,
+    ) in balls.iter()
+    {
+        let (mut text, mut visibility) =
+            match labels.get_mut(estimate.label) {
+                Ok(label) => label,
+                Err(_) => continue,
+            };
+        let ball = GolfBallSettings {
+            mass: match mass {
+                ColliderMassProperties::Mass(mass) => *mass,
+                _ => ball_settings.mass,
+            },
+            density: density.0,
+            prediction_steps: estimates.steps,
+            prediction_dt: estimates.dt,
+            ..ball_settings.clone()
+        };
+        let start = transform.translation().xy();
+        let radius = ball.radius();
+        let landed = obstacles.iter().any(|obstacle| {
+            obstacle.contact(start, radius + 1.0).is_some()
+        });
+        if landed {
+            if visibility.is_visible {
+                visibility.is_visible = false;
+            }
+            continue;
+        }
+
+        // the ball doesn't pull on itself
+        let mut sources: Vec<GravitySource> = bodies
+            .iter()
+            .filter(|(other, _, _, is_ball)| {
+                *other != entity
+                    && gravity_mode
+                        .attracts(is_ball.is_some())
+            })
+            .map(|(_, transform, point_mass, _)| {
+                GravitySource {
+                    position: transform.translation().xy(),
+                    mu: point_mass.mu() * g_scale,
+                }
+            })
+            .collect();
+        sources.extend(attractors.iter().map(
+            |(transform, attractor)| GravitySource {
+                position: transform.translation().xy(),
+                mu: attractor.mu() * g_scale,
+            },
+        ));
+
+        let impact = time_to_impact(
+            &sources,
+            &obstacles,
+            start,
+            velocity.linvel,
+            &ball,
+            &estimates.lookahead,
+        );
+        // the hole only counts if the ball gets there
+        // before it lands anywhere else
+        let hole = holes
+            .iter()
+            .map(|(transform, hole)| {
+                (
+                    closest_approach(
+                        &sources,
+                        &obstacles,
+                        start,
+                        velocity.linvel,
+                        &ball,
+                        &estimates.lookahead,
+                        transform.translation().xy(),
+                    ),
+                    hole.radius,
+                )
+            })
+            .filter(|(approach, radius)| {
+                approach.distance <= *radius
+                    && impact.is_none_or(|impact| {
+                        approach.time <= impact + 1e-3
+                    })
+            })
+            .map(|(approach, _)| approach.time)
+            .min_by(|a, b| a.total_cmp(b));
+
+        let value = match (hole, impact) {
+            (Some(time), _) => {
+                format!("hole in {:.1}s", time)
+            }
+            (None, Some(time)) => {
+                format!("impact in {:.1}s", time)
+            }
+            (None, None) => String::from("no impact"),
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+        if !visibility.is_visible {
+            visibility.is_visible = true;
+        }
+    }
+}
+
+/// Keeps each label next to its ball, hides it while the
+/// ball is sunk, and cleans up the labels of balls that
+/// are gone.
+fn follow_balls(
+    mut commands: Commands,
+    balls: Query<
+        (&GlobalTransform, Option<&Sunk>),
+        With<GolfBall>,
+    >,
+    mut labels: Query<
+        (
+            Entity,
+            &ImpactLabel,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        Without<GolfBall>,
+    >,
+) {
+    for (
+        label,
+        impact_label,
+        mut transform,
+        mut visibility,
+    ) in labels.iter_mut()
+    {
+        match balls.get(impact_label.ball) {
+            Ok((_, Some(_))) => {
+                if visibility.is_visible {
+                    visibility.is_visible = false;
+                }
+            }
+            Ok((ball, None)) => {
+                transform.translation =
+                    (ball.translation().xy()
+                        + LABEL_OFFSET)
+                        .extend(10.0);
+            }
+            Err(_) => commands.entity(label).despawn(),
+        }
+    }
+}
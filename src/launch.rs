@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+
+use crate::{golf_ball::GolfBallSettings, menu::playing};
+
+/// How a shot's strength is picked while aiming. Switched
+/// with `B`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LaunchScheme {
+    /// Drag back from the ball like a slingshot; the
+    /// further the drag, the harder the shot.
+    #[default]
+    DragVector,
+    /// Aim with the drag direction and hold the button to
+    /// charge. The power swings up and down a meter until
+    /// the button is let go.
+    HoldCharge,
+}
+
+/// The launch scheme in use, and how a held shot
+/// charges up.
+pub struct LaunchCharge {
+    pub scheme: LaunchScheme,
+    /// the launch speed of a fully charged shot
+    pub max_power: f32,
+    /// seconds for the meter to fill from empty
+    pub fill_time: f32,
+    /// how long the current shot has been charging
+    held: f32,
+}
+
+impl Default for LaunchCharge {
+    fn default() -> Self {
+        Self {
+            scheme: LaunchScheme::default(),
+            max_power: 600.0,
+            fill_time: 1.0,
+            held: 0.0,
+        }
+    }
+}
+
+impl LaunchCharge {
+    /// How full the meter is, from 0.0 to 1.0. It fills
+    /// up, empties again and starts over for as long as
+    /// the button is held.
+    pub fn fraction(&self) -> f32 {
+        if self.fill_time <= 0.0 {
+            return 1.0;
+        }
+        let phase = (self.held / self.fill_time) % 2.0;
+        if phase <= 1.0 {
+            phase
+        } else {
+            2.0 - phase
+        }
+    }
+
+    /// The launch velocity for a ball placed at
+    /// `place_pos` with the pointer at `aim_pos`.
+    pub fn velocity(
+        &self,
+        place_pos: Vec2,
+        aim_pos: Vec2,
+    ) -> Vec2 {
+        let drag = place_pos - aim_pos;
+        match self.scheme {
+            LaunchScheme::DragVector => drag,
+            LaunchScheme::HoldCharge => {
+                drag.normalize_or_zero()
+                    * self.fraction()
+                    * self.max_power
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct ChargeMeter;
+
+#[derive(Component)]
+struct ChargeMeterFill;
+
+/// Charges held shots and shows the power meter at the
+/// bottom of the screen while one is charging.
+pub struct LaunchChargePlugin;
+
+impl Plugin for LaunchChargePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LaunchCharge>()
+            .add_startup_system(spawn_meter)
+            .add_system(
+                toggle_scheme.with_run_criteria(playing),
+            )
+            .add_system(charge_launch.after(toggle_scheme))
+            .add_system(update_meter.after(charge_launch));
+    }
+}
+
+fn toggle_scheme(
+    keyboard: Res<Input<KeyCode>>,
+    mut charge: ResMut<LaunchCharge>,
+) {
+    if keyboard.just_pressed(KeyCode::B) {
+        charge.scheme = match charge.scheme {
+            LaunchScheme::DragVector => {
+                LaunchScheme::HoldCharge
+            }
+            LaunchScheme::HoldCharge => {
+                LaunchScheme::DragVector
+            }
+        };
+    }
+}
+
+/// Charges for as long as a ball is being aimed, which is
+/// exactly as long as the button is held.
+pub fn charge_launch(
+    time: Res<Time>,
+    ball: Res<GolfBallSettings>,
+    mut charge: ResMut<LaunchCharge>,
+) {
+    if charge.scheme == LaunchScheme::HoldCharge
+        && ball.position.is_some()
+    {
+        charge.held += time.delta_seconds();
+    } else if charge.held != 0.0 {
+        charge.held = 0.0;
+    }
+}
+
+fn spawn_meter(mut commands: Commands) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(
+                    Val::Px(200.0),
+                    Val::Px(16.0),
+                ),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Percent(50.0),
+                    bottom: Val::Px(40.0),
+                    ..default()
+                },
+                margin: UiRect {
+                    left: Val::Px(-100.0),
+                    ..default()
+                },
+                ..default()
+            },
+            color: Color::rgba(1.0, 1.0, 1.0, 0.2).into(),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(ChargeMeter)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(
+                            Val::Percent(0.0),
+                            Val::Percent(100.0),
+                        ),
+                        ..default()
+                    },
+                    color: Color::ORANGE.into(),
+                    ..default()
+                })
+                .insert(ChargeMeterFill);
+        });
+}
+
+#[allow(clippy::type_complexity)]
+fn update_meter(
+    charge: Res<LaunchCharge>,
+    ball: Res<GolfBallSettings>,
+    mut meters: Query<&mut Visibility, With<ChargeMeter>>,
+    mut fills: Query<
+        (&mut Style, &mut Visibility),
+        (With<ChargeMeterFill>, Without<ChargeMeter>),
+    >,
+) {
+    let charging = charge.scheme
+        == LaunchScheme::HoldCharge
+        && ball.position.is_some();
+    for mut visibility in meters.iter_mut() {
+        if visibility.is_visible != charging {
+            visibility.is_visible = charging;
+        }
+    }
+    for (mut style, mut visibility) in fills.iter_mut() {
+        if visibility.is_visible != charging {
+            visibility.is_visible = charging;
+        }
+        if charging {
+            style.size.width =
+                Val::Percent(charge.fraction() * 100.0);
+        }
+    }
+}
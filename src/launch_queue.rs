@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use crate::{
+    golf_ball::SpawnBall, sim_state::sim_running,
+    time_scale::TimeScale,
+};
+
+/// Balls waiting to be fired one after another, one every
+/// `interval`, for patterns and stress tests that would
+/// take far too much clicking by hand. Each goes out as a
+/// [`SpawnBall`], so like those they don't take a stroke
+/// and `SpawnBallPlugin` has to be added too.
+///
+/// The interval counts simulated time, so the queue waits
+/// while the game is paused and follows the `TimeScale`.
+/// The first ball queued while the queue is empty goes out
+/// one `interval` later.
+pub struct LaunchQueue {
+    pub pending: VecDeque<SpawnBall>,
+    pub interval: Timer,
+}
+
+impl Default for LaunchQueue {
+    fn default() -> Self {
+        Self::new(0.25)
+    }
+}
+
+impl LaunchQueue {
+    pub fn new(interval: f32) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            interval: Timer::from_seconds(interval, true),
+        }
+    }
+
+    /// Queues `ball` behind every ball already waiting.
+    pub fn push(&mut self, ball: SpawnBall) {
+        self.pending.push_back(ball);
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+impl Extend<SpawnBall> for LaunchQueue {
+    fn extend<T: IntoIterator<Item = SpawnBall>>(
+        &mut self,
+        balls: T,
+    ) {
+        self.pending.extend(balls);
+    }
+}
+
+pub struct LaunchQueuePlugin;
+
+impl Plugin for LaunchQueuePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LaunchQueue>().add_system(
+            launch_queued_balls
+                .with_run_criteria(sim_running),
+        );
+    }
+}
+
+fn launch_queued_balls(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut queue: ResMut<LaunchQueue>,
+    mut spawn_ball: EventWriter<SpawnBall>,
+) {
+    if queue.is_empty() {
+        if queue.interval.elapsed_secs() > 0.0 {
+            queue.interval.reset();
+        }
+        return;
+    }
+    let delta = time.delta().mul_f32(time_scale.0);
+    let due = queue
+        .interval
+        .tick(delta)
+        .times_finished_this_tick();
+    for _ in 0..due {
+        match queue.pending.pop_front() {
+            Some(ball) => spawn_ball.send(ball),
+            None => break,
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use bevy::{app::AppExit, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::course::{Course, LevelComplete};
+
+/// The fewest strokes each level has been finished in,
+/// keyed by level name.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub best: BTreeMap<String, u32>,
+    /// where the leaderboard is saved to, if there's
+    /// anywhere to save it
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+/// Sent when a level is finished in fewer strokes than
+/// ever before.
+pub struct NewBest {
+    pub level: String,
+    pub strokes: u32,
+    /// the record that was beaten, if there was one
+    pub previous: Option<u32>,
+}
+
+impl Leaderboard {
+    /// `leaderboard.ron` in the platform's config
+    /// directory.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| {
+            dir.join("space-golf").join("leaderboard.ron")
+        })
+    }
+
+    /// Reads the leaderboard at `path`. A missing file
+    /// is an empty leaderboard, and so is one that can't
+    /// be read, after a warning.
+    pub fn load(path: &Path) -> Self {
+        let best = match fs::read_to_string(path) {
+            Ok(contents) => {
+                match ron::from_str::<Leaderboard>(
+                    &contents,
+                ) {
+                    Ok(leaderboard) => leaderboard.best,
+                    Err(error) => {
+                        warn!(
+                            "ignoring corrupt leaderboard {}: {}",
+                            path.display(),
+                            error
+                        );
+                        BTreeMap::new()
+                    }
+                }
+            }
+            Err(error)
+                if error.kind()
+                    == io::ErrorKind::NotFound =>
+            {
+                BTreeMap::new()
+            }
+            Err(error) => {
+                warn!(
+                    "couldn't read leaderboard {}: {}",
+                    path.display(),
+                    error
+                );
+                BTreeMap::new()
+            }
+        };
+        Self {
+            best,
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )
+        .map_err(io::Error::other)?;
+        fs::write(path, contents)
+    }
+
+    /// Records a finished level, returning the previous
+    /// best if this beat it.
+    pub fn record(
+        &mut self,
+        level: &str,
+        strokes: u32,
+    ) -> Option<Option<u32>> {
+        let previous = self.best.get(level).copied();
+        match previous {
+            Some(best) if best <= strokes => None,
+            _ => {
+                self.best
+                    .insert(level.to_string(), strokes);
+                Some(previous)
+            }
+        }
+    }
+}
+
+/// Loads the leaderboard at startup and saves it whenever
+/// a record is broken and when the app exits.
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        let leaderboard = match Leaderboard::default_path()
+        {
+            Some(path) => Leaderboard::load(&path),
+            None => Leaderboard::default(),
+        };
+        app.insert_resource(leaderboard)
+            .add_event::<NewBest>()
+            .add_system(record_scores)
+            .add_system_to_stage(
+                CoreStage::Last,
+                save_on_exit,
+            );
+    }
+}
+
+fn save(leaderboard: &Leaderboard) {
+    if let Err(error) = leaderboard.save() {
+        warn!("couldn't save leaderboard: {}", error);
+    }
+}
+
+fn record_scores(
+    mut complete_events: EventReader<LevelComplete>,
+    mut new_best_events: EventWriter<NewBest>,
+    course: Res<Course>,
+    mut leaderboard: ResMut<Leaderboard>,
+) {
+    for event in complete_events.iter() {
+        let level = match course.levels.get(event.level) {
+            Some(level) => level,
+            None => continue,
+        };
+        if let Some(previous) =
+            leaderboard.record(&level.name, event.strokes)
+        {
+            save(&leaderboard);
+            new_best_events.send(NewBest {
+                level: level.name.clone(),
+                strokes: event.strokes,
+                previous,
+            });
+        }
+    }
+}
+
+fn save_on_exit(
+    mut exit_events: EventReader<AppExit>,
+    leaderboard: Res<Leaderboard>,
+) {
+    if exit_events.iter().last().is_some() {
+        save(&leaderboard);
+    }
+}
@@ -0,0 +1,681 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{
+    golf_ball::GolfBallSettings,
+    hole::{spawn_hole, Hole},
+    phase::PhaseMask,
+    planet_spec::{spawn_planet, PlanetSpec},
+};
+
+/// How far above the ground holes placed with
+/// `place_hole_on_surface` float, so they don't count as
+/// overlapping the planet.
+const HOLE_SURFACE_GAP: f32 = 1.0;
+
+/// Pushes apart any of `circles`, given as center and
+/// radius, that overlap, until each pair is at least
+/// `clearance` apart or `max_iterations` passes have been
+/// made. Each pass moves both circles of an overlapping
+/// pair away from each other by half the overlap. Returns
+/// whether everything ended up clear.
+pub fn resolve_overlaps(
+    circles: &mut [(Vec2, f32)],
+    clearance: f32,
+    max_iterations: usize,
+) -> bool {
+    for _ in 0..max_iterations {
+        let mut moved = false;
+        for i in 0..circles.len() {
+            for j in i + 1..circles.len() {
+                let (a, a_radius) = circles[i];
+                let (b, b_radius) = circles[j];
+                let reach = a_radius + b_radius + clearance;
+                let offset = b - a;
+                let distance = offset.length();
+                if distance >= reach {
+                    continue;
+                }
+                // circles sitting exactly on top of each
+                // other still need some way to go
+                let direction = if distance > f32::EPSILON {
+                    offset / distance
+                } else {
+                    Vec2::X
+                };
+                let push =
+                    direction * (reach - distance) / 2.0;
+                circles[i].0 -= push;
+                circles[j].0 += push;
+                moved = true;
+            }
+        }
+        if !moved {
+            return true;
+        }
+    }
+    false
+}
+
+/// One hole of a course: the planets, where shots start
+/// from and where they need to end up.
+///
+/// ```ignore
+/// let mut level = Level::new();
+/// level
+///     .add_planet(PlanetSpec::default())
+///     .set_tee(Vec2::new(0.0, -250.0))
+///     .set_hole(Vec2::new(0.0, 150.0), Hole::default())
+///     .set_par(3);
+/// level.validate()?;
+/// ```
+///
+/// Levels are saved as RON with [`Level::to_ron`], or as
+/// JSON with the `json` feature. Both use the same field
+/// names, and anything left out gets its default.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Level {
+    pub name: String,
+    pub planets: Vec<PlanetSpec>,
+    pub tee: Option<Vec2>,
+    pub tee_box: Option<TeeBox>,
+    /// every hole has to be filled to finish the level
+    pub holes: Vec<(Vec2, Hole)>,
+    /// When set every hole in the level is this big,
+    /// whatever radius it was added with, so a level's
+    /// difficulty can be tuned in one place. Smaller holes
+    /// take more accurate shots.
+    pub hole_radius: Option<f32>,
+    pub par: u32,
+    /// the clear color while the level is played, black
+    /// when not set
+    pub background: Option<Color>,
+    /// a track under `assets/` looped while the level is
+    /// played, crossfading from the last level's
+    pub music: Option<String>,
+    /// the phase layers every ball shot in the level is
+    /// given, so it passes through the planets on them
+    pub ball_phase: PhaseMask,
+    /// The gravitational constant while the level is
+    /// played. When set it takes precedence over the
+    /// global `GravitySettings::g`, which comes back as
+    /// soon as a level without one loads.
+    pub g: Option<f32>,
+}
+
+/// Where balls may be launched from in
+/// `GameMode::Challenge`, so a level can't be cheesed by
+/// shooting from right next to the hole. Ignored in the
+/// sandbox.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize,
+)]
+pub struct TeeBox {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl TeeBox {
+    /// A box `half_size` out from `center` each way.
+    pub fn around(center: Vec2, half_size: Vec2) -> Self {
+        Self {
+            min: center - half_size.abs(),
+            max: center + half_size.abs(),
+        }
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all()
+            && point.cmple(self.max).all()
+    }
+}
+
+/// Why a `Level` can't be played.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LevelError {
+    MissingTee,
+    MissingHole,
+    /// nothing in the level has any mass to pull on a ball
+    NoGravitySource,
+    /// the tee is inside of the planet at this index
+    TeeInsidePlanet(usize),
+    /// the tee can't be launched from in challenge mode
+    TeeOutsideTeeBox,
+    /// the hole at the first index overlaps the planet at
+    /// the second
+    HoleInsidePlanet(usize, usize),
+    /// the planets at these two indices overlap
+    PlanetsOverlap(usize, usize),
+    /// the hole at this index is no bigger than a ball, so
+    /// nothing could ever be sunk in it
+    HoleTooSmall(usize),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelError::MissingTee => {
+                write!(f, "the level has no tee")
+            }
+            LevelError::MissingHole => {
+                write!(f, "the level has no hole")
+            }
+            LevelError::NoGravitySource => write!(
+                f,
+                "the level has no planet with any mass"
+            ),
+            LevelError::TeeInsidePlanet(planet) => write!(
+                f,
+                "the tee is inside of planet {}",
+                planet
+            ),
+            LevelError::TeeOutsideTeeBox => write!(
+                f,
+                "the tee is outside of the tee box"
+            ),
+            LevelError::HoleInsidePlanet(hole, planet) => {
+                write!(
+                    f,
+                    "hole {} overlaps planet {}",
+                    hole, planet
+                )
+            }
+            LevelError::PlanetsOverlap(a, b) => {
+                write!(f, "planets {} and {} overlap", a, b)
+            }
+            LevelError::HoleTooSmall(hole) => write!(
+                f,
+                "hole {} is too small for a ball to sink in",
+                hole
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl Level {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_name(
+        &mut self,
+        name: impl Into<String>,
+    ) -> &mut Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn add_planet(
+        &mut self,
+        planet: PlanetSpec,
+    ) -> &mut Self {
+        self.planets.push(planet);
+        self
+    }
+
+    pub fn set_tee(&mut self, tee: Vec2) -> &mut Self {
+        self.tee = Some(tee);
+        self
+    }
+
+    pub fn set_tee_box(
+        &mut self,
+        tee_box: TeeBox,
+    ) -> &mut Self {
+        self.tee_box = Some(tee_box);
+        self
+    }
+
+    /// Replaces any holes with just this one.
+    pub fn set_hole(
+        &mut self,
+        position: Vec2,
+        hole: Hole,
+    ) -> &mut Self {
+        self.holes = vec![(position, hole)];
+        self
+    }
+
+    /// Adds another hole, for levels where several have
+    /// to be filled.
+    pub fn add_hole(
+        &mut self,
+        position: Vec2,
+        hole: Hole,
+    ) -> &mut Self {
+        self.holes.push((position, hole));
+        self
+    }
+
+    /// Sizes every hole in the level, see
+    /// [`Level::hole_radius`].
+    pub fn set_hole_radius(
+        &mut self,
+        radius: f32,
+    ) -> &mut Self {
+        self.hole_radius = Some(radius);
+        self
+    }
+
+    /// The holes as they're spawned, with `hole_radius`
+    /// applied.
+    pub fn sized_holes(
+        &self,
+    ) -> impl Iterator<Item = (Vec2, Hole)> + '_ {
+        self.holes.iter().map(|(position, hole)| {
+            let radius =
+                self.hole_radius.unwrap_or(hole.radius);
+            (*position, Hole { radius })
+        })
+    }
+
+    /// Adds a hole sitting on the surface of the planet at
+    /// index `planet`, `angle` radians counter clockwise
+    /// from +x. Does nothing if there is no such planet,
+    /// which `validate` reports if it leaves the level
+    /// without a hole.
+    pub fn place_hole_on_surface(
+        &mut self,
+        planet: usize,
+        angle: f32,
+        hole: Hole,
+    ) -> &mut Self {
+        let radius =
+            self.hole_radius.unwrap_or(hole.radius);
+        if let Some(planet) = self.planets.get(planet) {
+            let position = planet.surface_point(
+                angle,
+                radius + HOLE_SURFACE_GAP,
+            );
+            self.holes.push((position, hole));
+        }
+        self
+    }
+
+    pub fn set_par(&mut self, par: u32) -> &mut Self {
+        self.par = par;
+        self
+    }
+
+    pub fn set_background(
+        &mut self,
+        color: Color,
+    ) -> &mut Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn set_ball_phase(
+        &mut self,
+        phase: PhaseMask,
+    ) -> &mut Self {
+        self.ball_phase = phase;
+        self
+    }
+
+    pub fn set_g(&mut self, g: f32) -> &mut Self {
+        self.g = Some(g);
+        self
+    }
+
+    pub fn set_music(
+        &mut self,
+        path: impl Into<String>,
+    ) -> &mut Self {
+        self.music = Some(path.into());
+        self
+    }
+
+    /// Checks the level can actually be played, returning
+    /// the first problem found. Holes are checked against
+    /// a ball with the default `GolfBallSettings`.
+    pub fn validate(&self) -> Result<(), LevelError> {
+        let tee = self.tee.ok_or(LevelError::MissingTee)?;
+        if self.holes.is_empty() {
+            return Err(LevelError::MissingHole);
+        }
+        let ball_radius =
+            GolfBallSettings::default().radius();
+        if let Some(i) =
+            self.sized_holes().position(|(_, hole)| {
+                hole.radius <= ball_radius
+            })
+        {
+            return Err(LevelError::HoleTooSmall(i));
+        }
+        if !self
+            .planets
+            .iter()
+            .any(|planet| planet.mass > 0.0)
+        {
+            return Err(LevelError::NoGravitySource);
+        }
+        if self
+            .tee_box
+            .is_some_and(|tee_box| !tee_box.contains(tee))
+        {
+            return Err(LevelError::TeeOutsideTeeBox);
+        }
+        for (i, planet) in self.planets.iter().enumerate() {
+            if planet.contains(tee, 0.0) {
+                return Err(LevelError::TeeInsidePlanet(i));
+            }
+            for (j, (position, hole)) in
+                self.sized_holes().enumerate()
+            {
+                if planet.contains(position, hole.radius) {
+                    return Err(
+                        LevelError::HoleInsidePlanet(j, i),
+                    );
+                }
+            }
+        }
+        for (i, a) in self.planets.iter().enumerate() {
+            for (j, b) in
+                self.planets.iter().enumerate().skip(i + 1)
+            {
+                // close enough for the mostly round planets
+                // levels are made of
+                let reach = a.semi_axes().max_element()
+                    + b.semi_axes().max_element();
+                if a.position
+                    .truncate()
+                    .distance(b.position.truncate())
+                    < reach
+                {
+                    return Err(
+                        LevelError::PlanetsOverlap(i, j),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )
+    }
+
+    pub fn from_ron(
+        ron: &str,
+    ) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(
+        &self,
+    ) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(
+        json: &str,
+    ) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Nudges apart any planets that overlap, see
+    /// [`resolve_overlaps`]. Holes don't move along with
+    /// their planet, so it's best done before they're
+    /// placed. Returns whether the planets ended up clear.
+    pub fn resolve_overlaps(&mut self) -> bool {
+        // the same reach `validate` checks against
+        let mut circles: Vec<(Vec2, f32)> = self
+            .planets
+            .iter()
+            .map(|planet| {
+                (
+                    planet.position.truncate(),
+                    planet.semi_axes().max_element(),
+                )
+            })
+            .collect();
+        let resolved =
+            resolve_overlaps(&mut circles, 0.0, 100);
+        for (planet, (position, _)) in
+            self.planets.iter_mut().zip(circles)
+        {
+            planet.position =
+                position.extend(planet.position.z);
+        }
+        resolved
+    }
+
+    /// Spawns the planets and the holes. Doesn't validate
+    /// the level first.
+    pub fn spawn(
+        &self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<ColorMaterial>,
+    ) {
+        for planet in self.planets.iter() {
+            spawn_planet(
+                commands, meshes, materials, planet,
+            );
+        }
+        for (position, hole) in self.sized_holes() {
+            spawn_hole(
+                commands, meshes, materials, position, hole,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        atmosphere::Atmosphere, planet_spec::PlanetShape,
+    };
+
+    /// a level with every field set, so a field that
+    /// doesn't survive saving shows up
+    fn full_level() -> Level {
+        let mut level = Level::new();
+        level
+            .set_name("round trip")
+            .add_planet(PlanetSpec {
+                position: Vec3::new(-120.0, 40.0, 1.0),
+                shape: PlanetShape::Ellipse {
+                    a: 2.0,
+                    b: 1.0,
+                },
+                rim_color: Some(Color::ORANGE),
+                atmosphere: Some(Atmosphere {
+                    radius: 80.0,
+                    drag: 0.5,
+                }),
+                ..default()
+            })
+            .add_planet(PlanetSpec {
+                position: Vec3::new(200.0, 0.0, 1.0),
+                mass: 3.5E5,
+                core_radius: Some(12.5),
+                ..default()
+            })
+            .set_tee(Vec2::new(0.0, -250.0))
+            .set_tee_box(TeeBox::around(
+                Vec2::new(0.0, -250.0),
+                Vec2::splat(30.0),
+            ))
+            .set_hole(
+                Vec2::new(0.0, 150.0),
+                Hole::default(),
+            )
+            .add_hole(
+                Vec2::new(40.0, 180.0),
+                Hole::default(),
+            )
+            .set_hole_radius(12.0)
+            .set_par(4)
+            .set_background(Color::rgb(0.1, 0.0, 0.2))
+            .set_music("music/orbit.ogg")
+            .set_ball_phase(PhaseMask::layer(2))
+            .set_g(0.75);
+        level
+    }
+
+    /// one planet between a tee and a hole, the least a
+    /// level needs to pass `validate`
+    fn playable_level() -> Level {
+        let mut level = Level::new();
+        level
+            .add_planet(PlanetSpec::default())
+            .set_tee(Vec2::new(0.0, -300.0))
+            .set_hole(
+                Vec2::new(0.0, 300.0),
+                Hole::default(),
+            );
+        level
+    }
+
+    #[test]
+    fn playable_levels_validate() {
+        assert_eq!(playable_level().validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_catches_unplayable_levels() {
+        let mut level = playable_level();
+        level.tee = None;
+        assert_eq!(
+            level.validate(),
+            Err(LevelError::MissingTee)
+        );
+
+        let mut level = playable_level();
+        level.holes.clear();
+        assert_eq!(
+            level.validate(),
+            Err(LevelError::MissingHole)
+        );
+
+        let mut level = playable_level();
+        level.set_hole_radius(
+            GolfBallSettings::default().radius(),
+        );
+        assert_eq!(
+            level.validate(),
+            Err(LevelError::HoleTooSmall(0))
+        );
+
+        let mut level = playable_level();
+        level.planets[0].mass = 0.0;
+        assert_eq!(
+            level.validate(),
+            Err(LevelError::NoGravitySource)
+        );
+
+        let mut level = playable_level();
+        level.set_tee_box(TeeBox::around(
+            Vec2::new(200.0, -300.0),
+            Vec2::splat(30.0),
+        ));
+        assert_eq!(
+            level.validate(),
+            Err(LevelError::TeeOutsideTeeBox)
+        );
+
+        let mut level = playable_level();
+        level.set_tee(Vec2::ZERO);
+        assert_eq!(
+            level.validate(),
+            Err(LevelError::TeeInsidePlanet(0))
+        );
+
+        let mut level = playable_level();
+        level.add_hole(Vec2::ZERO, Hole::default());
+        assert_eq!(
+            level.validate(),
+            Err(LevelError::HoleInsidePlanet(1, 0))
+        );
+
+        let mut level = playable_level();
+        level.add_planet(PlanetSpec {
+            position: Vec3::new(10.0, 0.0, 1.0),
+            ..default()
+        });
+        assert_eq!(
+            level.validate(),
+            Err(LevelError::PlanetsOverlap(0, 1))
+        );
+    }
+
+    #[test]
+    fn resolve_overlaps_separates_overlapping_planets() {
+        let mut level = playable_level();
+        level.add_planet(PlanetSpec {
+            position: Vec3::new(10.0, 0.0, 1.0),
+            ..default()
+        });
+        assert_eq!(
+            level.validate(),
+            Err(LevelError::PlanetsOverlap(0, 1))
+        );
+
+        assert!(level.resolve_overlaps());
+        assert_eq!(level.validate(), Ok(()));
+        // pushed apart along the line between them, and
+        // left on their own layer
+        assert!(level.planets[0].position.x < 0.0);
+        assert!(level.planets[1].position.x > 10.0);
+        assert!(level.planets.iter().all(|planet| {
+            planet.position.y == 0.0
+                && planet.position.z == 1.0
+        }));
+    }
+
+    #[test]
+    fn resolve_overlaps_separates_stacked_circles() {
+        let mut circles =
+            [(Vec2::ZERO, 10.0), (Vec2::ZERO, 10.0)];
+        assert!(resolve_overlaps(&mut circles, 5.0, 10));
+        let apart = circles[0].0.distance(circles[1].0);
+        assert!(apart >= 25.0 - 1E-4, "{}", apart);
+        // and gives up when it runs out of passes
+        let mut circles = [
+            (Vec2::ZERO, 10.0),
+            (Vec2::new(1.0, 0.0), 10.0),
+            (Vec2::new(0.0, 1.0), 10.0),
+        ];
+        assert!(!resolve_overlaps(&mut circles, 0.0, 1));
+    }
+
+    #[test]
+    fn ron_round_trips() {
+        let ron = full_level().to_ron().unwrap();
+        let loaded = Level::from_ron(&ron).unwrap();
+        assert_eq!(loaded.to_ron().unwrap(), ron);
+    }
+
+    #[test]
+    fn missing_fields_get_their_defaults() {
+        let level =
+            Level::from_ron("(name: \"bare\")").unwrap();
+        assert_eq!(level.name, "bare");
+        assert!(level.planets.is_empty());
+        assert_eq!(level.tee, None);
+    }
+
+    // RON -> Level -> JSON -> Level has to lose nothing
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_loads_the_same_level_as_ron() {
+        let ron = full_level().to_ron().unwrap();
+        let from_ron = Level::from_ron(&ron).unwrap();
+        let json = from_ron.to_json().unwrap();
+        let from_json = Level::from_json(&json).unwrap();
+        assert_eq!(from_json.to_ron().unwrap(), ron);
+        assert_eq!(from_json.to_json().unwrap(), json);
+    }
+}
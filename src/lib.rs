@@ -1,10 +1,105 @@
 use bevy::prelude::*;
+use golf_ball::{GolfBall, PointMass};
+use gravity::{Attractor, GravityMode, GravitySettings};
 use particular::{prelude::*, ParticleSet};
 
+pub mod afterimage;
+pub mod assist;
+pub mod atmosphere;
+pub mod auto_aim;
+pub mod backdrop;
+pub mod blast;
+pub mod bounds;
+pub mod contact_points;
+pub mod contacts;
+pub mod contours;
+pub mod course;
+#[cfg(feature = "three_d")]
+pub mod crater;
+pub mod cup;
+pub mod density;
+pub mod editor;
+pub mod elements;
+pub mod energy;
+pub mod field_zone;
+pub mod flyby_rail;
+pub mod focus;
+pub mod forces;
+pub mod fragile;
+pub mod framing;
+pub mod freeze;
+pub mod generate;
+pub mod ghost;
 pub mod golf_ball;
+pub mod gravity;
+pub mod gravity_budget;
+pub mod grid;
+pub mod groups;
+pub mod hole;
+pub mod hud;
+pub mod impact;
+pub mod launch;
+pub mod launch_queue;
+pub mod leaderboard;
+pub mod level;
+pub mod measure;
+#[cfg(feature = "export")]
+pub mod mesh_export;
+pub mod menu;
+pub mod null_points;
+pub mod orbit;
+pub mod orbit_camera;
+pub mod paint;
+pub mod patrol;
+pub mod phase;
+pub mod photo;
+pub mod pin;
 pub mod planet;
+pub mod planet_spec;
+pub mod practice;
+pub mod prediction;
+pub mod render;
+pub mod replay;
+pub mod rng;
+pub mod self_gravity;
+pub mod share;
+pub mod shot_paths;
+pub mod sim_state;
+pub mod soi;
+pub mod solve;
+pub mod spin;
+pub mod split_screen;
+pub mod stats;
+pub mod surface_snap;
+pub mod terminator;
+#[cfg(feature = "three_d")]
+pub mod three_d;
+pub mod thrust;
+pub mod thumbnail;
+pub mod time_scale;
+pub mod tooltip;
+pub mod trace;
+pub mod tractor;
+pub mod trail_export;
+pub mod typed_launch;
+pub mod undo;
+pub mod velocity_arrows;
+pub mod well_depth;
 
-#[derive(Particle)]
+/// The scale the 2D game runs rapier at. Anything read
+/// straight out of rapier's own structures is in meters and
+/// has to be multiplied back up by this.
+pub const PIXELS_PER_METER: f32 = 100.0;
+
+#[derive(
+    SystemLabel, Debug, Clone, PartialEq, Eq, Hash,
+)]
+pub enum GravitySystem {
+    Sync,
+    Accelerate,
+}
+
+#[derive(Particle, Reflect)]
 pub struct Body {
     pub position: Vec3,
     pub mu: f32,
@@ -23,3 +118,104 @@ impl Body {
         }
     }
 }
+
+/// Refreshes the `ParticleSet` from every `PointMass` and
+/// every `Attractor`.
+/// Positions are kept in full 3D so the same set works for
+/// the 2D game and the `three_d` mode.
+///
+/// Bodies that `GravityMode` says shouldn't attract are
+/// added with a `mu` of zero, which particular treats as
+/// massless: they still feel gravity but don't exert any.
+///
+/// Bodies are added in `Entity` order, so the same world
+/// always sums its gravity up the same way.
+///
+/// Every `mu` is scaled to the gravitational constant in
+/// effect, [`GravitySettings::g`], so whatever reads the
+/// set follows the current level's gravity.
+///
+/// While the same bodies are in play, their positions and
+/// `mu`s are updated in place, so the set's storage lasts
+/// from frame to frame. It's only rebuilt when a body
+/// comes or goes, or starts or stops pulling.
+pub fn sync_particle_set(
+    mut particle_set: ResMut<ParticleSet<Body>>,
+    mut bodies: Local<Vec<Body>>,
+    gravity_mode: Res<GravityMode>,
+    gravity_settings: Res<GravitySettings>,
+    query: Query<(
+        Entity,
+        &GlobalTransform,
+        &PointMass,
+        Option<&GolfBall>,
+    )>,
+    attractors: Query<
+        (Entity, &GlobalTransform, &Attractor),
+        Without<PointMass>,
+    >,
+) {
+    let g_scale = gravity_settings.g_scale();
+    bodies.clear();
+    bodies.extend(
+        query
+            .iter()
+            .map(|(entity, tranform, point_mass, ball)| {
+                let mu = if gravity_mode
+                    .attracts(ball.is_some())
+                {
+                    point_mass.mu() * g_scale
+                } else {
+                    0.0
+                };
+                Body::new(
+                    tranform.translation(),
+                    mu,
+                    entity,
+                )
+            })
+            .chain(attractors.iter().map(
+                |(entity, transform, attractor)| {
+                    Body::new(
+                        transform.translation(),
+                        attractor.mu() * g_scale,
+                        entity,
+                    )
+                },
+            )),
+    );
+    // queries come out in archetype order, which changes
+    // as components come and go. Every entity is only in
+    // here once, so the sort that doesn't allocate comes
+    // out the same as a stable one.
+    bodies.sort_unstable_by_key(|body| {
+        (body.entity.id(), body.entity.generation())
+    });
+
+    // particular keeps the bodies with a `mu` ahead of the
+    // massless ones, each in the order they were added
+    let massive = |body: &&Body| body.mu != 0.0;
+    let in_set_order = || {
+        bodies.iter().filter(massive).chain(
+            bodies.iter().filter(|body| !massive(body)),
+        )
+    };
+    let same_bodies = particle_set
+        .iter()
+        .map(|body| (body.entity, body.mu != 0.0))
+        .eq(in_set_order()
+            .map(|body| (body.entity, body.mu != 0.0)));
+    if same_bodies {
+        for (body, synced) in
+            particle_set.iter_mut().zip(in_set_order())
+        {
+            body.position = synced.position;
+            body.mu = synced.mu;
+        }
+    } else {
+        *particle_set = ParticleSet::new();
+        for body in bodies.drain(..) {
+            particle_set.add(body);
+        }
+    }
+}
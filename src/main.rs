@@ -1,28 +1,124 @@
-use std::f32::consts::PI;
-
 use bevy::{
-    input::{mouse::MouseButtonInput, ButtonState},
+    diagnostic::FrameTimeDiagnosticsPlugin,
+    input::{
+        mouse::MouseButtonInput, touch::Touch, ButtonState,
+    },
     math::Vec3Swizzles,
     prelude::*,
-    sprite::MaterialMesh2dBundle,
 };
-use bevy_inspector_egui::WorldInspectorPlugin;
+use bevy_inspector_egui::{
+    bevy_egui::EguiContext, WorldInspectorPlugin,
+};
 use bevy_mouse_tracking_plugin::{
-    MousePosPlugin, MousePosWorld,
+    MainCamera, MousePosPlugin, MousePosWorld,
 };
+use bevy_prototype_debug_lines::DebugLinesPlugin;
 use bevy_rapier2d::{
     prelude::*, render::RapierDebugRenderPlugin,
 };
 use particular::ParticleSet;
 use space_golf::{
+    afterimage::AfterimagePlugin,
+    assist::GravityAssistPlugin,
+    atmosphere::{Atmosphere, AtmospherePlugin},
+    auto_aim::{AutoAimPlugin, LaunchShot},
+    backdrop::{BackdropPlugin, DEFAULT_BACKGROUND},
+    blast::BlastPlugin,
+    bounds::WorldBoundsPlugin,
+    contact_points::ContactPointsPlugin,
+    contacts::BallContactPlugin,
+    contours::PotentialContoursPlugin,
+    course::{
+        Bounces, Course, CoursePlugin, GameMode, ShotTimer,
+        Strokes,
+    },
+    cup::CupPlugin,
+    density::DensityPlugin,
+    editor::EditorPlugin,
+    elements::OrbitalElementsPlugin,
+    energy::EnergyDiagnosticPlugin,
+    field_zone::{
+        spawn_field_zone, FieldZone, FieldZonePlugin,
+    },
+    flyby_rail::FlybyRailPlugin,
+    focus::CameraFocusPlugin,
+    forces::ForceModifierPlugin,
+    fragile::FragileBallsPlugin,
+    framing::FramingPlugin,
+    freeze::FreezeGravityPlugin,
+    generate::GeneratePlugin,
+    ghost::GhostBallPlugin,
     golf_ball::{
-        CircleWithGravity, GolfBallSettings, PointMass,
+        spawn_ball, BallAssets, BallColor, BallPalette,
+        GolfBall, GolfBallSettings, PointMass,
+        SpawnBallPlugin,
+    },
+    gravity::{
+        accelerate_particles, apply_ball_gravity,
+        clamp_speeds, finish_substeps,
+        hand_gravity_to_rapier, Attractor, BallGravity,
+        GravityCache, GravityMode, GravitySettings,
+        SubstepCorrections,
+    },
+    gravity_budget::GravityBudgetPlugin,
+    grid::GridPlugin,
+    hole::{Hole, HoleAssistPlugin},
+    hud::HudPlugin,
+    impact::ImpactEstimatesPlugin,
+    launch::{
+        charge_launch, LaunchCharge, LaunchChargePlugin,
+    },
+    launch_queue::LaunchQueuePlugin,
+    leaderboard::LeaderboardPlugin,
+    level::{Level, TeeBox},
+    measure::MeasurePlugin,
+    menu::{return_to_menu, AppState, LevelMenuPlugin},
+    null_points::NullPointsPlugin,
+    orbit::OrbitPresetPlugin,
+    paint::PaintPlugin,
+    patrol::PatrolPlugin,
+    phase::{PhaseMask, PhasePlugin},
+    photo::PhotoModePlugin,
+    pin::PinBallsPlugin,
+    planet_spec::{
+        Planet, PlanetPreset, PlanetShape, PlanetSpec,
+        PlanetTexturePlugin, SurfaceType,
+    },
+    practice::{
+        is_practice, practice_groups, Practice,
+        PracticePlugin, PRACTICE_ALPHA,
+    },
+    prediction::{draw_prediction, PredictionPlugin},
+    render::PolyLinePlugin,
+    replay::ReplayPlugin,
+    rng::RngPlugin,
+    share::ShareCodePlugin,
+    shot_paths::ShotPathsPlugin,
+    sim_state::{sim_running, SimStatePlugin},
+    soi::SoiPlugin,
+    spin::{FrameDraggingPlugin, Spin, SpinPlugin},
+    split_screen::{
+        Player, PlayerTwoCamera, SplitScreen,
+        SplitScreenPlugin,
     },
-    planet::PlanetPlugin,
-    Body,
+    stats::StatsOverlayPlugin,
+    surface_snap::{SurfaceSnapPlugin, SurfaceSnapper},
+    sync_particle_set,
+    terminator::TerminatorPlugin,
+    thrust::{Thrust, ThrustPlugin},
+    thumbnail::{ThumbnailCamera, ThumbnailPlugin},
+    time_scale::TimeScalePlugin,
+    tooltip::TooltipPlugin,
+    trace::TraceToHolePlugin,
+    tractor::GravityTractorPlugin,
+    trail_export::TrailExportPlugin,
+    typed_launch::TypedLaunchPlugin,
+    undo::{ShotHistory, UndoPlugin},
+    velocity_arrows::VelocityArrowPlugin,
+    well_depth::WellDepthPlugin,
+    Body, GravitySystem, PIXELS_PER_METER,
 };
-
-const G: f32 = 1000.0;
+use std::f32::consts::{FRAC_PI_2, PI};
 
 fn main() {
     App::new()
@@ -33,25 +129,143 @@ fn main() {
             ..Default::default()
         })
         .init_resource::<GolfBallSettings>()
-        .insert_resource(ClearColor(Color::BLACK))
+        .register_type::<GolfBallSettings>()
+        .register_type::<PointMass>()
+        .register_type::<Attractor>()
+        .register_type::<GolfBall>()
+        .register_type::<BallColor>()
+        .register_type::<Planet>()
+        .register_type::<Hole>()
+        .init_resource::<BallPalette>()
+        .init_resource::<GravityMode>()
+        .init_resource::<BallGravity>()
+        .init_resource::<GravitySettings>()
+        .init_resource::<GravityCache>()
+        .init_resource::<SubstepCorrections>()
+        .insert_resource(ClearColor(DEFAULT_BACKGROUND))
         .insert_resource(ParticleSet::<Body>::new())
         .add_plugins(DefaultPlugins)
-        .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+        .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXELS_PER_METER))
         .add_plugin(RapierDebugRenderPlugin::default())
         .add_plugin(MousePosPlugin::SingleCamera)
         .add_plugin(WorldInspectorPlugin::new())
+        .add_plugin(DebugLinesPlugin::default())
         // .add_plugin(PlanetPlugin)
+        .add_plugin(RngPlugin::default())
+        .add_plugin(SimStatePlugin)
+        .add_plugin(TimeScalePlugin)
+        .add_plugin(AtmospherePlugin)
+        .add_plugin(FragileBallsPlugin)
+        .add_plugin(FieldZonePlugin)
+        .add_plugin(GridPlugin)
+        .add_plugin(SpinPlugin)
+        .add_plugin(FrameDraggingPlugin)
+        .add_plugin(PolyLinePlugin)
+        .add_plugin(PredictionPlugin)
+        .add_plugin(ReplayPlugin)
+        .add_plugin(ThrustPlugin)
+        .add_plugin(EditorPlugin)
+        .add_plugin(GeneratePlugin)
+        .add_plugin(CoursePlugin)
+        .add_plugin(HudPlugin)
+        .add_plugin(LeaderboardPlugin)
+        .add_plugin(GhostBallPlugin)
+        .add_plugin(TooltipPlugin)
+        .add_plugin(OrbitPresetPlugin)
+        .add_plugin(HoleAssistPlugin)
+        .add_plugin(VelocityArrowPlugin)
+        .add_plugin(UndoPlugin)
+        .add_plugin(DensityPlugin)
+        .add_plugin(BallContactPlugin)
+        .add_plugin(AutoAimPlugin)
+        .add_plugin(WorldBoundsPlugin)
+        .add_plugin(FrameTimeDiagnosticsPlugin)
+        .add_plugin(EnergyDiagnosticPlugin)
+        .add_plugin(StatsOverlayPlugin)
+        .add_plugin(PlanetTexturePlugin)
+        .add_plugin(SoiPlugin)
+        .add_plugin(PinBallsPlugin)
+        .add_plugin(PhotoModePlugin)
+        .add_plugin(GravityAssistPlugin)
+        .add_plugin(SpawnBallPlugin)
+        .add_plugin(ShotPathsPlugin)
+        .add_plugin(BackdropPlugin)
+        .add_plugin(PotentialContoursPlugin)
+        .add_plugin(BlastPlugin)
+        .add_plugin(OrbitalElementsPlugin)
+        .add_plugin(ThumbnailPlugin)
+        .add_plugin(CameraFocusPlugin)
+        .add_plugin(WellDepthPlugin)
+        .add_plugin(TraceToHolePlugin)
+        .add_plugin(CupPlugin)
+        .add_plugin(FreezeGravityPlugin)
+        .add_plugin(TrailExportPlugin)
+        .add_plugin(LaunchChargePlugin)
+        .add_plugin(NullPointsPlugin)
+        .add_plugin(TerminatorPlugin)
+        .add_plugin(LevelMenuPlugin)
+        .add_plugin(ImpactEstimatesPlugin)
+        .add_plugin(FramingPlugin)
+        .add_plugin(PracticePlugin)
+        .add_plugin(ForceModifierPlugin)
+        .add_plugin(LaunchQueuePlugin)
+        .add_plugin(PhasePlugin)
+        .add_plugin(PatrolPlugin)
+        .add_plugin(SurfaceSnapPlugin)
+        .add_plugin(ContactPointsPlugin)
+        .add_plugin(PaintPlugin)
+        .add_plugin(FlybyRailPlugin)
+        .add_plugin(SplitScreenPlugin)
+        .add_plugin(AfterimagePlugin)
+        .add_plugin(GravityBudgetPlugin)
+        .add_plugin(ShareCodePlugin)
+        .add_plugin(MeasurePlugin)
+        .add_plugin(TypedLaunchPlugin)
+        .add_plugin(GravityTractorPlugin)
         .add_startup_system(setup)
-        .add_system(place_body)
+        .add_system(track_aim_curl.before(place_body))
+        .add_system(
+            place_body
+                .after(charge_launch)
+                .after(return_to_menu)
+                .before(draw_prediction)
+                .with_run_criteria(State::on_update(
+                    AppState::Playing,
+                )),
+        )
         .add_system_set_to_stage(
             CoreStage::PreUpdate,
             SystemSet::new()
-                .with_system(sync_particle_set),
+                .with_run_criteria(sim_running)
+                .with_system(
+                    apply_ball_gravity
+                        .before(GravitySystem::Sync),
+                )
+                .with_system(
+                    sync_particle_set
+                        .label(GravitySystem::Sync),
+                ),
         )
         .add_system_set_to_stage(
             CoreStage::Update,
             SystemSet::new()
-                .with_system(accelerate_particles),
+                .with_run_criteria(sim_running)
+                .with_system(
+                    accelerate_particles
+                        .label(GravitySystem::Accelerate),
+                )
+                .with_system(
+                    hand_gravity_to_rapier
+                        .after(GravitySystem::Accelerate),
+                )
+                .with_system(
+                    clamp_speeds
+                        .after(GravitySystem::Accelerate),
+                ),
+        )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            finish_substeps,
         )
         .run();
 }
@@ -63,182 +277,460 @@ fn setup(
     mut rapier_config: ResMut<RapierConfiguration>,
 ) {
     rapier_config.gravity = Vec2::ZERO;
-    commands.spawn_bundle(Camera2dBundle::default());
+    commands
+        .spawn_bundle(Camera2dBundle::default())
+        // the one the mouse is tracked through, even while
+        // level thumbnails have cameras of their own
+        .insert(MainCamera);
 
-    /// Planet 1
-    let mass = 10E5;
-    let density = 20.0;
-    let radius = (mass / (density * PI)).sqrt();
-    let entity = commands.spawn_bundle(CircleWithGravity {
-        shape_bundle: MaterialMesh2dBundle {
-            mesh: meshes
-                .add(Mesh::from(shape::Circle {
-                    radius,
-                    ..default()
-                }))
-                .into(),
-            transform: Transform::from_xyz(
-                -300.0, -50.0, 1.0,
-            ),
-            material: materials
-                .add(ColorMaterial::from(Color::BLUE)),
-            ..default()
-        },
-        collider: Collider::ball(radius),
-        friction: Friction {
-            coefficient: 10.0,
-            ..default()
-        },
-        mass: ColliderMassProperties::Mass(mass),
-        restitution: Restitution {
-            coefficient: 0.0,
-            ..default()
-        },
-        rigidbody: RigidBody::Fixed,
-        velocity: Velocity::zero(),
-        acceleration: ExternalForce::default(),
-        point_mass: PointMass::HasGravity {
-            // mass: body_info.mass,
-            mass: mass,
-        },
-    });
+    let mut level = Level::new();
+    level
+        .set_name("Two Planets")
+        .add_planet(
+            PlanetSpec {
+                position: Vec3::new(-300.0, -50.0, 1.0),
+                mass: 10E5,
+                atmosphere: Some(Atmosphere {
+                    radius: 200.0,
+                    drag: 50.0,
+                }),
+                ..default()
+            }
+            .with_preset(PlanetPreset::Ocean),
+        )
+        .add_planet(
+            PlanetSpec {
+                position: Vec3::new(200.0, 10.0, 1.0),
+                mass: 8E5,
+                spin: Some(Spin {
+                    axis: Vec3::Z,
+                    rate: 0.5,
+                }),
+                ..default()
+            }
+            .with_preset(PlanetPreset::Desert),
+        )
+        .set_tee(Vec2::new(0.0, -250.0))
+        .place_hole_on_surface(
+            1,
+            FRAC_PI_2,
+            Hole::default(),
+        )
+        .set_par(3);
 
-    /// Planet 2
-    let mass = 8E5;
-    let density = 20.0;
-    let radius = (mass / (density * PI)).sqrt();
-    let entity = commands.spawn_bundle(CircleWithGravity {
-        shape_bundle: MaterialMesh2dBundle {
-            mesh: meshes
-                .add(Mesh::from(shape::Circle {
-                    radius,
-                    ..default()
-                }))
-                .into(),
-            transform: Transform::from_xyz(
-                200.0, 10.0, 1.0,
-            ),
-            material: materials
-                .add(ColorMaterial::from(Color::BLUE)),
-            ..default()
-        },
-        collider: Collider::ball(radius),
-        friction: Friction {
-            coefficient: 10.0,
-            ..default()
-        },
-        mass: ColliderMassProperties::Mass(mass),
-        restitution: Restitution {
-            coefficient: 0.0,
-            ..default()
-        },
-        rigidbody: RigidBody::Fixed,
-        velocity: Velocity::zero(),
-        acceleration: ExternalForce::default(),
-        point_mass: PointMass::HasGravity {
-            // mass: body_info.mass,
-            mass: mass,
+    // both holes have to be filled to finish this one
+    let mut twin_holes = Level::new();
+    twin_holes
+        .set_name("Twin Holes")
+        .add_planet(
+            PlanetSpec {
+                position: Vec3::new(0.0, 0.0, 1.0),
+                mass: 12E5,
+                ..default()
+            }
+            .with_preset(PlanetPreset::Lava),
+        )
+        .set_tee(Vec2::new(-450.0, 0.0))
+        .set_tee_box(TeeBox::around(
+            Vec2::new(-450.0, 0.0),
+            Vec2::new(100.0, 150.0),
+        ))
+        .place_hole_on_surface(
+            0,
+            FRAC_PI_2,
+            Hole::default(),
+        )
+        .place_hole_on_surface(
+            0,
+            -FRAC_PI_2,
+            Hole::default(),
+        )
+        .set_par(4)
+        .set_background(Color::rgb(0.02, 0.0, 0.08));
+
+    // lumpy asteroids guarding the gap to the planet, with
+    // gravity turned up from the course's usual `G`
+    let mut asteroids = Level::new();
+    asteroids
+        .set_name("Asteroids")
+        .add_planet(
+            PlanetSpec {
+                position: Vec3::new(250.0, 0.0, 1.0),
+                mass: 10E5,
+                ..default()
+            }
+            .with_preset(PlanetPreset::Forest),
+        )
+        .add_planet(
+            PlanetSpec {
+                position: Vec3::new(-150.0, 130.0, 1.0),
+                mass: 2E5,
+                shape: PlanetShape::Polygon(vec![
+                    Vec2::new(-1.0, -0.6),
+                    Vec2::new(0.2, -0.9),
+                    Vec2::new(1.0, -0.2),
+                    Vec2::new(0.7, 0.7),
+                    Vec2::new(-0.3, 0.9),
+                    Vec2::new(-0.9, 0.3),
+                ]),
+                color: Color::GRAY,
+                ..default()
+            }
+            .with_surface(SurfaceType::Rough),
+        )
+        .add_planet(
+            PlanetSpec {
+                position: Vec3::new(-100.0, -140.0, 1.0),
+                mass: 1.5E5,
+                // a dent on the top for balls to settle in
+                shape: PlanetShape::Polygon(vec![
+                    Vec2::new(-1.0, -0.5),
+                    Vec2::new(0.8, -0.8),
+                    Vec2::new(1.0, 0.4),
+                    Vec2::new(0.2, 0.1),
+                    Vec2::new(-0.4, 0.6),
+                ]),
+                color: Color::DARK_GRAY,
+                ..default()
+            }
+            .with_surface(SurfaceType::Rough),
+        )
+        .set_tee(Vec2::new(-500.0, 0.0))
+        .place_hole_on_surface(0, PI, Hole::default())
+        .set_g(1500.0)
+        .set_par(3);
+
+    // the moon in the way only pulls, balls go straight
+    // through it
+    let mut phantom_moon = Level::new();
+    phantom_moon
+        .set_name("Phantom Moon")
+        .add_planet(
+            PlanetSpec {
+                position: Vec3::new(0.0, 0.0, 1.0),
+                mass: 8E5,
+                color: Color::rgba(0.7, 0.7, 0.9, 0.4),
+                phase: PhaseMask::layer(0),
+                ..default()
+            },
+        )
+        .add_planet(
+            PlanetSpec {
+                position: Vec3::new(380.0, 0.0, 1.0),
+                mass: 6E5,
+                ..default()
+            }
+            .with_preset(PlanetPreset::Ice),
+        )
+        .set_tee(Vec2::new(-450.0, 0.0))
+        .place_hole_on_surface(1, PI, Hole::default())
+        .set_ball_phase(PhaseMask::layer(0))
+        .set_par(2);
+
+    let levels =
+        vec![level, twin_holes, asteroids, phantom_moon];
+    for level in levels.iter() {
+        if let Err(error) = level.validate() {
+            warn!(
+                "level {} is not playable: {}",
+                level.name, error
+            );
+        }
+    }
+    levels[0].spawn(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+    );
+    commands.insert_resource(Course { levels, current: 0 });
+    spawn_field_zone(
+        &mut commands,
+        FieldZone {
+            min: Vec2::new(-100.0, 150.0),
+            max: Vec2::new(100.0, 300.0),
+            accel: Vec2::new(50.0, 0.0),
         },
-    });
+    );
 }
 
-fn sync_particle_set(
-    mut particle_set: ResMut<ParticleSet<Body>>,
-    query: Query<(Entity, &GlobalTransform, &PointMass)>,
-) {
-    *particle_set = ParticleSet::new();
-    query.for_each(|(entity, tranform, point_mass)| {
-        let mu = match point_mass {
-            PointMass::HasGravity { mass } => *mass * G,
-            PointMass::AffectedByGravity => 0.0,
-        };
-        particle_set.add(Body::new(
-            tranform.translation(),
-            mu,
-            entity,
-        ));
-    })
+/// A shot being aimed, launched or called off, from the
+/// mouse or from a touch.
+enum AimInput {
+    Press(Vec3),
+    Release(Vec3),
+    Cancel,
+    /// a shot that skips aiming altogether
+    Launch(Vec3, Vec2),
 }
 
-fn accelerate_particles(
-    mut particle_set: ResMut<ParticleSet<Body>>,
-    mut query: Query<&mut ExternalForce, With<PointMass>>,
+/// Adds up how far the aim direction turns while a shot
+/// is being dragged, for `GolfBallSettings::aim_curl`.
+fn track_aim_curl(
+    mut body_info: ResMut<GolfBallSettings>,
+    mouse_pos: Res<MousePosWorld>,
+    mut last_aim: Local<Option<Vec2>>,
 ) {
-    for (body, gravity) in particle_set.result() {
-        if let Ok(mut acceleration) =
-            query.get_mut(body.entity)
-        {
-            acceleration.force = gravity.xy();
+    let place_pos = match body_info.position {
+        Some(place_pos) => place_pos.xy(),
+        None => {
+            *last_aim = None;
+            return;
         }
+    };
+    let aim = place_pos - mouse_pos.truncate();
+    // a drag that's barely started has no direction yet
+    if aim.length_squared() < 1.0 {
+        return;
+    }
+    if let Some(last) = *last_aim {
+        // the signed angle between them, always the short
+        // way around
+        let turn = last.perp_dot(aim).atan2(last.dot(aim));
+        body_info.aim_curl += turn;
     }
+    *last_aim = Some(aim);
 }
 
+/// Turns a position in the window into one in the world,
+/// the way `MousePosWorld` does for the cursor.
+fn window_to_world(
+    windows: &Windows,
+    camera: (&GlobalTransform, &OrthographicProjection),
+    position: Vec2,
+) -> Option<Vec3> {
+    let window = windows.get_primary()?;
+    let (transform, projection) = camera;
+    let half_size =
+        Vec2::new(window.width(), window.height()) / 2.0;
+    Some(
+        (transform.translation().truncate()
+            + (position - half_size) * projection.scale)
+            .extend(0.0),
+    )
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
 fn place_body(
     mut commands: Commands,
     mut click_event: EventReader<MouseButtonInput>,
+    mut launches: EventReader<LaunchShot>,
+    keyboard: Res<Input<KeyCode>>,
+    touches: Res<Touches>,
+    windows: Res<Windows>,
+    cameras: Query<
+        (&GlobalTransform, &OrthographicProjection),
+        (
+            With<Camera2d>,
+            Without<ThumbnailCamera>,
+            Without<PlayerTwoCamera>,
+        ),
+    >,
     // mut lines: ResMut<DebugLines>,
+    (
+        course,
+        mode,
+        charge,
+        snapper,
+        split,
+        mut egui_context,
+    ): (
+        Res<Course>,
+        Res<GameMode>,
+        Res<LaunchCharge>,
+        SurfaceSnapper,
+        Res<SplitScreen>,
+        ResMut<EguiContext>,
+    ),
     mut body_info: ResMut<GolfBallSettings>,
+    mut palette: ResMut<BallPalette>,
+    mut strokes: ResMut<Strokes>,
+    mut history: ResMut<ShotHistory>,
     mouse_pos: Res<MousePosWorld>,
+    mut ball_assets: ResMut<BallAssets>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
     let mouse_pos = mouse_pos.truncate().extend(0.0);
 
+    if keyboard.just_pressed(KeyCode::Escape) {
+        body_info.position = None;
+    }
+
+    // shift-clicks select a ball instead, see
+    // `OrbitalElementsPlugin`
+    let selecting = keyboard
+        .any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    // clicks on a window, like the "Launch" button, don't
+    // start a shot, though one already being aimed can be
+    // let go over a window
+    let over_window =
+        egui_context.ctx_mut().is_pointer_over_area();
+    let mut inputs = Vec::new();
     for event in click_event.iter() {
-        if event.button == MouseButton::Left {
-            match event.state {
+        // right clicking while aiming cancels the shot
+        if event.button == MouseButton::Right
+            && event.state == ButtonState::Pressed
+        {
+            inputs.push(AimInput::Cancel);
+        }
+        if event.button == MouseButton::Left
+            && !selecting
+            && !(over_window
+                && event.state == ButtonState::Pressed)
+        {
+            inputs.push(match event.state {
                 ButtonState::Pressed => {
-                    body_info.position = Some(mouse_pos)
+                    AimInput::Press(mouse_pos)
                 }
                 ButtonState::Released => {
-                    if let Some(place_pos) =
-                        body_info.position.take()
-                    {
-                        let mass: f32 = 100.0_f32.max(1.0);
-                        let density = 1.0;
-                        let radius =
-                            (mass / (density * PI)).sqrt();
-                        let entity = commands.spawn_bundle(CircleWithGravity {
-                            shape_bundle: MaterialMesh2dBundle {
-                                mesh: meshes
-                                    .add(Mesh::from(shape::Circle {
-                                        radius,
-                                        ..default()
-                                    }))
-                                    .into(),
-                                transform: Transform::from_xyz(place_pos.x, place_pos.y, place_pos.z),
-                                material: materials
-                                    .add(ColorMaterial::from(Color::WHITE)),
-                                ..default()
-                            },
-                            collider: Collider::ball(radius),
-                            friction: Friction {
-                                coefficient: 10.0,
-                                ..default()
-                            },
-                            mass: ColliderMassProperties::Mass(mass),
-                            restitution: Restitution {
-                                coefficient: 0.0,
-                                ..default()
-                            },
-                            rigidbody: RigidBody::Dynamic,
-                            velocity: Velocity::linear((place_pos - mouse_pos).xy()),
-                            acceleration: ExternalForce::default(),
-                            point_mass: PointMass::HasGravity {
-                                // mass: body_info.mass,
-                                mass: mass,
-                            },
-                        });
+                    AimInput::Release(mouse_pos)
+                }
+            });
+        }
+    }
+    // a touch aims like the left mouse button, from
+    // wherever the finger is
+    if let Ok(camera) = cameras.get_single() {
+        let touch_pos = |touch: &Touch| {
+            window_to_world(
+                &windows,
+                camera,
+                touch.position(),
+            )
+        };
+        inputs.extend(
+            touches
+                .iter_just_pressed()
+                .filter_map(touch_pos)
+                .map(AimInput::Press),
+        );
+        inputs.extend(
+            touches
+                .iter_just_released()
+                .filter_map(touch_pos)
+                .map(AimInput::Release),
+        );
+    }
+    if touches.iter_just_cancelled().next().is_some() {
+        inputs.push(AimInput::Cancel);
+    }
+
+    inputs.extend(launches.iter().map(|launch| {
+        AimInput::Launch(launch.position, launch.velocity)
+    }));
 
-                        // if body_info.trail {
-                        //     entity.insert(Trail::new(
-                        //         20.0, 1,
-                        //     ));
-                        // }
-                    }
+    // challenge levels can only be played from inside
+    // their tee box
+    let outside_tee_box = |position: Vec3| {
+        course.tee_box(*mode).is_some_and(|tee_box| {
+            !tee_box.contains(position.xy())
+        })
+    };
+    for input in inputs {
+        let (place_pos, velocity, spin) = match input {
+            AimInput::Press(position) => {
+                // waiting for player two to shoot
+                if !split.can_shoot(Player::One) {
+                    continue;
+                }
+                // snapping can move the ball out of the
+                // tee box, so check where it ends up
+                let position = snapper
+                    .snap(position, body_info.radius());
+                if outside_tee_box(position) {
+                    continue;
+                }
+                body_info.position = Some(position);
+                body_info.aim_curl = 0.0;
+                continue;
+            }
+            AimInput::Cancel => {
+                body_info.position = None;
+                continue;
+            }
+            AimInput::Release(release_pos) => {
+                match body_info.position.take() {
+                    Some(place_pos) => (
+                        place_pos,
+                        charge.velocity(
+                            place_pos.xy(),
+                            release_pos.xy(),
+                        ),
+                        body_info.aim_curl
+                            * body_info.spin_per_curl,
+                    ),
+                    None => continue,
                 }
             }
+            AimInput::Launch(position, velocity) => {
+                if outside_tee_box(position) {
+                    continue;
+                }
+                (position, velocity, 0.0)
+            }
+        };
+        // scripted launches are never practice
+        let practice =
+            !matches!(input, AimInput::Launch(..))
+                && is_practice(&keyboard);
+        // out of strokes, only practice shots are left
+        if !practice
+            && *mode == GameMode::Challenge
+            && strokes.0 >= course.stroke_limit()
+        {
+            continue;
         }
+        let color = if practice {
+            *palette.peek_color().set_a(PRACTICE_ALPHA)
+        } else {
+            strokes.0 += 1;
+            palette.next_color()
+        };
+        let entity = spawn_ball(
+            &mut commands,
+            &mut ball_assets,
+            &mut meshes,
+            &mut materials,
+            place_pos,
+            velocity,
+            color,
+            &body_info,
+        );
+        if spin != 0.0 {
+            commands.entity(entity).insert(Velocity {
+                linvel: velocity,
+                angvel: spin,
+            });
+        }
+        if practice {
+            commands
+                .entity(entity)
+                .insert(Practice::default())
+                .insert(practice_groups());
+        } else {
+            history.push(entity);
+            commands
+                .entity(entity)
+                .insert(ShotTimer::new(
+                    body_info.max_shot_time,
+                ))
+                .insert(Bounces::default());
+        }
+        if body_info.thrust_fuel > 0.0 {
+            commands.entity(entity).insert(Thrust {
+                fuel: body_info.thrust_fuel,
+                power: body_info.thrust_power,
+            });
+        }
+
+        // if body_info.trail {
+        //     commands.entity(entity).insert(Trail::new(
+        //         20.0, 1,
+        //     ));
+        // }
     }
 
     // if let Some(place_pos) = body_info.position {
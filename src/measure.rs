@@ -0,0 +1,242 @@
+use bevy::{
+    input::{mouse::MouseButtonInput, ButtonState},
+    prelude::*,
+};
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    hole::Hole, menu::playing, planet_spec::Planet,
+};
+
+/// A ruler for laying out levels, toggled with `/`.
+/// Middle-click once to start measuring and again to
+/// finish: the distance, the angle counter clockwise from
+/// +x and the offset between the two points are shown on
+/// the line until the next measurement, or until
+/// `Shift+/` clears it. Clicking on or close to a planet
+/// or hole measures from its center.
+pub struct MeasureTool {
+    pub enabled: bool,
+    /// how close to a center a click has to be to snap to
+    /// it, on top of the body's own radius
+    pub snap_distance: f32,
+    start: Option<Vec2>,
+    end: Option<Vec2>,
+}
+
+impl Default for MeasureTool {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            snap_distance: 15.0,
+            start: None,
+            end: None,
+        }
+    }
+}
+
+impl MeasureTool {
+    /// The finished measurement, if there is one.
+    pub fn measurement(&self) -> Option<(Vec2, Vec2)> {
+        self.start.zip(self.end)
+    }
+
+    pub fn clear(&mut self) {
+        self.start = None;
+        self.end = None;
+    }
+}
+
+const RULER_COLOR: Color = Color::PINK;
+/// half the width of the cross on each end
+const END_SIZE: f32 = 4.0;
+
+#[derive(Component)]
+struct MeasureLabel;
+
+pub struct MeasurePlugin;
+
+impl Plugin for MeasurePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MeasureTool>()
+            .add_startup_system(spawn_measure_label)
+            .add_system(
+                toggle_measure.with_run_criteria(playing),
+            )
+            .add_system(
+                pick_measure_points
+                    .with_run_criteria(playing)
+                    .after(toggle_measure),
+            )
+            .add_system(
+                draw_measurement.after(pick_measure_points),
+            );
+    }
+}
+
+fn toggle_measure(
+    keyboard: Res<Input<KeyCode>>,
+    mut measure: ResMut<MeasureTool>,
+) {
+    if !keyboard.just_pressed(KeyCode::Slash) {
+        return;
+    }
+    if keyboard
+        .any_pressed([KeyCode::LShift, KeyCode::RShift])
+    {
+        measure.clear();
+    } else {
+        measure.enabled = !measure.enabled;
+    }
+}
+
+fn spawn_measure_label(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: asset_server
+                        .load("fonts/DejaVuSansMono.ttf"),
+                    font_size: 14.0,
+                    color: RULER_COLOR,
+                },
+            ),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(MeasureLabel);
+}
+
+/// The center of the planet or hole `point` is on or
+/// close to, the closest one if there are several, or
+/// `point` itself if there's none.
+#[allow(clippy::type_complexity)]
+fn snap(
+    point: Vec2,
+    snap_distance: f32,
+    bodies: &Query<
+        (&GlobalTransform, Option<&Collider>),
+        Or<(With<Planet>, With<Hole>)>,
+    >,
+) -> Vec2 {
+    bodies
+        .iter()
+        .filter_map(|(transform, collider)| {
+            let center = transform.translation().truncate();
+            let radius = collider
+                .and_then(|collider| collider.as_ball())
+                .map_or(0.0, |ball| ball.radius());
+            let distance = center.distance(point);
+            (distance <= radius + snap_distance)
+                .then_some((center, distance))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(point, |(center, _)| center)
+}
+
+#[allow(clippy::type_complexity)]
+fn pick_measure_points(
+    mut click_event: EventReader<MouseButtonInput>,
+    mut measure: ResMut<MeasureTool>,
+    mouse_pos: Res<MousePosWorld>,
+    bodies: Query<
+        (&GlobalTransform, Option<&Collider>),
+        Or<(With<Planet>, With<Hole>)>,
+    >,
+) {
+    for event in click_event.iter() {
+        if !measure.enabled
+            || event.button != MouseButton::Middle
+            || event.state != ButtonState::Pressed
+        {
+            continue;
+        }
+        let point = snap(
+            mouse_pos.truncate(),
+            measure.snap_distance,
+            &bodies,
+        );
+        match (measure.start, measure.end) {
+            (Some(_), None) => measure.end = Some(point),
+            // a finished measurement stays up until the
+            // next one is started
+            _ => {
+                measure.start = Some(point);
+                measure.end = None;
+            }
+        }
+    }
+}
+
+fn draw_measurement(
+    measure: Res<MeasureTool>,
+    mouse_pos: Res<MousePosWorld>,
+    mut lines: ResMut<DebugLines>,
+    mut labels: Query<
+        (&mut Text, &mut Transform, &mut Visibility),
+        With<MeasureLabel>,
+    >,
+) {
+    // while only the start is picked the ruler follows
+    // the mouse
+    let ends = match (measure.start, measure.end) {
+        (Some(start), Some(end)) => Some((start, end)),
+        (Some(start), None) if measure.enabled => {
+            Some((start, mouse_pos.truncate()))
+        }
+        _ => None,
+    };
+    let (start, end) = match ends {
+        Some(ends) => ends,
+        None => {
+            for (_, _, mut visibility) in labels.iter_mut()
+            {
+                if visibility.is_visible {
+                    visibility.is_visible = false;
+                }
+            }
+            return;
+        }
+    };
+    lines.line_colored(
+        start.extend(10.0),
+        end.extend(10.0),
+        0.0,
+        RULER_COLOR,
+    );
+    for point in [start, end] {
+        for arm in [
+            Vec2::new(END_SIZE, END_SIZE),
+            Vec2::new(END_SIZE, -END_SIZE),
+        ] {
+            lines.line_colored(
+                (point - arm).extend(10.0),
+                (point + arm).extend(10.0),
+                0.0,
+                RULER_COLOR,
+            );
+        }
+    }
+
+    let offset = end - start;
+    for (mut text, mut transform, mut visibility) in
+        labels.iter_mut()
+    {
+        visibility.is_visible = true;
+        text.sections[0].value = format!(
+            "{:.1} at {:.1}°\n({:.1}, {:.1})",
+            offset.length(),
+            offset.y.atan2(offset.x).to_degrees(),
+            offset.x,
+            offset.y
+        );
+        transform.translation =
+            ((start + end) / 2.0).extend(10.0);
+    }
+}
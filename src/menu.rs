@@ -0,0 +1,234 @@
+use bevy::{ecs::schedule::ShouldRun, prelude::*};
+
+use crate::{
+    course::{Assists, Course, Strokes},
+    golf_ball::{GolfBall, GolfBallSettings},
+    hole::Hole,
+    planet_spec::Planet,
+    replay::Checkpoint,
+    shot_paths::ShotPaths,
+    sim_state::SimState,
+    undo::ShotHistory,
+};
+
+/// Which screen the game is on. The game starts on the
+/// level select menu, and `Escape` pauses back to it from
+/// a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppState {
+    Menu,
+    Playing,
+}
+
+/// The level highlighted in the menu, moved with the up
+/// and down arrows and played with enter.
+#[derive(Default)]
+pub struct LevelSelection(pub usize);
+
+#[derive(Component)]
+struct LevelMenu;
+
+#[derive(Component)]
+struct LevelMenuText;
+
+/// Run criteria for anything the player does to a level,
+/// so no keys or clicks get through from behind the menu.
+/// Apps without the menu are always playing.
+pub fn playing(
+    state: Option<Res<State<AppState>>>,
+) -> ShouldRun {
+    match state {
+        Some(state)
+            if *state.current() != AppState::Playing =>
+        {
+            ShouldRun::No
+        }
+        _ => ShouldRun::Yes,
+    }
+}
+
+/// Lists the `Course`'s levels. The simulation is paused
+/// while the menu is up, and shots can only be taken while
+/// `Playing`.
+pub struct LevelMenuPlugin;
+
+impl Plugin for LevelMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state(AppState::Menu)
+            .init_resource::<LevelSelection>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Menu)
+                    .with_system(open_menu),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Menu)
+                    .with_system(navigate_menu)
+                    .with_system(
+                        update_menu.after(navigate_menu),
+                    ),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Menu)
+                    .with_system(close_menu),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Playing)
+                    .with_system(return_to_menu),
+            );
+    }
+}
+
+fn open_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    course: Res<Course>,
+    mut selection: ResMut<LevelSelection>,
+    mut sim_state: ResMut<SimState>,
+) {
+    selection.0 = course.current;
+    *sim_state = SimState::Paused;
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(
+                    Val::Percent(100.0),
+                    Val::Percent(100.0),
+                ),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+            ..default()
+        })
+        .insert(LevelMenu)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load(
+                            "fonts/DejaVuSansMono.ttf",
+                        ),
+                        font_size: 32.0,
+                        color: Color::WHITE,
+                    },
+                ))
+                .insert(LevelMenuText);
+        });
+}
+
+fn close_menu(
+    mut commands: Commands,
+    menus: Query<Entity, With<LevelMenu>>,
+    mut sim_state: ResMut<SimState>,
+) {
+    for menu in menus.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+    *sim_state = SimState::Running;
+}
+
+/// Playing a level starts it over, even if it's the one
+/// that was already being played: the shots, their paths
+/// and the replay checkpoint all belong to the old balls,
+/// so they're forgotten along with them.
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn navigate_menu(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    mut state: ResMut<State<AppState>>,
+    mut selection: ResMut<LevelSelection>,
+    mut course: ResMut<Course>,
+    mut strokes: ResMut<Strokes>,
+    mut assists: ResMut<Assists>,
+    mut history: ResMut<ShotHistory>,
+    mut paths: ResMut<ShotPaths>,
+    mut checkpoint: ResMut<Checkpoint>,
+    old: Query<
+        Entity,
+        Or<(With<Planet>, With<Hole>, With<GolfBall>)>,
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let count = course.levels.len();
+    if count == 0 {
+        return;
+    }
+    if keyboard.just_pressed(KeyCode::Up) {
+        selection.0 = (selection.0 + count - 1) % count;
+    }
+    if keyboard.just_pressed(KeyCode::Down) {
+        selection.0 = (selection.0 + 1) % count;
+    }
+    if !keyboard.just_pressed(KeyCode::Return) {
+        return;
+    }
+    for entity in old.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    course.current = selection.0.min(count - 1);
+    if let Some(level) = course.current_level() {
+        level.spawn(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+        );
+    }
+    strokes.0 = 0;
+    assists.0 = 0;
+    *history = ShotHistory::default();
+    paths.clear(&mut commands);
+    checkpoint.frame = None;
+    if let Err(error) = state.set(AppState::Playing) {
+        warn!("couldn't leave the level menu: {}", error);
+    }
+}
+
+fn update_menu(
+    course: Res<Course>,
+    selection: Res<LevelSelection>,
+    mut texts: Query<&mut Text, With<LevelMenuText>>,
+) {
+    let mut value = String::from("Select a level\n\n");
+    for (i, level) in course.levels.iter().enumerate() {
+        let marker =
+            if i == selection.0 { ">" } else { " " };
+        value.push_str(&format!(
+            "{} {}. {} (par {})\n",
+            marker,
+            i + 1,
+            level.name,
+            level.par
+        ));
+    }
+    value.push_str("\nUp/Down to choose, Enter to play");
+    for mut text in texts.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+/// Pauses back to the menu on `Escape`. While a shot is
+/// being aimed `Escape` only calls the shot off, so this
+/// has to run before whatever does that.
+pub fn return_to_menu(
+    keyboard: Res<Input<KeyCode>>,
+    ball: Res<GolfBallSettings>,
+    mut state: ResMut<State<AppState>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Escape)
+        || ball.position.is_some()
+    {
+        return;
+    }
+    if let Err(error) = state.set(AppState::Menu) {
+        warn!("couldn't open the level menu: {}", error);
+    }
+}
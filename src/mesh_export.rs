@@ -0,0 +1,115 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, VertexAttributeValues},
+        render_resource::PrimitiveTopology,
+    },
+};
+use std::{fmt::Write as _, fs, io, path::Path};
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+/// A triangle mesh as a Wavefront OBJ, which Blender and
+/// most other modelling tools can import: its positions,
+/// plus its normals and UVs when it has them. OBJ has no
+/// place for tangents or vertex colors, so those are left
+/// out. Meant for meshes built from a
+/// [`PlanetMesh`](crate::planet::PlanetMesh), but any
+/// indexed or unindexed triangle list works.
+pub fn mesh_obj(mesh: &Mesh) -> io::Result<String> {
+    if mesh.primitive_topology()
+        != PrimitiveTopology::TriangleList
+    {
+        return Err(invalid("only triangle lists export"));
+    }
+    let positions =
+        match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(
+                positions,
+            )) => positions,
+            _ => {
+                return Err(invalid(
+                    "the mesh has no positions",
+                ))
+            }
+        };
+    let normals =
+        match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(
+                normals,
+            )) => Some(normals),
+            _ => None,
+        };
+    let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+        Some(VertexAttributeValues::Float32x2(uvs)) => {
+            Some(uvs)
+        }
+        _ => None,
+    };
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(Indices::U16(indices)) => {
+            indices.iter().map(|i| *i as usize).collect()
+        }
+        Some(Indices::U32(indices)) => {
+            indices.iter().map(|i| *i as usize).collect()
+        }
+        None => (0..positions.len()).collect(),
+    };
+    if indices.len() % 3 != 0 {
+        return Err(invalid(
+            "the indices aren't whole triangles",
+        ));
+    }
+    if indices.iter().any(|i| *i >= positions.len()) {
+        return Err(invalid(
+            "an index points past the last vertex",
+        ));
+    }
+
+    let mut obj = String::from("# space golf planet\n");
+    // writing to a String can't fail
+    for [x, y, z] in positions {
+        let _ = writeln!(obj, "v {} {} {}", x, y, z);
+    }
+    for [u, v] in uvs.into_iter().flatten() {
+        // OBJ's v goes up the texture, wgpu's goes down
+        let _ = writeln!(obj, "vt {} {}", u, 1.0 - v);
+    }
+    for [x, y, z] in normals.into_iter().flatten() {
+        let _ = writeln!(obj, "vn {} {} {}", x, y, z);
+    }
+    // OBJ counts vertices from 1
+    let corner = |i: usize| match (uvs, normals) {
+        (Some(_), Some(_)) => {
+            format!("{}/{}/{}", i + 1, i + 1, i + 1)
+        }
+        (Some(_), None) => format!("{}/{}", i + 1, i + 1),
+        (None, Some(_)) => format!("{}//{}", i + 1, i + 1),
+        (None, None) => format!("{}", i + 1),
+    };
+    for triangle in indices.chunks(3) {
+        let _ = writeln!(
+            obj,
+            "f {} {} {}",
+            corner(triangle[0]),
+            corner(triangle[1]),
+            corner(triangle[2])
+        );
+    }
+    Ok(obj)
+}
+
+/// Writes [`mesh_obj`] to `path`, creating its directory
+/// if needed.
+pub fn export_planet_mesh(
+    mesh: &Mesh,
+    path: &Path,
+) -> io::Result<()> {
+    let obj = mesh_obj(mesh)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, obj)
+}
@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use particular::ParticleSet;
+
+use crate::{
+    gravity::{
+        falloff_gravity_at, GravitySettings, GravitySource,
+    },
+    menu::playing,
+    planet_spec::Planet,
+    Body,
+};
+
+/// Marks the points between planets where their pulls
+/// cancel out, toggled with `Y`. Between two planets this
+/// is the saddle a ball can balance on for a moment before
+/// falling one way or the other, which makes for good
+/// level design.
+pub struct NullPoints {
+    pub enabled: bool,
+    /// how many cells the search grid has along its
+    /// longer side
+    pub resolution: u32,
+    /// how weak the field has to be to count, as a
+    /// fraction of its average over the grid
+    pub threshold: f32,
+}
+
+impl Default for NullPoints {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            resolution: 64,
+            threshold: 0.05,
+        }
+    }
+}
+
+const MARKER_SIZE: f32 = 8.0;
+const MARKER_COLOR: Color = Color::FUCHSIA;
+
+/// Searches the grid from `min` to `max` for cells where
+/// the field is weaker than every one of their neighbours
+/// and than `threshold` times its average. Gravity only
+/// ever points towards its sources, so every null point
+/// lies between them and a grid around the sources finds
+/// them all.
+pub fn find_null_points(
+    sources: &[GravitySource],
+    min: Vec2,
+    max: Vec2,
+    resolution: u32,
+    threshold: f32,
+    gravity: &GravitySettings,
+) -> Vec<Vec2> {
+    let size = max - min;
+    let cell =
+        size.max_element() / resolution.max(1) as f32;
+    if cell <= 0.0 {
+        return Vec::new();
+    }
+    let columns = (size.x / cell).ceil() as usize + 1;
+    let rows = (size.y / cell).ceil() as usize + 1;
+    let position = |column: usize, row: usize| {
+        min + Vec2::new(column as f32, row as f32) * cell
+    };
+    let field: Vec<f32> = (0..rows)
+        .flat_map(|row| {
+            (0..columns).map(move |column| (column, row))
+        })
+        .map(|(column, row)| {
+            falloff_gravity_at(
+                sources,
+                position(column, row),
+                gravity.softening,
+                gravity.falloff_exponent,
+            )
+            .length()
+        })
+        .collect();
+    let average =
+        field.iter().sum::<f32>() / field.len() as f32;
+    let at = |column: usize, row: usize| {
+        field[row * columns + column]
+    };
+
+    let mut points = Vec::new();
+    // the border has no neighbours on one side, and the
+    // field there only ever fades away from the sources
+    for row in 1..rows.saturating_sub(1) {
+        for column in 1..columns.saturating_sub(1) {
+            let value = at(column, row);
+            if value >= threshold * average {
+                continue;
+            }
+            let lowest = (row - 1..=row + 1).all(|r| {
+                (column - 1..=column + 1).all(|c| {
+                    (c, r) == (column, row)
+                        || value < at(c, r)
+                })
+            });
+            // a cell right on top of a source leaves that
+            // source out, which looks like a dip
+            let point = position(column, row);
+            if lowest
+                && sources.iter().all(|source| {
+                    source.position.distance(point) > cell
+                })
+            {
+                points.push(point);
+            }
+        }
+    }
+    points
+}
+
+pub struct NullPointsPlugin;
+
+impl Plugin for NullPointsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NullPoints>()
+            .add_system(
+                toggle_null_points
+                    .with_run_criteria(playing),
+            )
+            .add_system(
+                draw_null_points.after(toggle_null_points),
+            );
+    }
+}
+
+fn toggle_null_points(
+    keyboard: Res<Input<KeyCode>>,
+    mut null_points: ResMut<NullPoints>,
+) {
+    if keyboard.just_pressed(KeyCode::Y) {
+        null_points.enabled = !null_points.enabled;
+    }
+}
+
+/// Only planets are searched around. Balls move too much
+/// for their null points to mean anything.
+fn draw_null_points(
+    mut lines: ResMut<DebugLines>,
+    null_points: Res<NullPoints>,
+    gravity: Res<GravitySettings>,
+    particle_set: Res<ParticleSet<Body>>,
+    planets: Query<(), With<Planet>>,
+) {
+    if !null_points.enabled {
+        return;
+    }
+    let sources: Vec<GravitySource> = particle_set
+        .iter()
+        .filter(|body| {
+            body.mu > 0.0 && planets.contains(body.entity)
+        })
+        .map(|body| GravitySource {
+            position: body.position.truncate(),
+            mu: body.mu,
+        })
+        .collect();
+    if sources.len() < 2 {
+        return;
+    }
+    let mut min = Vec2::splat(f32::INFINITY);
+    let mut max = Vec2::splat(f32::NEG_INFINITY);
+    for source in sources.iter() {
+        min = min.min(source.position);
+        max = max.max(source.position);
+    }
+    // planets in a row leave the box with no height
+    let padding = (max - min).max_element() * 0.1;
+    let points = find_null_points(
+        &sources,
+        min - padding,
+        max + padding,
+        null_points.resolution,
+        null_points.threshold,
+        &gravity,
+    );
+    for point in points {
+        for (a, b) in [
+            (Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0)),
+            (Vec2::new(-1.0, 1.0), Vec2::new(1.0, -1.0)),
+        ] {
+            lines.line_colored(
+                (point + a * MARKER_SIZE).extend(5.0),
+                (point + b * MARKER_SIZE).extend(5.0),
+                0.0,
+                MARKER_COLOR,
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+
+use crate::{
+    golf_ball::{
+        spawn_ball, BallAssets, BallPalette,
+        GolfBallSettings,
+    },
+    gravity::{
+        dominant_body, GravityBodies, GravitySettings,
+    },
+    menu::playing,
+    planet_spec::Planet,
+    Body,
+};
+
+/// Pressing `O` drops a ball into a circular orbit around
+/// the planet pulling hardest on the mouse cursor, on the
+/// side of the planet the cursor is on.
+pub struct OrbitPreset {
+    /// distance from the planet's center
+    pub radius: f32,
+}
+
+impl Default for OrbitPreset {
+    fn default() -> Self {
+        Self { radius: 250.0 }
+    }
+}
+
+pub struct OrbitPresetPlugin;
+
+impl Plugin for OrbitPresetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OrbitPreset>().add_system(
+            spawn_orbiting_ball.with_run_criteria(playing),
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_orbiting_ball(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    preset: Res<OrbitPreset>,
+    settings: Res<GolfBallSettings>,
+    mut palette: ResMut<BallPalette>,
+    particle_set: Res<ParticleSet<Body>>,
+    gravity_settings: Res<GravitySettings>,
+    mouse_pos: Res<MousePosWorld>,
+    bodies: GravityBodies,
+    colliders: Query<&Collider, With<Planet>>,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if !keyboard.just_pressed(KeyCode::O) {
+        return;
+    }
+    let mouse_pos = mouse_pos.truncate().extend(0.0);
+    let planet = dominant_body(&particle_set, mouse_pos);
+    let (center, mu) = match planet
+        .and_then(|entity| bodies.get(entity))
+    {
+        Some(planet) => planet,
+        None => return,
+    };
+
+    let center = center.truncate();
+    let direction = (mouse_pos.truncate() - center)
+        .try_normalize()
+        .unwrap_or(Vec2::X);
+    // never start underground, though an `Attractor` has
+    // no ground to start under
+    let surface = planet
+        .and_then(|entity| colliders.get(entity).ok())
+        .and_then(|collider| collider.as_ball())
+        .map_or(0.0, |ball| ball.radius())
+        + settings.radius();
+    let radius = preset.radius.max(surface);
+
+    // gravity reaches balls as a force, so the ball's own
+    // mass shows up in the circular orbit speed
+    let mu = mu * gravity_settings.g_scale();
+    let speed = (mu / (radius * settings.mass)).sqrt();
+    let velocity = direction.perp() * speed;
+
+    let color = palette.next_color();
+    spawn_ball(
+        &mut commands,
+        &mut ball_assets,
+        &mut meshes,
+        &mut materials,
+        (center + direction * radius).extend(0.0),
+        velocity,
+        color,
+        &settings,
+    );
+}
@@ -0,0 +1,111 @@
+use bevy::{
+    input::mouse::{MouseMotion, MouseWheel},
+    prelude::*,
+};
+use std::f32::consts::FRAC_PI_2;
+
+/// A 3D camera that circles `target`. Dragging with the
+/// left mouse button rotates around it and scrolling
+/// zooms in and out. It reads raw mouse motion, so it
+/// doesn't need `MousePosPlugin` and doesn't get in the
+/// way of the 2D camera.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct OrbitCamera {
+    pub target: Vec3,
+    pub distance: f32,
+    /// radians around the target's y axis
+    pub yaw: f32,
+    /// radians above (negative) or below the target
+    pub pitch: f32,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            target: Vec3::ZERO,
+            distance: 10.0,
+            yaw: 0.0,
+            pitch: -FRAC_PI_2 / 2.0,
+        }
+    }
+}
+
+impl OrbitCamera {
+    /// An orbit camera that starts out at `position`,
+    /// looking at `target`.
+    pub fn looking_from(
+        position: Vec3,
+        target: Vec3,
+    ) -> Self {
+        let offset = position - target;
+        let distance = offset.length();
+        if distance <= f32::EPSILON {
+            return Self {
+                target,
+                ..default()
+            };
+        }
+        Self {
+            target,
+            distance,
+            yaw: offset.x.atan2(offset.z),
+            pitch: (-offset.y / distance).asin(),
+        }
+    }
+
+    /// Where the camera sits.
+    pub fn position(&self) -> Vec3 {
+        let rotation = Quat::from_euler(
+            EulerRot::YXZ,
+            self.yaw,
+            self.pitch,
+            0.0,
+        );
+        self.target + rotation * Vec3::Z * self.distance
+    }
+}
+
+/// radians per pixel dragged
+const ROTATE_SPEED: f32 = 0.005;
+/// fraction of the distance per line scrolled
+const ZOOM_SPEED: f32 = 0.1;
+/// closest the camera can zoom in to its target
+const MIN_DISTANCE: f32 = 0.5;
+
+pub struct OrbitCameraPlugin;
+
+impl Plugin for OrbitCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(orbit_camera);
+    }
+}
+
+fn orbit_camera(
+    buttons: Res<Input<MouseButton>>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    mut cameras: Query<(&mut Transform, &mut OrbitCamera)>,
+) {
+    let drag =
+        motion.iter().fold(Vec2::ZERO, |drag, motion| {
+            drag + motion.delta
+        });
+    let scroll: f32 =
+        wheel.iter().map(|wheel| wheel.y).sum();
+    for (mut transform, mut camera) in cameras.iter_mut() {
+        if buttons.pressed(MouseButton::Left) {
+            camera.yaw -= drag.x * ROTATE_SPEED;
+            // stop short of straight up or down, where
+            // `looking_at` flips over
+            camera.pitch = (camera.pitch
+                - drag.y * ROTATE_SPEED)
+                .clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01);
+        }
+        camera.distance = (camera.distance
+            * (1.0 - scroll * ZOOM_SPEED))
+            .max(MIN_DISTANCE);
+        *transform =
+            Transform::from_translation(camera.position())
+                .looking_at(camera.target, Vec3::Y);
+    }
+}
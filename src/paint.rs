@@ -0,0 +1,243 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+use std::collections::VecDeque;
+
+use crate::{
+    golf_ball::{BallColor, GolfBall},
+    menu::playing,
+    planet_spec::Planet,
+    PIXELS_PER_METER,
+};
+
+/// Painting with gravity: balls leave a line of their
+/// `BallColor` wherever they bounce or roll along a
+/// planet, and it stays there. Marks are kept relative to
+/// the planet they were left on, so they turn and travel
+/// with it, and go when it does.
+///
+/// Toggled with `F10`, `Shift+F10` wipes every planet
+/// clean.
+pub struct Paint {
+    pub enabled: bool,
+    /// Past this many marks the oldest are dropped, since
+    /// every one is redrawn each frame.
+    pub max_marks: usize,
+    /// how far a ball rolls before its line gets another
+    /// mark
+    pub spacing: f32,
+}
+
+impl Default for Paint {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_marks: 5000,
+            spacing: 3.0,
+        }
+    }
+}
+
+/// One stretch of paint, in the planet's own space.
+struct Mark {
+    planet: Entity,
+    from: Vec2,
+    to: Vec2,
+    color: Color,
+}
+
+/// Every mark on every planet, oldest first.
+#[derive(Default)]
+pub struct PaintMarks {
+    marks: VecDeque<Mark>,
+    /// Each ball and planet in contact, with where on the
+    /// planet the ball last left paint, so the next mark
+    /// carries the line on from there.
+    touching: HashMap<(Entity, Entity), Option<Vec2>>,
+}
+
+impl PaintMarks {
+    pub fn len(&self) -> usize {
+        self.marks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.marks.clear();
+        for last in self.touching.values_mut() {
+            *last = None;
+        }
+    }
+}
+
+pub struct PaintPlugin;
+
+impl Plugin for PaintPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Paint>()
+            .init_resource::<PaintMarks>()
+            .add_system(
+                toggle_paint.with_run_criteria(playing),
+            )
+            .add_system(track_paint_contacts)
+            .add_system(
+                lay_paint
+                    .after(toggle_paint)
+                    .after(track_paint_contacts),
+            )
+            .add_system(draw_paint.after(lay_paint));
+    }
+}
+
+fn toggle_paint(
+    keyboard: Res<Input<KeyCode>>,
+    mut paint: ResMut<Paint>,
+    mut marks: ResMut<PaintMarks>,
+) {
+    if !keyboard.just_pressed(KeyCode::F10) {
+        return;
+    }
+    if keyboard
+        .any_pressed([KeyCode::LShift, KeyCode::RShift])
+    {
+        marks.clear();
+    } else {
+        paint.enabled = !paint.enabled;
+    }
+}
+
+/// Kept up to date while painting is off too, so a ball
+/// already rolling when it's turned on starts painting
+/// straight away.
+fn track_paint_contacts(
+    mut collisions: EventReader<CollisionEvent>,
+    mut marks: ResMut<PaintMarks>,
+    balls: Query<(), With<GolfBall>>,
+    planets: Query<(), With<Planet>>,
+) {
+    for event in collisions.iter() {
+        let (a, b, started) = match event {
+            CollisionEvent::Started(a, b, _) => {
+                (*a, *b, true)
+            }
+            CollisionEvent::Stopped(a, b, _) => {
+                (*a, *b, false)
+            }
+        };
+        let (ball, planet) =
+            if balls.contains(a) { (a, b) } else { (b, a) };
+        if !balls.contains(ball)
+            || !planets.contains(planet)
+        {
+            continue;
+        }
+        if started {
+            marks.touching.insert((ball, planet), None);
+        } else {
+            marks.touching.remove(&(ball, planet));
+        }
+    }
+}
+
+fn lay_paint(
+    paint: Res<Paint>,
+    mut paint_marks: ResMut<PaintMarks>,
+    context: Res<RapierContext>,
+    balls: Query<&BallColor, With<GolfBall>>,
+    planets: Query<&GlobalTransform, With<Planet>>,
+) {
+    let PaintMarks { marks, touching } = &mut *paint_marks;
+    // despawned balls and planets never send `Stopped`
+    touching.retain(|(ball, planet), _| {
+        balls.contains(*ball) && planets.contains(*planet)
+    });
+    if !paint.enabled {
+        return;
+    }
+    for ((ball, planet), last) in touching.iter_mut() {
+        let (color, transform) = match (
+            balls.get(*ball),
+            planets.get(*planet),
+        ) {
+            (Ok(color), Ok(transform)) => {
+                (color.0, transform)
+            }
+            _ => continue,
+        };
+        let point = match context
+            .contact_pair(*ball, *planet)
+            .and_then(|pair| {
+                pair.manifolds().find_map(|manifold| {
+                    manifold
+                        .solver_contacts()
+                        .next()
+                        .map(|contact| contact.point())
+                })
+            }) {
+            Some(point) => point * PIXELS_PER_METER,
+            // close enough for rapier to be watching the
+            // pair, but not actually touching this step
+            None => continue,
+        };
+        let local = transform
+            .compute_matrix()
+            .inverse()
+            .transform_point3(point.extend(0.0))
+            .truncate();
+        let from = match *last {
+            Some(from)
+                if from.distance(local) < paint.spacing =>
+            {
+                continue
+            }
+            Some(from) => from,
+            // a dab to start the line off
+            None => local,
+        };
+        marks.push_back(Mark {
+            planet: *planet,
+            from,
+            to: local,
+            color,
+        });
+        *last = Some(local);
+    }
+    while marks.len() > paint.max_marks {
+        marks.pop_front();
+    }
+}
+
+fn draw_paint(
+    mut marks: ResMut<PaintMarks>,
+    mut lines: ResMut<DebugLines>,
+    planets: Query<&GlobalTransform, With<Planet>>,
+) {
+    // whatever was painted on a planet goes with it
+    marks
+        .marks
+        .retain(|mark| planets.contains(mark.planet));
+    for mark in marks.marks.iter() {
+        let transform = match planets.get(mark.planet) {
+            Ok(transform) => transform,
+            Err(_) => continue,
+        };
+        let from =
+            transform.mul_vec3(mark.from.extend(0.0));
+        let to = transform.mul_vec3(mark.to.extend(0.0));
+        // a lone dab still needs something to show
+        let to = if from.distance(to) < 0.5 {
+            to + Vec3::X * 0.5
+        } else {
+            to
+        };
+        lines.line_colored(
+            from.truncate().extend(4.0),
+            to.truncate().extend(4.0),
+            0.0,
+            mark.color,
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+use crate::{
+    sim_state::sim_running, time_scale::TimeScale,
+};
+
+/// The shape of a [`Path`].
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize,
+)]
+pub enum PathKind {
+    /// there and back along a straight line, forever
+    BackAndForth { from: Vec2, to: Vec2 },
+    /// counter clockwise round a circle, starting from its
+    /// rightmost point
+    Circle { center: Vec2, radius: f32 },
+}
+
+/// Moves a planet along a scripted path instead of leaving
+/// it where it was put. Its gravity goes with it, since
+/// `sync_particle_set` reads every position fresh each
+/// frame.
+///
+/// A planet with a path is spawned as a
+/// `KinematicPositionBased` rigid body, so rapier works out
+/// how fast it's going and it still knocks balls about.
+/// Holes placed on its surface are left behind.
+#[derive(
+    Component, Clone, Copy, Debug, Serialize, Deserialize,
+)]
+pub struct Path {
+    pub kind: PathKind,
+    /// world units per second along the path
+    pub speed: f32,
+    /// how far along the path the planet has got
+    #[serde(default)]
+    pub travelled: f32,
+}
+
+impl Path {
+    pub fn new(kind: PathKind, speed: f32) -> Self {
+        Self {
+            kind,
+            speed,
+            travelled: 0.0,
+        }
+    }
+
+    /// Where the planet is once it's gone `distance` along
+    /// the path.
+    pub fn position(&self, distance: f32) -> Vec2 {
+        match self.kind {
+            PathKind::BackAndForth { from, to } => {
+                let length = from.distance(to);
+                if length <= 0.0 {
+                    return from;
+                }
+                // a triangle wave from 0 to 1 and back
+                let t = (distance / length).rem_euclid(2.0);
+                from.lerp(to, 1.0 - (t - 1.0).abs())
+            }
+            PathKind::Circle { center, radius } => {
+                if radius <= 0.0 {
+                    return center;
+                }
+                let angle = distance / radius;
+                center + Vec2::from_angle(angle) * radius
+            }
+        }
+    }
+
+    /// How long one trip round the path takes, `None` if
+    /// the planet never moves.
+    pub fn period(&self) -> Option<f32> {
+        let length = match self.kind {
+            PathKind::BackAndForth { from, to } => {
+                2.0 * from.distance(to)
+            }
+            PathKind::Circle { radius, .. } => TAU * radius,
+        };
+        (length > 0.0 && self.speed != 0.0)
+            .then(|| length / self.speed.abs())
+    }
+}
+
+pub struct PatrolPlugin;
+
+impl Plugin for PatrolPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            move_along_paths.with_run_criteria(sim_running),
+        );
+    }
+}
+
+/// Counts simulated time, so paths stop while the game is
+/// paused and follow the `TimeScale`.
+fn move_along_paths(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut planets: Query<(&mut Transform, &mut Path)>,
+) {
+    let dt = time.delta_seconds() * time_scale.0;
+    for (mut transform, mut path) in planets.iter_mut() {
+        path.travelled += path.speed * dt;
+        let position = path.position(path.travelled);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
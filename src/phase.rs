@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    course::Course,
+    golf_ball::GolfBall,
+    groups::{
+        BALL_FILTER, PHASE_SHIFT, PLANET_GROUP,
+        PRACTICE_FILTER,
+    },
+    planet_spec::Planet,
+    practice::Practice,
+};
+
+/// Up to eight phase layers. A ball passes straight
+/// through a planet whose layers are all in the ball's
+/// mask, but is still pulled by it: gravity never looks at
+/// colliders. A planet with no layers is solid to every
+/// ball, and a ball with none hits every planet.
+///
+/// Only the ball and planet contacts change. Holes,
+/// walls and other balls are hit the same as ever, and
+/// the aim preview still treats every planet as solid.
+#[derive(
+    Component,
+    Reflect,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+#[reflect(Component)]
+pub struct PhaseMask(pub u8);
+
+impl PhaseMask {
+    /// Just the one layer, from 0 to 7.
+    pub fn layer(layer: u32) -> Self {
+        PhaseMask(1 << layer.min(7))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The collision group bits the layers are kept in.
+    pub fn groups(&self) -> u32 {
+        (self.0 as u32) << PHASE_SHIFT
+    }
+}
+
+pub struct PhasePlugin;
+
+impl Plugin for PhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PhaseMask>()
+            .add_system(phase_new_balls)
+            .add_system(
+                apply_phase_masks.after(phase_new_balls),
+            );
+    }
+}
+
+/// Balls take the current level's `Level::ball_phase`
+/// when they're spawned, unless they've been given a mask
+/// of their own.
+fn phase_new_balls(
+    mut commands: Commands,
+    course: Option<Res<Course>>,
+    balls: Query<
+        Entity,
+        (Added<GolfBall>, Without<PhaseMask>),
+    >,
+) {
+    let phase = match course
+        .as_ref()
+        .and_then(|course| course.current_level())
+    {
+        Some(level) if !level.ball_phase.is_empty() => {
+            level.ball_phase
+        }
+        _ => return,
+    };
+    for ball in balls.iter() {
+        commands.entity(ball).insert(phase);
+    }
+}
+
+/// A phased planet leaves `PLANET_GROUP` for its phase
+/// groups, which every ball's filter has in it except for
+/// the ones the ball is phased to.
+#[allow(clippy::type_complexity)]
+fn apply_phase_masks(
+    mut bodies: Query<
+        (
+            &PhaseMask,
+            &mut CollisionGroups,
+            Option<&GolfBall>,
+            Option<&Practice>,
+            Option<&Planet>,
+        ),
+        Changed<PhaseMask>,
+    >,
+) {
+    for (phase, mut groups, ball, practice, planet) in
+        bodies.iter_mut()
+    {
+        if ball.is_some() {
+            let filters = if practice.is_some() {
+                PRACTICE_FILTER
+            } else {
+                BALL_FILTER
+            };
+            groups.filters = filters & !phase.groups();
+        } else if planet.is_some() {
+            groups.memberships = if phase.is_empty() {
+                PLANET_GROUP
+            } else {
+                phase.groups()
+            };
+        }
+    }
+}
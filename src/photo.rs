@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::WorldInspectorParams;
+use bevy_rapier2d::render::DebugRenderContext;
+
+/// Hides the collider wireframes, the inspector and every
+/// bit of UI while it's on, toggled with `P`, so orbits
+/// and planets can be screenshotted cleanly. Bevy has no
+/// screenshot API yet, so the capture itself is up to the
+/// OS.
+#[derive(Default)]
+pub struct PhotoMode {
+    pub enabled: bool,
+    /// UI that was visible when photo mode came on, to be
+    /// shown again when it goes off. Anything that was
+    /// already hidden, like the "Sunk!" banner, stays
+    /// hidden.
+    hidden: Vec<Entity>,
+}
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoMode>()
+            .add_system(toggle_photo_mode);
+    }
+}
+
+fn toggle_photo_mode(
+    keyboard: Res<Input<KeyCode>>,
+    mut photo_mode: ResMut<PhotoMode>,
+    mut debug_render: ResMut<DebugRenderContext>,
+    mut inspector: ResMut<WorldInspectorParams>,
+    mut nodes: Query<(Entity, &mut Visibility), With<Node>>,
+) {
+    if !keyboard.just_pressed(KeyCode::P) {
+        return;
+    }
+    photo_mode.enabled = !photo_mode.enabled;
+    debug_render.enabled = !photo_mode.enabled;
+    inspector.enabled = !photo_mode.enabled;
+    if photo_mode.enabled {
+        for (entity, mut visibility) in nodes.iter_mut() {
+            if visibility.is_visible {
+                visibility.is_visible = false;
+                photo_mode.hidden.push(entity);
+            }
+        }
+    } else {
+        for entity in photo_mode.hidden.drain(..) {
+            if let Ok((_, mut visibility)) =
+                nodes.get_mut(entity)
+            {
+                visibility.is_visible = true;
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::{
+    input::{mouse::MouseButtonInput, ButtonState},
+    prelude::*,
+};
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    course::{Bounces, ShotTimer},
+    golf_ball::{GolfBall, PointMass},
+    measure::MeasureTool,
+    menu::playing,
+};
+
+/// Marks a ball that's been pinned in place with a middle
+/// click. A pinned ball is fixed and pulls on everything
+/// around it like a small planet until it's clicked again.
+/// Holding shift turns a planet's gravity off instead, see
+/// [`GravityFrozen`](crate::freeze::GravityFrozen).
+#[derive(Component)]
+pub struct Pinned;
+
+pub struct PinBallsPlugin;
+
+impl Plugin for PinBallsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            toggle_pinned_balls.with_run_criteria(playing),
+        );
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_pinned_balls(
+    mut commands: Commands,
+    mut click_event: EventReader<MouseButtonInput>,
+    keyboard: Res<Input<KeyCode>>,
+    mouse_pos: Res<MousePosWorld>,
+    measure: Res<MeasureTool>,
+    mut balls: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Collider,
+            &ColliderMassProperties,
+            &mut RigidBody,
+            &mut Velocity,
+            &mut PointMass,
+            Option<&Pinned>,
+        ),
+        With<GolfBall>,
+    >,
+) {
+    let clicked = click_event.iter().any(|event| {
+        event.button == MouseButton::Middle
+            && event.state == ButtonState::Pressed
+    });
+    let shift = keyboard
+        .any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    // middle clicks pick points while measuring
+    if !clicked || shift || measure.enabled {
+        return;
+    }
+    let mouse_pos = mouse_pos.truncate();
+    let hit = balls.iter_mut().find(
+        |(_, transform, collider, ..)| {
+            collider.as_ball().is_some_and(|ball| {
+                transform
+                    .translation()
+                    .truncate()
+                    .distance(mouse_pos)
+                    <= ball.radius()
+            })
+        },
+    );
+    let (
+        entity,
+        _,
+        _,
+        mass_props,
+        mut rigidbody,
+        mut velocity,
+        mut point_mass,
+        pinned,
+    ) = match hit {
+        Some(hit) => hit,
+        None => return,
+    };
+    // `sync_particle_set` rereads every `PointMass` each
+    // frame, so the new gravity is picked up on the next
+    // one
+    *velocity = Velocity::zero();
+    if pinned.is_some() {
+        *rigidbody = RigidBody::Dynamic;
+        *point_mass = PointMass::AffectedByGravity;
+        commands.entity(entity).remove::<Pinned>();
+    } else {
+        *rigidbody = RigidBody::Fixed;
+        if let ColliderMassProperties::Mass(mass) =
+            mass_props
+        {
+            *point_mass =
+                PointMass::HasGravity { mass: *mass };
+        }
+        // a pinned ball is part of the course now, not a
+        // shot in play
+        commands
+            .entity(entity)
+            .insert(Pinned)
+            .remove::<ShotTimer>()
+            .remove::<Bounces>();
+    }
+}
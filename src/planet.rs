@@ -1,6 +1,6 @@
 use bevy::{
     math::Vec3Swizzles,
-    pbr::wireframe::{Wireframe, WireframePlugin},
+    pbr::wireframe::WireframePlugin,
     prelude::*,
     reflect::TypeUuid,
     render::{
@@ -10,7 +10,13 @@ use bevy::{
         },
     },
 };
+use std::collections::HashMap;
 
+use crate::spin::SpinPlugin;
+
+/// Everything needed to draw cubesphere planets. What
+/// gets spawned is up to the app, see
+/// `examples/planets.rs`.
 pub struct PlanetPlugin;
 
 impl Plugin for PlanetPlugin {
@@ -18,63 +24,71 @@ impl Plugin for PlanetPlugin {
         app.add_plugin(
             MaterialPlugin::<Planet3dMaterial>::default(),
         )
+        .add_plugin(
+            MaterialPlugin::<AtmosphereMaterial>::default(),
+        )
         .add_plugin(WireframePlugin)
-        .add_startup_system(setup);
+        .add_plugin(SpinPlugin)
+        .add_system(spawn_atmosphere_shells);
     }
 }
 
-fn setup(
+/// Gives a 3D planet a translucent shell of air, a
+/// slightly bigger cubesphere that's see-through in the
+/// middle and glows towards the planet's edge.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct AtmosphereShell {
+    /// the alpha is how thick the air looks at its
+    /// thickest
+    pub color: Color,
+    /// how far the shell reaches past the surface, as a
+    /// fraction of the planet's radius
+    pub thickness: f32,
+}
+
+impl Default for AtmosphereShell {
+    fn default() -> Self {
+        Self {
+            color: Color::rgba(0.4, 0.7, 1.0, 0.8),
+            thickness: 0.1,
+        }
+    }
+}
+
+/// The resolution atmosphere shells are built with. They
+/// have no surface detail, so they don't need much.
+const SHELL_RESOLUTION: u32 = 20;
+
+fn spawn_atmosphere_shells(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<Planet3dMaterial>>,
-    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<AtmosphereMaterial>>,
+    planets: Query<
+        (Entity, &AtmosphereShell),
+        Added<AtmosphereShell>,
+    >,
 ) {
-    // cube
-    commands
-        .spawn()
-        .insert_bundle(MaterialMeshBundle {
-            mesh: meshes.add(Mesh::from(PlanetMesh {
-                resolution: 10,
-            })),
-            transform: Transform::from_xyz(-2.0, 0.5, 0.0),
-            material: materials.add(Planet3dMaterial {
-                color: Color::RED,
-            }),
-            ..default()
-        })
-        .insert(Wireframe);
-    commands
-        .spawn()
-        .insert_bundle(MaterialMeshBundle {
-            mesh: meshes.add(Mesh::from(PlanetMesh {
-                resolution: 20,
-            })),
-            transform: Transform::from_xyz(0.0, 0.5, 0.0),
-            material: materials.add(Planet3dMaterial {
-                color: Color::RED,
-            }),
-            ..default()
-        })
-        .insert(Wireframe);
-    commands
-        .spawn()
-        .insert_bundle(MaterialMeshBundle {
-            mesh: meshes.add(Mesh::from(PlanetMesh {
-                resolution: 40,
-            })),
-            transform: Transform::from_xyz(2.0, 0.5, 0.0),
-            material: materials.add(Planet3dMaterial {
-                color: Color::RED,
-            }),
-            ..default()
-        })
-        .insert(Wireframe);
-    // camera
-    commands.spawn_bundle(Camera3dBundle {
-        transform: Transform::from_xyz(-2.0, 2.5, 5.0)
-            .looking_at(Vec3::ZERO, Vec3::Y),
-        ..default()
-    });
+    for (planet, shell) in planets.iter() {
+        // a child, so it follows the planet's position,
+        // spin and scale
+        let shell = commands
+            .spawn_bundle(MaterialMeshBundle {
+                mesh: meshes.add(Mesh::from(PlanetMesh {
+                    resolution: SHELL_RESOLUTION,
+                })),
+                transform: Transform::from_scale(
+                    Vec3::splat(1.0 + shell.thickness),
+                ),
+                material: materials.add(
+                    AtmosphereMaterial {
+                        color: shell.color,
+                    },
+                ),
+                ..default()
+            })
+            .id();
+        commands.entity(planet).add_child(shell);
+    }
 }
 
 /// The Material trait is very configurable, but comes with sensible defaults for all methods.
@@ -94,56 +108,75 @@ impl Material for Planet3dMaterial {
 #[uuid = "f690fdae-d598-45db-8225-97e2a3f056e0"]
 pub struct Planet3dMaterial {
     #[uniform(0)]
-    color: Color,
+    pub color: Color,
 }
 
-struct PlanetMesh {
-    resolution: u32,
+impl Material for AtmosphereMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/atmosphere_material.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
+
+#[derive(AsBindGroup, TypeUuid, Debug, Clone)]
+#[uuid = "5b3c1a8e-2f47-4d9b-9e61-0c7d2a4f8b13"]
+pub struct AtmosphereMaterial {
+    #[uniform(0)]
+    pub color: Color,
+}
+
+/// The way each of a cubesphere's six faces looks out.
+const FACE_DIRECTIONS: [Vec3; 6] = [
+    Vec3::Y,
+    Vec3::NEG_Y,
+    Vec3::NEG_X,
+    Vec3::X,
+    Vec3::Z,
+    Vec3::NEG_Z,
+];
+
+/// A unit "cubesphere". Scale the transform to set the
+/// radius.
+pub struct PlanetMesh {
+    pub resolution: u32,
 }
 
 impl From<PlanetMesh> for Mesh {
     fn from(planet: PlanetMesh) -> Self {
-        let directions = [
-            Vec3::Y,
-            Vec3::NEG_Y,
-            Vec3::NEG_X,
-            Vec3::X,
-            Vec3::Z,
-            Vec3::NEG_Z,
-        ];
-
-        let (vert_lists, triangle_lists): (
-            Vec<Vec<Vec3>>,
-            Vec<Vec<u32>>,
-        ) = directions
-            .iter()
-            .map(|direction| {
-                face(planet.resolution, *direction)
-            })
-            .unzip();
-
-        let vertices = vert_lists
-            .iter()
-            .flat_map(|v| v.iter().map(|v| [v.x, v.y, v.z]))
-            .collect::<Vec<[f32; 3]>>();
-
-        let triangle_list = triangle_lists
-            .iter()
-            .enumerate()
-            .flat_map(|(face_id, list)| {
-                // local_face_index indexes go up to resolution^2 - 1.
-                // so the last vertex in a face with a resolution of
-                // 10 is index 99 (100 indices, starting at 0).
-                //
-                // that makes the *index* of the second face's vertices
-                // start at 100 and end at 199.
-                list.iter().map(move |local_idx| {
-                    let num_indices = planet.resolution
-                        * planet.resolution;
-                    local_idx + face_id as u32 * num_indices
+        // neighbouring faces share their edges, and three
+        // faces share each corner. Every point on the cube
+        // is only given one vertex, so the sphere has no
+        // seams where the faces' normals would disagree.
+        let mut welded: HashMap<IVec3, u32> =
+            HashMap::new();
+        let mut vertices: Vec<[f32; 3]> = Vec::new();
+        let mut triangle_list: Vec<u32> = Vec::new();
+        for direction in FACE_DIRECTIONS {
+            let (points, triangles) =
+                face(planet.resolution, direction);
+            let indices: Vec<u32> = points
+                .iter()
+                .map(|point| {
+                    *welded.entry(*point).or_insert_with(
+                        || {
+                            let vertex =
+                                point.as_vec3().normalize();
+                            vertices
+                                .push(vertex.to_array());
+                            vertices.len() as u32 - 1
+                        },
+                    )
                 })
-            })
-            .collect::<Vec<u32>>();
+                .collect();
+            triangle_list.extend(
+                triangles
+                    .iter()
+                    .map(|local| indices[*local as usize]),
+            );
+        }
 
         let mut mesh =
             Mesh::new(PrimitiveTopology::TriangleList);
@@ -188,16 +221,32 @@ impl From<PlanetMesh> for Mesh {
     }
 }
 
+/// How many vertices a welded [`PlanetMesh`] has: the
+/// points of a `resolution` grid on the surface of a cube,
+/// 8 for the corners alone at a resolution of 2.
+pub fn cube_surface_points(resolution: u32) -> usize {
+    let resolution = resolution as usize;
+    resolution.pow(3) - resolution.saturating_sub(2).pow(3)
+}
+
 /// build one face of the "cubesphere"
 /// resolution is the per-face resolution,
 /// the number of lines, which in turns means
 /// resolution-1 squares per axis on each face
+///
+/// The face's points are on a cube reaching
+/// `resolution - 1` out from the center, in steps of two
+/// so they're all whole numbers. That way the points two
+/// faces share come out exactly equal, and can be welded
+/// together before they're pushed out onto the sphere.
 fn face(
     resolution: u32,
     local_up: Vec3,
-) -> (Vec<Vec3>, Vec<u32>) {
+) -> (Vec<IVec3>, Vec<u32>) {
+    let local_up = local_up.as_ivec3();
     let axis_a = local_up.yzx();
     let axis_b = local_up.cross(axis_a);
+    let half_width = resolution as i32 - 1;
 
     let mut vertices = Vec::with_capacity(
         resolution as usize * resolution as usize,
@@ -216,18 +265,11 @@ fn face(
     for y in 0..resolution {
         for x in 0..resolution {
             let i = x + y * resolution;
-            let percent_x =
-                x as f32 / (resolution - 1) as f32;
-            let percent_y =
-                y as f32 / (resolution - 1) as f32;
+            let point_on_cube = local_up * half_width
+                + (2 * x as i32 - half_width) * axis_a
+                + (2 * y as i32 - half_width) * axis_b;
 
-            let point_on_unit_cube = local_up
-                + (percent_x - 0.5) * 2.0 * axis_a
-                + (percent_y - 0.5) * 2.0 * axis_b;
-            let point_on_unit_sphere =
-                point_on_unit_cube.normalize();
-
-            vertices.push(point_on_unit_sphere);
+            vertices.push(point_on_cube);
 
             if x != resolution - 1 && y != resolution - 1 {
                 // triangle list vertices 1
@@ -244,3 +286,166 @@ fn face(
     }
     (vertices, triangles)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::mesh::MeshVertexAttribute;
+
+    /// closer than this is the same place
+    const EPSILON: f32 = 1e-5;
+
+    /// two triangles per grid square, three indices each
+    fn face_indices(resolution: u32) -> usize {
+        (resolution as usize - 1).pow(2) * 6
+    }
+
+    fn vectors(
+        mesh: &Mesh,
+        attribute: MeshVertexAttribute,
+    ) -> Vec<Vec3> {
+        let name = attribute.name;
+        match mesh.attribute(attribute) {
+            Some(VertexAttributeValues::Float32x3(
+                values,
+            )) => values
+                .iter()
+                .map(|value| Vec3::from(*value))
+                .collect(),
+            _ => panic!("the planet has no {}", name),
+        }
+    }
+
+    // the index math in `face` is easy to get subtly wrong
+    #[test]
+    fn faces_have_a_vertex_per_grid_point() {
+        for resolution in [2, 3, 10] {
+            for direction in FACE_DIRECTIONS {
+                let (vertices, triangles) =
+                    face(resolution, direction);
+                assert_eq!(
+                    vertices.len(),
+                    (resolution * resolution) as usize
+                );
+                assert_eq!(
+                    triangles.len(),
+                    face_indices(resolution)
+                );
+                assert!(triangles.iter().all(|index| {
+                    *index < resolution * resolution
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn faces_lie_on_their_side_of_the_cube() {
+        for resolution in [2, 3, 10] {
+            let half_width = resolution as i32 - 1;
+            for direction in FACE_DIRECTIONS {
+                let up = direction.as_ivec3();
+                let (vertices, _) =
+                    face(resolution, direction);
+                assert!(vertices.iter().all(|vertex| {
+                    vertex.abs().max_element() == half_width
+                        && vertex.dot(up) == half_width
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn every_face_contributes_the_same_indices() {
+        for resolution in [2, 3, 10] {
+            let mesh =
+                Mesh::from(PlanetMesh { resolution });
+            let indices = match mesh.indices() {
+                Some(Indices::U32(indices)) => {
+                    indices.len()
+                }
+                _ => panic!("the planet isn't indexed"),
+            };
+            assert_eq!(
+                indices,
+                6 * face_indices(resolution)
+            );
+        }
+    }
+
+    #[test]
+    fn vertices_are_on_the_unit_sphere() {
+        let mesh =
+            Mesh::from(PlanetMesh { resolution: 10 });
+        match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(
+                positions,
+            )) => {
+                assert!(positions.iter().all(|position| {
+                    (Vec3::from(*position).length() - 1.0)
+                        .abs()
+                        < 1e-4
+                }))
+            }
+            _ => panic!("the planet has no positions"),
+        }
+    }
+
+    #[test]
+    fn faces_are_welded_together() {
+        assert_eq!(cube_surface_points(2), 8);
+        for resolution in [2, 3, 10, 20] {
+            let mesh =
+                Mesh::from(PlanetMesh { resolution });
+            let positions =
+                vectors(&mesh, Mesh::ATTRIBUTE_POSITION);
+            assert_eq!(
+                positions.len(),
+                cube_surface_points(resolution),
+                "resolution {} isn't welded",
+                resolution
+            );
+            for (i, a) in positions.iter().enumerate() {
+                for b in &positions[i + 1..] {
+                    assert!(
+                        a.distance(*b) > EPSILON,
+                        "two vertices at {:?}",
+                        a
+                    );
+                }
+            }
+            let in_range = match mesh.indices() {
+                Some(Indices::U32(indices)) => {
+                    indices.iter().all(|index| {
+                        (*index as usize) < positions.len()
+                    })
+                }
+                _ => panic!("the planet isn't indexed"),
+            };
+            assert!(in_range, "a triangle lost a vertex");
+        }
+    }
+
+    // a normal that isn't its vertex's direction shows up
+    // as a seam between faces
+    #[test]
+    fn normals_point_out_through_their_vertex() {
+        for resolution in [2, 3, 10] {
+            let mesh =
+                Mesh::from(PlanetMesh { resolution });
+            let positions =
+                vectors(&mesh, Mesh::ATTRIBUTE_POSITION);
+            let normals =
+                vectors(&mesh, Mesh::ATTRIBUTE_NORMAL);
+            for (position, normal) in
+                positions.iter().zip(&normals)
+            {
+                assert!(
+                    position.abs_diff_eq(*normal, EPSILON),
+                    "the normal at {:?} is {:?}",
+                    position,
+                    normal
+                );
+            }
+        }
+    }
+}
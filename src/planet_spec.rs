@@ -0,0 +1,692 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::Indices, render_resource::PrimitiveTopology,
+    },
+    sprite::MaterialMesh2dBundle,
+};
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
+
+use crate::{
+    atmosphere::Atmosphere,
+    density::{Density, VisualScale},
+    golf_ball::{CircleWithGravity, PointMass},
+    groups::{PLANET_FILTER, PLANET_GROUP},
+    patrol::Path,
+    phase::PhaseMask,
+    spin::Spin,
+};
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Planet;
+
+#[derive(
+    Clone, Debug, PartialEq, Serialize, Deserialize, Default,
+)]
+pub enum PlanetShape {
+    #[default]
+    Circle,
+    /// An oblate planet. `a` and `b` only set the ratio of
+    /// the horizontal to the vertical axis; the ellipse
+    /// keeps the same area a circle of the same mass and
+    /// density would have.
+    Ellipse {
+        a: f32,
+        b: f32,
+    },
+    /// An asteroid or other lumpy body, outlined by these
+    /// points in order. Like `Ellipse` the points only set
+    /// the shape: the outline is moved so its centroid sits
+    /// on the planet's position, where gravity pulls from,
+    /// and scaled to the area of a circle of the same mass
+    /// and density. Fewer than three points, or ones that
+    /// enclose no area, give a circle.
+    Polygon(Vec<Vec2>),
+}
+
+/// The image a planet is drawn with. `spawn_planet` has no
+/// `AssetServer` to hand, so `PlanetTexturePlugin` loads it
+/// into the planet's material once it's spawned.
+#[derive(Component, Clone, Debug)]
+pub struct PlanetTexture(pub String);
+
+pub struct PlanetTexturePlugin;
+
+impl Plugin for PlanetTexturePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(load_planet_textures);
+    }
+}
+
+fn load_planet_textures(
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    planets: Query<
+        (&PlanetTexture, &Handle<ColorMaterial>),
+        Added<PlanetTexture>,
+    >,
+) {
+    for (texture, material) in planets.iter() {
+        if let Some(material) = materials.get_mut(material)
+        {
+            material.texture =
+                Some(asset_server.load(texture.0.as_str()));
+        }
+    }
+}
+
+/// Ready made looks for planets, so the planets in a level
+/// can be told apart at a glance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanetPreset {
+    Ocean,
+    Forest,
+    Desert,
+    Ice,
+    Lava,
+}
+
+impl PlanetPreset {
+    pub const ALL: [PlanetPreset; 5] = [
+        PlanetPreset::Ocean,
+        PlanetPreset::Forest,
+        PlanetPreset::Desert,
+        PlanetPreset::Ice,
+        PlanetPreset::Lava,
+    ];
+
+    /// The color at the center and the color at the rim.
+    pub fn colors(&self) -> (Color, Color) {
+        match self {
+            PlanetPreset::Ocean => (
+                Color::rgb(0.1, 0.3, 0.9),
+                Color::rgb(0.0, 0.1, 0.4),
+            ),
+            PlanetPreset::Forest => (
+                Color::rgb(0.2, 0.7, 0.2),
+                Color::rgb(0.05, 0.3, 0.1),
+            ),
+            PlanetPreset::Desert => (
+                Color::rgb(0.9, 0.7, 0.4),
+                Color::rgb(0.6, 0.35, 0.15),
+            ),
+            PlanetPreset::Ice => (
+                Color::rgb(0.9, 0.95, 1.0),
+                Color::rgb(0.5, 0.7, 0.9),
+            ),
+            PlanetPreset::Lava => (
+                Color::rgb(1.0, 0.6, 0.1),
+                Color::rgb(0.5, 0.05, 0.0),
+            ),
+        }
+    }
+}
+
+/// How grippy a planet's surface is, which decides how
+/// balls roll and settle on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SurfaceType {
+    #[default]
+    Normal,
+    /// balls slide a long way before they stop
+    Icy,
+    /// balls grab and stop rolling almost right away
+    Rough,
+}
+
+impl SurfaceType {
+    pub fn friction(&self) -> f32 {
+        match self {
+            SurfaceType::Normal => 10.0,
+            SurfaceType::Icy => 0.5,
+            SurfaceType::Rough => 30.0,
+        }
+    }
+}
+
+/// Everything needed to spawn a planet into the 2d game.
+/// Fields left out of a saved level get their defaults.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanetSpec {
+    pub position: Vec3,
+    pub mass: f32,
+    pub density: f32,
+    pub shape: PlanetShape,
+    pub color: Color,
+    /// When set the planet is shaded from `color` at its
+    /// center out to this at its surface.
+    pub rim_color: Option<Color>,
+    /// The radius of the solid part balls actually hit,
+    /// for gas giants whose outer layers balls can sink
+    /// into. `None` means the whole planet is solid.
+    /// Gravity still pulls from the center either way.
+    pub core_radius: Option<f32>,
+    /// An image under `assets/` to draw on the planet. It
+    /// replaces `color`, but a `rim_color` gradient is
+    /// still blended over it.
+    pub texture: Option<String>,
+    /// the friction coefficient of the surface, see
+    /// [`SurfaceType`] for some ready made ones
+    pub friction: f32,
+    pub atmosphere: Option<Atmosphere>,
+    pub spin: Option<Spin>,
+    /// balls phased to all of these layers pass through
+    /// the planet, see [`PhaseMask`]
+    pub phase: PhaseMask,
+    /// Moves the planet along a path, see [`Path`]. The
+    /// path says where the planet starts, so only the depth
+    /// of `position` is used. `None` leaves it fixed.
+    pub path: Option<Path>,
+    /// How much bigger or smaller the planet is drawn than
+    /// it really is, for a glowing halo or a tighter look.
+    /// Purely cosmetic: the collider, the mass and
+    /// everything worked out from `radius` keep the true
+    /// size. 1.0 draws it as it is.
+    pub visual_scale: f32,
+}
+
+impl Default for PlanetSpec {
+    fn default() -> Self {
+        Self {
+            position: Vec3::new(0.0, 0.0, 1.0),
+            mass: 10E5,
+            density: 20.0,
+            shape: PlanetShape::Circle,
+            color: Color::BLUE,
+            rim_color: None,
+            core_radius: None,
+            texture: None,
+            friction: SurfaceType::Normal.friction(),
+            atmosphere: None,
+            spin: None,
+            phase: PhaseMask::default(),
+            path: None,
+            visual_scale: 1.0,
+        }
+    }
+}
+
+impl PlanetSpec {
+    pub fn with_preset(
+        mut self,
+        preset: PlanetPreset,
+    ) -> Self {
+        let (color, rim_color) = preset.colors();
+        self.color = color;
+        self.rim_color = Some(rim_color);
+        self
+    }
+
+    pub fn with_surface(
+        mut self,
+        surface: SurfaceType,
+    ) -> Self {
+        self.friction = surface.friction();
+        self
+    }
+
+    /// The radius of a circular planet, or the radius of
+    /// the circle with the same area for other shapes.
+    pub fn radius(&self) -> f32 {
+        Density(self.density).radius(self.mass)
+    }
+
+    /// Horizontal and vertical semi-axes, or for a polygon
+    /// how far it reaches from its centroid each way.
+    pub fn semi_axes(&self) -> Vec2 {
+        let radius = self.radius();
+        match &self.shape {
+            PlanetShape::Ellipse { a, b }
+                if *a > 0.0 && *b > 0.0 =>
+            {
+                let ratio = (a / b).sqrt();
+                Vec2::new(radius * ratio, radius / ratio)
+            }
+            PlanetShape::Polygon(_) => {
+                match self.outline() {
+                    Some(outline) => outline.iter().fold(
+                        Vec2::ZERO,
+                        |reach, point| {
+                            reach.max(point.abs())
+                        },
+                    ),
+                    None => Vec2::splat(radius),
+                }
+            }
+            _ => Vec2::splat(radius),
+        }
+    }
+
+    /// The outline of a `PlanetShape::Polygon` around its
+    /// centroid, counter clockwise and scaled to the
+    /// planet's area. `None` for other shapes, and for
+    /// polygons that don't enclose anything.
+    pub fn outline(&self) -> Option<Vec<Vec2>> {
+        match &self.shape {
+            PlanetShape::Polygon(points) => {
+                let radius = self.radius();
+                polygon_outline(
+                    points,
+                    PI * radius * radius,
+                )
+            }
+            _ => None,
+        }
+    }
+
+    /// How much of the drawn planet is solid, see
+    /// `core_radius`.
+    fn core_ratio(&self) -> f32 {
+        match self.core_radius {
+            Some(core_radius) => {
+                (core_radius / self.radius()).min(1.0)
+            }
+            None => 1.0,
+        }
+    }
+
+    /// The semi-axes of the solid core, the same as
+    /// `semi_axes` unless a smaller `core_radius` is set.
+    pub fn core_semi_axes(&self) -> Vec2 {
+        self.semi_axes() * self.core_ratio()
+    }
+
+    /// The outline of the solid core of a polygon planet.
+    fn core_outline(&self) -> Option<Vec<Vec2>> {
+        let ratio = self.core_ratio();
+        self.outline().map(|outline| {
+            outline
+                .iter()
+                .map(|point| *point * ratio)
+                .collect()
+        })
+    }
+
+    /// Whether `point` is inside of the planet's solid
+    /// core, or within `margin` of its surface.
+    pub fn contains(
+        &self,
+        point: Vec2,
+        margin: f32,
+    ) -> bool {
+        let offset = point - self.position.truncate();
+        if let Some(outline) = self.core_outline() {
+            return polygon_contains(&outline, offset)
+                || polygon_distance(&outline, offset)
+                    <= margin;
+        }
+        let semi_axes = self.core_semi_axes() + margin;
+        (offset / semi_axes).length_squared() <= 1.0
+    }
+
+    /// The point `clearance` out from the solid surface, in
+    /// the direction `angle` radians counter clockwise from
+    /// +x as seen from the planet's center.
+    pub fn surface_point(
+        &self,
+        angle: f32,
+        clearance: f32,
+    ) -> Vec2 {
+        let direction = Vec2::new(angle.cos(), angle.sin());
+        if let Some(outline) = self.core_outline() {
+            return self.position.truncate()
+                + direction
+                    * (polygon_reach(&outline, direction)
+                        + clearance);
+        }
+        let semi_axes = self.core_semi_axes() + clearance;
+        self.position.truncate()
+            + direction / (direction / semi_axes).length()
+    }
+}
+
+/// Points around the outline of an ellipse, counter
+/// clockwise starting from +x.
+fn ellipse_outline(
+    semi_axes: Vec2,
+    segments: usize,
+) -> Vec<Vec2> {
+    (0..segments)
+        .map(|i| {
+            let angle = i as f32 / segments as f32 * TAU;
+            Vec2::new(angle.cos(), angle.sin()) * semi_axes
+        })
+        .collect()
+}
+
+/// `points` moved so their centroid is at the origin,
+/// wound counter clockwise and scaled to enclose `area`.
+fn polygon_outline(
+    points: &[Vec2],
+    area: f32,
+) -> Option<Vec<Vec2>> {
+    if points.len() < 3 {
+        return None;
+    }
+    // the shoelace formula, for the area and the centroid
+    // of the area rather than of the points
+    let mut signed_area = 0.0;
+    let mut centroid = Vec2::ZERO;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let cross = a.perp_dot(b);
+        signed_area += cross / 2.0;
+        centroid += (*a + b) * cross;
+    }
+    if signed_area.abs() <= f32::EPSILON {
+        return None;
+    }
+    let centroid = centroid / (6.0 * signed_area);
+    let scale = (area / signed_area.abs()).sqrt();
+    let mut outline: Vec<Vec2> = points
+        .iter()
+        .map(|point| (*point - centroid) * scale)
+        .collect();
+    if signed_area < 0.0 {
+        outline.reverse();
+    }
+    Some(outline)
+}
+
+/// The edges of a closed outline, as pairs of points.
+fn edges(
+    outline: &[Vec2],
+) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    outline.iter().enumerate().map(|(i, a)| {
+        (*a, outline[(i + 1) % outline.len()])
+    })
+}
+
+/// Even-odd test, so it works for concave outlines too.
+fn polygon_contains(outline: &[Vec2], point: Vec2) -> bool {
+    edges(outline).fold(false, |inside, (a, b)| {
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x
+                < a.x
+                    + (point.y - a.y) / (b.y - a.y)
+                        * (b.x - a.x)
+        {
+            !inside
+        } else {
+            inside
+        }
+    })
+}
+
+/// How far `point` is from the nearest edge.
+fn polygon_distance(outline: &[Vec2], point: Vec2) -> f32 {
+    edges(outline)
+        .map(|(a, b)| {
+            let along = b - a;
+            let t = if along == Vec2::ZERO {
+                0.0
+            } else {
+                ((point - a).dot(along)
+                    / along.length_squared())
+                .clamp(0.0, 1.0)
+            };
+            point.distance(a + along * t)
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// How far out from the origin the outline's surface is
+/// in `direction`, the furthest crossing for outlines
+/// that cross more than once.
+fn polygon_reach(outline: &[Vec2], direction: Vec2) -> f32 {
+    edges(outline)
+        .filter_map(|(a, b)| {
+            let along = b - a;
+            let denominator = direction.perp_dot(along);
+            if denominator == 0.0 {
+                return None;
+            }
+            let distance = a.perp_dot(along) / denominator;
+            let t = a.perp_dot(direction) / denominator;
+            ((0.0..=1.0).contains(&t) && distance >= 0.0)
+                .then_some(distance)
+        })
+        .fold(0.0, f32::max)
+}
+
+/// Whether every turn around the outline is to the left.
+fn is_convex(outline: &[Vec2]) -> bool {
+    outline.iter().enumerate().all(|(i, a)| {
+        let b = outline[(i + 1) % outline.len()];
+        let c = outline[(i + 2) % outline.len()];
+        (b - *a).perp_dot(c - b) >= 0.0
+    })
+}
+
+/// A triangle fan filling an ellipse, with uvs laid out
+/// the same way as `shape::Circle`.
+fn ellipse_mesh(semi_axes: Vec2, segments: usize) -> Mesh {
+    fan_mesh(
+        &ellipse_outline(semi_axes, segments),
+        semi_axes,
+    )
+}
+
+/// A triangle fan from the origin out to `outline`, with
+/// uvs stretched over `semi_axes` each way. Only fills
+/// outlines whose whole edge can be seen from the origin,
+/// which is most asteroids.
+fn fan_mesh(outline: &[Vec2], semi_axes: Vec2) -> Mesh {
+    let segments = outline.len();
+    let mut positions = vec![[0.0, 0.0, 0.0]];
+    let mut uvs = vec![[0.5, 0.5]];
+    for point in outline.iter() {
+        positions.push([point.x, point.y, 0.0]);
+        let unit = *point / semi_axes;
+        uvs.push([0.5 + unit.x * 0.5, 0.5 - unit.y * 0.5]);
+    }
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+    let indices = (0..segments as u32)
+        .flat_map(|i| {
+            [0, i + 1, (i + 1) % segments as u32 + 1]
+        })
+        .collect();
+
+    let mut mesh =
+        Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        positions,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh
+}
+
+/// Vertex colors for a `fan_mesh`, blending from
+/// `center` at the middle of the fan to `rim` around the
+/// outline.
+fn radial_gradient(
+    center: Color,
+    rim: Color,
+    segments: usize,
+) -> Vec<[f32; 4]> {
+    let mut colors = vec![center.as_linear_rgba_f32()];
+    colors.extend(std::iter::repeat_n(
+        rim.as_linear_rgba_f32(),
+        segments,
+    ));
+    colors
+}
+
+pub fn spawn_planet(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    spec: &PlanetSpec,
+) -> Entity {
+    let radius = spec.radius();
+    let semi_axes = spec.semi_axes();
+    let outline = spec.outline();
+    // only the mesh is scaled, the collider below keeps the
+    // true size
+    let visual_outline = outline.as_ref().map(|outline| {
+        outline
+            .iter()
+            .map(|point| *point * spec.visual_scale)
+            .collect::<Vec<_>>()
+    });
+    let visual_semi_axes = semi_axes * spec.visual_scale;
+    let mut mesh = match (&spec.shape, spec.rim_color) {
+        (PlanetShape::Circle, None) => {
+            Mesh::from(shape::Circle {
+                radius: radius * spec.visual_scale,
+                ..default()
+            })
+        }
+        _ => match &visual_outline {
+            Some(outline) => {
+                fan_mesh(outline, visual_semi_axes)
+            }
+            // a gradient needs the center vertex the fan
+            // has
+            None => ellipse_mesh(visual_semi_axes, 64),
+        },
+    };
+    let segments = outline.as_ref().map_or(64, Vec::len);
+    // gravity still treats every shape as a point mass at
+    // its center, only the visuals and collider change.
+    // The collider only covers the solid core, which can
+    // be smaller than what's drawn.
+    let core_semi_axes = spec.core_semi_axes();
+    let core_radius =
+        spec.core_radius.map_or(radius, |core_radius| {
+            core_radius.min(radius)
+        });
+    let collider = match (&spec.shape, spec.core_outline())
+    {
+        (PlanetShape::Ellipse { .. }, _) => {
+            Collider::convex_hull(&ellipse_outline(
+                core_semi_axes,
+                64,
+            ))
+            .unwrap_or_else(|| Collider::ball(core_radius))
+        }
+        (PlanetShape::Polygon(_), Some(core)) => {
+            if is_convex(&core) {
+                Collider::convex_hull(&core).unwrap_or_else(
+                    || Collider::ball(core_radius),
+                )
+            } else {
+                // a closed loop, so the dents can be
+                // rolled into
+                let indices = (0..core.len() as u32)
+                    .map(|i| {
+                        [i, (i + 1) % core.len() as u32]
+                    })
+                    .collect();
+                Collider::polyline(core, Some(indices))
+            }
+        }
+        _ => Collider::ball(core_radius),
+    };
+    // the material's color multiplies the vertex colors
+    // and the texture, so it's left white when they're used
+    let material_color = match spec.rim_color {
+        Some(rim_color) => {
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_COLOR,
+                radial_gradient(
+                    spec.color, rim_color, segments,
+                ),
+            );
+            Color::WHITE
+        }
+        None if spec.texture.is_some() => Color::WHITE,
+        None => spec.color,
+    };
+    let position = match spec.path {
+        Some(path) => path
+            .position(path.travelled)
+            .extend(spec.position.z),
+        None => spec.position,
+    };
+    let mut planet =
+        commands.spawn_bundle(CircleWithGravity {
+            shape_bundle: MaterialMesh2dBundle {
+                mesh: meshes.add(mesh).into(),
+                transform: Transform::from_translation(
+                    position,
+                ),
+                material: materials.add(
+                    ColorMaterial::from(material_color),
+                ),
+                ..default()
+            },
+            collider,
+            friction: Friction {
+                coefficient: spec.friction,
+                ..default()
+            },
+            mass: ColliderMassProperties::Mass(spec.mass),
+            restitution: Restitution {
+                coefficient: 0.0,
+                ..default()
+            },
+            rigidbody: if spec.path.is_some() {
+                RigidBody::KinematicPositionBased
+            } else {
+                RigidBody::Fixed
+            },
+            velocity: Velocity::zero(),
+            acceleration: ExternalForce::default(),
+            point_mass: PointMass::HasGravity {
+                mass: spec.mass,
+            },
+        });
+    planet
+        .insert(Planet)
+        .insert(Density(spec.density))
+        .insert(VisualScale(spec.visual_scale))
+        .insert(CollisionGroups::new(
+            PLANET_GROUP,
+            PLANET_FILTER,
+        ));
+    if let Some(spin) = spec.spin {
+        planet.insert(spin);
+    }
+    if !spec.phase.is_empty() {
+        planet.insert(spec.phase);
+    }
+    if let Some(path) = spec.path {
+        planet.insert(path);
+    }
+    if let Some(texture) = &spec.texture {
+        planet.insert(PlanetTexture(texture.clone()));
+    }
+
+    if let Some(atmosphere) = spec.atmosphere {
+        let mut color = spec.color;
+        color.set_a(0.2);
+        planet.insert(atmosphere).with_children(|parent| {
+            // drawn just behind the planet so only the
+            // part outside of the surface shows up as a ring
+            parent.spawn_bundle(MaterialMesh2dBundle {
+                mesh: meshes
+                    .add(Mesh::from(shape::Circle {
+                        radius: atmosphere.radius,
+                        ..default()
+                    }))
+                    .into(),
+                transform: Transform::from_xyz(
+                    0.0, 0.0, -0.5,
+                ),
+                material: materials
+                    .add(ColorMaterial::from(color)),
+                ..default()
+            });
+        });
+    }
+
+    planet.id()
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    golf_ball::GolfBall,
+    groups::{PRACTICE_FILTER, PRACTICE_GROUP},
+    sim_state::sim_running,
+    time_scale::TimeScale,
+};
+
+/// How long a practice ball stays in play, in simulated
+/// seconds.
+pub const PRACTICE_LIFETIME: f32 = 5.0;
+
+/// How see-through practice balls are drawn, so they can't
+/// be mistaken for the real thing.
+pub const PRACTICE_ALPHA: f32 = 0.35;
+
+/// Marks a practice shot, taken by holding `Alt` when the
+/// ball is let go. It flies like any other ball but
+/// doesn't take a stroke, can't be undone, can't sink and
+/// goes away by itself after [`PRACTICE_LIFETIME`], so a
+/// shot can be tried out without it costing anything.
+///
+/// Practice balls are in their own collision group, see
+/// [`practice_groups`], so they can't knock the balls that
+/// count about either.
+#[derive(Component)]
+pub struct Practice {
+    pub timer: Timer,
+}
+
+impl Default for Practice {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(
+                PRACTICE_LIFETIME,
+                false,
+            ),
+        }
+    }
+}
+
+/// The `CollisionGroups` of a practice ball, in place of
+/// the ones `spawn_ball` gives every ball.
+pub fn practice_groups() -> CollisionGroups {
+    CollisionGroups::new(PRACTICE_GROUP, PRACTICE_FILTER)
+}
+
+/// Whether the shot being let go right now is a practice
+/// shot.
+pub fn is_practice(keyboard: &Input<KeyCode>) -> bool {
+    keyboard.any_pressed([KeyCode::LAlt, KeyCode::RAlt])
+}
+
+pub struct PracticePlugin;
+
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            expire_practice_balls
+                .with_run_criteria(sim_running),
+        );
+    }
+}
+
+/// Counts simulated time like `ShotTimer`, so practice
+/// balls wait while the game is paused.
+fn expire_practice_balls(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut balls: Query<
+        (Entity, &mut Practice),
+        With<GolfBall>,
+    >,
+) {
+    let delta = time.delta().mul_f32(time_scale.0);
+    for (ball, mut practice) in balls.iter_mut() {
+        if practice.timer.tick(delta).just_finished() {
+            commands.entity(ball).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,629 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+use std::{
+    f32::consts::TAU,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    golf_ball::GolfBallSettings,
+    gravity::{
+        cutoff_gravity_at, gravity_at, GravityMode,
+        GravitySettings, GravitySource,
+    },
+    launch::LaunchCharge,
+    planet_spec::Planet,
+    render::{spawn_poly_line, PolyLine},
+    Body,
+};
+
+/// How predicted shots bounce and are drawn. How far
+/// ahead, and how finely, they're followed is up to the
+/// ball, see [`GolfBallSettings::prediction_steps`].
+#[derive(Clone, Copy, Debug)]
+pub struct PredictionSettings {
+    /// how many times the predicted path can bounce off
+    /// of a planet before we stop following it
+    pub max_bounces: u32,
+    /// only every `point_stride`th predicted point is
+    /// drawn, to keep long lines light. The predicted path
+    /// itself isn't affected.
+    pub point_stride: usize,
+}
+
+impl Default for PredictionSettings {
+    fn default() -> Self {
+        Self {
+            max_bounces: 3,
+            point_stride: 1,
+        }
+    }
+}
+
+/// Something the predicted ball can run into: a circle,
+/// or for anything that isn't round, a collider with the
+/// circle around it.
+#[derive(Clone, Debug)]
+pub struct Obstacle {
+    pub center: Vec2,
+    pub radius: f32,
+    /// the true outline and its rotation, in radians, for
+    /// anything that isn't round. The ball is only checked
+    /// against it once it's inside of `radius`.
+    pub shape: Option<(Collider, f32)>,
+}
+
+impl Obstacle {
+    /// A planet's `collider` where `transform` puts it.
+    /// Ellipses and polygons keep their own outline, so
+    /// predicted paths go round them rather than through.
+    pub fn from_collider(
+        transform: &GlobalTransform,
+        collider: &Collider,
+    ) -> Self {
+        let (_, rotation, translation) =
+            transform.to_scale_rotation_translation();
+        let center = translation.truncate();
+        if let Some(ball) = collider.as_ball() {
+            return Self {
+                center,
+                radius: ball.radius(),
+                shape: None,
+            };
+        }
+        let bounds =
+            collider.raw.compute_local_bounding_sphere();
+        Self {
+            center,
+            // the bounding circle's own center can be off
+            // of the collider's
+            radius: bounds.center.coords.norm()
+                + bounds.radius,
+            shape: Some((
+                collider.clone(),
+                rotation.to_euler(EulerRot::XYZ).2,
+            )),
+        }
+    }
+
+    /// How far a ball of `radius` at `position` has sunk
+    /// into this, and the way back out, or `None` if it
+    /// isn't touching.
+    pub fn contact(
+        &self,
+        position: Vec2,
+        radius: f32,
+    ) -> Option<(f32, Vec2)> {
+        let offset = position - self.center;
+        if offset.length() >= self.radius + radius {
+            return None;
+        }
+        let (collider, rotation) = match &self.shape {
+            Some(shape) => shape,
+            None => {
+                return Some((
+                    self.radius + radius - offset.length(),
+                    offset.normalize_or_zero(),
+                ))
+            }
+        };
+        let projection = collider.project_point(
+            self.center,
+            *rotation,
+            position,
+            false,
+        );
+        let offset = position - projection.point;
+        let (separation, outward) = if projection.is_inside
+        {
+            (-offset.length(), -offset)
+        } else {
+            (offset.length(), offset)
+        };
+        let outward =
+            outward.try_normalize().unwrap_or_else(|| {
+                (projection.point - self.center)
+                    .normalize_or_zero()
+            });
+        (separation < radius)
+            .then_some((radius - separation, outward))
+    }
+}
+
+/// Forward-integrate a ball launched from `start` with
+/// `velocity`, returning every position it passes
+/// through. The ball bounces off of `obstacles` with the
+/// same restitution it would be spawned with, and the
+/// path ends at the first contact if it wouldn't bounce.
+pub fn predict_trajectory(
+    sources: &[GravitySource],
+    obstacles: &[Obstacle],
+    start: Vec2,
+    velocity: Vec2,
+    ball: &GolfBallSettings,
+    settings: &PredictionSettings,
+) -> Vec<Vec2> {
+    predict_trajectory_with(
+        sources, obstacles, start, velocity, ball,
+        settings, gravity_at,
+    )
+}
+
+/// [`predict_trajectory`] with the pull of `sources` at a
+/// point worked out by `gravity`, for following softened,
+/// differently falling off or cut off gravity the way the
+/// simulation does.
+pub fn predict_trajectory_with(
+    sources: &[GravitySource],
+    obstacles: &[Obstacle],
+    start: Vec2,
+    velocity: Vec2,
+    ball: &GolfBallSettings,
+    settings: &PredictionSettings,
+    gravity: impl Fn(&[GravitySource], Vec2) -> Vec2,
+) -> Vec<Vec2> {
+    let radius = ball.radius();
+    let mut position = start;
+    let mut velocity = velocity;
+    let mut bounces = 0;
+    let dt = ball.prediction_dt;
+    let mut points =
+        Vec::with_capacity(ball.prediction_steps + 1);
+    points.push(position);
+
+    for _ in 0..ball.prediction_steps {
+        // gravity is applied to balls as a force, so the
+        // ball's mass scales it down, same as in rapier.
+        let acceleration =
+            gravity(sources, position) / ball.mass;
+        velocity += acceleration * dt;
+        position += velocity * dt;
+
+        let hit = obstacles.iter().find_map(|obstacle| {
+            obstacle.contact(position, radius)
+        });
+        if let Some((depth, normal)) = hit {
+            position += normal * depth;
+            points.push(position);
+
+            bounces += 1;
+            if ball.restitution <= 0.0
+                || bounces > settings.max_bounces
+            {
+                break;
+            }
+            velocity -= (1.0 + ball.restitution)
+                * velocity.dot(normal)
+                * normal;
+            continue;
+        }
+        points.push(position);
+    }
+    points
+}
+
+/// Seconds until a ball at `start` moving at `velocity`
+/// first touches one of `obstacles`, or `None` if it
+/// doesn't within the `prediction_steps * prediction_dt`
+/// that `ball` looks ahead.
+pub fn time_to_impact(
+    sources: &[GravitySource],
+    obstacles: &[Obstacle],
+    start: Vec2,
+    velocity: Vec2,
+    ball: &GolfBallSettings,
+    settings: &PredictionSettings,
+) -> Option<f32> {
+    // stopping at the first contact leaves it as the last
+    // point
+    let settings = PredictionSettings {
+        max_bounces: 0,
+        ..*settings
+    };
+    let points = predict_trajectory(
+        sources, obstacles, start, velocity, ball,
+        &settings,
+    );
+    let last = *points.last()?;
+    let radius = ball.radius();
+    obstacles
+        .iter()
+        .any(|obstacle| {
+            obstacle.contact(last, radius + 1e-3).is_some()
+        })
+        .then(|| {
+            (points.len() - 1) as f32 * ball.prediction_dt
+        })
+}
+
+/// The closest a predicted shot gets to some target.
+#[derive(Clone, Copy, Debug)]
+pub struct ClosestApproach {
+    pub distance: f32,
+    /// seconds after launch
+    pub time: f32,
+    pub position: Vec2,
+}
+
+/// Run the same forward simulation as
+/// [`predict_trajectory`] and find where the ball gets
+/// closest to `target`, usually a [`Hole`].
+///
+/// [`Hole`]: crate::hole::Hole
+pub fn closest_approach(
+    sources: &[GravitySource],
+    obstacles: &[Obstacle],
+    start: Vec2,
+    velocity: Vec2,
+    ball: &GolfBallSettings,
+    settings: &PredictionSettings,
+    target: Vec2,
+) -> ClosestApproach {
+    let points = predict_trajectory(
+        sources, obstacles, start, velocity, ball, settings,
+    );
+    let mut closest = ClosestApproach {
+        distance: start.distance(target),
+        time: 0.0,
+        position: start,
+    };
+    // each step adds exactly one point, so the closest
+    // point on a segment maps straight back to a time.
+    for (i, segment) in points.windows(2).enumerate() {
+        let along = segment[1] - segment[0];
+        let t = if along == Vec2::ZERO {
+            0.0
+        } else {
+            ((target - segment[0]).dot(along)
+                / along.length_squared())
+            .clamp(0.0, 1.0)
+        };
+        let position = segment[0] + along * t;
+        let distance = position.distance(target);
+        if distance < closest.distance {
+            closest = ClosestApproach {
+                distance,
+                time: (i as f32 + t) * ball.prediction_dt,
+                position,
+            };
+        }
+    }
+    closest
+}
+
+/// The launches [`find_shot`] tries: `directions` evenly
+/// spread angles, each at `speeds` speeds going up in
+/// steps of `speed_step`.
+#[derive(Clone, Debug)]
+pub struct ShotSearch {
+    pub directions: u32,
+    pub speeds: u32,
+    pub speed_step: f32,
+    /// stop looking after this long
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for ShotSearch {
+    fn default() -> Self {
+        Self {
+            directions: 48,
+            speeds: 8,
+            speed_step: 50.0,
+            time_limit: None,
+        }
+    }
+}
+
+/// The first launch velocity in `search` whose predicted
+/// path from `start` passes within `radius` of `target`,
+/// or `None` if none do before the time limit.
+#[allow(clippy::too_many_arguments)]
+pub fn find_shot(
+    sources: &[GravitySource],
+    obstacles: &[Obstacle],
+    start: Vec2,
+    target: Vec2,
+    radius: f32,
+    ball: &GolfBallSettings,
+    settings: &PredictionSettings,
+    search: &ShotSearch,
+) -> Option<Vec2> {
+    let started = Instant::now();
+    for i in 0..search.directions {
+        if search
+            .time_limit
+            .is_some_and(|limit| started.elapsed() > limit)
+        {
+            return None;
+        }
+        let direction = Mat2::from_angle(
+            i as f32 / search.directions as f32 * TAU,
+        ) * Vec2::X;
+        for speed in 1..=search.speeds {
+            let velocity = direction
+                * speed as f32
+                * search.speed_step;
+            let approach = closest_approach(
+                sources, obstacles, start, velocity, ball,
+                settings, target,
+            );
+            if approach.distance < radius {
+                return Some(velocity);
+            }
+        }
+    }
+    None
+}
+
+pub struct PredictionPlugin;
+
+impl Plugin for PredictionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PredictionSettings>()
+            .add_startup_system(spawn_prediction_line)
+            .add_system(draw_prediction);
+    }
+}
+
+/// The ribbon the predicted path is drawn with.
+#[derive(Component)]
+pub struct PredictionLine;
+
+fn spawn_prediction_line(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let line = spawn_poly_line(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        // fade out along the path so the direction of
+        // travel is obvious
+        PolyLine::new(Vec::new(), 3.0, Color::WHITE)
+            .with_gradient(
+                1.0,
+                Color::rgba(1.0, 1.0, 1.0, 0.0),
+            ),
+        5.0,
+    );
+    commands.entity(line).insert(PredictionLine);
+}
+
+/// Redraws the predicted path of the shot being aimed
+/// from the ball's place and the pointer, and clears it
+/// when there's no shot being aimed. Run it after whatever
+/// places and launches balls, so the path goes the same
+/// frame the shot does.
+///
+/// The ball is pulled by the same bodies the simulation
+/// pulls with, straight out of the `ParticleSet`, and as a
+/// massless test particle doesn't pull back on any of
+/// them.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_prediction(
+    mut lines: Query<&mut PolyLine, With<PredictionLine>>,
+    ball: Res<GolfBallSettings>,
+    settings: Res<PredictionSettings>,
+    charge: Res<LaunchCharge>,
+    mouse_pos: Res<MousePosWorld>,
+    gravity_mode: Res<GravityMode>,
+    gravity_settings: Res<GravitySettings>,
+    particle_set: Res<ParticleSet<Body>>,
+    planets: Query<
+        (&GlobalTransform, &Collider),
+        With<Planet>,
+    >,
+) {
+    let place_pos = match ball.position {
+        Some(place_pos) => place_pos.xy(),
+        None => {
+            for mut line in lines.iter_mut() {
+                if !line.points.is_empty() {
+                    line.points.clear();
+                }
+            }
+            return;
+        }
+    };
+    let velocity =
+        charge.velocity(place_pos, mouse_pos.truncate());
+
+    // the set's `mu`s are already scaled to the level's
+    // gravity, and zero for bodies `GravityMode` says
+    // don't pull
+    let sources: Vec<GravitySource> = particle_set
+        .iter()
+        .filter(|body| body.mu != 0.0)
+        .map(|body| GravitySource {
+            position: body.position.xy(),
+            mu: body.mu,
+        })
+        .collect();
+    let obstacles: Vec<Obstacle> = planets
+        .iter()
+        .map(|(transform, collider)| {
+            Obstacle::from_collider(transform, collider)
+        })
+        .collect();
+
+    // the same gravity the simulation pulls balls with
+    let cutoff = gravity_settings.cutoff(*gravity_mode);
+    let mut points = predict_trajectory_with(
+        &sources,
+        &obstacles,
+        place_pos,
+        velocity,
+        &ball,
+        &settings,
+        |sources, point| {
+            cutoff_gravity_at(
+                sources,
+                point,
+                gravity_settings.softening,
+                gravity_settings.falloff_exponent,
+                cutoff,
+            )
+        },
+    );
+    let stride = settings.point_stride.max(1);
+    if stride > 1 {
+        // keep the end of the path where it is
+        let last = points.last().copied();
+        points =
+            points.into_iter().step_by(stride).collect();
+        if let Some(last) = last {
+            if points.last() != Some(&last) {
+                points.push(last);
+            }
+        }
+    }
+    for mut line in lines.iter_mut() {
+        line.points = points.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approach(target: Vec2) -> ClosestApproach {
+        // nothing pulling, so the ball flies straight
+        // along the x axis at 100 a second
+        closest_approach(
+            &[],
+            &[],
+            Vec2::ZERO,
+            Vec2::new(100.0, 0.0),
+            &GolfBallSettings::default(),
+            &PredictionSettings::default(),
+            target,
+        )
+    }
+
+    #[test]
+    fn closest_approach_finds_the_nearest_point() {
+        let closest = approach(Vec2::new(50.0, 10.0));
+        assert!((closest.distance - 10.0).abs() < 1e-3);
+        assert!((closest.time - 0.5).abs() < 1e-3);
+        assert!(closest
+            .position
+            .abs_diff_eq(Vec2::new(50.0, 0.0), 1e-3));
+    }
+
+    #[test]
+    fn closest_approach_to_a_target_behind_is_the_start() {
+        let closest = approach(Vec2::new(-30.0, 40.0));
+        assert_eq!(closest.distance, 50.0);
+        assert_eq!(closest.time, 0.0);
+        assert_eq!(closest.position, Vec2::ZERO);
+    }
+
+    #[test]
+    fn paths_go_round_colliders_that_arent_round() {
+        // a long flat slab, well inside the circle around
+        // it
+        let slab = [Obstacle::from_collider(
+            &GlobalTransform::identity(),
+            &Collider::cuboid(200.0, 10.0),
+        )];
+        let ball = GolfBallSettings::default();
+        let radius = ball.radius();
+        let path = |start, velocity| {
+            predict_trajectory(
+                &[],
+                &slab,
+                start,
+                velocity,
+                &ball,
+                &PredictionSettings::default(),
+            )
+        };
+
+        // passing over it inside the circle
+        let over = path(
+            Vec2::new(-300.0, 50.0),
+            Vec2::new(300.0, 0.0),
+        );
+        assert_eq!(
+            over.len(),
+            ball.prediction_steps + 1,
+            "the path stopped at {:?}",
+            over.last()
+        );
+        // and dropping onto it stops on its surface
+        let onto = path(
+            Vec2::new(150.0, 150.0),
+            Vec2::new(0.0, -300.0),
+        );
+        let last = *onto.last().unwrap();
+        assert!(
+            (last.y - (10.0 + radius)).abs() < 1e-3,
+            "the path stopped at {:?}",
+            last
+        );
+    }
+
+    #[test]
+    fn closest_approach_matches_a_keplerian_flyby() {
+        let ball = GolfBallSettings {
+            prediction_steps: 4000,
+            prediction_dt: 1e-3,
+            ..default()
+        };
+        let planet = GravitySource {
+            position: Vec2::ZERO,
+            mu: 1e9,
+        };
+        let start = Vec2::new(-600.0, 150.0);
+        let velocity = Vec2::new(200.0, 0.0);
+        let closest = closest_approach(
+            &[planet],
+            &[],
+            start,
+            velocity,
+            &ball,
+            &PredictionSettings::default(),
+            planet.position,
+        );
+
+        // the same flyby worked out in closed form, in
+        // f64. Gravity reaches the ball as a force, so its
+        // own mass divides the planet's pull.
+        let mu = planet.mu as f64 / ball.mass as f64;
+        let (r, v) = (
+            start.as_dvec2().length(),
+            velocity.as_dvec2().length(),
+        );
+        let h =
+            start.as_dvec2().perp_dot(velocity.as_dvec2());
+        let energy = v * v / 2.0 - mu / r;
+        assert!(energy > 0.0, "the flyby isn't hyperbolic");
+        let a = mu / (2.0 * energy);
+        let e =
+            (1.0 + 2.0 * energy * h * h / (mu * mu)).sqrt();
+        let periapsis = a * (e - 1.0);
+        // hyperbolic anomaly at the start, and the mean
+        // anomaly it's swept through by periapsis
+        let anomaly = ((1.0 + r / a) / e).acosh();
+        let time = (e * anomaly.sinh() - anomaly)
+            / (mu / (a * a * a)).sqrt();
+
+        assert!(
+            (closest.distance as f64 - periapsis).abs()
+                < periapsis * 0.01,
+            "{} != {}",
+            closest.distance,
+            periapsis
+        );
+        assert!(
+            (closest.time as f64 - time).abs()
+                < time * 0.01,
+            "{} != {}",
+            closest.time,
+            time
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::Indices, render_resource::PrimitiveTopology,
+    },
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+/// A line through `points` drawn as a ribbon of triangles
+/// `width` world units wide, so it stays smooth and keeps
+/// its thickness at any zoom.
+///
+/// Width and color blend from the start values to the
+/// `end_` values along the line.
+#[derive(Component, Clone, Debug)]
+pub struct PolyLine {
+    pub points: Vec<Vec2>,
+    pub width: f32,
+    pub color: Color,
+    pub end_width: f32,
+    pub end_color: Color,
+}
+
+impl Default for PolyLine {
+    fn default() -> Self {
+        PolyLine::new(Vec::new(), 2.0, Color::WHITE)
+    }
+}
+
+impl PolyLine {
+    pub fn new(
+        points: Vec<Vec2>,
+        width: f32,
+        color: Color,
+    ) -> Self {
+        Self {
+            points,
+            width,
+            color,
+            end_width: width,
+            end_color: color,
+        }
+    }
+
+    pub fn with_gradient(
+        mut self,
+        end_width: f32,
+        end_color: Color,
+    ) -> Self {
+        self.end_width = end_width;
+        self.end_color = end_color;
+        self
+    }
+
+    /// Builds the ribbon. Each point gets a pair of
+    /// vertices, offset along the average of the normals
+    /// of the segments on either side of it.
+    pub fn to_mesh(&self) -> Mesh {
+        let count = self.points.len();
+        let start = self.color.as_linear_rgba_f32();
+        let end = self.end_color.as_linear_rgba_f32();
+
+        let mut positions = Vec::with_capacity(count * 2);
+        let mut colors = Vec::with_capacity(count * 2);
+        for (i, point) in self.points.iter().enumerate() {
+            let previous = self.points[i.saturating_sub(1)];
+            let next = self.points[(i + 1).min(count - 1)];
+            let normal = (next - previous)
+                .normalize_or_zero()
+                .perp();
+            let t = if count > 1 {
+                i as f32 / (count - 1) as f32
+            } else {
+                0.0
+            };
+            let half_width = (self.width
+                + (self.end_width - self.width) * t)
+                / 2.0;
+            let color = [
+                start[0] + (end[0] - start[0]) * t,
+                start[1] + (end[1] - start[1]) * t,
+                start[2] + (end[2] - start[2]) * t,
+                start[3] + (end[3] - start[3]) * t,
+            ];
+            for side in [1.0, -1.0] {
+                let vertex =
+                    *point + normal * half_width * side;
+                positions.push([vertex.x, vertex.y, 0.0]);
+                colors.push(color);
+            }
+        }
+
+        let mut indices =
+            Vec::with_capacity(count.saturating_sub(1) * 6);
+        for i in 0..count.saturating_sub(1) as u32 {
+            let left = i * 2;
+            indices.extend_from_slice(&[
+                left,
+                left + 1,
+                left + 2,
+                left + 1,
+                left + 3,
+                left + 2,
+            ]);
+        }
+
+        let normals =
+            vec![[0.0, 0.0, 1.0]; positions.len()];
+        let uvs = vec![[0.0, 0.0]; positions.len()];
+        let mut mesh =
+            Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_indices(Some(Indices::U32(indices)));
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            positions,
+        );
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_NORMAL,
+            normals,
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_COLOR,
+            colors,
+        );
+        mesh
+    }
+}
+
+/// Keeps the mesh of every `PolyLine` entity in step with
+/// its points. Lines with fewer than two points are hidden.
+pub struct PolyLinePlugin;
+
+impl Plugin for PolyLinePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            update_poly_lines,
+        );
+    }
+}
+
+/// Spawns an entity that draws `line`. Change its
+/// `PolyLine` to redraw it.
+pub fn spawn_poly_line(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    line: PolyLine,
+    z: f32,
+) -> Entity {
+    // never upload an empty mesh, even for a line that
+    // isn't drawn yet
+    let mesh = if line.points.len() >= 2 {
+        line.to_mesh()
+    } else {
+        PolyLine {
+            points: vec![Vec2::ZERO; 2],
+            ..line.clone()
+        }
+        .to_mesh()
+    };
+    commands
+        .spawn_bundle(MaterialMesh2dBundle {
+            mesh: meshes.add(mesh).into(),
+            // the vertex colors do the coloring
+            material: materials
+                .add(ColorMaterial::from(Color::WHITE)),
+            transform: Transform::from_xyz(0.0, 0.0, z),
+            ..default()
+        })
+        .insert(line)
+        .id()
+}
+
+fn update_poly_lines(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut lines: Query<
+        (&PolyLine, &Mesh2dHandle, &mut Visibility),
+        Changed<PolyLine>,
+    >,
+) {
+    for (line, handle, mut visibility) in lines.iter_mut() {
+        // keep the last mesh around rather than uploading
+        // an empty one
+        visibility.is_visible = line.points.len() >= 2;
+        if !visibility.is_visible {
+            continue;
+        }
+        if let Some(mesh) = meshes.get_mut(&handle.0) {
+            *mesh = line.to_mesh();
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use std::time::Duration;
+
+use crate::{
+    course::{Bounces, GameMode, ShotTimer, Strokes, Sunk},
+    golf_ball::GolfBall,
+    gravity::frame_dt,
+    menu::playing,
+    sim_state::{sim_running, SimState},
+    undo::ShotHistory,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct BallState {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+    /// how far into its `ShotTimer` the ball was, if it
+    /// had one
+    pub shot_elapsed: Option<f32>,
+    pub bounces: Option<u32>,
+}
+
+/// Every ball's state at one point in the recording.
+#[derive(Clone, Debug, Default)]
+pub struct Frame {
+    /// seconds since the recording started
+    pub time: f32,
+    pub balls: Vec<BallState>,
+}
+
+/// Captures a frame every simulation step while
+/// `recording`. Toggled with `R`.
+///
+/// Frames are taken once rapier has stepped, and their
+/// `time`s count simulated seconds, so a faster
+/// `TimeScale` spaces them further apart. When rapier
+/// catches up with several fixed steps in one go, under
+/// `RenderInterpolation`, only where they end up is
+/// captured.
+#[derive(Default)]
+pub struct Recorder {
+    pub recording: bool,
+    pub frames: Vec<Frame>,
+    elapsed: f32,
+    /// how far this frame's physics steps take the
+    /// simulation, noted before they're taken
+    step: f32,
+}
+
+/// Drives balls from the recorded frames instead of the
+/// live simulation. `T` enters and leaves playback and the
+/// arrow keys scrub through the recording.
+///
+/// Leaving playback resumes the live simulation from
+/// whichever frame is showing. Balls that were despawned
+/// since the recording can't be brought back, and balls
+/// spawned after it are left where they are.
+#[derive(Default)]
+pub struct Playback {
+    pub active: bool,
+    pub playing: bool,
+    pub frame: usize,
+    resume_state: Option<SimState>,
+}
+
+/// A frame of the recording to come back to. `F6` sets it
+/// to the frame just recorded, or the one showing during
+/// playback, and `F7` rewinds the live simulation to it:
+/// every ball is put back where it was, how fast it was
+/// going and spinning, how far into its `ShotTimer` it was
+/// and how many `Bounces` it had, and the recording
+/// carries on from there as if what came after had never
+/// happened.
+///
+/// Balls spawned after the checkpoint didn't exist yet, so
+/// they're despawned. In `GameMode::Sandbox` the strokes
+/// they cost no longer count, like an undo; in
+/// `GameMode::Challenge` they still do. Balls that have
+/// sunk or been despawned since can't be brought back.
+#[derive(Default)]
+pub struct Checkpoint {
+    pub frame: Option<usize>,
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Recorder>()
+            .init_resource::<Playback>()
+            .init_resource::<Checkpoint>()
+            .add_system(
+                replay_controls.with_run_criteria(playing),
+            )
+            .add_system(
+                checkpoint_controls
+                    .with_run_criteria(playing)
+                    .after(replay_controls),
+            )
+            .add_system(
+                note_step
+                    .with_run_criteria(sim_running)
+                    .after(replay_controls),
+            )
+            // after rapier has written back what its step
+            // did
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                record_frame.with_run_criteria(sim_running),
+            )
+            .add_system(play_back.after(replay_controls));
+    }
+}
+
+fn replay_controls(
+    keyboard: Res<Input<KeyCode>>,
+    mut recorder: ResMut<Recorder>,
+    mut playback: ResMut<Playback>,
+    mut checkpoint: ResMut<Checkpoint>,
+    mut sim_state: ResMut<SimState>,
+) {
+    if keyboard.just_pressed(KeyCode::R) && !playback.active
+    {
+        recorder.recording = !recorder.recording;
+        if recorder.recording {
+            checkpoint.frame = None;
+            recorder.frames.clear();
+            recorder.elapsed = 0.0;
+        }
+    }
+
+    if keyboard.just_pressed(KeyCode::T) {
+        if playback.active {
+            playback.active = false;
+            if let Some(state) =
+                playback.resume_state.take()
+            {
+                *sim_state = state;
+            }
+        } else if !recorder.frames.is_empty() {
+            recorder.recording = false;
+            *playback = Playback {
+                active: true,
+                playing: true,
+                frame: 0,
+                resume_state: Some(*sim_state),
+            };
+            *sim_state = SimState::Paused;
+        }
+    }
+
+    if playback.active {
+        let last = recorder.frames.len().saturating_sub(1);
+        if keyboard.pressed(KeyCode::Left) {
+            playback.playing = false;
+            playback.frame =
+                playback.frame.saturating_sub(1);
+        }
+        if keyboard.pressed(KeyCode::Right) {
+            playback.playing = false;
+            playback.frame = (playback.frame + 1).min(last);
+        }
+    }
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn checkpoint_controls(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    mut recorder: ResMut<Recorder>,
+    playback: Res<Playback>,
+    mut checkpoint: ResMut<Checkpoint>,
+    mode: Res<GameMode>,
+    mut history: ResMut<ShotHistory>,
+    mut strokes: ResMut<Strokes>,
+    mut balls: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            Option<&mut ShotTimer>,
+            Option<&mut Bounces>,
+        ),
+        (With<GolfBall>, Without<Sunk>),
+    >,
+) {
+    if keyboard.just_pressed(KeyCode::F6) {
+        checkpoint.frame = if playback.active {
+            Some(playback.frame)
+        } else {
+            recorder.frames.len().checked_sub(1)
+        };
+    }
+
+    // playback already has the arrow keys for this
+    if !keyboard.just_pressed(KeyCode::F7)
+        || playback.active
+    {
+        return;
+    }
+    let index = match checkpoint.frame {
+        Some(index) if index < recorder.frames.len() => {
+            index
+        }
+        _ => return,
+    };
+    recorder.frames.truncate(index + 1);
+    let frame = &recorder.frames[index];
+    for (
+        ball,
+        mut transform,
+        mut velocity,
+        timer,
+        bounces,
+    ) in balls.iter_mut()
+    {
+        match frame
+            .balls
+            .iter()
+            .find(|state| state.entity == ball)
+        {
+            Some(state) => {
+                transform.translation.x = state.position.x;
+                transform.translation.y = state.position.y;
+                velocity.linvel = state.velocity;
+                velocity.angvel = state.angular_velocity;
+                if let (Some(mut timer), Some(elapsed)) =
+                    (timer, state.shot_elapsed)
+                {
+                    // a finished timer stays finished
+                    // until it's reset
+                    timer.0.reset();
+                    timer.0.set_elapsed(
+                        Duration::from_secs_f32(elapsed),
+                    );
+                }
+                if let (Some(mut bounces), Some(count)) =
+                    (bounces, state.bounces)
+                {
+                    bounces.0 = count;
+                }
+            }
+            None => {
+                commands.entity(ball).despawn_recursive();
+                if history.remove(ball)
+                    && *mode != GameMode::Challenge
+                {
+                    strokes.0 = strokes.0.saturating_sub(1);
+                }
+            }
+        }
+    }
+    recorder.elapsed = frame.time;
+}
+
+fn note_step(
+    time: Res<Time>,
+    rapier_config: Res<RapierConfiguration>,
+    sim_to_render: Res<SimulationToRenderTime>,
+    mut recorder: ResMut<Recorder>,
+) {
+    recorder.step =
+        frame_dt(&rapier_config, &time, &sim_to_render);
+}
+
+#[allow(clippy::type_complexity)]
+fn record_frame(
+    mut recorder: ResMut<Recorder>,
+    balls: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Velocity,
+            Option<&ShotTimer>,
+            Option<&Bounces>,
+        ),
+        With<GolfBall>,
+    >,
+) {
+    let step = std::mem::take(&mut recorder.step);
+    // rapier didn't step, so nothing's moved
+    if !recorder.recording || step <= 0.0 {
+        return;
+    }
+    recorder.elapsed += step;
+    let frame = Frame {
+        time: recorder.elapsed,
+        balls: balls
+            .iter()
+            .map(
+                |(
+                    entity,
+                    transform,
+                    velocity,
+                    timer,
+                    bounces,
+                )| BallState {
+                    entity,
+                    position: transform
+                        .translation()
+                        .truncate(),
+                    velocity: velocity.linvel,
+                    angular_velocity: velocity.angvel,
+                    shot_elapsed: timer.map(|timer| {
+                        timer.0.elapsed_secs()
+                    }),
+                    bounces: bounces
+                        .map(|bounces| bounces.0),
+                },
+            )
+            .collect(),
+    };
+    recorder.frames.push(frame);
+}
+
+fn play_back(
+    recorder: Res<Recorder>,
+    mut playback: ResMut<Playback>,
+    mut balls: Query<
+        (&mut Transform, &mut Velocity),
+        With<GolfBall>,
+    >,
+) {
+    if !playback.active {
+        return;
+    }
+    let frame = match recorder.frames.get(playback.frame) {
+        Some(frame) => frame,
+        None => return,
+    };
+    for state in frame.balls.iter() {
+        if let Ok((mut transform, mut velocity)) =
+            balls.get_mut(state.entity)
+        {
+            transform.translation.x = state.position.x;
+            transform.translation.y = state.position.y;
+            velocity.linvel = state.velocity;
+            velocity.angvel = state.angular_velocity;
+        }
+    }
+    if playback.playing {
+        let last = recorder.frames.len() - 1;
+        if playback.frame < last {
+            playback.frame += 1;
+        } else {
+            playback.playing = false;
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The one source of randomness for the game. Anything
+/// random draws from this so that running with the same
+/// seed plays out the same way.
+///
+/// Implements `RngCore`, so `rand::Rng` methods like
+/// `gen_range` work on it directly.
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// The seed this session was started with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Starts the sequence over from a new seed.
+    pub fn reseed(&mut self, seed: u64) {
+        *self = GameRng::new(seed);
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Inserts the `GameRng`. Leaving `seed` as `None` picks
+/// one from the clock; either way it is logged at startup
+/// so an interesting session can be replayed.
+#[derive(Default)]
+pub struct RngPlugin {
+    pub seed: Option<u64>,
+}
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = self.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_nanos() as u64)
+        });
+        info!("rng seed: {}", seed);
+        app.insert_resource(GameRng::new(seed));
+    }
+}
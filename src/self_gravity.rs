@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use particular::ParticleSet;
+
+use crate::{
+    gravity::{GravityBodies, G},
+    Body,
+};
+
+/// A planet that's moved along by the pull of every other
+/// `PointMass::HasGravity` body or `Attractor`, rather
+/// than staying where it was put. Balls don't pull on
+/// planets at all, they're far too light to matter.
+///
+/// Moves the `Transform` directly, so in the `three_d`
+/// mode an orbiting planet's rigid body has to be
+/// `KinematicPositionBased` instead of `Fixed`.
+#[derive(
+    Component, Reflect, Clone, Copy, Debug, Default,
+)]
+#[reflect(Component)]
+pub struct Orbiting {
+    pub velocity: Vec3,
+}
+
+/// The planets' own particle set. Kept apart from the
+/// `ParticleSet<Body>` the balls are pulled through so the
+/// two don't fight over when it's rebuilt.
+pub struct PlanetParticleSet(ParticleSet<Body>);
+
+// `Body` holds an `Entity`, which has no default, so the
+// set can't derive one
+impl Default for PlanetParticleSet {
+    fn default() -> Self {
+        Self(ParticleSet::new())
+    }
+}
+
+/// Two planets of `masses` going round their common center
+/// of mass in circles `separation` apart, in the plane at
+/// right angles to `normal`. Any masses and separation
+/// give a stable orbit; heavier planets or a smaller
+/// separation just make it faster.
+#[derive(Clone, Copy, Debug)]
+pub struct Binary {
+    pub masses: [f32; 2],
+    pub separation: f32,
+    pub normal: Vec3,
+}
+
+impl Default for Binary {
+    fn default() -> Self {
+        Self {
+            masses: [0.01, 0.005],
+            separation: 4.0,
+            normal: Vec3::Y,
+        }
+    }
+}
+
+impl Binary {
+    /// Where each planet starts, relative to the center of
+    /// mass, and the velocity it needs for a circular
+    /// orbit. Each planet is `d * m_other / M` from the
+    /// center and goes round at
+    /// `m_other * sqrt(G / (M d))`.
+    pub fn bodies(&self) -> [(Vec3, Orbiting); 2] {
+        let [a, b] = self.masses;
+        let total = a + b;
+        if total <= 0.0 || self.separation <= 0.0 {
+            return [(Vec3::ZERO, Orbiting::default()); 2];
+        }
+        let normal = self.normal.normalize_or_zero();
+        let axis = normal.any_orthonormal_vector();
+        let along = normal.cross(axis);
+        let speed = (G / (total * self.separation)).sqrt();
+        let distance = self.separation / total;
+        [
+            (
+                axis * distance * b,
+                Orbiting {
+                    velocity: along * speed * b,
+                },
+            ),
+            (
+                -axis * distance * a,
+                Orbiting {
+                    velocity: -along * speed * a,
+                },
+            ),
+        ]
+    }
+}
+
+/// Lets `Orbiting` planets pull each other around. Works
+/// on its own, without rapier, so the `planets` example
+/// can use it too.
+pub struct SelfGravityPlugin;
+
+impl Plugin for SelfGravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Orbiting>()
+            .init_resource::<PlanetParticleSet>()
+            .add_system(
+                sync_planet_particle_set
+                    .before(move_orbiting_planets),
+            )
+            .add_system(move_orbiting_planets);
+    }
+}
+
+fn sync_planet_particle_set(
+    mut particle_set: ResMut<PlanetParticleSet>,
+    bodies: GravityBodies,
+) {
+    particle_set.0 = ParticleSet::new();
+    for (entity, position, mu, _) in
+        bodies.iter().filter(|(.., ball)| !ball)
+    {
+        particle_set.0.add(Body::new(position, mu, entity));
+    }
+}
+
+/// Semi-implicit Euler, the same as rapier uses for balls,
+/// which keeps a circular orbit from spiraling in or out.
+fn move_orbiting_planets(
+    time: Res<Time>,
+    mut particle_set: ResMut<PlanetParticleSet>,
+    mut planets: Query<(&mut Transform, &mut Orbiting)>,
+) {
+    let dt = time.delta_seconds();
+    for (body, acceleration) in particle_set.0.result() {
+        if let Ok((mut transform, mut orbiting)) =
+            planets.get_mut(body.entity)
+        {
+            orbiting.velocity += acceleration * dt;
+            transform.translation += orbiting.velocity * dt;
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use std::fmt;
+
+use crate::{
+    auto_aim::LaunchShot,
+    course::Course,
+    golf_ball::GolfBall,
+    menu::playing,
+    practice::Practice,
+    time_scale::{apply_time_scale, FixedSteps},
+};
+
+/// The format [`ShareCode::encode`] writes, first byte of
+/// every code. Bumped whenever the layout changes, so an
+/// old game turns down a newer code instead of misreading
+/// it.
+pub const SHARE_CODE_VERSION: u8 = 1;
+
+/// version, level, position, velocity and a checksum
+const CODE_BYTES: usize = 1 + 2 + 4 * 4 + 1;
+
+/// URL-safe base64, with no padding.
+const ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// A shot small enough to paste into a chat: which level
+/// of the course it was taken on, and where and how fast
+/// the ball was launched. A code's shot is played with
+/// [`FixedSteps`], so it comes out the same at any frame
+/// rate. The live shot it was taken from followed the
+/// frame rate, so it can have played out a little
+/// differently.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShareCode {
+    /// the level's index in the `Course`
+    pub level: u16,
+    pub position: Vec2,
+    pub velocity: Vec2,
+}
+
+/// Why a share code couldn't be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShareError {
+    /// this character isn't part of any share code
+    InvalidCharacter(char),
+    /// the code decodes to this many bytes instead of the
+    /// ones a code has
+    WrongLength(usize),
+    /// the code was written in this format version, which
+    /// this build can't read
+    UnsupportedVersion(u8),
+    /// the code was mistyped or cut short somewhere
+    BadChecksum,
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShareError::InvalidCharacter(character) => {
+                write!(
+                    f,
+                    "{:?} can't be in a share code",
+                    character
+                )
+            }
+            ShareError::WrongLength(length) => write!(
+                f,
+                "the code holds {} bytes instead of {}",
+                length, CODE_BYTES
+            ),
+            ShareError::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "the code is version {}, this game \
+                     reads version {}",
+                    version, SHARE_CODE_VERSION
+                )
+            }
+            ShareError::BadChecksum => {
+                write!(f, "the code has been mistyped")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+impl ShareCode {
+    /// 27 characters of URL-safe base64.
+    pub fn encode(&self) -> String {
+        let mut bytes = Vec::with_capacity(CODE_BYTES);
+        bytes.push(SHARE_CODE_VERSION);
+        bytes.extend(self.level.to_le_bytes());
+        for value in [
+            self.position.x,
+            self.position.y,
+            self.velocity.x,
+            self.velocity.y,
+        ] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.push(checksum(&bytes));
+        encode_base64(&bytes)
+    }
+
+    /// Reads a code written by [`ShareCode::encode`].
+    /// Whitespace around it is ignored.
+    pub fn decode(code: &str) -> Result<Self, ShareError> {
+        let bytes = decode_base64(code.trim())?;
+        // the version goes first so a newer, longer code
+        // says so instead of just being the wrong length
+        match bytes.first() {
+            Some(&SHARE_CODE_VERSION) => {}
+            Some(version) => {
+                return Err(ShareError::UnsupportedVersion(
+                    *version,
+                ))
+            }
+            None => return Err(ShareError::WrongLength(0)),
+        }
+        if bytes.len() != CODE_BYTES {
+            return Err(ShareError::WrongLength(
+                bytes.len(),
+            ));
+        }
+        let (body, sum) = bytes.split_at(CODE_BYTES - 1);
+        if checksum(body) != sum[0] {
+            return Err(ShareError::BadChecksum);
+        }
+        let float = |at: usize| {
+            f32::from_le_bytes([
+                body[at],
+                body[at + 1],
+                body[at + 2],
+                body[at + 3],
+            ])
+        };
+        Ok(ShareCode {
+            level: u16::from_le_bytes([body[1], body[2]]),
+            position: Vec2::new(float(3), float(7)),
+            velocity: Vec2::new(float(11), float(15)),
+        })
+    }
+}
+
+/// Catches a mistyped or swapped character, which a plain
+/// sum wouldn't always.
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0u8, |sum, byte| sum.rotate_left(1) ^ byte)
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut code = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(
+            0u32,
+            |bits, (i, byte)| {
+                bits | ((*byte as u32) << (16 - 8 * i))
+            },
+        );
+        // n bytes take n + 1 characters
+        for i in 0..=chunk.len() {
+            let index = (bits >> (18 - 6 * i)) & 0x3f;
+            code.push(ALPHABET[index as usize] as char);
+        }
+    }
+    code
+}
+
+fn decode_base64(
+    code: &str,
+) -> Result<Vec<u8>, ShareError> {
+    let values = code
+        .chars()
+        .map(|character| {
+            ALPHABET
+                .iter()
+                .position(|c| *c as char == character)
+                .map(|value| value as u32)
+                .ok_or(ShareError::InvalidCharacter(
+                    character,
+                ))
+        })
+        .collect::<Result<Vec<u32>, _>>()?;
+    let mut bytes =
+        Vec::with_capacity(values.len() * 3 / 4);
+    for chunk in values.chunks(4) {
+        let bits = chunk.iter().enumerate().fold(
+            0u32,
+            |bits, (i, value)| {
+                bits | (value << (18 - 6 * i))
+            },
+        );
+        // n + 1 characters hold n bytes, and a lone
+        // character doesn't hold a whole one
+        for i in 0..chunk.len().saturating_sub(1) {
+            bytes.push((bits >> (16 - 8 * i)) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+/// The share code of the last shot taken, logged as each
+/// shot is launched. `F1` takes it again.
+#[derive(Default)]
+pub struct ShareCodes {
+    pub last: Option<ShareCode>,
+    /// a code's shot has been asked for and its ball
+    /// hasn't been launched yet
+    pending: bool,
+}
+
+/// Takes the shot a share code was made from, if it was
+/// made on the level being played.
+pub struct PlayShareCode(pub String);
+
+pub struct ShareCodePlugin;
+
+impl Plugin for ShareCodePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShareCodes>()
+            .add_event::<PlayShareCode>()
+            .add_system(record_share_codes)
+            .add_system(
+                replay_last_shot.with_run_criteria(playing),
+            )
+            .add_system(
+                play_share_codes
+                    .after(replay_last_shot)
+                    .before(apply_time_scale),
+            );
+    }
+}
+
+/// Practice balls don't count, so the code is always the
+/// last shot that mattered. A shot that didn't come from
+/// a code goes back to following the frame rate.
+#[allow(clippy::type_complexity)]
+fn record_share_codes(
+    course: Res<Course>,
+    mut share_codes: ResMut<ShareCodes>,
+    mut fixed_steps: ResMut<FixedSteps>,
+    balls: Query<
+        (&Transform, &Velocity),
+        (Added<GolfBall>, Without<Practice>),
+    >,
+) {
+    for (transform, velocity) in balls.iter() {
+        let code = ShareCode {
+            level: course.current as u16,
+            position: transform.translation.truncate(),
+            velocity: velocity.linvel,
+        };
+        info!("share code: {}", code.encode());
+        share_codes.last = Some(code);
+        if share_codes.pending {
+            share_codes.pending = false;
+        } else if fixed_steps.0 {
+            fixed_steps.0 = false;
+        }
+    }
+}
+
+/// Goes through the code rather than straight to the
+/// launch, so it plays exactly what someone pasting the
+/// code in would get.
+fn replay_last_shot(
+    keyboard: Res<Input<KeyCode>>,
+    share_codes: Res<ShareCodes>,
+    mut plays: EventWriter<PlayShareCode>,
+) {
+    if !keyboard.just_pressed(KeyCode::F1) {
+        return;
+    }
+    if let Some(code) = share_codes.last {
+        plays.send(PlayShareCode(code.encode()));
+    }
+}
+
+fn play_share_codes(
+    course: Res<Course>,
+    mut plays: EventReader<PlayShareCode>,
+    mut share_codes: ResMut<ShareCodes>,
+    mut fixed_steps: ResMut<FixedSteps>,
+    mut launches: EventWriter<LaunchShot>,
+) {
+    for PlayShareCode(code) in plays.iter() {
+        let code = match ShareCode::decode(code) {
+            Ok(code) => code,
+            Err(error) => {
+                warn!("share code: {}", error);
+                continue;
+            }
+        };
+        if code.level as usize != course.current {
+            warn!(
+                "share code: the shot was taken on level \
+                 {}, not this one",
+                code.level as usize + 1
+            );
+            continue;
+        }
+        share_codes.pending = true;
+        fixed_steps.0 = true;
+        launches.send(LaunchShot {
+            position: code.position.extend(0.0),
+            velocity: code.velocity,
+        });
+    }
+}
@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    course::{
+        BallLost, BallSunk, Course, LevelFailed,
+        ShotTimedOut, Strokes, Sunk,
+    },
+    golf_ball::GolfBall,
+    render::{spawn_poly_line, PolyLine},
+};
+
+/// Where each ball in flight has been so far, and the
+/// paths of finished shots drawn faintly behind the level
+/// so it's easy to see what's already been tried. A shot
+/// is finished when its ball sinks, times out or is lost.
+/// The paths are colored by stroke and cleared when the
+/// level changes or restarts.
+pub struct ShotPaths {
+    /// older paths are dropped past this many
+    pub max_paths: usize,
+    /// the path and stroke number of each ball in flight
+    in_flight: HashMap<Entity, (u32, Vec<Vec2>)>,
+    /// the lines drawn for finished shots, oldest first
+    drawn: VecDeque<Entity>,
+    /// the stroke number and path of the last finished
+    /// shot
+    last: Option<(u32, Vec<Vec2>)>,
+}
+
+impl Default for ShotPaths {
+    fn default() -> Self {
+        Self {
+            max_paths: 5,
+            in_flight: HashMap::new(),
+            drawn: VecDeque::new(),
+            last: None,
+        }
+    }
+}
+
+impl ShotPaths {
+    /// Where `ball` has been so far, while it's in flight.
+    pub fn path(&self, ball: Entity) -> Option<&[Vec2]> {
+        self.in_flight
+            .get(&ball)
+            .map(|(_, points)| points.as_slice())
+    }
+
+    /// The stroke number and path of the last shot to
+    /// finish.
+    pub fn last_finished(&self) -> Option<(u32, &[Vec2])> {
+        self.last.as_ref().map(|(stroke, points)| {
+            (*stroke, points.as_slice())
+        })
+    }
+
+    /// Forgets every path and despawns the lines drawn for
+    /// them.
+    pub fn clear(&mut self, commands: &mut Commands) {
+        for line in self.drawn.drain(..) {
+            commands.entity(line).despawn();
+        }
+        self.in_flight.clear();
+        self.last = None;
+    }
+}
+
+/// how far a ball moves before another point is recorded
+const POINT_SPACING: f32 = 5.0;
+const PATH_WIDTH: f32 = 2.0;
+const PATH_ALPHA: f32 = 0.25;
+
+/// A different color for each stroke, so consecutive
+/// shots are easy to tell apart.
+fn stroke_color(stroke: u32) -> Color {
+    Color::hsla(
+        (stroke as f32 * 67.0) % 360.0,
+        0.8,
+        0.6,
+        PATH_ALPHA,
+    )
+}
+
+pub struct ShotPathsPlugin;
+
+impl Plugin for ShotPathsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShotPaths>()
+            .add_system(record_shot_paths)
+            .add_system(
+                finish_shot_paths.after(record_shot_paths),
+            )
+            .add_system(
+                clear_shot_paths.after(finish_shot_paths),
+            );
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn record_shot_paths(
+    mut paths: ResMut<ShotPaths>,
+    strokes: Res<Strokes>,
+    balls: Query<
+        (Entity, &GlobalTransform),
+        (With<GolfBall>, Without<Sunk>),
+    >,
+) {
+    for (ball, transform) in balls.iter() {
+        let position = transform.translation().truncate();
+        // a ball is seen for the first time right after
+        // the stroke that launched it was counted
+        let (_, points) = paths
+            .in_flight
+            .entry(ball)
+            .or_insert_with(|| (strokes.0, Vec::new()));
+        if points.last().is_none_or(|last| {
+            last.distance(position) >= POINT_SPACING
+        }) {
+            points.push(position);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn finish_shot_paths(
+    mut commands: Commands,
+    mut paths: ResMut<ShotPaths>,
+    mut sunk: EventReader<BallSunk>,
+    mut timed_out: EventReader<ShotTimedOut>,
+    mut lost: EventReader<BallLost>,
+    balls: Query<(), (With<GolfBall>, Without<Sunk>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let finished: Vec<Entity> = sunk
+        .iter()
+        .map(|event| event.ball)
+        .chain(timed_out.iter().map(|event| event.ball))
+        .chain(lost.iter().map(|event| event.ball))
+        .collect();
+    for ball in finished {
+        let (stroke, points) =
+            match paths.in_flight.remove(&ball) {
+                Some(path) => path,
+                None => continue,
+            };
+        paths.last = Some((stroke, points.clone()));
+        let line = spawn_poly_line(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            PolyLine::new(
+                points,
+                PATH_WIDTH,
+                stroke_color(stroke),
+            ),
+            // behind the planets and balls
+            -1.0,
+        );
+        paths.drawn.push_back(line);
+        while paths.drawn.len() > paths.max_paths {
+            if let Some(oldest) = paths.drawn.pop_front() {
+                commands.entity(oldest).despawn();
+            }
+        }
+    }
+    // balls that went away some other way, like an undo,
+    // don't leave a path behind
+    paths.in_flight.retain(|ball, _| balls.contains(*ball));
+}
+
+fn clear_shot_paths(
+    mut commands: Commands,
+    mut paths: ResMut<ShotPaths>,
+    course: Res<Course>,
+    mut failed: EventReader<LevelFailed>,
+) {
+    // a failed level restarts without the course changing
+    if !course.is_changed()
+        && failed.iter().last().is_none()
+    {
+        return;
+    }
+    paths.clear(&mut commands);
+}
@@ -0,0 +1,99 @@
+use bevy::{
+    ecs::schedule::ShouldRun, input::InputSystem,
+    prelude::*,
+};
+use bevy_rapier2d::prelude::*;
+
+use crate::{menu::playing, GravitySystem};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimState {
+    Running,
+    Paused,
+}
+
+/// Set for exactly one frame when the user asks to
+/// advance a paused simulation.
+#[derive(Default)]
+pub struct SingleStep(pub bool);
+
+/// Whether the simulation advances this frame. It's
+/// worked out once per frame from the `SimState` and
+/// `SingleStep`, and it's the only thing rapier and the
+/// gravity systems look at, so the two halves of the
+/// physics can't disagree about being paused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PhysicsActive(pub bool);
+
+impl Default for PhysicsActive {
+    fn default() -> Self {
+        PhysicsActive(true)
+    }
+}
+
+pub struct SimStatePlugin;
+
+impl Plugin for SimStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SimState::Running)
+            .init_resource::<SingleStep>()
+            .init_resource::<PhysicsActive>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                sim_controls
+                    .with_run_criteria(playing)
+                    .after(InputSystem),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                sync_physics_active
+                    .after(sim_controls)
+                    .before(GravitySystem::Sync),
+            );
+    }
+}
+
+/// Run criteria for anything that advances the
+/// simulation, such as the gravity systems.
+pub fn sim_running(
+    active: Res<PhysicsActive>,
+) -> ShouldRun {
+    if active.0 {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+fn sim_controls(
+    keyboard: Res<Input<KeyCode>>,
+    mut state: ResMut<SimState>,
+    mut step: ResMut<SingleStep>,
+) {
+    step.0 = false;
+    if keyboard.just_pressed(KeyCode::Space) {
+        *state = match *state {
+            SimState::Running => SimState::Paused,
+            SimState::Paused => SimState::Running,
+        };
+    }
+    if keyboard.just_pressed(KeyCode::Period)
+        && *state == SimState::Paused
+    {
+        step.0 = true;
+    }
+}
+
+/// Works out `PhysicsActive` and hands it on to rapier.
+fn sync_physics_active(
+    state: Res<SimState>,
+    step: Res<SingleStep>,
+    mut active: ResMut<PhysicsActive>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    active.0 = *state == SimState::Running || step.0;
+    // rapier's variable timestep clamps each step to
+    // `max_dt`, so resuming after a long pause doesn't
+    // integrate the whole paused duration in one go.
+    rapier_config.physics_pipeline_active = active.0;
+}
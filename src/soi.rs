@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use particular::ParticleSet;
+use std::f32::consts::TAU;
+
+use crate::{
+    gravity::sphere_of_influence, menu::playing,
+    planet_spec::Planet, Body,
+};
+
+/// Whether each planet's sphere of influence is drawn,
+/// toggled with `I`. Inside the circle the planet is what
+/// a ball mostly orbits, which is handy for planning a
+/// slingshot from one planet to the next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShowSOI(pub bool);
+
+const SEGMENTS: usize = 64;
+const SOI_COLOR: Color = Color::rgba(0.4, 0.8, 1.0, 0.6);
+
+pub struct SoiPlugin;
+
+impl Plugin for SoiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShowSOI>()
+            .add_system(
+                toggle_soi.with_run_criteria(playing),
+            )
+            .add_system(draw_soi.after(toggle_soi));
+    }
+}
+
+fn toggle_soi(
+    keyboard: Res<Input<KeyCode>>,
+    mut show: ResMut<ShowSOI>,
+) {
+    if keyboard.just_pressed(KeyCode::I) {
+        show.0 = !show.0;
+    }
+}
+
+fn draw_soi(
+    mut lines: ResMut<DebugLines>,
+    show: Res<ShowSOI>,
+    particle_set: Res<ParticleSet<Body>>,
+    planets: Query<(), With<Planet>>,
+) {
+    if !show.0 {
+        return;
+    }
+    for body in particle_set.iter() {
+        if !planets.contains(body.entity) {
+            continue;
+        }
+        // the heaviest planet dominates everywhere, so it
+        // doesn't get a circle
+        let radius = match sphere_of_influence(
+            &particle_set,
+            body.position,
+            body.mu,
+        ) {
+            Some(radius) if radius > 0.0 => radius,
+            _ => continue,
+        };
+        let center = body.position.truncate();
+        let point = |i: usize| {
+            let angle = i as f32 / SEGMENTS as f32 * TAU;
+            (center
+                + Vec2::new(angle.cos(), angle.sin())
+                    * radius)
+                .extend(5.0)
+        };
+        for i in 0..SEGMENTS {
+            lines.line_colored(
+                point(i),
+                point(i + 1),
+                0.0,
+                SOI_COLOR,
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+
+use crate::{
+    golf_ball::GolfBallSettings,
+    gravity::{GravitySource, G},
+    level::Level,
+    prediction::{
+        closest_approach, find_shot, ClosestApproach,
+        Obstacle, PredictionSettings, ShotSearch,
+    },
+};
+
+/// A launch from a level's tee that sinks one of its
+/// holes.
+#[derive(Clone, Copy, Debug)]
+pub struct LaunchSolution {
+    pub velocity: Vec2,
+    /// which of `Level::holes` it sinks
+    pub hole: usize,
+    /// where the shot passes over the hole
+    pub approach: ClosestApproach,
+}
+
+/// The ball solutions are looked for with: a default one,
+/// followed for ten seconds of flight, long enough to go
+/// round a planet or two.
+pub fn solve_ball() -> GolfBallSettings {
+    GolfBallSettings {
+        prediction_steps: 600,
+        ..default()
+    }
+}
+
+/// Looks for a launch from the tee that sinks hole `hole`
+/// of `level`, trying every shot in `search` with
+/// [`solve_ball`]. Gravity is the level's own, and planets
+/// the level's balls are phased through don't get in the
+/// way. Planets are taken to stay where they start, so a
+/// level with moving planets can be solved here and still
+/// be missed in game.
+///
+/// `None` if the level has no tee or no such hole, or
+/// nothing in `search` sinks it before its time limit.
+pub fn solve_hole(
+    level: &Level,
+    hole: usize,
+    search: &ShotSearch,
+) -> Option<LaunchSolution> {
+    let tee = level.tee?;
+    let (target, sized) = level.sized_holes().nth(hole)?;
+    let g = level.g.unwrap_or(G);
+    let sources: Vec<GravitySource> = level
+        .planets
+        .iter()
+        .map(|planet| GravitySource {
+            position: planet.position.truncate(),
+            mu: planet.mass * g,
+        })
+        .collect();
+    let obstacles: Vec<Obstacle> = level
+        .planets
+        .iter()
+        .filter(|planet| {
+            planet.phase.is_empty()
+                || planet.phase.0 & !level.ball_phase.0 != 0
+        })
+        .map(|planet| Obstacle {
+            center: planet.position.truncate(),
+            radius: planet.radius(),
+            shape: None,
+        })
+        .collect();
+    let ball = solve_ball();
+    let settings = PredictionSettings::default();
+
+    let velocity = find_shot(
+        &sources,
+        &obstacles,
+        tee,
+        target,
+        sized.radius,
+        &ball,
+        &settings,
+        search,
+    )?;
+    Some(LaunchSolution {
+        velocity,
+        hole,
+        approach: closest_approach(
+            &sources, &obstacles, tee, velocity, &ball,
+            &settings, target,
+        ),
+    })
+}
+
+/// The first of `level`'s holes [`solve_hole`] finds a
+/// launch for, trying them in order. Each hole gets the
+/// whole of `search`'s time limit.
+pub fn solve_level(
+    level: &Level,
+    search: &ShotSearch,
+) -> Option<LaunchSolution> {
+    (0..level.holes.len())
+        .find_map(|hole| solve_hole(level, hole, search))
+}
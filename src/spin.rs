@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    golf_ball::GolfBall, sim_state::sim_running,
+    GravitySystem,
+};
+
+/// Rotates a body around `axis` at `rate` radians per
+/// second.
+#[derive(
+    Component,
+    Reflect,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[reflect(Component)]
+pub struct Spin {
+    pub axis: Vec3,
+    pub rate: f32,
+}
+
+pub struct SpinPlugin;
+
+impl Plugin for SpinPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Spin>().add_system(spin);
+    }
+}
+
+fn spin(
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &Spin)>,
+) {
+    for (mut transform, spin) in query.iter_mut() {
+        transform.rotate(Quat::from_axis_angle(
+            spin.axis.normalize(),
+            spin.rate * time.delta_seconds(),
+        ));
+    }
+}
+
+/// A weak tangential pull on balls close to a spinning
+/// planet, hinting at the planet's rotation. Spin is
+/// purely cosmetic unless this is enabled.
+pub struct FrameDragging {
+    pub enabled: bool,
+    pub strength: f32,
+    /// distance from the planet's center past which
+    /// there is no dragging at all
+    pub range: f32,
+}
+
+impl Default for FrameDragging {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strength: 500.0,
+            range: 250.0,
+        }
+    }
+}
+
+pub struct FrameDraggingPlugin;
+
+impl Plugin for FrameDraggingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameDragging>().add_system(
+            frame_dragging
+                .with_run_criteria(sim_running)
+                .after(GravitySystem::Accelerate),
+        );
+    }
+}
+
+fn frame_dragging(
+    settings: Res<FrameDragging>,
+    planets: Query<(&GlobalTransform, &Spin)>,
+    mut balls: Query<
+        (&GlobalTransform, &mut ExternalForce),
+        With<GolfBall>,
+    >,
+) {
+    if !settings.enabled {
+        return;
+    }
+    for (ball_transform, mut force) in balls.iter_mut() {
+        let position =
+            ball_transform.translation().truncate();
+        for (transform, spin) in planets.iter() {
+            let offset = position
+                - transform.translation().truncate();
+            let distance = offset.length();
+            if distance >= settings.range || distance == 0.0
+            {
+                continue;
+            }
+            // only rotation around z shows up in 2d
+            let angular_velocity =
+                spin.axis.normalize().z * spin.rate;
+            let falloff = 1.0 - distance / settings.range;
+            force.force += offset.perp() / distance
+                * angular_velocity
+                * settings.strength
+                * falloff;
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::*, render::camera::Viewport,
+};
+use bevy_mouse_tracking_plugin::{
+    ExcludeMouseTracking, MainCamera,
+};
+use bevy_prototype_debug_lines::DebugLines;
+
+use crate::{
+    course::{Bounces, Course, ShotTimer, Strokes},
+    golf_ball::{
+        spawn_ball, BallAssets, BallPalette, GolfBall,
+        GolfBallSettings,
+    },
+    launch::LaunchCharge,
+    menu::playing,
+    practice::Practice,
+    undo::ShotHistory,
+};
+
+/// Which of the two local players a ball belongs to.
+/// Player one shoots with the mouse, and anything else
+/// that launches a ball shoots for them too; player two
+/// shoots with a gamepad.
+#[derive(
+    Component, Clone, Copy, Debug, PartialEq, Eq, Default,
+)]
+pub enum Player {
+    #[default]
+    One,
+    Two,
+}
+
+impl Player {
+    pub fn other(self) -> Self {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+}
+
+/// Who gets to shoot when, switched with `Shift+F12`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TurnOrder {
+    /// each shot hands the turn to the other player
+    #[default]
+    Alternate,
+    /// both players shoot whenever they like
+    Simultaneous,
+}
+
+/// Two player local golf, toggled with `F12`. The window
+/// is split down the middle: player one aims with the
+/// mouse on the left, where `MousePosWorld` still lines up
+/// since that half keeps the window's left edge and full
+/// height. Player two aims from the tee with a gamepad's
+/// left stick on the right, and shoots with its south
+/// button. Each half follows its own player's latest ball.
+#[derive(Default)]
+pub struct SplitScreen {
+    pub enabled: bool,
+    pub turns: TurnOrder,
+    /// whose shot it is with `TurnOrder::Alternate`
+    pub turn: Player,
+}
+
+impl SplitScreen {
+    /// Whether `player` may take a shot right now. With
+    /// the screen whole, only player one is playing.
+    pub fn can_shoot(&self, player: Player) -> bool {
+        match (self.enabled, self.turns) {
+            (false, _) => player == Player::One,
+            (true, TurnOrder::Simultaneous) => true,
+            (true, TurnOrder::Alternate) => {
+                self.turn == player
+            }
+        }
+    }
+}
+
+/// The camera drawing player two's half of the window.
+#[derive(Component)]
+pub struct PlayerTwoCamera;
+
+/// stick deflection below this doesn't aim
+const DEADZONE: f32 = 0.2;
+/// world units of aim line per unit of launch speed
+const AIM_LINE_SCALE: f32 = 0.25;
+const AIM_COLOR: Color = Color::CYAN;
+
+pub struct SplitScreenPlugin;
+
+impl Plugin for SplitScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SplitScreen>()
+            .add_system(
+                toggle_split_screen
+                    .with_run_criteria(playing),
+            )
+            .add_system(
+                arrange_cameras.after(toggle_split_screen),
+            )
+            .add_system(
+                gamepad_shot.after(toggle_split_screen),
+            )
+            .add_system(assign_players)
+            .add_system(
+                follow_player_two.after(assign_players),
+            );
+    }
+}
+
+fn toggle_split_screen(
+    keyboard: Res<Input<KeyCode>>,
+    mut split: ResMut<SplitScreen>,
+) {
+    if !keyboard.just_pressed(KeyCode::F12) {
+        return;
+    }
+    if keyboard
+        .any_pressed([KeyCode::LShift, KeyCode::RShift])
+    {
+        split.turns = match split.turns {
+            TurnOrder::Alternate => TurnOrder::Simultaneous,
+            TurnOrder::Simultaneous => TurnOrder::Alternate,
+        };
+    } else {
+        split.enabled = !split.enabled;
+        split.turn = Player::One;
+    }
+}
+
+/// Splits the window between the two cameras, spawning
+/// player two's when the split is turned on and removing
+/// it again when it's turned off. Run every frame so the
+/// halves follow the window as it's resized.
+#[allow(clippy::type_complexity)]
+fn arrange_cameras(
+    mut commands: Commands,
+    split: Res<SplitScreen>,
+    windows: Res<Windows>,
+    mut main: Query<
+        (&mut Camera, &Transform, &OrthographicProjection),
+        (With<MainCamera>, Without<PlayerTwoCamera>),
+    >,
+    mut second: Query<
+        (Entity, &mut Camera),
+        (With<PlayerTwoCamera>, Without<MainCamera>),
+    >,
+) {
+    let (mut main, transform, projection) =
+        match main.get_single_mut() {
+            Ok(main) => main,
+            Err(_) => return,
+        };
+    let size = windows.get_primary().map(|window| {
+        UVec2::new(
+            window.physical_width(),
+            window.physical_height(),
+        )
+    });
+    let size = match size {
+        Some(size)
+            if split.enabled
+                && size.x > 1
+                && size.y > 0 =>
+        {
+            size
+        }
+        _ => {
+            if main.viewport.is_some() {
+                main.viewport = None;
+            }
+            for (entity, _) in second.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            return;
+        }
+    };
+    let left = Viewport {
+        physical_position: UVec2::ZERO,
+        physical_size: UVec2::new(size.x / 2, size.y),
+        ..default()
+    };
+    let right = Viewport {
+        physical_position: UVec2::new(size.x / 2, 0),
+        physical_size: UVec2::new(
+            size.x - size.x / 2,
+            size.y,
+        ),
+        ..default()
+    };
+    // only touched when they change, so the cameras
+    // aren't marked as changed every frame
+    let fits = |viewport: &Option<Viewport>,
+                wanted: &Viewport| {
+        viewport.as_ref().is_some_and(|viewport| {
+            viewport.physical_position
+                == wanted.physical_position
+                && viewport.physical_size
+                    == wanted.physical_size
+        })
+    };
+    if !fits(&main.viewport, &left) {
+        main.viewport = Some(left);
+    }
+    match second.get_single_mut() {
+        Ok((_, mut camera)) => {
+            if !fits(&camera.viewport, &right) {
+                camera.viewport = Some(right);
+            }
+        }
+        Err(_) => {
+            let mut camera = Camera2dBundle::default();
+            camera.camera.viewport = Some(right);
+            // after player one's, which has already cleared
+            // the whole window
+            camera.camera.priority = 1;
+            camera.camera_2d.clear_color =
+                ClearColorConfig::None;
+            camera.transform = *transform;
+            camera.projection.scale = projection.scale;
+            commands
+                .spawn_bundle(camera)
+                .insert(PlayerTwoCamera)
+                .insert(UiCameraConfig { show_ui: false })
+                // the mouse is player one's
+                .insert(ExcludeMouseTracking);
+        }
+    }
+}
+
+/// Player two aims from the tee with the first gamepad's
+/// left stick: the ball goes the way the stick points, as
+/// fast as it's pushed, up to `LaunchCharge::max_power`.
+#[allow(clippy::too_many_arguments)]
+fn gamepad_shot(
+    mut commands: Commands,
+    split: Res<SplitScreen>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<Input<GamepadButton>>,
+    course: Res<Course>,
+    charge: Res<LaunchCharge>,
+    settings: Res<GolfBallSettings>,
+    mut palette: ResMut<BallPalette>,
+    mut strokes: ResMut<Strokes>,
+    mut history: ResMut<ShotHistory>,
+    mut lines: ResMut<DebugLines>,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if !split.can_shoot(Player::Two) {
+        return;
+    }
+    let gamepad = match gamepads.iter().next() {
+        Some(gamepad) => *gamepad,
+        None => return,
+    };
+    let tee = match course
+        .current_level()
+        .and_then(|level| level.tee)
+    {
+        Some(tee) => tee,
+        None => return,
+    };
+    let axis = |axis_type| {
+        axes.get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or(0.0)
+    };
+    let stick = Vec2::new(
+        axis(GamepadAxisType::LeftStickX),
+        axis(GamepadAxisType::LeftStickY),
+    );
+    if stick.length() < DEADZONE {
+        return;
+    }
+    let velocity =
+        stick.clamp_length_max(1.0) * charge.max_power;
+    lines.line_colored(
+        tee.extend(5.0),
+        (tee + velocity * AIM_LINE_SCALE).extend(5.0),
+        0.0,
+        AIM_COLOR,
+    );
+    if !buttons.just_pressed(GamepadButton::new(
+        gamepad,
+        GamepadButtonType::South,
+    )) {
+        return;
+    }
+    let entity = spawn_ball(
+        &mut commands,
+        &mut ball_assets,
+        &mut meshes,
+        &mut materials,
+        tee.extend(0.0),
+        velocity,
+        palette.next_color(),
+        &settings,
+    );
+    commands
+        .entity(entity)
+        .insert(Player::Two)
+        .insert(ShotTimer::new(settings.max_shot_time))
+        .insert(Bounces::default());
+    strokes.0 += 1;
+    history.push(entity);
+}
+
+/// Balls nobody has claimed are player one's. A shot
+/// hands the turn over, practice shots aside.
+#[allow(clippy::type_complexity)]
+fn assign_players(
+    mut commands: Commands,
+    mut split: ResMut<SplitScreen>,
+    added: Query<
+        (Entity, Option<&Player>, Option<&Practice>),
+        Added<GolfBall>,
+    >,
+) {
+    for (ball, player, practice) in added.iter() {
+        let player = match player {
+            Some(player) => *player,
+            None => {
+                commands.entity(ball).insert(Player::One);
+                Player::One
+            }
+        };
+        if split.enabled
+            && split.turns == TurnOrder::Alternate
+            && practice.is_none()
+        {
+            split.turn = player.other();
+        }
+    }
+}
+
+/// Player one's half follows `CameraFocus` as the whole
+/// screen does; player two's follows their latest ball
+/// still in flight, and stays put once it's down.
+fn follow_player_two(
+    history: Res<ShotHistory>,
+    balls: Query<(&GlobalTransform, &Player)>,
+    mut cameras: Query<
+        &mut Transform,
+        With<PlayerTwoCamera>,
+    >,
+) {
+    let position = history.iter().rev().find_map(|ball| {
+        balls
+            .get(ball)
+            .ok()
+            .filter(|(_, player)| **player == Player::Two)
+            .map(|(transform, _)| transform.translation())
+    });
+    let position = match position {
+        Some(position) => position,
+        None => return,
+    };
+    for mut transform in cameras.iter_mut() {
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
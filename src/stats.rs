@@ -0,0 +1,272 @@
+use bevy::{
+    diagnostic::{
+        DiagnosticId, Diagnostics,
+        FrameTimeDiagnosticsPlugin,
+    },
+    prelude::*,
+};
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+
+use crate::{
+    energy::EnergyDiagnosticPlugin,
+    gravity::{GravityMode, GravitySettings},
+    gravity_budget::GravityBudget,
+    menu::playing,
+    Body,
+};
+
+/// A dashboard of simulation stats in the top right
+/// corner, toggled with `F3`: frame rate, body count, how
+/// long gravity took, kinetic energy, total momentum,
+/// the center of mass and the `GravityMode`.
+///
+/// `F4` logs the whole `ParticleSet`, see
+/// [`particle_set_table`].
+///
+/// The frame rate and energy come from `Diagnostics`, so
+/// they only show up alongside `FrameTimeDiagnosticsPlugin`
+/// and `EnergyDiagnosticPlugin`.
+pub struct StatsOverlayPlugin;
+
+#[derive(Component)]
+struct StatsText;
+
+impl Plugin for StatsOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_stats_overlay)
+            .add_system(
+                toggle_stats_overlay
+                    .with_run_criteria(playing),
+            )
+            .add_system(
+                dump_particle_set
+                    .with_run_criteria(playing),
+            )
+            .add_system(
+                update_stats_overlay
+                    .after(toggle_stats_overlay),
+            );
+    }
+}
+
+/// The total linear momentum of a set of bodies and where
+/// their center of mass is. With nothing but their own
+/// gravity acting on them neither should change, so a jump
+/// in either points at a bug.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Momentum {
+    pub linear: Vec2,
+    /// `None` if none of the bodies have any mass
+    pub center_of_mass: Option<Vec2>,
+}
+
+/// Adds up `bodies`, given as position, mass and velocity.
+pub fn momentum(bodies: &[(Vec2, f32, Vec2)]) -> Momentum {
+    let mut linear = Vec2::ZERO;
+    let mut weighted = Vec2::ZERO;
+    let mut total_mass = 0.0;
+    for (position, mass, velocity) in bodies.iter() {
+        linear += *velocity * *mass;
+        weighted += *position * *mass;
+        total_mass += mass;
+    }
+    Momentum {
+        linear,
+        center_of_mass: (total_mass > 0.0)
+            .then(|| weighted / total_mass),
+    }
+}
+
+fn spawn_stats_overlay(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: asset_server
+                        .load("fonts/DejaVuSansMono.ttf"),
+                    font_size: 16.0,
+                    color: Color::YELLOW_GREEN,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(Visibility { is_visible: false })
+        .insert(StatsText);
+}
+
+/// The energy is only measured while the overlay is up.
+fn toggle_stats_overlay(
+    keyboard: Res<Input<KeyCode>>,
+    mut diagnostics: ResMut<Diagnostics>,
+    mut overlays: Query<&mut Visibility, With<StatsText>>,
+) {
+    if keyboard.just_pressed(KeyCode::F3) {
+        for mut visibility in overlays.iter_mut() {
+            visibility.is_visible = !visibility.is_visible;
+            EnergyDiagnosticPlugin::enable(
+                &mut diagnostics,
+                visibility.is_visible,
+            );
+        }
+    }
+}
+
+/// The `ParticleSet` as a table, one row per body: what
+/// goes into the gravity solve, and the acceleration that
+/// comes out of it. It's solved again to get those, so
+/// keep it for debugging.
+pub fn particle_set_table(
+    particle_set: &mut ParticleSet<Body>,
+) -> String {
+    let mut table = format!(
+        "{:<12} {:>24} {:>12} {:>24}\n",
+        "entity", "position", "mu", "acceleration"
+    );
+    for (body, result) in particle_set.result() {
+        let entity = format!("{:?}", body.entity);
+        let position = format!(
+            "({:.2}, {:.2})",
+            body.position.x, body.position.y
+        );
+        let acceleration =
+            format!("({:.4}, {:.4})", result.x, result.y);
+        table.push_str(&format!(
+            "{:<12} {:>24} {:>12.4e} {:>24}\n",
+            entity, position, body.mu, acceleration
+        ));
+    }
+    table
+}
+
+/// Logs the `ParticleSet` on `F4`, for telling a body
+/// synced wrong from gravity worked out wrong.
+fn dump_particle_set(
+    keyboard: Res<Input<KeyCode>>,
+    mut particle_set: ResMut<ParticleSet<Body>>,
+) {
+    if keyboard.just_pressed(KeyCode::F4) {
+        info!(
+            "particle set, {} bodies:\n{}",
+            particle_set.iter().count(),
+            particle_set_table(&mut particle_set)
+        );
+    }
+}
+
+/// The smoothed value of a diagnostic to `precision`
+/// decimal places, or a dash if it isn't being measured.
+fn diagnostic(
+    diagnostics: &Diagnostics,
+    id: DiagnosticId,
+    precision: usize,
+) -> String {
+    diagnostics
+        .get(id)
+        .and_then(|diagnostic| diagnostic.average())
+        .map_or("-".to_string(), |value| {
+            format!("{:.*}", precision, value)
+        })
+}
+
+fn update_stats_overlay(
+    diagnostics: Res<Diagnostics>,
+    particle_set: Res<ParticleSet<Body>>,
+    gravity_budget: Res<GravityBudget>,
+    gravity_mode: Res<GravityMode>,
+    gravity_settings: Res<GravitySettings>,
+    bodies: Query<(
+        Option<&Velocity>,
+        Option<&ColliderMassProperties>,
+    )>,
+    mut overlays: Query<
+        (&mut Text, &Visibility),
+        With<StatsText>,
+    >,
+) {
+    for (mut text, visibility) in overlays.iter_mut() {
+        if !visibility.is_visible {
+            continue;
+        }
+        // masses worked out the same way as for the energy
+        let state: Vec<(Vec2, f32, Vec2)> = particle_set
+            .iter()
+            .map(|body| {
+                let (velocity, mass_props) = bodies
+                    .get(body.entity)
+                    .unwrap_or((None, None));
+                let mass = match mass_props {
+                    Some(ColliderMassProperties::Mass(
+                        mass,
+                    )) => *mass,
+                    _ => body.mu / gravity_settings.g(),
+                };
+                (
+                    body.position.truncate(),
+                    mass,
+                    velocity
+                        .map_or(Vec2::ZERO, |velocity| {
+                            velocity.linvel
+                        }),
+                )
+            })
+            .collect();
+        let momentum = momentum(&state);
+        let lines = [
+            format!(
+                "fps      {}",
+                diagnostic(
+                    &diagnostics,
+                    FrameTimeDiagnosticsPlugin::FPS,
+                    0
+                )
+            ),
+            format!(
+                "bodies   {}",
+                particle_set.iter().count()
+            ),
+            format!(
+                "gravity  {:.3} ms",
+                gravity_budget.last_solve.as_secs_f64()
+                    * 1000.0
+            ),
+            format!(
+                "kinetic  {}",
+                diagnostic(
+                    &diagnostics,
+                    EnergyDiagnosticPlugin::KINETIC,
+                    0
+                )
+            ),
+            format!(
+                "momentum ({:.0}, {:.0}) |{:.0}|",
+                momentum.linear.x,
+                momentum.linear.y,
+                momentum.linear.length()
+            ),
+            momentum.center_of_mass.map_or(
+                "com      -".to_string(),
+                |center| {
+                    format!(
+                        "com      ({:.1}, {:.1})",
+                        center.x, center.y
+                    )
+                },
+            ),
+            format!("mode     {:?}", *gravity_mode),
+        ];
+        text.sections[0].value = lines.join("\n");
+    }
+}
@@ -0,0 +1,130 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+
+use crate::{
+    gravity::dominant_body, menu::playing,
+    planet_spec::Planet, Body,
+};
+
+/// Starts shots on the ground, for putting. With it on, a
+/// shot pressed near a planet starts resting on the
+/// surface of the planet pulling hardest there, right
+/// below where it was pressed. Toggled with `F8`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SurfaceSnap(pub bool);
+
+/// Presses further than this from the surface are in open
+/// space and left where they are.
+pub const SNAP_DISTANCE: f32 = 60.0;
+
+/// How far off the surface a snapped ball starts, so it
+/// doesn't begin the shot already touching.
+const CLEARANCE: f32 = 0.5;
+
+/// Everything `place_body` needs to snap a shot to the
+/// ground.
+#[derive(SystemParam)]
+pub struct SurfaceSnapper<'w, 's> {
+    snap: Res<'w, SurfaceSnap>,
+    particle_set: Res<'w, ParticleSet<Body>>,
+    planets: Query<
+        'w,
+        's,
+        (&'static GlobalTransform, &'static Collider),
+        With<Planet>,
+    >,
+}
+
+impl<'w, 's> SurfaceSnapper<'w, 's> {
+    /// Where a ball of `ball_radius` pressed at `point`
+    /// starts: `point` itself unless `SurfaceSnap` is on
+    /// and there's a surface to snap it to.
+    pub fn snap(
+        &self,
+        point: Vec3,
+        ball_radius: f32,
+    ) -> Vec3 {
+        if !self.snap.0 {
+            return point;
+        }
+        let planet = match dominant_body(
+            &self.particle_set,
+            point,
+        ) {
+            Some(planet) => planet,
+            None => return point,
+        };
+        let (transform, collider) =
+            match self.planets.get(planet) {
+                Ok(planet) => planet,
+                Err(_) => return point,
+            };
+        snap_to_surface(
+            transform,
+            collider,
+            point.truncate(),
+            ball_radius,
+        )
+        .map_or(point, |snapped| snapped.extend(point.z))
+    }
+}
+
+/// Where a ball of `ball_radius` rests on the outside of
+/// `collider`, as close to `point` as it can get. `None`
+/// when `point` is more than [`SNAP_DISTANCE`] from the
+/// surface.
+pub fn snap_to_surface(
+    transform: &GlobalTransform,
+    collider: &Collider,
+    point: Vec2,
+    ball_radius: f32,
+) -> Option<Vec2> {
+    let (_, rotation, translation) =
+        transform.to_scale_rotation_translation();
+    let projection = collider.project_point(
+        translation.truncate(),
+        rotation.to_euler(EulerRot::XYZ).2,
+        point,
+        false,
+    );
+    let offset = point - projection.point;
+    if offset.length() > SNAP_DISTANCE {
+        return None;
+    }
+    // out of the surface, whichever side of it the press
+    // was on
+    let outward = if projection.is_inside {
+        -offset
+    } else {
+        offset
+    }
+    .try_normalize()
+    .unwrap_or_else(|| {
+        (projection.point - translation.truncate())
+            .normalize_or_zero()
+    });
+    Some(
+        projection.point
+            + outward * (ball_radius + CLEARANCE),
+    )
+}
+
+pub struct SurfaceSnapPlugin;
+
+impl Plugin for SurfaceSnapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SurfaceSnap>().add_system(
+            toggle_surface_snap.with_run_criteria(playing),
+        );
+    }
+}
+
+fn toggle_surface_snap(
+    keyboard: Res<Input<KeyCode>>,
+    mut snap: ResMut<SurfaceSnap>,
+) {
+    if keyboard.just_pressed(KeyCode::F8) {
+        snap.0 = !snap.0;
+    }
+}
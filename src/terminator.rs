@@ -0,0 +1,203 @@
+use bevy::{
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        mesh::VertexAttributeValues,
+        render_resource::{AsBindGroup, ShaderRef},
+    },
+    sprite::{
+        Material2d, Material2dPlugin, MaterialMesh2dBundle,
+        Mesh2dHandle,
+    },
+};
+
+use crate::planet_spec::Planet;
+
+/// Where the light falling on planets comes from, in the
+/// plane of the course. With it set every planet gets a
+/// lit side facing it and a shadowed side facing away,
+/// which makes the flat discs read as spheres. Without it
+/// planets are lit evenly, which is the default.
+#[derive(Clone, Copy, Debug)]
+pub struct SunDirection(pub Vec2);
+
+/// How dark the night side of a planet gets.
+const SHADOW: Color = Color::rgba(0.0, 0.0, 0.05, 0.65);
+
+/// Darkens the half of the mesh facing away from `sun`.
+/// The mesh's UVs hold each vertex's position scaled so
+/// the planet's edge is 1.0 away from its center.
+#[derive(AsBindGroup, TypeUuid, Debug, Clone)]
+#[uuid = "c2d54f6b-8a3e-4f1d-b0a7-6e9f13d2c845"]
+pub struct TerminatorMaterial {
+    #[uniform(0)]
+    pub shadow: Color,
+    /// in the planet's own frame, so it stays put as the
+    /// planet spins
+    #[uniform(0)]
+    pub sun: Vec2,
+}
+
+impl Material2d for TerminatorMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/terminator_material.wgsl".into()
+    }
+}
+
+/// The shadow drawn over a planet, a child of it so it
+/// follows the planet around.
+#[derive(Component)]
+pub struct Terminator {
+    overlay: Entity,
+    material: Handle<TerminatorMaterial>,
+}
+
+pub struct TerminatorPlugin;
+
+impl Plugin for TerminatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(Material2dPlugin::<
+            TerminatorMaterial,
+        >::default())
+            .add_system(remove_terminators)
+            .add_system(add_terminators)
+            .add_system(
+                aim_terminators.after(add_terminators),
+            );
+    }
+}
+
+/// The planet's own mesh with the color taken out and
+/// its UVs replaced by where each vertex sits on the
+/// planet.
+fn overlay_mesh(planet: &Mesh) -> Option<Mesh> {
+    let positions =
+        match planet.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(
+                positions,
+            )) => positions.clone(),
+            _ => return None,
+        };
+    let radius = positions
+        .iter()
+        .map(|position| {
+            Vec2::new(position[0], position[1]).length()
+        })
+        .fold(0.0, f32::max);
+    if radius <= 0.0 {
+        return None;
+    }
+    let uvs: Vec<[f32; 2]> = positions
+        .iter()
+        .map(|position| {
+            [position[0] / radius, position[1] / radius]
+        })
+        .collect();
+    let mut mesh = planet.clone();
+    mesh.remove_attribute(Mesh::ATTRIBUTE_COLOR);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    Some(mesh)
+}
+
+/// Also rebuilds the shadow of a planet whose mesh has
+/// been swapped out, like when its density changes.
+#[allow(clippy::type_complexity)]
+fn add_terminators(
+    mut commands: Commands,
+    sun: Option<Res<SunDirection>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<TerminatorMaterial>>,
+    planets: Query<
+        (Entity, &Mesh2dHandle, Option<&Terminator>),
+        (
+            With<Planet>,
+            Or<(
+                Without<Terminator>,
+                Changed<Mesh2dHandle>,
+            )>,
+        ),
+    >,
+) {
+    if sun.is_none() {
+        return;
+    }
+    for (planet, mesh, terminator) in planets.iter() {
+        let mesh = match meshes
+            .get(&mesh.0)
+            .and_then(overlay_mesh)
+        {
+            Some(mesh) => mesh,
+            None => continue,
+        };
+        if let Some(terminator) = terminator {
+            commands
+                .entity(terminator.overlay)
+                .despawn_recursive();
+        }
+        // aimed properly by `aim_terminators`
+        let material = materials.add(TerminatorMaterial {
+            shadow: SHADOW,
+            sun: Vec2::ZERO,
+        });
+        let overlay = commands
+            .spawn_bundle(MaterialMesh2dBundle {
+                mesh: meshes.add(mesh).into(),
+                material: material.clone(),
+                // just in front of the planet
+                transform: Transform::from_xyz(
+                    0.0, 0.0, 0.1,
+                ),
+                ..default()
+            })
+            .id();
+        commands
+            .entity(planet)
+            .add_child(overlay)
+            .insert(Terminator { overlay, material });
+    }
+}
+
+fn remove_terminators(
+    mut commands: Commands,
+    sun: Option<Res<SunDirection>>,
+    planets: Query<(Entity, &Terminator)>,
+) {
+    if sun.is_some() {
+        return;
+    }
+    for (planet, terminator) in planets.iter() {
+        commands
+            .entity(terminator.overlay)
+            .despawn_recursive();
+        commands.entity(planet).remove::<Terminator>();
+    }
+}
+
+fn aim_terminators(
+    sun: Option<Res<SunDirection>>,
+    mut materials: ResMut<Assets<TerminatorMaterial>>,
+    planets: Query<(&GlobalTransform, &Terminator)>,
+) {
+    let sun = match sun {
+        Some(sun) => sun.0.normalize_or_zero(),
+        None => return,
+    };
+    for (transform, terminator) in planets.iter() {
+        let (_, rotation, _) =
+            transform.to_scale_rotation_translation();
+        let local = (rotation.inverse() * sun.extend(0.0))
+            .truncate();
+        let current =
+            match materials.get(&terminator.material) {
+                Some(material) => material.sun,
+                None => continue,
+            };
+        if current.distance_squared(local) > 1e-6 {
+            if let Some(material) =
+                materials.get_mut(&terminator.material)
+            {
+                material.sun = local;
+            }
+        }
+    }
+}
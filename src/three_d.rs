@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use particular::ParticleSet;
+
+use crate::{
+    crater::CraterPlugin,
+    golf_ball::{GolfBall, GolfBallSettings, PointMass},
+    gravity::{GravityMode, GravitySettings},
+    planet::{Planet3dMaterial, PlanetMesh},
+    planet_spec::Planet,
+    self_gravity::SelfGravityPlugin,
+    sync_particle_set, Body, GravitySystem,
+};
+
+/// Space golf in full 3D. The cubesphere planets from
+/// `PlanetPlugin` become gravity sources and rapier3d
+/// handles the collisions. It shares `PointMass`, `Body`
+/// and `sync_particle_set` with the 2D game; only the
+/// acceleration step differs, because it keeps the z
+/// component instead of truncating it away.
+///
+/// Planets stay put unless they're given `Orbiting`, see
+/// `SelfGravityPlugin`.
+pub struct ThreeDPlugin;
+
+impl Plugin for ThreeDPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ParticleSet::<Body>::new())
+            .init_resource::<GravityMode>()
+            .init_resource::<GravitySettings>()
+            .add_plugin(
+                MaterialPlugin::<Planet3dMaterial>::default(),
+            )
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+            .add_plugin(CraterPlugin)
+            .add_plugin(SelfGravityPlugin)
+            .add_startup_system(disable_rapier_gravity)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                sync_particle_set.label(GravitySystem::Sync),
+            )
+            .add_system(
+                accelerate_particles
+                    .label(GravitySystem::Accelerate),
+            );
+    }
+}
+
+fn disable_rapier_gravity(
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    rapier_config.gravity = Vec3::ZERO;
+}
+
+fn accelerate_particles(
+    mut particle_set: ResMut<ParticleSet<Body>>,
+    mut query: Query<&mut ExternalForce, With<PointMass>>,
+) {
+    for (body, gravity) in particle_set.result() {
+        if let Ok(mut acceleration) =
+            query.get_mut(body.entity)
+        {
+            acceleration.force = gravity;
+        }
+    }
+}
+
+pub fn spawn_planet_3d(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<Planet3dMaterial>,
+    position: Vec3,
+    radius: f32,
+    mass: f32,
+    color: Color,
+) -> Entity {
+    commands
+        .spawn_bundle(MaterialMeshBundle {
+            // every planet gets its own mesh so craters
+            // only dent the planet that was hit
+            mesh: meshes.add(Mesh::from(PlanetMesh {
+                resolution: 20,
+            })),
+            transform: Transform::from_translation(
+                position,
+            )
+            .with_scale(Vec3::splat(radius)),
+            material: materials
+                .add(Planet3dMaterial { color }),
+            ..default()
+        })
+        .insert(RigidBody::Fixed)
+        // the collider is scaled along with the unit mesh
+        .insert(Collider::ball(1.0))
+        .insert(Friction::coefficient(10.0))
+        .insert(PointMass::HasGravity { mass })
+        .insert(Planet)
+        .id()
+}
+
+pub fn spawn_ball_3d(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    position: Vec3,
+    velocity: Vec3,
+    color: Color,
+    settings: &GolfBallSettings,
+) -> Entity {
+    let radius = settings.radius();
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::UVSphere {
+                radius,
+                ..default()
+            })),
+            material: materials.add(color.into()),
+            transform: Transform::from_translation(
+                position,
+            ),
+            ..default()
+        })
+        .insert(RigidBody::Dynamic)
+        .insert(Collider::ball(radius))
+        .insert(ColliderMassProperties::Mass(settings.mass))
+        .insert(Restitution::coefficient(
+            settings.restitution,
+        ))
+        .insert(Friction::coefficient(settings.friction))
+        // `BallDamping::component` is rapier2d's `Damping`,
+        // which a rapier3d body never reads
+        .insert(Damping {
+            linear_damping: settings.damping.linear,
+            angular_damping: settings.damping.angular,
+        })
+        .insert(ExternalForce::default())
+        .insert(Velocity::linear(velocity))
+        .insert(PointMass::HasGravity {
+            mass: settings.mass,
+        })
+        .insert(GolfBall)
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .id()
+}
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use bevy_rapier2d::prelude::*;
+
+use crate::{sim_state::sim_running, GravitySystem};
+
+/// Lets a ball steer itself after launch with `WASD`
+/// until it runs out of fuel.
+#[derive(
+    Component, Reflect, Clone, Copy, Debug, Default,
+)]
+#[reflect(Component)]
+pub struct Thrust {
+    /// seconds of burn left
+    pub fuel: f32,
+    pub power: f32,
+}
+
+/// Set while thrust is being applied so the exhaust can be
+/// drawn.
+#[derive(Component, Default)]
+struct Firing(Vec2);
+
+pub struct ThrustPlugin;
+
+impl Plugin for ThrustPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Thrust>()
+            .add_system(
+                apply_thrust
+                    .with_run_criteria(sim_running)
+                    .after(GravitySystem::Accelerate),
+            )
+            .add_system(draw_thrust.after(apply_thrust));
+    }
+}
+
+fn thrust_direction(keyboard: &Input<KeyCode>) -> Vec2 {
+    let mut direction = Vec2::ZERO;
+    if keyboard.pressed(KeyCode::W) {
+        direction.y += 1.0;
+    }
+    if keyboard.pressed(KeyCode::S) {
+        direction.y -= 1.0;
+    }
+    if keyboard.pressed(KeyCode::A) {
+        direction.x -= 1.0;
+    }
+    if keyboard.pressed(KeyCode::D) {
+        direction.x += 1.0;
+    }
+    direction.normalize_or_zero()
+}
+
+fn apply_thrust(
+    mut commands: Commands,
+    time: Res<Time>,
+    keyboard: Res<Input<KeyCode>>,
+    mut balls: Query<(
+        Entity,
+        &mut Thrust,
+        &mut ExternalForce,
+    )>,
+) {
+    let direction = thrust_direction(&keyboard);
+    for (entity, mut thrust, mut force) in balls.iter_mut()
+    {
+        if direction == Vec2::ZERO || thrust.fuel <= 0.0 {
+            commands.entity(entity).remove::<Firing>();
+            continue;
+        }
+        force.force += direction * thrust.power;
+        thrust.fuel =
+            (thrust.fuel - time.delta_seconds()).max(0.0);
+        commands.entity(entity).insert(Firing(direction));
+    }
+}
+
+fn draw_thrust(
+    mut lines: ResMut<DebugLines>,
+    balls: Query<(
+        &GlobalTransform,
+        &Thrust,
+        Option<&Firing>,
+    )>,
+) {
+    for (transform, thrust, firing) in balls.iter() {
+        let position = transform.translation();
+        // remaining fuel as a bar floating above the ball
+        let bar_start =
+            position + Vec3::new(-10.0, 12.0, 0.0);
+        lines.line_colored(
+            bar_start,
+            bar_start + Vec3::X * thrust.fuel * 10.0,
+            0.0,
+            Color::YELLOW,
+        );
+        if let Some(Firing(direction)) = firing {
+            lines.line_gradient(
+                position,
+                position - direction.extend(0.0) * 20.0,
+                0.0,
+                Color::ORANGE,
+                Color::rgba(1.0, 0.2, 0.0, 0.0),
+            );
+        }
+    }
+}
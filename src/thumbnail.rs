@@ -0,0 +1,274 @@
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension,
+            TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+    sprite::MaterialMesh2dBundle,
+};
+use bevy_mouse_tracking_plugin::ExcludeMouseTracking;
+use std::collections::HashMap;
+
+use crate::{
+    backdrop::DEFAULT_BACKGROUND, course::Course,
+    level::Level,
+};
+
+/// The render layer thumbnails are drawn on, which the
+/// game's own camera never looks at.
+const THUMBNAIL_LAYER: u8 = 1;
+/// How far apart thumbnail scenes are laid out, so
+/// several can render in the same frame without seeing
+/// each other.
+const THUMBNAIL_SPACING: f32 = 100_000.0;
+/// Frames a thumbnail camera is kept around for before
+/// its scene is torn down. The image keeps what was last
+/// drawn into it.
+const THUMBNAIL_FRAMES: u8 = 2;
+
+/// Small pictures of levels for a level select screen,
+/// rendered once per level name and kept.
+pub struct LevelThumbnails {
+    pub size: UVec2,
+    cache: HashMap<String, Handle<Image>>,
+    rendered: usize,
+}
+
+impl Default for LevelThumbnails {
+    fn default() -> Self {
+        Self {
+            size: UVec2::new(160, 90),
+            cache: HashMap::new(),
+            rendered: 0,
+        }
+    }
+}
+
+impl LevelThumbnails {
+    /// The thumbnail of a level, if one has been asked for.
+    pub fn get(
+        &self,
+        level: &Level,
+    ) -> Option<Handle<Image>> {
+        self.cache.get(&level.name).cloned()
+    }
+
+    /// The thumbnail of `level`, rendering it the first
+    /// time it's asked for. See [`render_level_thumbnail`].
+    pub fn get_or_render(
+        &mut self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<ColorMaterial>,
+        images: &mut Assets<Image>,
+        level: &Level,
+    ) -> Handle<Image> {
+        if let Some(image) = self.get(level) {
+            return image;
+        }
+        let offset = Vec2::new(
+            0.0,
+            -THUMBNAIL_SPACING * (self.rendered + 1) as f32,
+        );
+        let image = render_level_thumbnail(
+            commands, meshes, materials, images, level,
+            self.size, offset,
+        );
+        self.rendered += 1;
+        self.cache
+            .insert(level.name.clone(), image.clone());
+        image
+    }
+}
+
+/// Marks the cameras drawing thumbnails, so anything
+/// looking for the game's camera can leave them out.
+#[derive(Component)]
+pub struct ThumbnailCamera;
+
+/// Everything spawned to draw one thumbnail.
+#[derive(Component, Clone, Copy)]
+struct ThumbnailScene {
+    frames_left: u8,
+}
+
+/// Lays `level` out to scale around `offset`, out of
+/// sight of the game's camera, and points a camera that
+/// draws into a new `size` image at it. Rendering happens
+/// at the end of the frame, so the returned image is blank
+/// until then; the scene is cleaned up by
+/// [`ThumbnailPlugin`] once it's been drawn.
+pub fn render_level_thumbnail(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    images: &mut Assets<Image>,
+    level: &Level,
+    size: UVec2,
+    offset: Vec2,
+) -> Handle<Image> {
+    let extent = Extent3d {
+        width: size.x.max(1),
+        height: size.y.max(1),
+        ..default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size: extent,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+        },
+        ..default()
+    };
+    // fills the image with zeroes
+    image.resize(extent);
+    let image = images.add(image);
+
+    // what to draw, as circles: planets to scale, and the
+    // tee and holes big enough to still show up
+    let mut circles: Vec<(Vec2, f32, Color)> = level
+        .planets
+        .iter()
+        .map(|planet| {
+            (
+                planet.position.truncate(),
+                planet.radius() * planet.visual_scale,
+                planet.color,
+            )
+        })
+        .collect();
+    circles.extend(level.sized_holes().map(
+        |(position, hole)| {
+            (position, hole.radius, Color::DARK_GRAY)
+        },
+    ));
+    if let Some(tee) = level.tee {
+        circles.push((tee, 5.0, Color::WHITE));
+    }
+
+    let (min, max) = circles.iter().fold(
+        (
+            Vec2::splat(f32::INFINITY),
+            Vec2::splat(f32::NEG_INFINITY),
+        ),
+        |(min, max), (position, radius, _)| {
+            (
+                min.min(*position - *radius),
+                max.max(*position + *radius),
+            )
+        },
+    );
+    let (center, extent) = if circles.is_empty() {
+        (Vec2::ZERO, Vec2::ONE)
+    } else {
+        // leave a little space around the edges
+        ((min + max) / 2.0, (max - min) * 1.1)
+    };
+    let scale = (extent.x / size.x.max(1) as f32)
+        .max(extent.y / size.y.max(1) as f32)
+        .max(f32::EPSILON);
+
+    let layer = RenderLayers::layer(THUMBNAIL_LAYER);
+    let scene = ThumbnailScene {
+        frames_left: THUMBNAIL_FRAMES,
+    };
+    for (position, radius, color) in circles {
+        // the smallest things still get a pixel or two
+        let radius = radius.max(scale * 1.5);
+        commands
+            .spawn_bundle(MaterialMesh2dBundle {
+                mesh: meshes
+                    .add(Mesh::from(shape::Circle {
+                        radius,
+                        ..default()
+                    }))
+                    .into(),
+                material: materials
+                    .add(ColorMaterial::from(color)),
+                transform: Transform::from_translation(
+                    (position - center + offset)
+                        .extend(1.0),
+                ),
+                ..default()
+            })
+            .insert(layer)
+            .insert(scene);
+    }
+    let mut camera = Camera2dBundle::default();
+    camera.camera.target =
+        RenderTarget::Image(image.clone());
+    // before the game's camera
+    camera.camera.priority = -1;
+    camera.camera_2d.clear_color = ClearColorConfig::Custom(
+        level.background.unwrap_or(DEFAULT_BACKGROUND),
+    );
+    camera.projection.scale = scale;
+    camera.transform.translation.x = offset.x;
+    camera.transform.translation.y = offset.y;
+    commands
+        .spawn_bundle(camera)
+        .insert(layer)
+        .insert(scene)
+        .insert(ThumbnailCamera)
+        // the cursor is never over a thumbnail
+        .insert(ExcludeMouseTracking);
+    image
+}
+
+pub struct ThumbnailPlugin;
+
+impl Plugin for ThumbnailPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelThumbnails>()
+            .add_system(render_course_thumbnails)
+            .add_system(clean_up_thumbnails);
+    }
+}
+
+/// Every level in the `Course` gets a thumbnail as soon
+/// as it's added.
+fn render_course_thumbnails(
+    mut commands: Commands,
+    course: Res<Course>,
+    mut thumbnails: ResMut<LevelThumbnails>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !course.is_changed() {
+        return;
+    }
+    for level in course.levels.iter() {
+        thumbnails.get_or_render(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut images,
+            level,
+        );
+    }
+}
+
+fn clean_up_thumbnails(
+    mut commands: Commands,
+    mut scenes: Query<(Entity, &mut ThumbnailScene)>,
+) {
+    for (entity, mut scene) in scenes.iter_mut() {
+        if scene.frames_left == 0 {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            scene.frames_left -= 1;
+        }
+    }
+}
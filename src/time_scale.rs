@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::menu::playing;
+
+/// Speeds up or slows down the simulation. `[` halves it,
+/// `]` doubles it and `\` resets it to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        TimeScale(1.0)
+    }
+}
+
+/// Steps the physics at a fixed `BASE_MAX_DT` instead of
+/// once per frame, and draws dynamic bodies part way
+/// between their last two physics positions so they still
+/// move smoothly on screens refreshing faster than that.
+/// Rendered positions trail the physics by up to a step.
+/// Off by default, toggled with `F5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderInterpolation(pub bool);
+
+/// Steps the physics by the same `BASE_MAX_DT` every
+/// frame while set, however long the frame took, so what
+/// plays out doesn't depend on the frame rate. It runs
+/// slower or faster than real time on screens that aren't
+/// refreshing at 60Hz. Takes precedence over
+/// `RenderInterpolation`. Set while a shot from a
+/// [`ShareCode`](crate::share::ShareCode) plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FixedSteps(pub bool);
+
+const MIN_TIME_SCALE: f32 = 0.125;
+const MAX_TIME_SCALE: f32 = 8.0;
+/// rapier's default `max_dt`, the step the simulation
+/// takes at normal speed
+pub const BASE_MAX_DT: f32 = 1.0 / 60.0;
+
+pub struct TimeScalePlugin;
+
+impl Plugin for TimeScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeScale>()
+            .init_resource::<RenderInterpolation>()
+            .init_resource::<FixedSteps>()
+            .add_system(
+                time_scale_controls
+                    .with_run_criteria(playing),
+            )
+            .add_system(
+                apply_time_scale.after(time_scale_controls),
+            )
+            .add_system(
+                interpolate_bodies
+                    .after(time_scale_controls),
+            );
+    }
+}
+
+fn time_scale_controls(
+    keyboard: Res<Input<KeyCode>>,
+    mut time_scale: ResMut<TimeScale>,
+    mut interpolation: ResMut<RenderInterpolation>,
+) {
+    if keyboard.just_pressed(KeyCode::F5) {
+        interpolation.0 = !interpolation.0;
+    }
+    if keyboard.just_pressed(KeyCode::LBracket) {
+        time_scale.0 = (time_scale.0 / 2.0)
+            .clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+    }
+    if keyboard.just_pressed(KeyCode::RBracket) {
+        time_scale.0 = (time_scale.0 * 2.0)
+            .clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+    }
+    if keyboard.just_pressed(KeyCode::Backslash) {
+        time_scale.0 = 1.0;
+    }
+}
+
+/// Speeding up takes more rapier substeps per frame
+/// instead of bigger ones, so each substep stays as small
+/// as it would be at normal speed and fast orbits stay
+/// stable.
+pub fn apply_time_scale(
+    time_scale: Res<TimeScale>,
+    interpolation: Res<RenderInterpolation>,
+    fixed_steps: Res<FixedSteps>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    if !time_scale.is_changed()
+        && !interpolation.is_changed()
+        && !fixed_steps.is_changed()
+    {
+        return;
+    }
+    let scale = time_scale.0.max(MIN_TIME_SCALE);
+    let substeps = scale.ceil().max(1.0) as usize;
+    rapier_config.timestep_mode = if fixed_steps.0 {
+        TimestepMode::Fixed {
+            dt: BASE_MAX_DT * scale,
+            substeps,
+        }
+    } else if interpolation.0 {
+        TimestepMode::Interpolated {
+            dt: BASE_MAX_DT,
+            time_scale: scale,
+            substeps,
+        }
+    } else {
+        TimestepMode::Variable {
+            max_dt: BASE_MAX_DT * scale.max(1.0),
+            time_scale: scale,
+            substeps,
+        }
+    };
+}
+
+/// rapier does the interpolating itself, for any body with
+/// a `TransformInterpolation`.
+fn interpolate_bodies(
+    mut commands: Commands,
+    interpolation: Res<RenderInterpolation>,
+    bodies: Query<(
+        Entity,
+        &RigidBody,
+        Option<&TransformInterpolation>,
+    )>,
+) {
+    for (entity, rigidbody, interpolated) in bodies.iter() {
+        let wanted = interpolation.0
+            && *rigidbody == RigidBody::Dynamic;
+        if wanted && interpolated.is_none() {
+            commands
+                .entity(entity)
+                .insert(TransformInterpolation::default());
+        } else if !wanted && interpolated.is_some() {
+            commands
+                .entity(entity)
+                .remove::<TransformInterpolation>();
+        }
+    }
+}
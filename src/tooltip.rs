@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_rapier2d::prelude::*;
+
+use crate::{golf_ball::PointMass, hole::Hole};
+
+#[derive(Component)]
+struct Tooltip;
+
+/// Shows the mass, radius, speed and distance to the hole
+/// of whatever body is under the mouse.
+pub struct TooltipPlugin;
+
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_tooltip)
+            .add_system(update_tooltip);
+    }
+}
+
+fn spawn_tooltip(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: asset_server
+                        .load("fonts/DejaVuSansMono.ttf"),
+                    font_size: 14.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                ..default()
+            }),
+        )
+        .insert(Visibility { is_visible: false })
+        .insert(Tooltip);
+}
+
+fn collider_radius(collider: &Collider) -> f32 {
+    match collider.as_ball() {
+        Some(ball) => ball.radius(),
+        None => collider
+            .raw
+            .compute_local_bounding_sphere()
+            .radius(),
+    }
+}
+
+/// When bodies overlap under the cursor, like a ball
+/// resting on a planet, the smallest one wins since the
+/// bigger one is still easy to hover somewhere else.
+#[allow(clippy::type_complexity)]
+fn update_tooltip(
+    windows: Res<Windows>,
+    mouse_pos: Res<MousePosWorld>,
+    bodies: Query<(
+        &GlobalTransform,
+        &Collider,
+        &PointMass,
+        Option<&ColliderMassProperties>,
+        Option<&Velocity>,
+    )>,
+    holes: Query<&GlobalTransform, With<Hole>>,
+    mut tooltips: Query<
+        (&mut Text, &mut Style, &mut Visibility),
+        With<Tooltip>,
+    >,
+) {
+    let cursor = windows
+        .get_primary()
+        .and_then(|window| window.cursor_position());
+    let mouse_pos = mouse_pos.truncate();
+
+    // (position, radius, mass, speed) of every body under
+    // the cursor, smallest first
+    let mut hovered: Vec<(Vec2, f32, f32, f32)> = bodies
+        .iter()
+        .filter_map(
+            |(
+                transform,
+                collider,
+                point_mass,
+                mass_props,
+                velocity,
+            )| {
+                let position =
+                    transform.translation().truncate();
+                let radius = collider_radius(collider);
+                if position.distance(mouse_pos) > radius {
+                    return None;
+                }
+                let mass = match (point_mass, mass_props) {
+                    (PointMass::HasGravity { mass }, _) => {
+                        *mass
+                    }
+                    (
+                        _,
+                        Some(ColliderMassProperties::Mass(
+                            mass,
+                        )),
+                    ) => *mass,
+                    _ => 0.0,
+                };
+                let speed = velocity
+                    .map_or(0.0, |velocity| {
+                        velocity.linvel.length()
+                    });
+                Some((position, radius, mass, speed))
+            },
+        )
+        .collect();
+    hovered.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    for (mut text, mut style, mut visibility) in
+        tooltips.iter_mut()
+    {
+        let (cursor, (position, radius, mass, speed)) =
+            match (cursor, hovered.first()) {
+                (Some(cursor), Some(body)) => {
+                    (cursor, *body)
+                }
+                _ => {
+                    visibility.is_visible = false;
+                    continue;
+                }
+            };
+        let to_hole = holes
+            .iter()
+            .map(|hole| {
+                hole.translation()
+                    .truncate()
+                    .distance(position)
+            })
+            .min_by(|a, b| a.total_cmp(b));
+
+        let mut value = format!(
+            "mass: {:.0}\nradius: {:.1}\nspeed: {:.1}",
+            mass, radius, speed
+        );
+        match to_hole {
+            Some(distance) => value.push_str(&format!(
+                "\nto hole: {:.1}",
+                distance
+            )),
+            None => value.push_str("\nto hole: -"),
+        }
+        if hovered.len() > 1 {
+            value.push_str(&format!(
+                "\n(+{} more underneath)",
+                hovered.len() - 1
+            ));
+        }
+        text.sections[0].value = value;
+        style.position = UiRect {
+            left: Val::Px(cursor.x + 16.0),
+            bottom: Val::Px(cursor.y + 16.0),
+            ..default()
+        };
+        visibility.is_visible = true;
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+
+use crate::{
+    course::Filled, golf_ball::GolfBallSettings,
+    hole::Hole, menu::playing, prediction::PredictionLine,
+    render::PolyLine,
+};
+
+/// While aiming, draws the straight line from the ball to
+/// the nearest open hole next to the predicted path, and
+/// labels how far gravity bends the shot away from where
+/// it's aimed. Toggled with `U`.
+#[derive(Default)]
+pub struct TraceToHole {
+    pub enabled: bool,
+}
+
+const STRAIGHT_COLOR: Color = Color::CYAN;
+
+#[derive(Component)]
+struct BendLabel;
+
+pub struct TraceToHolePlugin;
+
+impl Plugin for TraceToHolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TraceToHole>()
+            .add_startup_system(spawn_bend_label)
+            .add_system(
+                toggle_trace.with_run_criteria(playing),
+            )
+            .add_system(draw_trace.after(toggle_trace));
+    }
+}
+
+fn toggle_trace(
+    keyboard: Res<Input<KeyCode>>,
+    mut trace: ResMut<TraceToHole>,
+) {
+    if keyboard.just_pressed(KeyCode::U) {
+        trace.enabled = !trace.enabled;
+    }
+}
+
+fn spawn_bend_label(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: asset_server
+                        .load("fonts/DejaVuSansMono.ttf"),
+                    font_size: 16.0,
+                    color: STRAIGHT_COLOR,
+                },
+            ),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(BendLabel);
+}
+
+/// The bend is the angle between the aim and the direction
+/// to where the predicted path comes closest to the hole,
+/// so a shot gravity doesn't touch reads zero.
+fn draw_trace(
+    trace: Res<TraceToHole>,
+    ball: Res<GolfBallSettings>,
+    mut lines: ResMut<DebugLines>,
+    predictions: Query<&PolyLine, With<PredictionLine>>,
+    holes: Query<
+        &GlobalTransform,
+        (With<Hole>, Without<Filled>),
+    >,
+    mut labels: Query<
+        (&mut Text, &mut Transform, &mut Visibility),
+        With<BendLabel>,
+    >,
+) {
+    let start =
+        ball.position.map(|position| position.truncate());
+    let hole = start.and_then(|start| {
+        holes
+            .iter()
+            .map(|hole| hole.translation().truncate())
+            .min_by(|a, b| {
+                a.distance_squared(start)
+                    .total_cmp(&b.distance_squared(start))
+            })
+    });
+    let (start, hole) = match (trace.enabled, start, hole) {
+        (true, Some(start), Some(hole)) => (start, hole),
+        _ => {
+            for (_, _, mut visibility) in labels.iter_mut()
+            {
+                if visibility.is_visible {
+                    visibility.is_visible = false;
+                }
+            }
+            return;
+        }
+    };
+    lines.line_colored(
+        start.extend(0.0),
+        hole.extend(0.0),
+        0.0,
+        STRAIGHT_COLOR,
+    );
+
+    let bend = predictions.iter().next().and_then(|path| {
+        let aim =
+            *path.points.get(1)? - *path.points.first()?;
+        let nearest =
+            path.points.iter().min_by(|a, b| {
+                a.distance_squared(hole)
+                    .total_cmp(&b.distance_squared(hole))
+            })?;
+        let reached = *nearest - start;
+        (aim != Vec2::ZERO && reached != Vec2::ZERO).then(
+            || aim.angle_between(reached).to_degrees(),
+        )
+    });
+    for (mut text, mut transform, mut visibility) in
+        labels.iter_mut()
+    {
+        visibility.is_visible = bend.is_some();
+        if let Some(bend) = bend {
+            text.sections[0].value =
+                format!("bent {:.0}°", bend.abs());
+            transform.translation =
+                ((start + hole) / 2.0).extend(5.0);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use bevy_prototype_debug_lines::DebugLines;
+use std::f32::consts::TAU;
+
+use crate::{gravity::Attractor, menu::playing};
+
+/// Hold `;` to turn the cursor into a heavy attractor that
+/// drags nearby balls towards it, or `Shift+;` to push them
+/// away instead. It's an `Attractor` like any other while
+/// it's held, so it goes into the `ParticleSet` with the
+/// planets, and it's gone again as soon as it's let go.
+pub struct GravityTractor {
+    /// the mass the cursor pulls with, made negative to
+    /// push
+    pub mass: f32,
+}
+
+impl Default for GravityTractor {
+    fn default() -> Self {
+        Self { mass: 10E5 }
+    }
+}
+
+/// The attractor following the cursor while the tractor
+/// is held.
+#[derive(Component)]
+pub struct Tractor;
+
+const PULL_COLOR: Color = Color::CYAN;
+const PUSH_COLOR: Color = Color::ORANGE_RED;
+/// how big the gizmo around the cursor is
+const GIZMO_RADIUS: f32 = 24.0;
+
+pub struct GravityTractorPlugin;
+
+impl Plugin for GravityTractorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GravityTractor>().add_system(
+            run_tractor.with_run_criteria(playing),
+        );
+    }
+}
+
+fn run_tractor(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    mouse_pos: Res<MousePosWorld>,
+    settings: Res<GravityTractor>,
+    mut lines: ResMut<DebugLines>,
+    mut tractors: Query<
+        (Entity, &mut Transform, &mut Attractor),
+        With<Tractor>,
+    >,
+) {
+    if !keyboard.pressed(KeyCode::Semicolon) {
+        for (entity, ..) in tractors.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    let push = keyboard
+        .any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let mass =
+        if push { -settings.mass } else { settings.mass };
+    let position = mouse_pos.truncate();
+
+    match tractors.get_single_mut() {
+        Ok((_, mut transform, mut attractor)) => {
+            transform.translation = position.extend(0.0);
+            attractor.mass = mass;
+        }
+        Err(_) => {
+            commands
+                .spawn_bundle(
+                    TransformBundle::from_transform(
+                        Transform::from_translation(
+                            position.extend(0.0),
+                        ),
+                    ),
+                )
+                .insert(Attractor { mass })
+                .insert(Tractor);
+        }
+    }
+
+    // a ring with spokes pointing the way balls get
+    // moved
+    let color = if push { PUSH_COLOR } else { PULL_COLOR };
+    let segments = 24;
+    let direction = |i: i32| {
+        let angle = i as f32 / segments as f32 * TAU;
+        Vec2::new(angle.cos(), angle.sin())
+    };
+    for i in 0..segments {
+        lines.line_colored(
+            (position + direction(i) * GIZMO_RADIUS)
+                .extend(10.0),
+            (position + direction(i + 1) * GIZMO_RADIUS)
+                .extend(10.0),
+            0.0,
+            color,
+        );
+        if i % 3 != 0 {
+            continue;
+        }
+        let spoke = direction(i);
+        let (from, to) =
+            if push { (0.5, 1.0) } else { (1.0, 0.5) };
+        let tip = position + spoke * GIZMO_RADIUS * to;
+        lines.line_colored(
+            (position + spoke * GIZMO_RADIUS * from)
+                .extend(10.0),
+            tip.extend(10.0),
+            0.0,
+            color,
+        );
+        // an arrowhead on the tip, pointing along the
+        // spoke
+        let back = spoke * (from - to).signum() * 4.0;
+        for side in [1.0, -1.0] {
+            lines.line_colored(
+                tip.extend(10.0),
+                (tip + back + back.perp() * side)
+                    .extend(10.0),
+                0.0,
+                color,
+            );
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    menu::playing, planet_spec::Planet,
+    prediction::Obstacle, shot_paths::ShotPaths,
+};
+
+/// Empty space around everything in an exported trail.
+const MARGIN: f32 = 20.0;
+
+/// A shot's path as an SVG: the path as a polyline and
+/// every planet as a circle, with the world's y axis
+/// pointing up like it does in game.
+pub fn trail_svg(
+    points: &[Vec2],
+    planets: &[Obstacle],
+) -> String {
+    let mut min = Vec2::splat(f32::INFINITY);
+    let mut max = Vec2::splat(f32::NEG_INFINITY);
+    for point in points {
+        min = min.min(*point);
+        max = max.max(*point);
+    }
+    for planet in planets {
+        min = min.min(planet.center - planet.radius);
+        max = max.max(planet.center + planet.radius);
+    }
+    if !min.is_finite() || !max.is_finite() {
+        min = Vec2::ZERO;
+        max = Vec2::ZERO;
+    }
+    min -= MARGIN;
+    max += MARGIN;
+    let size = max - min;
+
+    let mut svg = String::new();
+    // writing to a String can't fail
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min.x, -max.y, size.x, size.y
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="black"/>"#,
+        min.x, -max.y, size.x, size.y
+    );
+    for planet in planets {
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="{}" fill="gray"/>"#,
+            planet.center.x,
+            -planet.center.y,
+            planet.radius
+        );
+    }
+    let polyline: Vec<String> = points
+        .iter()
+        .map(|point| format!("{},{}", point.x, -point.y))
+        .collect();
+    let _ = writeln!(
+        svg,
+        r#"<polyline points="{}" fill="none" stroke="white" stroke-width="2"/>"#,
+        polyline.join(" ")
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes [`trail_svg`] to `path`, creating its directory
+/// if needed.
+pub fn export_trail(
+    path: &Path,
+    points: &[Vec2],
+    planets: &[Obstacle],
+) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, trail_svg(points, planets))
+}
+
+/// `trails/` in the platform's data directory.
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::data_dir()
+        .map(|dir| dir.join("space-golf").join("trails"))
+}
+
+/// Saves the last finished shot's path as an SVG when `L`
+/// is pressed.
+pub struct TrailExportPlugin;
+
+impl Plugin for TrailExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            export_last_trail.with_run_criteria(playing),
+        );
+    }
+}
+
+fn export_last_trail(
+    keyboard: Res<Input<KeyCode>>,
+    paths: Res<ShotPaths>,
+    planets: Query<
+        (&GlobalTransform, &Collider),
+        With<Planet>,
+    >,
+) {
+    if !keyboard.just_pressed(KeyCode::L) {
+        return;
+    }
+    let (stroke, points) = match paths.last_finished() {
+        Some(last) => last,
+        None => {
+            info!("no finished shot to export yet");
+            return;
+        }
+    };
+    let dir = match default_dir() {
+        Some(dir) => dir,
+        None => return,
+    };
+    // anything that isn't round is drawn as the circle
+    // around it
+    let planets: Vec<Obstacle> = planets
+        .iter()
+        .map(|(transform, collider)| {
+            Obstacle::from_collider(transform, collider)
+        })
+        .collect();
+    let path = dir.join(format!("stroke-{}.svg", stroke));
+    match export_trail(&path, points, &planets) {
+        Ok(()) => {
+            info!("saved trail to {}", path.display())
+        }
+        Err(error) => warn!(
+            "couldn't save trail to {}: {}",
+            path.display(),
+            error
+        ),
+    }
+}
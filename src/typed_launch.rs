@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{
+    egui::{self, collapsing_header::CollapsingState},
+    EguiContext,
+};
+use std::fmt;
+
+use crate::{
+    auto_aim::LaunchShot, course::Course,
+    launch::LaunchCharge,
+};
+
+/// Why a typed shot couldn't be taken.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LaunchInputError {
+    /// the angle isn't a number
+    InvalidAngle(String),
+    /// the power isn't a number
+    InvalidPower(String),
+    /// a shot can't be weaker than standing still
+    NegativePower(f32),
+}
+
+impl fmt::Display for LaunchInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LaunchInputError::InvalidAngle(angle) => {
+                write!(
+                    f,
+                    "{:?} isn't an angle in degrees",
+                    angle
+                )
+            }
+            LaunchInputError::InvalidPower(power) => {
+                write!(f, "{:?} isn't a power", power)
+            }
+            LaunchInputError::NegativePower(power) => {
+                write!(f, "the power {} is negative", power)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LaunchInputError {}
+
+/// The launch velocity for a shot typed in as an angle in
+/// degrees, counter clockwise from +x like the angles
+/// auto aim logs, and a power, which is the launch speed.
+/// The power is clamped to `max_power`, the same as a
+/// fully charged shot.
+pub fn typed_velocity(
+    angle: &str,
+    power: &str,
+    max_power: f32,
+) -> Result<Vec2, LaunchInputError> {
+    let angle_degrees = angle
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|angle| angle.is_finite())
+        .ok_or_else(|| {
+            LaunchInputError::InvalidAngle(
+                angle.to_string(),
+            )
+        })?;
+    let power_value = power
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|power| !power.is_nan())
+        .ok_or_else(|| {
+            LaunchInputError::InvalidPower(
+                power.to_string(),
+            )
+        })?;
+    if power_value < 0.0 {
+        return Err(LaunchInputError::NegativePower(
+            power_value,
+        ));
+    }
+    let (sin, cos) = angle_degrees.to_radians().sin_cos();
+    Ok(Vec2::new(cos, sin) * power_value.min(max_power))
+}
+
+/// What's typed into the "Launch" window, kept between
+/// frames so a shot can be tweaked and taken again.
+pub struct TypedLaunch {
+    pub angle: String,
+    pub power: String,
+    /// why the last launch wasn't taken
+    error: Option<LaunchInputError>,
+}
+
+impl Default for TypedLaunch {
+    fn default() -> Self {
+        Self {
+            angle: "90".to_string(),
+            power: "300".to_string(),
+            error: None,
+        }
+    }
+}
+
+/// A collapsed "Launch" window for taking a shot from the
+/// tee at an exact angle and power, for repeatable shots
+/// and following along with a tutorial.
+pub struct TypedLaunchPlugin;
+
+impl Plugin for TypedLaunchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TypedLaunch>()
+            .add_system(show_typed_launch);
+    }
+}
+
+fn show_typed_launch(
+    mut egui_context: ResMut<EguiContext>,
+    mut typed: ResMut<TypedLaunch>,
+    course: Res<Course>,
+    charge: Res<LaunchCharge>,
+    mut launches: EventWriter<LaunchShot>,
+) {
+    let tee =
+        course.current_level().and_then(|level| level.tee);
+    let mut launch = false;
+    let ctx = egui_context.ctx_mut();
+    // egui windows can't be told to start collapsed, so
+    // the window's collapsed state is stored before it's
+    // first shown instead
+    let collapsing =
+        egui::Id::new("Launch").with("collapsing");
+    if CollapsingState::load(ctx, collapsing).is_none() {
+        CollapsingState::load_with_default_open(
+            ctx, collapsing, false,
+        )
+        .store(ctx);
+    }
+    egui::Window::new("Launch").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("angle (degrees)");
+            ui.text_edit_singleline(&mut typed.angle);
+        });
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "power (up to {:.0})",
+                charge.max_power
+            ));
+            ui.text_edit_singleline(&mut typed.power);
+        });
+        launch = ui
+            .add_enabled(
+                tee.is_some(),
+                egui::Button::new("Launch"),
+            )
+            .clicked();
+        if tee.is_none() {
+            ui.label("this level has no tee");
+        }
+        if let Some(error) = &typed.error {
+            ui.colored_label(
+                egui::Color32::RED,
+                error.to_string(),
+            );
+        }
+    });
+
+    let tee = match tee {
+        Some(tee) if launch => tee,
+        _ => return,
+    };
+    match typed_velocity(
+        &typed.angle,
+        &typed.power,
+        charge.max_power,
+    ) {
+        Ok(velocity) => {
+            typed.error = None;
+            launches.send(LaunchShot {
+                position: tee.extend(0.0),
+                velocity,
+            });
+        }
+        Err(error) => typed.error = Some(error),
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::{
+    course::{GameMode, Strokes, Sunk},
+    golf_ball::GolfBall,
+    menu::playing,
+    practice::Practice,
+};
+
+/// How many shots can be taken back in a row.
+const MAX_UNDO: usize = 10;
+
+/// The balls of the most recent shots, newest last.
+/// `Ctrl+Z` takes back the newest one: its ball is
+/// despawned and the stroke no longer counts. The camera
+/// cycles through them too, see `CameraFocus`.
+///
+/// Only the ball itself is undone. A shot whose ball has
+/// already sunk, shattered into fragments or timed out
+/// can't be taken back, and neither can anything it did
+/// to other balls on the way. Shots are only taken back
+/// in `GameMode::Sandbox`: in `GameMode::Challenge` every
+/// stroke counts, and `Ctrl+Z` only takes back the newest
+/// `Practice` ball, which never cost one.
+#[derive(Default)]
+pub struct ShotHistory {
+    shots: Vec<Entity>,
+}
+
+impl ShotHistory {
+    pub fn push(&mut self, ball: Entity) {
+        if self.shots.len() >= MAX_UNDO {
+            self.shots.remove(0);
+        }
+        self.shots.push(ball);
+    }
+
+    pub fn pop(&mut self) -> Option<Entity> {
+        self.shots.pop()
+    }
+
+    /// The balls of the shots on record, oldest first.
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = Entity> + '_ {
+        self.shots.iter().copied()
+    }
+
+    /// The ball of the most recent shot still on record.
+    pub fn newest(&self) -> Option<Entity> {
+        self.shots.last().copied()
+    }
+
+    /// Forgets `ball`, returning whether it was one of the
+    /// shots that can still be taken back.
+    pub fn remove(&mut self, ball: Entity) -> bool {
+        let len = self.shots.len();
+        self.shots.retain(|shot| *shot != ball);
+        self.shots.len() != len
+    }
+}
+
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShotHistory>().add_system(
+            undo_last_shot.with_run_criteria(playing),
+        );
+    }
+}
+
+fn undo_last_shot(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    mode: Res<GameMode>,
+    mut history: ResMut<ShotHistory>,
+    mut strokes: ResMut<Strokes>,
+    balls: Query<(), (With<GolfBall>, Without<Sunk>)>,
+    practice_balls: Query<(Entity, &Practice)>,
+) {
+    if !(keyboard.any_pressed([
+        KeyCode::LControl,
+        KeyCode::RControl,
+    ]) && keyboard.just_pressed(KeyCode::Z))
+    {
+        return;
+    }
+    if *mode == GameMode::Challenge {
+        // practice balls aren't in the history, the newest
+        // is the one that's been around the shortest
+        if let Some((ball, _)) =
+            practice_balls.iter().min_by_key(
+                |(_, practice)| practice.timer.elapsed(),
+            )
+        {
+            commands.entity(ball).despawn_recursive();
+        }
+        return;
+    }
+    let ball = match history.pop() {
+        Some(ball) => ball,
+        None => return,
+    };
+    // the record is dropped either way, so the next undo
+    // moves on to the shot before
+    if balls.contains(ball) {
+        commands.entity(ball).despawn_recursive();
+        strokes.0 = strokes.0.saturating_sub(1);
+    }
+}
@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    golf_ball::{BallColor, GolfBall},
+    menu::playing,
+    render::{spawn_poly_line, PolyLine},
+};
+
+/// Whether every ball shows an arrow for its current
+/// velocity, toggled with `V`. Where the aim arrow is the
+/// shot before launch and a trail is the path so far,
+/// this is the ball's state right now, so it shows where
+/// a ball speeds up and slows down.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShowVelocities(pub bool);
+
+/// world units of arrow per unit of speed
+const ARROW_SCALE: f32 = 0.5;
+const SHAFT_WIDTH: f32 = 2.0;
+const HEAD_LENGTH: f32 = 10.0;
+const HEAD_WIDTH: f32 = 8.0;
+
+/// One of the two lines an arrow is drawn with, following
+/// `ball` around.
+#[derive(Component)]
+struct VelocityArrow {
+    ball: Entity,
+    head: bool,
+}
+
+/// Marks balls that already have an arrow.
+#[derive(Component)]
+struct HasVelocityArrow;
+
+pub struct VelocityArrowPlugin;
+
+impl Plugin for VelocityArrowPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShowVelocities>()
+            .add_system(
+                toggle_velocities
+                    .with_run_criteria(playing),
+            )
+            .add_system(spawn_velocity_arrows)
+            .add_system(
+                update_velocity_arrows
+                    .after(toggle_velocities),
+            );
+    }
+}
+
+fn toggle_velocities(
+    keyboard: Res<Input<KeyCode>>,
+    mut show: ResMut<ShowVelocities>,
+) {
+    if keyboard.just_pressed(KeyCode::V) {
+        show.0 = !show.0;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn spawn_velocity_arrows(
+    mut commands: Commands,
+    balls: Query<
+        (Entity, Option<&BallColor>),
+        (With<GolfBall>, Without<HasVelocityArrow>),
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (ball, color) in balls.iter() {
+        let color =
+            color.map_or(Color::WHITE, |color| color.0);
+        // the head narrows to nothing at the tip
+        let lines = [
+            (
+                false,
+                PolyLine::new(
+                    Vec::new(),
+                    SHAFT_WIDTH,
+                    color,
+                ),
+            ),
+            (
+                true,
+                PolyLine::new(
+                    Vec::new(),
+                    HEAD_WIDTH,
+                    color,
+                )
+                .with_gradient(0.0, color),
+            ),
+        ];
+        for (head, line) in lines {
+            let arrow = spawn_poly_line(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                line,
+                4.0,
+            );
+            commands
+                .entity(arrow)
+                .insert(VelocityArrow { ball, head });
+        }
+        commands.entity(ball).insert(HasVelocityArrow);
+    }
+}
+
+/// The shaft and head of an arrow from `start` along
+/// `velocity`. Both are empty for a ball at rest.
+fn arrow_points(
+    start: Vec2,
+    velocity: Vec2,
+) -> (Vec<Vec2>, Vec<Vec2>) {
+    let arrow = velocity * ARROW_SCALE;
+    let length = arrow.length();
+    if length <= f32::EPSILON {
+        return (Vec::new(), Vec::new());
+    }
+    let tip = start + arrow;
+    let base =
+        tip - arrow / length * HEAD_LENGTH.min(length);
+    (vec![start, base], vec![base, tip])
+}
+
+fn update_velocity_arrows(
+    mut commands: Commands,
+    show: Res<ShowVelocities>,
+    balls: Query<
+        (&GlobalTransform, &Velocity),
+        With<GolfBall>,
+    >,
+    mut arrows: Query<(
+        Entity,
+        &VelocityArrow,
+        &mut PolyLine,
+    )>,
+) {
+    for (entity, arrow, mut line) in arrows.iter_mut() {
+        let (transform, velocity) =
+            match balls.get(arrow.ball) {
+                Ok(ball) => ball,
+                // the ball sank, was lost or got cleared away
+                Err(_) => {
+                    commands.entity(entity).despawn();
+                    continue;
+                }
+            };
+        if !show.0 {
+            // only touch hidden lines once, so their meshes
+            // aren't rebuilt every frame
+            if !line.points.is_empty() {
+                line.points.clear();
+            }
+            continue;
+        }
+        let (shaft, head) = arrow_points(
+            transform.translation().truncate(),
+            velocity.linvel,
+        );
+        line.points = if arrow.head { head } else { shaft };
+    }
+}
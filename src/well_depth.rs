@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use bevy_mouse_tracking_plugin::MousePosWorld;
+use particular::ParticleSet;
+
+use crate::{
+    gravity::{
+        falloff_gravity_at, falloff_potential_at,
+        GravitySettings, GravitySource,
+    },
+    menu::playing,
+    Body,
+};
+
+/// A readout of how deep the gravity well is under the
+/// cursor, and how hard it pulls, toggled with `J`. Much
+/// cheaper than the `PotentialContours` since it's only
+/// worked out at a single point.
+#[derive(Default)]
+pub struct WellDepthReadout {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+struct WellDepthText;
+
+pub struct WellDepthPlugin;
+
+impl Plugin for WellDepthPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WellDepthReadout>()
+            .add_startup_system(spawn_readout)
+            .add_system(
+                toggle_readout.with_run_criteria(playing),
+            )
+            .add_system(
+                update_readout.after(toggle_readout),
+            );
+    }
+}
+
+fn toggle_readout(
+    keyboard: Res<Input<KeyCode>>,
+    mut readout: ResMut<WellDepthReadout>,
+) {
+    if keyboard.just_pressed(KeyCode::J) {
+        readout.enabled = !readout.enabled;
+    }
+}
+
+fn spawn_readout(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: asset_server
+                        .load("fonts/DejaVuSansMono.ttf"),
+                    font_size: 18.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(Visibility { is_visible: false })
+        .insert(WellDepthText);
+}
+
+/// Uses the simulation's own softening length, so the
+/// readout stays finite with the cursor right on top of a
+/// planet's center when softening is on. Without it the
+/// planet under the cursor is left out at that exact point.
+fn update_readout(
+    readout: Res<WellDepthReadout>,
+    settings: Res<GravitySettings>,
+    particle_set: Res<ParticleSet<Body>>,
+    mouse_pos: Res<MousePosWorld>,
+    mut texts: Query<
+        (&mut Text, &mut Visibility),
+        With<WellDepthText>,
+    >,
+) {
+    for (mut text, mut visibility) in texts.iter_mut() {
+        if visibility.is_visible != readout.enabled {
+            visibility.is_visible = readout.enabled;
+        }
+        if !readout.enabled {
+            continue;
+        }
+        let sources: Vec<GravitySource> = particle_set
+            .iter()
+            .map(|body| GravitySource {
+                position: body.position.truncate(),
+                mu: body.mu,
+            })
+            .collect();
+        let point = mouse_pos.truncate();
+        let potential = falloff_potential_at(
+            &sources,
+            point,
+            settings.softening,
+            settings.falloff_exponent,
+        );
+        let field = falloff_gravity_at(
+            &sources,
+            point,
+            settings.softening,
+            settings.falloff_exponent,
+        );
+        text.sections[0].value = format!(
+            "Potential: {:.0}\nField: {:.1}",
+            potential,
+            field.length()
+        );
+    }
+}
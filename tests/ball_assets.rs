@@ -0,0 +1,47 @@
+//! Counts the meshes and materials a crowd of balls ends
+//! up with. Before `BallAssets` every ball added a circle
+//! mesh and a material of its own, so this scene used to
+//! hold one of each per ball.
+use bevy::{asset::AssetPlugin, prelude::*};
+use space_golf::golf_ball::{
+    BallAssets, BallPalette, GolfBallSettings, SpawnBall,
+    SpawnBallPlugin,
+};
+
+const BALLS: usize = 100;
+
+#[test]
+fn balls_share_meshes_and_materials() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .add_asset::<Image>()
+        .init_resource::<GolfBallSettings>()
+        .init_resource::<BallPalette>()
+        .add_plugin(SpawnBallPlugin);
+
+    let mut events =
+        app.world.resource_mut::<Events<SpawnBall>>();
+    for i in 0..BALLS {
+        events.send(SpawnBall {
+            position: Vec3::new(i as f32 * 20.0, 0.0, 0.0),
+            velocity: Vec2::ZERO,
+            mass: None,
+        });
+    }
+    app.update();
+
+    let meshes = app.world.resource::<Assets<Mesh>>().len();
+    let materials =
+        app.world.resource::<Assets<ColorMaterial>>().len();
+    let colors =
+        app.world.resource::<BallPalette>().colors.len();
+    assert_eq!(meshes, 1);
+    assert_eq!(materials, colors);
+    assert_eq!(
+        app.world.resource::<BallAssets>().len(),
+        (meshes, materials)
+    );
+}
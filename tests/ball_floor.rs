@@ -0,0 +1,89 @@
+//! Asks for balls below `GolfBallSettings::min_mass` and
+//! `min_radius` and checks they come out at the floor
+//! instead, while a ball above it is left alone.
+use bevy::{asset::AssetPlugin, prelude::*};
+use bevy_rapier2d::prelude::*;
+use space_golf::{
+    density::Density,
+    golf_ball::{
+        BallPalette, GolfBall, GolfBallSettings, SpawnBall,
+        SpawnBallPlugin,
+    },
+};
+
+#[test]
+fn balls_below_the_floor_are_raised_to_it() {
+    let settings = GolfBallSettings::default();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .add_asset::<Image>()
+        .insert_resource(settings.clone())
+        .init_resource::<BallPalette>()
+        .add_plugin(SpawnBallPlugin);
+
+    // far too light, light enough to be too small, and
+    // comfortably normal
+    let requested = [0.01, 5.0, settings.mass];
+    let mut events =
+        app.world.resource_mut::<Events<SpawnBall>>();
+    for (i, mass) in requested.iter().enumerate() {
+        events.send(SpawnBall {
+            position: Vec3::new(i as f32 * 50.0, 0.0, 0.0),
+            velocity: Vec2::ZERO,
+            mass: Some(*mass),
+        });
+    }
+    app.update();
+
+    let mut query = app.world.query_filtered::<(
+        &Transform,
+        &Collider,
+        &ColliderMassProperties,
+        &Density,
+    ), With<GolfBall>>();
+    let mut balls: Vec<_> = query
+        .iter(&app.world)
+        .map(|(transform, collider, mass, density)| {
+            let mass = match mass {
+                ColliderMassProperties::Mass(mass) => *mass,
+                _ => {
+                    panic!("balls are spawned with a mass")
+                }
+            };
+            let radius =
+                collider.as_ball().unwrap().radius();
+            (
+                transform.translation.x,
+                mass,
+                radius,
+                density.0,
+            )
+        })
+        .collect();
+    balls.sort_by(|a, b| a.0.total_cmp(&b.0));
+    assert_eq!(balls.len(), requested.len());
+
+    for (_, mass, radius, density) in balls.iter() {
+        assert!(*mass >= settings.min_mass);
+        assert!(*radius >= settings.min_radius - 1e-4);
+        assert!(
+            (Density(*density).radius(*mass) - radius)
+                .abs()
+                < 1e-3,
+            "the density doesn't match the radius"
+        );
+    }
+    // the normal ball is spawned just as it was asked for
+    let (_, mass, radius, density) = balls[2];
+    assert_eq!(mass, settings.mass);
+    assert_eq!(radius, settings.radius());
+    assert_eq!(density, settings.density);
+    // the middle one keeps its mass but grows to the floor
+    assert_eq!(balls[1].1, requested[1]);
+    assert!(
+        (balls[1].2 - settings.min_radius).abs() < 1e-4
+    );
+}
@@ -0,0 +1,98 @@
+//! Syncing the `ParticleSet` every frame must never keep
+//! a despawned body around, since everything that applies
+//! gravity looks bodies up by entity.
+use bevy::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::{GolfBall, PointMass},
+    gravity::{GravityMode, GravitySettings},
+    sync_particle_set, Body,
+};
+
+/// Looks every body in the set up the same way
+/// `accelerate_particles` does, but insists it's there.
+fn apply_to_live_bodies(
+    particle_set: Res<ParticleSet<Body>>,
+    mut query: Query<&mut Transform, With<PointMass>>,
+) {
+    for body in particle_set.iter() {
+        assert!(
+            query.get_mut(body.entity).is_ok(),
+            "{:?} is in the particle set but not the world",
+            body.entity
+        );
+    }
+}
+
+#[test]
+fn despawned_bodies_leave_the_particle_set() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(ParticleSet::<Body>::new())
+        .init_resource::<GravityMode>()
+        .init_resource::<GravitySettings>()
+        .add_system(sync_particle_set)
+        .add_system(
+            apply_to_live_bodies.after(sync_particle_set),
+        );
+
+    let planet = app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .insert(PointMass::HasGravity { mass: 10000.0 })
+        .id();
+    let balls: Vec<Entity> = (1..=3)
+        .map(|i| {
+            app.world
+                .spawn()
+                .insert_bundle(TransformBundle::from(
+                    Transform::from_xyz(
+                        i as f32 * 100.0,
+                        0.0,
+                        0.0,
+                    ),
+                ))
+                .insert(PointMass::AffectedByGravity)
+                .insert(GolfBall)
+                .id()
+        })
+        .collect();
+
+    app.update();
+    let contains = |app: &App, entity: Entity| {
+        app.world
+            .resource::<ParticleSet<Body>>()
+            .iter()
+            .any(|body| body.entity == entity)
+    };
+    assert!(contains(&app, planet));
+    assert!(balls.iter().all(|ball| contains(&app, *ball)));
+
+    // what cleanup, merging and timeouts all end up doing
+    app.world.despawn(balls[1]);
+    app.update();
+    assert!(!contains(&app, balls[1]));
+    assert!(contains(&app, balls[0]));
+    assert!(contains(&app, balls[2]));
+    assert_eq!(
+        app.world
+            .resource::<ParticleSet<Body>>()
+            .iter()
+            .count(),
+        3
+    );
+
+    // and with nothing left at all
+    app.world.despawn(planet);
+    app.world.despawn(balls[0]);
+    app.world.despawn(balls[2]);
+    app.update();
+    assert_eq!(
+        app.world
+            .resource::<ParticleSet<Body>>()
+            .iter()
+            .count(),
+        0
+    );
+}
@@ -0,0 +1,277 @@
+//! Puts a ball in a circular orbit around a planet and
+//! measures how far each integrator lets its energy drift
+//! from where it started. Semi-implicit Euler's orbit
+//! wobbles in and out, so its energy swings far more than
+//! Verlet's. Far from the origin `f32` positions run out
+//! of decimal places, and running the orbit in `f64`
+//! instead has to win that back. A fast flyby skimming a
+//! planet, flown through rapier the way the game flies it,
+//! is where splitting each frame into substeps pays off.
+use bevy::{
+    hierarchy::HierarchyPlugin, prelude::*,
+    transform::TransformPlugin,
+};
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::{GolfBall, PointMass},
+    gravity::{
+        accelerate_particles, finish_substeps, run_gravity,
+        step_gravity, GravityCache, GravityMode,
+        GravitySettings, Integrator, Precision, SimBody,
+        SubstepCorrections, G,
+    },
+    gravity_budget::GravityBudget,
+    sync_particle_set, Body, GravitySystem,
+    PIXELS_PER_METER,
+};
+
+/// about ten orbits
+const STEPS: usize = 6000;
+const DT: f32 = 1.0 / 60.0;
+const PLANET_MASS: f32 = 10000.0;
+const ORBIT_RADIUS: f32 = 300.0;
+/// Where the far off orbit is centered. A position out
+/// here only gets steps of about a hundredth of a unit in
+/// `f32`.
+const FAR_AWAY: Vec2 = Vec2::new(100_000.0, 0.0);
+
+/// Kinetic plus potential energy of the ball around the
+/// planet.
+fn energy(bodies: &[SimBody]) -> f32 {
+    let planet = &bodies[0];
+    let ball = &bodies[1];
+    let distance = planet.position.distance(ball.position);
+    0.5 * ball.mass * ball.velocity.length_squared()
+        - planet.point_mass.mu() * ball.mass / distance
+}
+
+fn orbit(center: Vec2) -> Vec<SimBody> {
+    let mu = G * PLANET_MASS;
+    vec![
+        SimBody {
+            position: center,
+            velocity: Vec2::ZERO,
+            // heavy enough that the ball barely moves it
+            mass: f32::MAX,
+            point_mass: PointMass::HasGravity {
+                mass: PLANET_MASS,
+            },
+            golf_ball: false,
+        },
+        SimBody {
+            position: center + Vec2::new(ORBIT_RADIUS, 0.0),
+            velocity: Vec2::new(
+                0.0,
+                (mu / ORBIT_RADIUS).sqrt(),
+            ),
+            mass: 1.0,
+            point_mass: PointMass::AffectedByGravity,
+            golf_ball: true,
+        },
+    ]
+}
+
+/// The furthest the orbit's energy gets from where it
+/// started, relative to it.
+fn worst_drift(integrator: Integrator) -> f32 {
+    let mut bodies = orbit(Vec2::ZERO);
+    let mut particle_set = ParticleSet::new();
+    let mut cache = GravityCache::default();
+    let start = energy(&bodies);
+    let mut worst: f32 = 0.0;
+    for _ in 0..STEPS {
+        step_gravity(
+            &mut particle_set,
+            &mut cache,
+            &mut bodies,
+            DT,
+            integrator,
+            GravityMode::All,
+            &GravitySettings::default(),
+            Precision::F32,
+        );
+        worst = worst
+            .max(((energy(&bodies) - start) / start).abs());
+    }
+    worst
+}
+
+#[test]
+fn verlet_drifts_less_than_euler() {
+    let euler = worst_drift(Integrator::SemiImplicitEuler);
+    let verlet = worst_drift(Integrator::Verlet);
+    // Verlet's error shrinks with the square of the step
+    // rather than with the step, so it's well over an
+    // order of magnitude smaller here
+    assert!(
+        verlet * 4.0 < euler,
+        "Verlet drifted {:.2e}, Euler {:.2e}",
+        verlet,
+        euler
+    );
+}
+
+/// [`energy`] worked out in `f64`, so measuring it doesn't
+/// add rounding of its own this far out.
+fn energy_f64(bodies: &[SimBody]) -> f64 {
+    let planet = &bodies[0];
+    let ball = &bodies[1];
+    let distance = planet
+        .position
+        .as_dvec2()
+        .distance(ball.position.as_dvec2());
+    0.5 * ball.mass as f64
+        * ball.velocity.as_dvec2().length_squared()
+        - (G as f64 * PLANET_MASS as f64) * ball.mass as f64
+            / distance
+}
+
+/// The furthest the far off orbit's energy gets from
+/// where it started, run a step at a time through
+/// `run_gravity` in `precision`.
+fn far_drift(precision: Precision) -> f64 {
+    let mut bodies = orbit(FAR_AWAY);
+    let mut particle_set = ParticleSet::new();
+    let mut cache = GravityCache::default();
+    let start = energy_f64(&bodies);
+    let mut worst: f64 = 0.0;
+    // checked every orbit or so, with the run kept in
+    // `precision` in between
+    for _ in 0..STEPS / 600 {
+        run_gravity(
+            &mut particle_set,
+            &mut cache,
+            &mut bodies,
+            DT,
+            600,
+            Integrator::Verlet,
+            GravityMode::All,
+            &GravitySettings::default(),
+            precision,
+        );
+        worst = worst.max(
+            ((energy_f64(&bodies) - start) / start).abs(),
+        );
+    }
+    worst
+}
+
+#[test]
+fn f64_drifts_less_far_from_the_origin() {
+    let f32_drift = far_drift(Precision::F32);
+    let f64_drift = far_drift(Precision::F64);
+    assert!(
+        f64_drift * 10.0 < f32_drift,
+        "f64 drifted {:.2e}, f32 {:.2e}",
+        f64_drift,
+        f32_drift
+    );
+}
+
+/// How far a ball skimming past a planet comes out with a
+/// different energy than it went in with, relative to it,
+/// when each frame's gravity is split into `substeps`.
+/// Flown headless through rapier with the systems the game
+/// runs.
+fn flyby_drift(substeps: u32) -> f32 {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .insert_resource(ParticleSet::<Body>::new())
+        .insert_resource(GravityMode::All)
+        .insert_resource(GravitySettings {
+            substeps,
+            max_speed: None,
+            ..default()
+        })
+        .init_resource::<GravityCache>()
+        .init_resource::<GravityBudget>()
+        .init_resource::<SubstepCorrections>()
+        .insert_resource(RapierConfiguration {
+            gravity: Vec2::ZERO,
+            timestep_mode: TimestepMode::Fixed {
+                dt: DT,
+                substeps: 1,
+            },
+            ..default()
+        })
+        .add_plugin(
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(
+                PIXELS_PER_METER,
+            ),
+        )
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            sync_particle_set.label(GravitySystem::Sync),
+        )
+        .add_system(
+            accelerate_particles
+                .label(GravitySystem::Accelerate),
+        )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            finish_substeps,
+        );
+    // no collider, so the ball passes through instead of
+    // bouncing off
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .insert(RigidBody::Fixed)
+        .insert(PointMass::HasGravity {
+            mass: PLANET_MASS,
+        });
+    // coming in fast from far off to the left, aimed to
+    // pass just off the planet's center
+    let ball = app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(
+            Transform::from_xyz(-900.0, 30.0, 0.0),
+        ))
+        .insert(RigidBody::Dynamic)
+        .insert(Collider::ball(5.0))
+        .insert(ColliderMassProperties::Mass(1.0))
+        .insert(Velocity::linear(Vec2::new(800.0, 0.0)))
+        .insert(ExternalForce::default())
+        .insert(PointMass::AffectedByGravity)
+        .insert(GolfBall)
+        .id();
+    let energy = |world: &World| {
+        let position = world
+            .get::<Transform>(ball)
+            .unwrap()
+            .translation
+            .truncate();
+        let velocity = world.get::<Velocity>(ball).unwrap();
+        let mu = G * PLANET_MASS;
+        let distance = position.length();
+        // rapier's velocity is half a step behind the
+        // position, so it's brought level first
+        let pull = -position * mu / distance.powi(3);
+        let velocity = velocity.linvel + pull * DT / 2.0;
+        0.5 * velocity.length_squared() - mu / distance
+    };
+    let start = energy(&app.world);
+    // long enough to be well clear of the planet again
+    for _ in 0..180 {
+        app.update();
+    }
+    ((energy(&app.world) - start) / start).abs()
+}
+#[test]
+fn substeps_drift_less_on_a_close_flyby() {
+    let one = flyby_drift(1);
+    let eight = flyby_drift(8);
+    // each substep's error is a fraction of the whole
+    // step's, so eight of them come out well over an order
+    // of magnitude closer
+    assert!(
+        eight * 10.0 < one,
+        "8 substeps drifted {:.2e}, 1 substep {:.2e}",
+        eight,
+        one
+    );
+}
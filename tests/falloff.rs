@@ -0,0 +1,151 @@
+//! Checks that the hand written force law behind
+//! `GravitySettings::falloff_exponent` is plain inverse
+//! square gravity at an exponent of 2. A few bodies are
+//! stepped once through particular, the game's own path,
+//! and once summed up directly in `f64` with the same
+//! exponent, and the two have to agree to within
+//! [`TOLERANCE`].
+//!
+//! The wells behind the other exponents are checked too:
+//! the potential has to slope exactly as steeply as the
+//! pull at every exponent.
+use bevy::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::PointMass,
+    gravity::{
+        falloff_gravity_at, falloff_potential_at,
+        step_gravity, GravityCache, GravityMode,
+        GravitySettings, GravitySource, Integrator,
+        Precision, SimBody,
+    },
+};
+
+/// relative to the largest velocity change in the step
+const TOLERANCE: f32 = 1e-4;
+const DT: f32 = 1.0 / 60.0;
+
+fn bodies() -> Vec<SimBody> {
+    let planet = |x: f32, y: f32, mass: f32| SimBody {
+        position: Vec2::new(x, y),
+        velocity: Vec2::ZERO,
+        mass,
+        point_mass: PointMass::HasGravity { mass },
+        golf_ball: false,
+    };
+    vec![
+        planet(0.0, 0.0, 5E4),
+        planet(400.0, 100.0, 2E4),
+        planet(-250.0, 300.0, 1E4),
+        SimBody {
+            position: Vec2::new(150.0, -200.0),
+            velocity: Vec2::new(30.0, 10.0),
+            mass: 100.0,
+            point_mass: PointMass::AffectedByGravity,
+            golf_ball: true,
+        },
+    ]
+}
+
+#[test]
+fn exponent_two_matches_particular() {
+    let mut particle_set = ParticleSet::new();
+    let mut cache = GravityCache::default();
+    let mut particular = bodies();
+    step_gravity(
+        &mut particle_set,
+        &mut cache,
+        &mut particular,
+        DT,
+        Integrator::SemiImplicitEuler,
+        GravityMode::All,
+        &GravitySettings::default(),
+        Precision::F32,
+    );
+    let mut direct = bodies();
+    step_gravity(
+        &mut particle_set,
+        &mut cache,
+        &mut direct,
+        DT,
+        Integrator::SemiImplicitEuler,
+        GravityMode::All,
+        &GravitySettings::default(),
+        Precision::F64,
+    );
+
+    let start = bodies();
+    let scale = particular
+        .iter()
+        .zip(&start)
+        .map(|(body, start)| {
+            (body.velocity - start.velocity).length()
+        })
+        .fold(0.0, f32::max);
+    for (i, (a, b)) in
+        particular.iter().zip(&direct).enumerate()
+    {
+        let error = a.velocity.distance(b.velocity) / scale;
+        assert!(
+            error < TOLERANCE,
+            "body {} differs by {:e} at exponent 2",
+            i,
+            error
+        );
+    }
+}
+
+#[test]
+fn potential_slopes_with_the_pull() {
+    let sources = [GravitySource {
+        position: Vec2::ZERO,
+        mu: 1E7,
+    }];
+    for exponent in [1.0, 1.5, 2.0, 3.0] {
+        for distance in [50.0, 200.0, 800.0] {
+            let potential = |x: f32| {
+                falloff_potential_at(
+                    &sources,
+                    Vec2::new(x, 0.0),
+                    10.0,
+                    exponent,
+                )
+            };
+            let h = distance * 1e-3;
+            let slope = (potential(distance + h)
+                - potential(distance - h))
+                / (2.0 * h);
+            let pull = falloff_gravity_at(
+                &sources,
+                Vec2::new(distance, 0.0),
+                10.0,
+                exponent,
+            )
+            .length();
+            let error = (slope - pull).abs() / pull;
+            assert!(
+                error < 1e-2,
+                "n={} at {}: slope {} but pull {}",
+                exponent,
+                distance,
+                slope,
+                pull
+            );
+        }
+    }
+}
+
+#[test]
+fn lower_exponents_reach_further() {
+    let sources = [GravitySource {
+        position: Vec2::ZERO,
+        mu: 1E7,
+    }];
+    let point = Vec2::new(800.0, 0.0);
+    let pull = |exponent| {
+        falloff_gravity_at(&sources, point, 0.0, exponent)
+            .length()
+    };
+    assert!(pull(1.0) > pull(2.0));
+    assert!(pull(2.0) > pull(3.0));
+}
@@ -0,0 +1,211 @@
+//! Flies a ball past a planet at a few different distances
+//! and compares `AdaptiveSubsteps` with a single substep a
+//! frame. Both are flown headless through rapier with the
+//! systems the game runs, and measured against the same
+//! flyby worked out by `step_gravity` in far finer pieces.
+//! The adaptive run should get well closer to it on a close
+//! flyby while only paying for the substeps near the
+//! planet.
+use bevy::{
+    hierarchy::HierarchyPlugin, prelude::*,
+    transform::TransformPlugin,
+};
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::{GolfBall, PointMass},
+    gravity::{
+        accelerate_particles, closest_distance,
+        finish_substeps, step_gravity, AdaptiveSubsteps,
+        GravityCache, GravityMode, GravitySettings,
+        Integrator, Precision, SimBody, SubstepCorrections,
+    },
+    gravity_budget::GravityBudget,
+    sync_particle_set, Body, GravitySystem,
+    PIXELS_PER_METER,
+};
+
+const PLANET_MASS: f32 = 10000.0;
+const FRAMES: usize = 240;
+const DT: f32 = 1.0 / 60.0;
+const START: Vec2 = Vec2::new(-900.0, 0.0);
+const SPEED: f32 = 800.0;
+/// how far the ball would pass from the planet's center
+/// without gravity bending its path
+const MISS_DISTANCES: [f32; 5] =
+    [30.0, 60.0, 100.0, 200.0, 300.0];
+/// how many pieces each frame is split into for the path
+/// the others are measured against
+const REFERENCE_SUBSTEPS: u32 = 1024;
+
+/// Where the ball ends up, and how many substeps it took
+/// to get there.
+fn fly(
+    miss_distance: f32,
+    adaptive_substeps: Option<AdaptiveSubsteps>,
+) -> (Vec2, u32) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .insert_resource(ParticleSet::<Body>::new())
+        .insert_resource(GravityMode::All)
+        .insert_resource(GravitySettings {
+            adaptive_substeps,
+            max_speed: None,
+            ..default()
+        })
+        .init_resource::<GravityCache>()
+        .init_resource::<GravityBudget>()
+        .init_resource::<SubstepCorrections>()
+        .insert_resource(RapierConfiguration {
+            gravity: Vec2::ZERO,
+            timestep_mode: TimestepMode::Fixed {
+                dt: DT,
+                substeps: 1,
+            },
+            ..default()
+        })
+        .add_plugin(
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(
+                PIXELS_PER_METER,
+            ),
+        )
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            sync_particle_set.label(GravitySystem::Sync),
+        )
+        .add_system(
+            accelerate_particles
+                .label(GravitySystem::Accelerate),
+        )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            finish_substeps,
+        );
+    // no collider, so the ball passes through instead of
+    // bouncing off
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .insert(RigidBody::Fixed)
+        .insert(PointMass::HasGravity {
+            mass: PLANET_MASS,
+        });
+    let ball = app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(
+            Transform::from_translation(
+                (START + Vec2::Y * miss_distance)
+                    .extend(0.0),
+            ),
+        ))
+        .insert(RigidBody::Dynamic)
+        .insert(Collider::ball(5.0))
+        .insert(ColliderMassProperties::Mass(1.0))
+        .insert(Velocity::linear(Vec2::X * SPEED))
+        .insert(ExternalForce::default())
+        .insert(PointMass::AffectedByGravity)
+        .insert(GolfBall)
+        .id();
+    let position = |world: &World| {
+        world
+            .get::<Transform>(ball)
+            .unwrap()
+            .translation
+            .truncate()
+    };
+    let mut cost = 0;
+    for _ in 0..FRAMES {
+        // the same pick `accelerate_particles` makes
+        cost += adaptive_substeps.map_or(1, |adaptive| {
+            closest_distance(
+                &[position(&app.world)],
+                &[Vec2::ZERO],
+            )
+            .map_or(1, |distance| {
+                adaptive.substeps(distance)
+            })
+        });
+        app.update();
+    }
+    (position(&app.world), cost)
+}
+
+/// Where the ball ends up with every frame split into
+/// `REFERENCE_SUBSTEPS`, close enough to the true flyby to
+/// measure the others against.
+fn reference(miss_distance: f32) -> Vec2 {
+    let mut bodies = [
+        SimBody {
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            // heavy enough that the ball barely moves it
+            mass: f32::MAX,
+            point_mass: PointMass::HasGravity {
+                mass: PLANET_MASS,
+            },
+            golf_ball: false,
+        },
+        SimBody {
+            position: START + Vec2::Y * miss_distance,
+            velocity: Vec2::X * SPEED,
+            mass: 1.0,
+            point_mass: PointMass::AffectedByGravity,
+            golf_ball: true,
+        },
+    ];
+    let settings = GravitySettings {
+        substeps: REFERENCE_SUBSTEPS,
+        max_speed: None,
+        ..default()
+    };
+    let mut particle_set = ParticleSet::new();
+    let mut cache = GravityCache::default();
+    for _ in 0..FRAMES {
+        step_gravity(
+            &mut particle_set,
+            &mut cache,
+            &mut bodies,
+            DT,
+            Integrator::SemiImplicitEuler,
+            GravityMode::All,
+            &settings,
+            Precision::F32,
+        );
+    }
+    bodies[1].position
+}
+
+#[test]
+fn adaptive_substeps_get_closer_for_less() {
+    let adaptive = AdaptiveSubsteps::default();
+    let max_cost = FRAMES as u32 * adaptive.max_substeps;
+    for miss_distance in MISS_DISTANCES {
+        let target = reference(miss_distance);
+        let (single, _) = fly(miss_distance, None);
+        let (adapted, cost) =
+            fly(miss_distance, Some(adaptive));
+        let single_error = single.distance(target);
+        let adaptive_error = adapted.distance(target);
+        assert!(
+            cost * 4 < max_cost,
+            "adaptive substeps cost {} of {}",
+            cost,
+            max_cost
+        );
+        // only the close flybys get enough substeps to
+        // make a real difference
+        if miss_distance < adaptive.near {
+            assert!(
+                adaptive_error * 2.0 < single_error,
+                "passing {} away, adaptive was off by {} \
+                 and a single substep by {}",
+                miss_distance,
+                adaptive_error,
+                single_error
+            );
+        }
+    }
+}
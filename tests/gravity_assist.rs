@@ -0,0 +1,114 @@
+//! Plans a flyby with `find_flyby`: a planet sits between
+//! the tee and a spot off to its far side, and the shot
+//! has to swing round the planet to get there. Checks the
+//! planned path passes inside the planet's reach, comes
+//! within a hole's radius of the target, and that a search
+//! out of time gives up instead.
+use bevy::prelude::*;
+use space_golf::{
+    density::Density,
+    flyby_rail::{find_flyby, FlybyPlanet, FlybySearch},
+    golf_ball::GolfBallSettings,
+    gravity::{GravitySource, G},
+    prediction::{Obstacle, PredictionSettings},
+};
+use std::time::Duration;
+
+const PLANET_MASS: f32 = 10E5;
+const PLANET_DENSITY: f32 = 20.0;
+const TEE: Vec2 = Vec2::new(-450.0, 0.0);
+const TARGET: Vec2 = Vec2::new(300.0, 300.0);
+/// the default `Hole`'s
+const HOLE_RADIUS: f32 = 10.0;
+
+#[test]
+fn a_flyby_swings_round_to_the_target() {
+    let radius =
+        Density(PLANET_DENSITY).radius(PLANET_MASS);
+    let planet = FlybyPlanet {
+        center: Vec2::ZERO,
+        radius,
+        mu: PLANET_MASS * G,
+        reach: radius * 3.0,
+    };
+    let sources = [GravitySource {
+        position: planet.center,
+        mu: planet.mu,
+    }];
+    let obstacles = [Obstacle {
+        center: planet.center,
+        radius,
+        shape: None,
+    }];
+    // four seconds ahead, long enough to get round
+    let ball = GolfBallSettings {
+        prediction_steps: 240,
+        ..default()
+    };
+    let prediction = PredictionSettings::default();
+    let search = FlybySearch {
+        time_limit: None,
+        ..default()
+    };
+
+    let flyby = find_flyby(
+        &sources,
+        &obstacles,
+        TEE,
+        &planet,
+        TARGET,
+        &ball,
+        &prediction,
+        &search,
+    )
+    .expect("no flyby was found");
+    assert!(
+        flyby.periapsis > radius
+            && flyby.periapsis <= planet.reach,
+        "the flyby passed {} from the center",
+        flyby.periapsis
+    );
+    assert!(
+        flyby.miss < HOLE_RADIUS,
+        "the flyby missed by {}",
+        flyby.miss
+    );
+    let rail = flyby.rail(&planet);
+    assert!(rail.len() > 1, "the flyby has no rail");
+    // the target is round the back of the planet, so the
+    // ball has to have swung past it first
+    let closest = |to: Vec2| {
+        flyby
+            .path
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                a.distance(to).total_cmp(&b.distance(to))
+            })
+            .map(|(i, _)| i)
+            .unwrap()
+    };
+    assert!(
+        closest(planet.center) < closest(TARGET),
+        "the path reached the target before the flyby"
+    );
+
+    let out_of_time = FlybySearch {
+        time_limit: Some(Duration::ZERO),
+        ..default()
+    };
+    assert!(
+        find_flyby(
+            &sources,
+            &obstacles,
+            TEE,
+            &planet,
+            TARGET,
+            &ball,
+            &prediction,
+            &out_of_time,
+        )
+        .is_none(),
+        "a search with no time still found a flyby"
+    );
+}
@@ -0,0 +1,157 @@
+//! Two clusters of planets far apart, and balls spread
+//! around the first. With a cutoff bigger than the whole
+//! level every ball has to feel exactly the uncut pull;
+//! with one that only reaches the nearby cluster the far
+//! cluster has to drop out, and the pull can only be off
+//! by the far cluster's small share. The substepped
+//! simulation has to drop the far cluster too, the same as
+//! the preview does.
+use bevy::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::PointMass,
+    gravity::{
+        cutoff_gravity_at, falloff_gravity_at,
+        step_gravity, GravityCache, GravityMode,
+        GravitySettings, GravitySource, Integrator,
+        Precision, SimBody, G,
+    },
+};
+
+const MU: f32 = 1e6;
+/// how far apart the two clusters are
+const SEPARATION: f32 = 20_000.0;
+/// reaches all of the near cluster and none of the far
+const TIGHT: f32 = 2_000.0;
+/// the most the far cluster can make up of a ball's pull
+const TOLERANCE: f32 = 0.01;
+
+fn cluster(center: Vec2) -> Vec<GravitySource> {
+    [
+        Vec2::ZERO,
+        Vec2::new(300.0, 0.0),
+        Vec2::new(0.0, -400.0),
+        Vec2::new(-250.0, 200.0),
+    ]
+    .into_iter()
+    .map(|offset| GravitySource {
+        position: center + offset,
+        mu: MU,
+    })
+    .collect()
+}
+
+#[test]
+fn a_cutoff_only_drops_far_sources() {
+    let near = cluster(Vec2::ZERO);
+    let mut sources = near.clone();
+    sources.extend(cluster(Vec2::X * SEPARATION));
+
+    for i in 0..64 {
+        let angle = i as f32 / 64.0 * std::f32::consts::TAU;
+        let radius = 500.0 + (i % 8) as f32 * 100.0;
+        let ball =
+            Vec2::new(angle.cos(), angle.sin()) * radius;
+        let exact =
+            falloff_gravity_at(&sources, ball, 0.0, 2.0);
+
+        let large = cutoff_gravity_at(
+            &sources,
+            ball,
+            0.0,
+            2.0,
+            Some(SEPARATION * 10.0),
+        );
+        assert_eq!(
+            large, exact,
+            "a cutoff past everything changed the pull at \
+             {:?}",
+            ball
+        );
+
+        let tight = cutoff_gravity_at(
+            &sources,
+            ball,
+            0.0,
+            2.0,
+            Some(TIGHT),
+        );
+        assert_eq!(
+            tight,
+            falloff_gravity_at(&near, ball, 0.0, 2.0),
+            "a tight cutoff didn't keep just the near \
+             cluster at {:?}",
+            ball
+        );
+        let error =
+            (tight - exact).length() / exact.length();
+        assert!(
+            error < TOLERANCE,
+            "culling the far cluster was off by {:.2}% at \
+             {:?}",
+            error * 100.0,
+            ball
+        );
+    }
+}
+
+/// The near cluster, the far one too if `far`, and a ball
+/// passing through the near one.
+fn scene(far: bool) -> Vec<SimBody> {
+    let mut planets = cluster(Vec2::ZERO);
+    if far {
+        planets.extend(cluster(Vec2::X * SEPARATION));
+    }
+    let mut bodies: Vec<SimBody> = planets
+        .into_iter()
+        .map(|planet| SimBody {
+            position: planet.position,
+            velocity: Vec2::ZERO,
+            mass: MU / G,
+            point_mass: PointMass::HasGravity {
+                mass: MU / G,
+            },
+            golf_ball: false,
+        })
+        .collect();
+    bodies.push(SimBody {
+        position: Vec2::new(600.0, 300.0),
+        velocity: Vec2::new(-200.0, 50.0),
+        mass: 1.0,
+        point_mass: PointMass::AffectedByGravity,
+        golf_ball: true,
+    });
+    bodies
+}
+
+#[test]
+fn substeps_drop_far_sources_too() {
+    let settings = GravitySettings {
+        substeps: 4,
+        gravity_cutoff: Some(TIGHT),
+        ..default()
+    };
+    let mut particle_set = ParticleSet::new();
+    let mut cache = GravityCache::default();
+    let mut step = |bodies: &mut Vec<SimBody>| {
+        for _ in 0..60 {
+            step_gravity(
+                &mut particle_set,
+                &mut cache,
+                bodies,
+                1.0 / 60.0,
+                Integrator::SemiImplicitEuler,
+                GravityMode::PlanetsOnly,
+                &settings,
+                Precision::F32,
+            );
+        }
+        bodies.last().unwrap().position
+    };
+    let with_far = step(&mut scene(true));
+    let without_far = step(&mut scene(false));
+    assert_eq!(
+        with_far, without_far,
+        "the far cluster pulled past the cutoff"
+    );
+}
@@ -0,0 +1,76 @@
+//! Checks which balls `Hole::captures` lets into a hole.
+//! A ball bigger than the hole mustn't fit when all of it
+//! has to be over the hole, however well it's centered,
+//! and a ball resting on the ground under a hole has to
+//! sink with the default settings.
+use space_golf::{
+    cup::CupSettings, density::Density,
+    golf_ball::GolfBallSettings, hole::Hole, level::Level,
+    planet_spec::PlanetSpec,
+};
+
+const FULLY: f32 = 1.0;
+
+#[test]
+fn a_large_ball_misses_a_tiny_hole() {
+    // a heavy ball, radius about 17.8, dead center over
+    // a hole of radius 4
+    let tiny = Hole { radius: 4.0 };
+    let large = Density(1.0).radius(1000.0);
+    assert!(large > tiny.radius);
+    assert!(!tiny.captures(0.0, large, FULLY));
+    // it only has to touch with a fraction of zero
+    assert!(tiny.captures(large + 3.9, large, 0.0));
+}
+
+#[test]
+fn capture_fraction_sets_how_much_is_over_the_hole() {
+    // a default sized ball fits a default hole, but only
+    // once all of it is over the hole
+    let hole = Hole::default();
+    let ball = Density(1.0).radius(100.0);
+    assert!(hole.captures(0.0, ball, FULLY));
+    assert!(hole.captures(hole.radius - ball, ball, FULLY));
+    assert!(!hole.captures(
+        hole.radius - ball + 0.1,
+        ball,
+        FULLY
+    ));
+    // half of it is its center
+    assert!(hole.captures(hole.radius, ball, 0.5));
+    assert!(!hole.captures(hole.radius + 0.1, ball, 0.5));
+}
+
+#[test]
+fn a_ball_resting_under_a_hole_sinks() {
+    let planet = PlanetSpec::default();
+    let mut level = Level::new();
+    level.add_planet(planet.clone()).place_hole_on_surface(
+        0,
+        1.0,
+        Hole::default(),
+    );
+    let (hole_position, hole) =
+        level.sized_holes().next().unwrap();
+
+    let cup = CupSettings::default();
+    let ball = GolfBallSettings::default();
+    let radius = Density(ball.density).radius(ball.mass);
+    // sitting on the ground right under the hole's middle
+    let resting = planet.surface_point(1.0, radius);
+    let distance = resting.distance(hole_position);
+    assert!(
+        hole.captures(
+            distance,
+            radius,
+            cup.capture_fraction
+        ),
+        "a resting ball {} from the middle doesn't sink",
+        distance
+    );
+    assert!(
+        distance <= hole.radius * cup.sink_radius,
+        "a resting ball {} from the middle never settles",
+        distance
+    );
+}
@@ -0,0 +1,97 @@
+//! Rolls a ball from `spawn_ball` straight across a hole
+//! from `spawn_hole`, headless through rapier with no
+//! gravity. The hole is a sensor, so the ball has to come
+//! out the far side going exactly as it went in.
+use bevy::{
+    asset::AssetPlugin, hierarchy::HierarchyPlugin,
+    prelude::*, transform::TransformPlugin,
+};
+use bevy_rapier2d::prelude::*;
+use space_golf::{
+    golf_ball::{
+        spawn_ball, BallAssets, GolfBall, GolfBallSettings,
+    },
+    hole::{spawn_hole, Hole},
+    PIXELS_PER_METER,
+};
+
+const DT: f32 = 1.0 / 60.0;
+const STEPS: usize = 60;
+const START: Vec2 = Vec2::new(-100.0, 0.0);
+/// crosses the whole hole in under a second
+const VELOCITY: Vec2 = Vec2::new(200.0, 0.0);
+
+type Physics = RapierPhysicsPlugin<NoUserData>;
+
+fn launch(
+    mut commands: Commands,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    spawn_hole(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        Vec2::ZERO,
+        Hole::default(),
+    );
+    spawn_ball(
+        &mut commands,
+        &mut ball_assets,
+        &mut meshes,
+        &mut materials,
+        START.extend(0.0),
+        VELOCITY,
+        Color::WHITE,
+        &GolfBallSettings::default(),
+    );
+}
+
+#[test]
+fn a_ball_passes_through_a_hole_undeflected() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .init_resource::<BallAssets>()
+        .insert_resource(RapierConfiguration {
+            gravity: Vec2::ZERO,
+            timestep_mode: TimestepMode::Fixed {
+                dt: DT,
+                substeps: 1,
+            },
+            ..default()
+        })
+        .add_plugin(Physics::pixels_per_meter(
+            PIXELS_PER_METER,
+        ))
+        .add_startup_system(launch);
+    for _ in 0..STEPS {
+        app.update();
+    }
+
+    let mut balls = app.world.query_filtered::<
+        (&Transform, &Velocity),
+        With<GolfBall>,
+    >();
+    let (transform, velocity) = balls.single(&app.world);
+    assert!(
+        transform.translation.x > Hole::default().radius,
+        "the ball never made it past the hole, it's at {}",
+        transform.translation
+    );
+    assert!(
+        transform.translation.y.abs() < 1E-3,
+        "the ball was knocked off its line to {}",
+        transform.translation
+    );
+    assert!(
+        velocity.linvel.abs_diff_eq(VELOCITY, 1E-3),
+        "the ball came out at {}",
+        velocity.linvel
+    );
+}
@@ -0,0 +1,116 @@
+//! Queues a ring of balls with a `LaunchQueue`, each one
+//! moving at the speed for a circular orbit around a
+//! planet at the origin, and lets the queue fire them off
+//! one at a time.
+use bevy::{asset::AssetPlugin, prelude::*};
+use bevy_rapier2d::prelude::*;
+use space_golf::{
+    golf_ball::{
+        BallPalette, GolfBall, GolfBallSettings, SpawnBall,
+        SpawnBallPlugin,
+    },
+    gravity::G,
+    launch_queue::{LaunchQueue, LaunchQueuePlugin},
+    sim_state::PhysicsActive,
+    time_scale::TimeScale,
+};
+use std::{
+    f32::consts::TAU,
+    time::{Duration, Instant},
+};
+
+const BALLS: usize = 12;
+const RADIUS: f32 = 300.0;
+const PLANET_MASS: f32 = 10000.0;
+const INTERVAL: f32 = 0.05;
+
+/// `count` balls evenly spaced round a circle of `radius`,
+/// all going the same way round.
+fn ring(
+    count: usize,
+    radius: f32,
+    mu: f32,
+) -> Vec<SpawnBall> {
+    let speed = (mu / radius).sqrt();
+    (0..count)
+        .map(|i| {
+            let angle = i as f32 / count as f32 * TAU;
+            let direction = Vec2::from_angle(angle);
+            SpawnBall {
+                position: (direction * radius).extend(1.0),
+                velocity: direction.perp() * speed,
+                mass: None,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn balls_launch_in_order_one_per_interval() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .add_asset::<Image>()
+        .init_resource::<GolfBallSettings>()
+        .init_resource::<BallPalette>()
+        .init_resource::<PhysicsActive>()
+        .init_resource::<TimeScale>()
+        .insert_resource(LaunchQueue::new(INTERVAL))
+        .add_plugin(SpawnBallPlugin)
+        .add_plugin(LaunchQueuePlugin);
+
+    let balls = ring(BALLS, RADIUS, G * PLANET_MASS);
+    app.world
+        .resource_mut::<LaunchQueue>()
+        .extend(balls.iter().copied());
+
+    let started = Instant::now();
+    let mut spawned: Vec<(Entity, Vec3, Vec2, Duration)> =
+        Vec::new();
+    while spawned.len() < BALLS {
+        app.update();
+        let mut query = app.world.query_filtered::<(
+            Entity,
+            &Transform,
+            &Velocity,
+        ), With<GolfBall>>(
+        );
+        for (entity, transform, velocity) in
+            query.iter(&app.world)
+        {
+            if !spawned.iter().any(|(e, ..)| *e == entity) {
+                spawned.push((
+                    entity,
+                    transform.translation,
+                    velocity.linvel,
+                    started.elapsed(),
+                ));
+            }
+        }
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "only {} of {} balls came out",
+            spawned.len(),
+            BALLS
+        );
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    // at most a ball or two come out each frame, and a
+    // frame's balls are in the order they were spawned
+    for (ball, (_, position, velocity, _)) in
+        balls.iter().zip(spawned.iter())
+    {
+        assert_eq!(*position, ball.position);
+        assert_eq!(*velocity, ball.velocity);
+    }
+    let total = spawned.last().unwrap().3.as_secs_f32();
+    assert!(
+        total >= INTERVAL * BALLS as f32,
+        "{} balls came out in {}s",
+        BALLS,
+        total
+    );
+}
@@ -0,0 +1,255 @@
+//! Two planets and a crowd of massless balls, some sitting
+//! right on top of a planet's center or of each other, all
+//! stepped headless with every precision and falloff.
+//! Every position and velocity has to stay finite, and the
+//! massless balls mustn't pull on the planets at all: each
+//! planet has to feel exactly what the other planet alone
+//! pulls it with. The same goes for particular's own
+//! `ParticleSet`, which the game's gravity is solved with:
+//! two massless balls next to each other have to fall
+//! towards a planet exactly as if the other weren't there.
+use bevy::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::PointMass,
+    gravity::{
+        average_gravity, falloff_gravity_at, step_gravity,
+        GravityCache, GravityMode, GravitySettings,
+        GravitySource, Integrator, Precision, SimBody,
+    },
+    Body,
+};
+
+const BALLS: usize = 200;
+const STEPS: usize = 120;
+const DT: f32 = 1.0 / 60.0;
+const PLANET_MASS: f32 = 10E5;
+
+fn scene() -> Vec<SimBody> {
+    let planet = |position: Vec2| SimBody {
+        position,
+        velocity: Vec2::ZERO,
+        mass: PLANET_MASS,
+        point_mass: PointMass::HasGravity {
+            mass: PLANET_MASS,
+        },
+        golf_ball: false,
+    };
+    let mut bodies =
+        vec![planet(Vec2::ZERO), planet(Vec2::X * 500.0)];
+    for i in 0..BALLS {
+        let position = match i % 4 {
+            // right on a planet's center
+            0 => Vec2::ZERO,
+            // stacked on another ball
+            1 => Vec2::new(250.0, 100.0),
+            _ => Vec2::new(i as f32 * 3.0, -200.0),
+        };
+        bodies.push(SimBody {
+            position,
+            velocity: Vec2::ZERO,
+            mass: 1.0,
+            point_mass: PointMass::AffectedByGravity,
+            golf_ball: true,
+        });
+    }
+    bodies
+}
+
+/// Solves `bodies` through a `ParticleSet` the way the game
+/// does, one acceleration per body, in order.
+fn particle_accelerations(
+    bodies: &[(Vec3, PointMass)],
+) -> Vec<Vec3> {
+    let mut particle_set = ParticleSet::new();
+    for (i, (position, point_mass)) in
+        bodies.iter().enumerate()
+    {
+        particle_set.add(Body::new(
+            *position,
+            point_mass.mu(),
+            Entity::from_raw(i as u32),
+        ));
+    }
+    let mut accelerations: Vec<(u32, Vec3)> = particle_set
+        .result()
+        .map(|(body, acceleration)| {
+            (body.entity.id(), acceleration)
+        })
+        .collect();
+    accelerations.sort_by_key(|(id, _)| *id);
+    accelerations
+        .into_iter()
+        .map(|(_, acceleration)| acceleration)
+        .collect()
+}
+
+/// One planet and two massless balls almost touching.
+#[test]
+fn massless_balls_ignore_each_other_in_a_particle_set() {
+    let planet = (
+        Vec3::ZERO,
+        PointMass::HasGravity { mass: PLANET_MASS },
+    );
+    let first = (
+        Vec3::new(200.0, 0.0, 0.0),
+        PointMass::AffectedByGravity,
+    );
+    let second = (
+        Vec3::new(200.0, 0.5, 0.0),
+        PointMass::AffectedByGravity,
+    );
+    let together = particle_accelerations(&[
+        planet.clone(),
+        first.clone(),
+        second.clone(),
+    ]);
+    let first_alone =
+        particle_accelerations(&[planet.clone(), first]);
+    let second_alone =
+        particle_accelerations(&[planet, second]);
+
+    assert_eq!(
+        together[0],
+        Vec3::ZERO,
+        "the massless balls pulled on the planet"
+    );
+    assert_eq!(
+        together[1], first_alone[1],
+        "the second ball pulled on the first"
+    );
+    assert_eq!(
+        together[2], second_alone[1],
+        "the first ball pulled on the second"
+    );
+    for acceleration in &together[1..] {
+        assert!(
+            acceleration.is_finite()
+                && acceleration.x < 0.0,
+            "a ball isn't falling towards the planet: {:?}",
+            acceleration
+        );
+    }
+}
+
+fn assert_finite(bodies: &[SimBody], what: &str) {
+    for (i, body) in bodies.iter().enumerate() {
+        assert!(
+            body.position.is_finite()
+                && body.velocity.is_finite(),
+            "{}: body {} went to {:?}, {:?}",
+            what,
+            i,
+            body.position,
+            body.velocity
+        );
+    }
+}
+
+#[test]
+fn massless_balls_stay_finite_and_pull_on_nothing() {
+    let mut particle_set = ParticleSet::new();
+    let mut cache = GravityCache::default();
+    for precision in [Precision::F32, Precision::F64] {
+        for (softening, falloff) in
+            [(0.0, 2.0), (5.0, 2.0), (0.0, 1.0), (0.0, 3.0)]
+        {
+            let what = format!(
+                "{:?}, softening {}, falloff {}",
+                precision, softening, falloff
+            );
+            let mut bodies = scene();
+            let mut alone = bodies[..2].to_vec();
+            for _ in 0..STEPS {
+                step_gravity(
+                    &mut particle_set,
+                    &mut cache,
+                    &mut bodies,
+                    DT,
+                    Integrator::SemiImplicitEuler,
+                    GravityMode::All,
+                    &GravitySettings {
+                        softening,
+                        falloff_exponent: falloff,
+                        ..default()
+                    },
+                    precision,
+                );
+                step_gravity(
+                    &mut particle_set,
+                    &mut cache,
+                    &mut alone,
+                    DT,
+                    Integrator::SemiImplicitEuler,
+                    GravityMode::All,
+                    &GravitySettings {
+                        softening,
+                        falloff_exponent: falloff,
+                        ..default()
+                    },
+                    precision,
+                );
+            }
+            assert_finite(&bodies, &what);
+            for (crowded, alone) in
+                bodies.iter().zip(&alone)
+            {
+                assert_eq!(
+                    crowded.position, alone.position,
+                    "{}: massless balls moved a planet",
+                    what
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn massless_and_close_sources_stay_finite() {
+    // a massless source, and a massive one so close
+    // its falloff underflows
+    let sources = [
+        GravitySource {
+            position: Vec2::ZERO,
+            mu: 0.0,
+        },
+        GravitySource {
+            position: Vec2::new(1e-20, 0.0),
+            mu: PLANET_MASS,
+        },
+    ];
+    let pull =
+        falloff_gravity_at(&sources, Vec2::ZERO, 0.0, 2.0);
+    assert!(
+        pull.is_finite(),
+        "a close source gave {:?}",
+        pull
+    );
+    assert_eq!(
+        falloff_gravity_at(
+            &sources[..1],
+            Vec2::X,
+            0.0,
+            2.0
+        ),
+        Vec2::ZERO,
+        "a massless source pulled"
+    );
+    // and a body with no mass of its own
+    let average = average_gravity(
+        &sources[1..],
+        Vec2::X * 100.0,
+        Vec2::ZERO,
+        0.0,
+        DT,
+        8,
+        0.0,
+        2.0,
+        None,
+    );
+    assert!(
+        average.is_finite(),
+        "a massless body averaged {:?}",
+        average
+    );
+}
@@ -0,0 +1,147 @@
+//! Drops a ball from `spawn_ball` from far above a small
+//! planet, headless through rapier with only the planet's
+//! pull on it, once with no `GravitySettings::max_speed`
+//! and once with the default. Falling that far the ball
+//! covers more than the planet's width each step by the
+//! time it arrives, so unclamped it falls straight through,
+//! and clamped the planet has to catch it.
+use bevy::{
+    asset::AssetPlugin, hierarchy::HierarchyPlugin,
+    prelude::*, transform::TransformPlugin,
+};
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::{
+        spawn_ball, BallAssets, GolfBall, GolfBallSettings,
+        PointMass,
+    },
+    gravity::{clamp_speeds, GravityMode, GravitySettings},
+    sync_particle_set, Body, GravitySystem,
+    PIXELS_PER_METER,
+};
+
+const DT: f32 = 1.0 / 60.0;
+const STEPS: usize = 300;
+const PLANET_RADIUS: f32 = 30.0;
+const PLANET_MASS: f32 = 1E8;
+const START: Vec2 = Vec2::new(0.0, 1000.0);
+
+type Physics = RapierPhysicsPlugin<NoUserData>;
+
+fn drop_ball(
+    mut commands: Commands,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        .spawn_bundle(TransformBundle::default())
+        .insert(RigidBody::Fixed)
+        .insert(Collider::ball(PLANET_RADIUS))
+        .insert(PointMass::HasGravity {
+            mass: PLANET_MASS,
+        });
+    spawn_ball(
+        &mut commands,
+        &mut ball_assets,
+        &mut meshes,
+        &mut materials,
+        START.extend(0.0),
+        Vec2::ZERO,
+        Color::WHITE,
+        &GolfBallSettings::default(),
+    );
+}
+
+/// The particle set's pull, as `main` applies it when
+/// nothing softens or cuts it off.
+fn accelerate(
+    mut particle_set: ResMut<ParticleSet<Body>>,
+    mut forces: Query<&mut ExternalForce>,
+) {
+    for (body, gravity) in particle_set.result() {
+        if let Ok(mut force) = forces.get_mut(body.entity) {
+            force.force = gravity.truncate();
+        }
+    }
+}
+
+/// The lowest the ball gets, and the fastest it goes.
+fn fall(max_speed: Option<f32>) -> (f32, f32) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .init_resource::<BallAssets>()
+        .insert_resource(ParticleSet::<Body>::new())
+        .insert_resource(GravityMode::All)
+        .insert_resource(GravitySettings {
+            max_speed,
+            ..default()
+        })
+        .insert_resource(RapierConfiguration {
+            gravity: Vec2::ZERO,
+            timestep_mode: TimestepMode::Fixed {
+                dt: DT,
+                substeps: 1,
+            },
+            ..default()
+        })
+        .add_plugin(Physics::pixels_per_meter(
+            PIXELS_PER_METER,
+        ))
+        .add_startup_system(drop_ball)
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            sync_particle_set.label(GravitySystem::Sync),
+        )
+        .add_system(
+            accelerate.label(GravitySystem::Accelerate),
+        )
+        .add_system(
+            clamp_speeds.after(GravitySystem::Accelerate),
+        );
+    let mut lowest = START.y;
+    let mut fastest: f32 = 0.0;
+    for _ in 0..STEPS {
+        app.update();
+        let mut balls = app.world.query_filtered::<
+            (&Transform, &Velocity),
+            With<GolfBall>,
+        >();
+        let (transform, velocity) =
+            balls.single(&app.world);
+        lowest = lowest.min(transform.translation.y);
+        fastest = fastest.max(velocity.linvel.length());
+    }
+    (lowest, fastest)
+}
+
+#[test]
+fn a_small_planet_catches_a_ball_dropped_from_far() {
+    let (lowest, fastest) = fall(None);
+    assert!(
+        fastest * DT > 2.0 * PLANET_RADIUS,
+        "the ball only got to {} unclamped, so it's too \
+         slow to show anything",
+        fastest
+    );
+    assert!(
+        lowest < 0.0,
+        "the planet caught the ball without a max_speed, \
+         lowest it got was {}",
+        lowest
+    );
+
+    let (lowest, _) =
+        fall(GravitySettings::default().max_speed);
+    assert!(
+        lowest > 0.0,
+        "the ball fell through the planet to {}",
+        lowest
+    );
+}
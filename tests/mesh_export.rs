@@ -0,0 +1,69 @@
+//! Exports cubesphere planets at a few resolutions as OBJ
+//! files and reads them back: every vertex, normal and
+//! triangle of the mesh has to make it into the file, and
+//! every face has to point at vertices that are there.
+use bevy::{
+    prelude::*,
+    render::mesh::{Indices, VertexAttributeValues},
+};
+use space_golf::{
+    mesh_export::export_planet_mesh, planet::PlanetMesh,
+};
+use std::fs;
+
+#[test]
+fn exported_planets_keep_every_vertex_and_face() {
+    let dir = std::env::temp_dir().join("space-golf-test");
+    for resolution in [2, 5, 20] {
+        let mesh = Mesh::from(PlanetMesh { resolution });
+        let vertices = match mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+        {
+            Some(VertexAttributeValues::Float32x3(
+                positions,
+            )) => positions.len(),
+            _ => panic!("the planet has no positions"),
+        };
+        let triangles = match mesh.indices() {
+            Some(Indices::U32(indices)) => {
+                indices.len() / 3
+            }
+            _ => {
+                panic!("the planet isn't indexed with u32")
+            }
+        };
+
+        let path =
+            dir.join(format!("planet_{}.obj", resolution));
+        export_planet_mesh(&mesh, &path)
+            .expect("couldn't write the planet");
+        let obj = fs::read_to_string(&path)
+            .expect("couldn't read the planet back");
+        let count = |prefix: &str| {
+            obj.lines()
+                .filter(|line| line.starts_with(prefix))
+                .count()
+        };
+        assert_eq!(count("v "), vertices, "vertices");
+        assert_eq!(count("vn "), vertices, "normals");
+        assert_eq!(count("f "), triangles, "triangles");
+        for face in obj
+            .lines()
+            .filter(|line| line.starts_with("f "))
+        {
+            for corner in face.split_whitespace().skip(1) {
+                let index: usize = corner
+                    .split('/')
+                    .next()
+                    .and_then(|index| index.parse().ok())
+                    .expect("a face corner isn't a number");
+                assert!(
+                    (1..=vertices).contains(&index),
+                    "{} points at a missing vertex",
+                    face
+                );
+            }
+        }
+        let _ = fs::remove_file(&path);
+    }
+}
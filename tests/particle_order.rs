@@ -0,0 +1,107 @@
+//! Spreads bodies over several archetypes, so a query
+//! hands them out in a different order from the one they
+//! were spawned in, and checks `sync_particle_set` still
+//! adds them in `Entity` order. Then moves some of them to
+//! new archetypes and checks again.
+use bevy::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::{GolfBall, PointMass},
+    gravity::{Attractor, GravityMode, GravitySettings},
+    sync_particle_set, Body,
+};
+
+const BODIES: usize = 24;
+
+#[derive(Component)]
+struct Tag;
+
+fn order(app: &App) -> Vec<Entity> {
+    app.world
+        .resource::<ParticleSet<Body>>()
+        .iter()
+        .map(|body| body.entity)
+        .collect()
+}
+
+/// particular keeps bodies with and without a `mu` apart,
+/// each in the order they were added
+fn assert_sorted(app: &App) {
+    let particle_set =
+        app.world.resource::<ParticleSet<Body>>();
+    assert_eq!(particle_set.iter().count(), BODIES);
+    for massive in [true, false] {
+        let entities: Vec<Entity> = particle_set
+            .iter()
+            .filter(|body| (body.mu != 0.0) == massive)
+            .map(|body| body.entity)
+            .collect();
+        assert_in_order(&entities);
+    }
+}
+
+fn assert_in_order(entities: &[Entity]) {
+    assert!(
+        entities.windows(2).all(|pair| {
+            (pair[0].id(), pair[0].generation())
+                < (pair[1].id(), pair[1].generation())
+        }),
+        "bodies were added out of order: {:?}",
+        entities
+    );
+}
+
+#[test]
+fn bodies_are_added_in_entity_order() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(ParticleSet::<Body>::new())
+        .init_resource::<GravityMode>()
+        .init_resource::<GravitySettings>()
+        .add_system(sync_particle_set);
+
+    let mut entities = Vec::new();
+    for i in 0..BODIES {
+        let transform = GlobalTransform::from_translation(
+            Vec3::new(i as f32 * 10.0, 0.0, 0.0),
+        );
+        let mut entity = app.world.spawn();
+        entity.insert(transform);
+        // every third is a ball, every fourth an attractor
+        // and every fifth tagged, so they end up in a
+        // jumble of archetypes
+        if i % 4 == 0 {
+            entity.insert(Attractor { mass: 10.0 });
+        } else {
+            entity.insert(PointMass::HasGravity {
+                mass: i as f32,
+            });
+        }
+        if i % 3 == 0 {
+            entity.insert(GolfBall);
+        }
+        if i % 5 == 0 {
+            entity.insert(Tag);
+        }
+        entities.push(entity.id());
+    }
+
+    app.update();
+    assert_sorted(&app);
+    let first = order(&app);
+
+    // moving bodies between archetypes reshuffles the
+    // query but mustn't change the order they're added in
+    for entity in entities.iter().step_by(2) {
+        let mut entity = app.world.entity_mut(*entity);
+        if entity.contains::<Tag>() {
+            entity.remove::<Tag>();
+        } else {
+            entity.insert(Tag);
+        }
+    }
+    app.update();
+    assert_sorted(&app);
+    let second = order(&app);
+    assert_eq!(first, second);
+}
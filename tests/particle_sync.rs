@@ -0,0 +1,225 @@
+//! Syncs the `ParticleSet` with a thousand bodies frame
+//! after frame, the bodies moving each time, and checks
+//! the set holds on to its storage instead of rebuilding
+//! it: a frame has to make no more allocations with a
+//! thousand bodies than with ten. Then spawns, despawns
+//! and swaps bodies between pulling and not, and checks
+//! the set always holds what a fresh rebuild would.
+use bevy::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::{GolfBall, PointMass},
+    gravity::{GravityMode, GravitySettings},
+    sync_particle_set, Body,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+const BODIES: usize = 1000;
+const FRAMES: usize = 600;
+
+/// Counts every allocation, so a frame's can be compared.
+/// Tests run side by side on their own threads, so each
+/// thread only counts its own.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> =
+        const { Cell::new(0) };
+}
+
+fn count_allocation() {
+    // the count is gone while a thread is shutting down
+    let _ = ALLOCATIONS
+        .try_with(|count| count.set(count.get() + 1));
+}
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        count_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Every tenth body is a planet, the rest are balls.
+fn spawn_world(bodies: usize) -> World {
+    let mut world = World::new();
+    world.insert_resource(ParticleSet::<Body>::new());
+    world.insert_resource(GravityMode::default());
+    world.insert_resource(GravitySettings::default());
+    for i in 0..bodies {
+        spawn_body(&mut world, i);
+    }
+    world
+}
+
+fn spawn_body(world: &mut World, i: usize) -> Entity {
+    let mut entity = world.spawn();
+    entity.insert(GlobalTransform::from_translation(
+        Vec3::new(i as f32 * 10.0, 0.0, 0.0),
+    ));
+    if i % 10 == 0 {
+        entity.insert(PointMass::HasGravity { mass: 10E5 });
+    } else {
+        entity
+            .insert(PointMass::AffectedByGravity)
+            .insert(GolfBall);
+    }
+    entity.id()
+}
+
+/// Nudges every body along, like a frame of flight.
+fn move_bodies(
+    world: &mut World,
+    transforms: &mut QueryState<&mut GlobalTransform>,
+    frame: usize,
+) {
+    for mut transform in transforms.iter_mut(world) {
+        let moved = transform.translation()
+            + Vec3::new(0.0, frame as f32 * 0.1, 0.0);
+        *transform =
+            GlobalTransform::from_translation(moved);
+    }
+}
+
+/// The set's storage, which a rebuild moves.
+fn storage(world: &World) -> Option<*const Body> {
+    world
+        .resource::<ParticleSet<Body>>()
+        .iter()
+        .next()
+        .map(|body| body as *const Body)
+}
+
+/// What a fresh rebuild of the set would hold, in the
+/// order particular hands it out: massive bodies in
+/// `Entity` order, then massless ones.
+fn assert_synced(world: &mut World) {
+    let mut expected: Vec<(Entity, Vec3, f32)> = world
+        .query::<(Entity, &GlobalTransform, &PointMass)>()
+        .iter(world)
+        .map(|(entity, transform, point_mass)| {
+            (
+                entity,
+                transform.translation(),
+                point_mass.mu(),
+            )
+        })
+        .collect();
+    expected.sort_by_key(|(entity, ..)| {
+        (entity.id(), entity.generation())
+    });
+    let (mut in_order, massless): (Vec<_>, Vec<_>) =
+        expected
+            .into_iter()
+            .partition(|(_, _, mu)| *mu != 0.0);
+    in_order.extend(massless);
+
+    let synced: Vec<(Entity, Vec3, f32)> = world
+        .resource::<ParticleSet<Body>>()
+        .iter()
+        .map(|body| (body.entity, body.position, body.mu))
+        .collect();
+    assert_eq!(synced, in_order, "the set drifted");
+}
+
+/// Allocations in an average frame of syncing `bodies`
+/// that keep moving, once the set has been built.
+fn allocations_per_frame(bodies: usize) -> f32 {
+    let mut world = spawn_world(bodies);
+    let mut stage = SystemStage::single_threaded()
+        .with_system(sync_particle_set);
+    let mut transforms =
+        world.query::<&mut GlobalTransform>();
+    // the first frames build the set and size every buffer
+    for frame in 0..10 {
+        move_bodies(&mut world, &mut transforms, frame);
+        stage.run(&mut world);
+    }
+    let storage_before = storage(&world);
+
+    let before = allocations();
+    for frame in 0..FRAMES {
+        move_bodies(&mut world, &mut transforms, frame);
+        stage.run(&mut world);
+    }
+    let per_frame =
+        (allocations() - before) as f32 / FRAMES as f32;
+    assert_eq!(
+        storage(&world),
+        storage_before,
+        "the set was rebuilt while the same bodies moved"
+    );
+    assert_synced(&mut world);
+    per_frame
+}
+
+#[test]
+fn syncing_doesnt_allocate_per_body() {
+    let few = allocations_per_frame(10);
+    let many = allocations_per_frame(BODIES);
+    assert!(
+        many <= few,
+        "syncing {} bodies allocates more than syncing 10",
+        BODIES
+    );
+}
+
+// bodies coming, going and starting to pull all have to
+// show up just like a rebuild
+#[test]
+fn the_set_matches_a_rebuild() {
+    let mut world = spawn_world(BODIES);
+    let mut stage = SystemStage::single_threaded()
+        .with_system(sync_particle_set);
+    stage.run(&mut world);
+    assert_synced(&mut world);
+
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, With<PointMass>>()
+        .iter(&world)
+        .collect();
+    for entity in entities.iter().step_by(7) {
+        world.despawn(*entity);
+    }
+    stage.run(&mut world);
+    assert_synced(&mut world);
+
+    for i in 0..20 {
+        spawn_body(&mut world, BODIES + i);
+    }
+    stage.run(&mut world);
+    assert_synced(&mut world);
+
+    for entity in entities.iter().skip(1).step_by(7).take(5)
+    {
+        world
+            .entity_mut(*entity)
+            .insert(PointMass::HasGravity { mass: 1.0 });
+    }
+    stage.run(&mut world);
+    assert_synced(&mut world);
+}
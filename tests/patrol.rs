@@ -0,0 +1,102 @@
+//! Sends a planet round a circular `Path` and watches the
+//! pull it has on a ball sitting at the middle of the
+//! circle. The planet keeps the same distance, so the pull
+//! should stay as strong but swing round with it.
+use bevy::{prelude::*, transform::TransformPlugin};
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::{GolfBall, PointMass},
+    gravity::{GravityMode, GravitySettings, G},
+    patrol::{Path, PathKind, PatrolPlugin},
+    sim_state::PhysicsActive,
+    sync_particle_set,
+    time_scale::TimeScale,
+    Body,
+};
+use std::{
+    f32::consts::PI,
+    time::{Duration, Instant},
+};
+
+const RADIUS: f32 = 200.0;
+const PLANET_MASS: f32 = 10E5;
+/// seconds per trip round the circle
+const PERIOD: f32 = 1.0;
+
+#[test]
+fn the_pull_follows_a_patrolling_planet() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .insert_resource(ParticleSet::<Body>::new())
+        .init_resource::<GravityMode>()
+        .init_resource::<GravitySettings>()
+        .init_resource::<PhysicsActive>()
+        .init_resource::<TimeScale>()
+        .add_plugin(PatrolPlugin)
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            sync_particle_set,
+        );
+
+    let path = Path::new(
+        PathKind::Circle {
+            center: Vec2::ZERO,
+            radius: RADIUS,
+        },
+        2.0 * PI * RADIUS / PERIOD,
+    );
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(
+            Transform::from_translation(
+                path.position(0.0).extend(0.0),
+            ),
+        ))
+        .insert(PointMass::HasGravity { mass: PLANET_MASS })
+        .insert(path);
+    let ball = app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .insert(PointMass::AffectedByGravity)
+        .insert(GolfBall)
+        .id();
+
+    let expected = G * PLANET_MASS / (RADIUS * RADIUS);
+    let started = Instant::now();
+    let mut last_angle: Option<f32> = None;
+    let mut turned = 0.0;
+    while started.elapsed()
+        < Duration::from_secs_f32(PERIOD * 1.2)
+    {
+        app.update();
+        let pull = app
+            .world
+            .resource_mut::<ParticleSet<Body>>()
+            .result()
+            .find(|(body, _)| body.entity == ball)
+            .map(|(_, pull)| pull.truncate())
+            .unwrap();
+        let relative =
+            (pull.length() - expected).abs() / expected;
+        assert!(
+            relative < 0.01,
+            "the pull was {} instead of {}",
+            pull.length(),
+            expected
+        );
+        let angle = pull.y.atan2(pull.x);
+        if let Some(last) = last_angle {
+            // unwrapped, so a whole turn adds up to TAU
+            let step = (angle - last + PI)
+                .rem_euclid(2.0 * PI)
+                - PI;
+            turned += step;
+        }
+        last_angle = Some(angle);
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    // counter clockwise, and most of the way round
+    assert!(turned > PI, "the pull only turned {}", turned);
+}
@@ -0,0 +1,169 @@
+//! Flies a ball from `spawn_ball` past a lone planet,
+//! headless through rapier, once with every pull as an
+//! `ExternalForce` worked out from the particle set and
+//! once with `GravityPipeline::RapierGravity` handing the
+//! planet's pull to rapier's gravity. Both have to trace
+//! the same path.
+use bevy::{
+    asset::AssetPlugin, hierarchy::HierarchyPlugin,
+    prelude::*, transform::TransformPlugin,
+};
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::{
+        spawn_ball, BallAssets, GolfBall, GolfBallSettings,
+        PointMass,
+    },
+    gravity::{
+        hand_gravity_to_rapier, GravityMode,
+        GravityPipeline, GravitySettings,
+    },
+    sync_particle_set,
+    undo::ShotHistory,
+    Body, GravitySystem, PIXELS_PER_METER,
+};
+
+const DT: f32 = 1.0 / 60.0;
+const STEPS: usize = 120;
+const PLANET_MASS: f32 = 200_000.0;
+const START: Vec2 = Vec2::new(0.0, 200.0);
+const SPEED: f32 = 300.0;
+
+type Physics = RapierPhysicsPlugin<NoUserData>;
+
+fn launch(
+    mut commands: Commands,
+    mut history: ResMut<ShotHistory>,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        .spawn_bundle(TransformBundle::default())
+        .insert(RigidBody::Fixed)
+        .insert(PointMass::HasGravity {
+            mass: PLANET_MASS,
+        });
+    let ball = spawn_ball(
+        &mut commands,
+        &mut ball_assets,
+        &mut meshes,
+        &mut materials,
+        START.extend(0.0),
+        Vec2::X * SPEED,
+        Color::WHITE,
+        &GolfBallSettings::default(),
+    );
+    history.push(ball);
+}
+
+/// The particle set's pull, as `main` applies it when
+/// nothing softens or cuts it off.
+fn accelerate(
+    mut particle_set: ResMut<ParticleSet<Body>>,
+    mut forces: Query<&mut ExternalForce>,
+) {
+    for (body, gravity) in particle_set.result() {
+        if let Ok(mut force) = forces.get_mut(body.entity) {
+            force.force = gravity.truncate();
+        }
+    }
+}
+
+/// Where the ball is after every step, and the largest
+/// gravity rapier was handed on the way.
+fn fly(pipeline: GravityPipeline) -> (Vec<Vec2>, f32) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .init_resource::<BallAssets>()
+        .init_resource::<ShotHistory>()
+        .insert_resource(ParticleSet::<Body>::new())
+        .insert_resource(GravityMode::All)
+        .insert_resource(GravitySettings {
+            pipeline,
+            max_speed: None,
+            ..default()
+        })
+        .insert_resource(RapierConfiguration {
+            gravity: Vec2::ZERO,
+            timestep_mode: TimestepMode::Fixed {
+                dt: DT,
+                substeps: 1,
+            },
+            ..default()
+        })
+        .add_plugin(Physics::pixels_per_meter(
+            PIXELS_PER_METER,
+        ))
+        .add_startup_system(launch)
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            sync_particle_set.label(GravitySystem::Sync),
+        )
+        .add_system(
+            accelerate.label(GravitySystem::Accelerate),
+        )
+        .add_system(
+            hand_gravity_to_rapier
+                .after(GravitySystem::Accelerate),
+        );
+    let mut path = Vec::new();
+    let mut strongest: f32 = 0.0;
+    for _ in 0..STEPS {
+        app.update();
+        let mut balls = app
+            .world
+            .query_filtered::<&Transform, With<GolfBall>>();
+        path.push(
+            balls.single(&app.world).translation.truncate(),
+        );
+        strongest = strongest.max(
+            app.world
+                .resource::<RapierConfiguration>()
+                .gravity
+                .length(),
+        );
+    }
+    (path, strongest)
+}
+
+#[test]
+fn rapier_gravity_follows_the_particle_set() {
+    let (forces, unused) =
+        fly(GravityPipeline::ExternalForce);
+    assert_eq!(unused, 0.0);
+    let (rapier, handed) =
+        fly(GravityPipeline::RapierGravity);
+    assert!(
+        handed > 0.0,
+        "rapier was never handed gravity"
+    );
+
+    let start = forces[0];
+    let end = forces[STEPS - 1];
+    assert!(
+        end.distance(start) > 100.0,
+        "the ball barely moved, so the paths can't differ"
+    );
+    assert!(
+        end.y < START.y - 10.0,
+        "the planet never bent the ball's path"
+    );
+    for (step, (forces, rapier)) in
+        forces.iter().zip(&rapier).enumerate()
+    {
+        assert!(
+            forces.distance(*rapier) < 1.0,
+            "step {}: {} with forces, {} through rapier",
+            step,
+            forces,
+            rapier
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Round trips shots through `ShareCode`: every code has
+//! to decode back to exactly the shot it was made from,
+//! using nothing but URL-safe characters. Codes from a
+//! newer format, cut short, mistyped or with stray
+//! characters have to be turned down with the matching
+//! `ShareError` rather than misread.
+use bevy::prelude::*;
+use space_golf::share::{
+    ShareCode, ShareError, SHARE_CODE_VERSION,
+};
+
+fn shots() -> [ShareCode; 3] {
+    [
+        ShareCode {
+            level: 0,
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+        },
+        ShareCode {
+            level: 3,
+            position: Vec2::new(0.0, -250.0),
+            velocity: Vec2::new(123.456, -78.9),
+        },
+        ShareCode {
+            level: u16::MAX,
+            position: Vec2::new(-1e-7, f32::MAX),
+            velocity: Vec2::new(f32::MIN, 1e30),
+        },
+    ]
+}
+
+#[test]
+fn codes_round_trip() {
+    for shot in shots() {
+        let code = shot.encode();
+        assert!(
+            code.chars().all(|character| {
+                character.is_ascii_alphanumeric()
+                    || character == '-'
+                    || character == '_'
+            }),
+            "{} isn't URL-safe",
+            code
+        );
+        assert_eq!(ShareCode::decode(&code), Ok(shot));
+        assert_eq!(
+            ShareCode::decode(&format!(" {}\n", code)),
+            Ok(shot),
+            "whitespace around a code wasn't ignored"
+        );
+    }
+}
+
+#[test]
+fn bad_codes_are_turned_down() {
+    let code = shots()[1].encode();
+    // the first character holds the top of the version
+    // byte, so bumping it makes a code from a version this
+    // build doesn't know
+    let newer = format!("B{}", &code[1..]);
+    assert_eq!(
+        ShareCode::decode(&newer),
+        Err(ShareError::UnsupportedVersion(
+            SHARE_CODE_VERSION + 4
+        ))
+    );
+    assert!(matches!(
+        ShareCode::decode(&code[..code.len() - 4]),
+        Err(ShareError::WrongLength(_))
+    ));
+    assert_eq!(
+        ShareCode::decode(
+            &code.replace(&code[10..11], "!")
+        ),
+        Err(ShareError::InvalidCharacter('!'))
+    );
+    // one character off in the middle
+    let mut typo: Vec<char> = code.chars().collect();
+    typo[12] = if typo[12] == 'A' { 'B' } else { 'A' };
+    let typo: String = typo.into_iter().collect();
+    assert_eq!(
+        ShareCode::decode(&typo),
+        Err(ShareError::BadChecksum)
+    );
+}
@@ -0,0 +1,157 @@
+//! Plays the same share code headless through rapier, once
+//! at 30 frames a second and once at 144, past a planet
+//! that bends the shot. The code's shot has to end up in
+//! the same place either way.
+use bevy::{
+    asset::AssetPlugin, hierarchy::HierarchyPlugin,
+    input::InputPlugin, prelude::*,
+    time::create_time_channels, transform::TransformPlugin,
+};
+use bevy_rapier2d::prelude::*;
+use particular::ParticleSet;
+use space_golf::{
+    auto_aim::LaunchShot,
+    course::Course,
+    golf_ball::{
+        spawn_ball, BallAssets, GolfBall, GolfBallSettings,
+        PointMass,
+    },
+    gravity::{
+        accelerate_particles, finish_substeps,
+        GravityCache, GravityMode, GravitySettings,
+        SubstepCorrections,
+    },
+    gravity_budget::GravityBudget,
+    share::{PlayShareCode, ShareCode, ShareCodePlugin},
+    sync_particle_set,
+    time_scale::TimeScalePlugin,
+    Body, GravitySystem, PIXELS_PER_METER,
+};
+use std::time::{Duration, Instant};
+
+const FRAMES: u32 = 120;
+const PLANET_MASS: f32 = 200_000.0;
+const SHOT: ShareCode = ShareCode {
+    level: 0,
+    position: Vec2::new(0.0, 200.0),
+    velocity: Vec2::new(300.0, 0.0),
+};
+
+/// Takes every launch the way `main` does, without the
+/// stroke counting.
+fn launch(
+    mut commands: Commands,
+    mut launches: EventReader<LaunchShot>,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for shot in launches.iter() {
+        spawn_ball(
+            &mut commands,
+            &mut ball_assets,
+            &mut meshes,
+            &mut materials,
+            shot.position,
+            shot.velocity,
+            Color::WHITE,
+            &GolfBallSettings::default(),
+        );
+    }
+}
+
+/// Where the code's ball is after `FRAMES` frames that
+/// each take `frame_time` seconds.
+fn play(frame_time: f32) -> Vec2 {
+    let (sender, receiver) = create_time_channels();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(AssetPlugin)
+        .add_plugin(InputPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .insert_resource(receiver)
+        .init_resource::<BallAssets>()
+        .insert_resource(Course {
+            levels: Vec::new(),
+            current: 0,
+        })
+        .insert_resource(ParticleSet::<Body>::new())
+        .insert_resource(GravityMode::All)
+        .init_resource::<GravitySettings>()
+        .init_resource::<GravityCache>()
+        .init_resource::<GravityBudget>()
+        .init_resource::<SubstepCorrections>()
+        .insert_resource(RapierConfiguration {
+            gravity: Vec2::ZERO,
+            ..default()
+        })
+        .add_plugin(
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(
+                PIXELS_PER_METER,
+            ),
+        )
+        .add_plugin(TimeScalePlugin)
+        .add_event::<LaunchShot>()
+        .add_plugin(ShareCodePlugin)
+        .add_system(launch)
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            sync_particle_set.label(GravitySystem::Sync),
+        )
+        .add_system(
+            accelerate_particles
+                .label(GravitySystem::Accelerate),
+        )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            finish_substeps,
+        );
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .insert(RigidBody::Fixed)
+        .insert(PointMass::HasGravity {
+            mass: PLANET_MASS,
+        });
+    app.world
+        .resource_mut::<Events<PlayShareCode>>()
+        .send(PlayShareCode(SHOT.encode()));
+
+    let start = Instant::now();
+    for frame in 0..FRAMES {
+        let elapsed = frame_time * frame as f32;
+        sender
+            .0
+            .send(start + Duration::from_secs_f32(elapsed))
+            .unwrap();
+        app.update();
+    }
+    let mut balls = app
+        .world
+        .query_filtered::<&Transform, With<GolfBall>>();
+    balls.single(&app.world).translation.truncate()
+}
+
+#[test]
+fn codes_play_the_same_at_any_frame_rate() {
+    let slow = play(1.0 / 30.0);
+    let fast = play(1.0 / 144.0);
+    assert!(
+        slow.distance(SHOT.position) > 100.0,
+        "the ball barely moved, so the runs can't differ"
+    );
+    assert!(
+        slow.y < SHOT.position.y - 10.0,
+        "the planet never bent the shot"
+    );
+    assert!(
+        slow.distance(fast) < 1e-3,
+        "at 30 frames a second the shot ended at {}, at \
+         144 at {}",
+        slow,
+        fast
+    );
+}
@@ -0,0 +1,82 @@
+//! Solves levels headlessly with `solve_level`: a hole
+//! on the side of a planet facing the tee has to be found,
+//! and the launch returned has to actually pass over it.
+//! A hole buried in the middle of a planet, and a level
+//! without a tee, mustn't be solved.
+use bevy::prelude::*;
+use space_golf::{
+    gravity::{GravitySource, G},
+    hole::Hole,
+    level::Level,
+    planet_spec::PlanetSpec,
+    prediction::{
+        closest_approach, Obstacle, PredictionSettings,
+        ShotSearch,
+    },
+    solve::{solve_ball, solve_level},
+};
+use std::f32::consts::PI;
+
+#[test]
+fn only_reachable_holes_are_solved() {
+    let planet = PlanetSpec::default();
+    let mut level = Level::new();
+    level
+        .add_planet(planet.clone())
+        .set_tee(Vec2::new(-400.0, 0.0))
+        .place_hole_on_surface(0, PI, Hole::default());
+    let search = ShotSearch::default();
+
+    let solution = solve_level(&level, &search)
+        .expect("the facing hole wasn't solved");
+    assert_eq!(solution.hole, 0);
+    let (hole_position, hole) = level.holes[0];
+    assert!(
+        solution.approach.distance < hole.radius,
+        "the solution misses by {}",
+        solution.approach.distance
+    );
+    // replaying the launch by hand passes over the hole
+    // too
+    let replay = closest_approach(
+        &[GravitySource {
+            position: planet.position.truncate(),
+            mu: planet.mass * G,
+        }],
+        &[Obstacle {
+            center: planet.position.truncate(),
+            radius: planet.radius(),
+            shape: None,
+        }],
+        level.tee.unwrap(),
+        solution.velocity,
+        &solve_ball(),
+        &PredictionSettings::default(),
+        hole_position,
+    );
+    assert!(
+        replay.distance < hole.radius,
+        "the replayed solution misses by {}",
+        replay.distance
+    );
+
+    let mut buried = Level::new();
+    buried
+        .add_planet(planet.clone())
+        .set_tee(Vec2::new(-400.0, 0.0))
+        .add_hole(
+            planet.position.truncate(),
+            Hole::default(),
+        );
+    assert!(
+        solve_level(&buried, &search).is_none(),
+        "a hole inside a planet was solved"
+    );
+
+    let mut teeless = level.clone();
+    teeless.tee = None;
+    assert!(
+        solve_level(&teeless, &search).is_none(),
+        "a level without a tee was solved"
+    );
+}
@@ -0,0 +1,109 @@
+//! Spawns one ball with `spawn_ball` and checks it comes
+//! out as a whole golf ball: where it was asked for, moving
+//! as asked, and with everything rapier and the gravity
+//! systems look for.
+use bevy::{
+    asset::AssetPlugin, prelude::*, sprite::Mesh2dHandle,
+};
+use bevy_rapier2d::prelude::*;
+use space_golf::{
+    density::Density,
+    golf_ball::{
+        spawn_ball, BallAssets, BallColor, GolfBall,
+        GolfBallSettings, PointMass,
+    },
+    groups::{BALL_FILTER, BALL_GROUP},
+};
+
+const POSITION: Vec3 = Vec3::new(120.0, -40.0, 0.0);
+const VELOCITY: Vec2 = Vec2::new(-30.0, 75.0);
+
+/// the ball `spawn_ball` handed back
+#[derive(Default)]
+struct Spawned(Option<Entity>);
+
+fn spawn(
+    mut commands: Commands,
+    mut spawned: ResMut<Spawned>,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    spawned.0 = Some(spawn_ball(
+        &mut commands,
+        &mut ball_assets,
+        &mut meshes,
+        &mut materials,
+        POSITION,
+        VELOCITY,
+        Color::RED,
+        &GolfBallSettings::default(),
+    ));
+}
+
+#[test]
+fn spawned_balls_have_everything_a_ball_needs() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .init_resource::<BallAssets>()
+        .init_resource::<Spawned>()
+        .add_startup_system(spawn);
+    app.update();
+
+    let ball = app
+        .world
+        .resource::<Spawned>()
+        .0
+        .expect("spawn_ball never ran");
+    let ball = app
+        .world
+        .get_entity(ball)
+        .expect("spawn_ball returned a dead entity");
+    let settings = GolfBallSettings::default();
+
+    assert!(ball.contains::<GolfBall>());
+    assert!(ball.contains::<Mesh2dHandle>());
+    assert!(ball.contains::<ExternalForce>());
+    assert!(ball.contains::<Density>());
+    assert_eq!(
+        ball.get::<Transform>().unwrap().translation,
+        POSITION
+    );
+    assert_eq!(
+        ball.get::<Velocity>().unwrap().linvel,
+        VELOCITY
+    );
+    assert_eq!(
+        ball.get::<RigidBody>(),
+        Some(&RigidBody::Dynamic)
+    );
+    assert_eq!(
+        ball.get::<PointMass>(),
+        Some(&PointMass::AffectedByGravity)
+    );
+    assert_eq!(
+        ball.get::<BallColor>().unwrap().0,
+        Color::RED
+    );
+    assert_eq!(
+        ball.get::<Collider>()
+            .and_then(|collider| collider.as_ball())
+            .map(|shape| shape.radius()),
+        Some(settings.radius())
+    );
+    assert!(matches!(
+        ball.get::<ColliderMassProperties>(),
+        Some(ColliderMassProperties::Mass(mass))
+            if *mass == settings.mass
+    ));
+    assert_eq!(
+        ball.get::<CollisionGroups>(),
+        Some(&CollisionGroups::new(
+            BALL_GROUP,
+            BALL_FILTER
+        ))
+    );
+}
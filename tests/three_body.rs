@@ -0,0 +1,140 @@
+//! Three equal masses started on the figure-eight orbit,
+//! stepped headless with each integrator. It doesn't check
+//! that the figure eight is traced out, only the
+//! conservation laws any n-body step has to keep, so it
+//! catches regressions in `step_gravity` with more than
+//! two bodies:
+//!
+//! - total momentum starts at zero and has to stay within
+//!   [`MOMENTUM_TOLERANCE`] of the bodies' total momentum
+//!   magnitude. Forces come in equal and opposite pairs,
+//!   so only f32 rounding should move it.
+//! - total energy has to stay within [`ENERGY_TOLERANCE`]
+//!   of where it started. Both integrators are
+//!   symplectic, so the error wobbles but doesn't grow.
+use bevy::{math::DVec2, prelude::*};
+use particular::ParticleSet;
+use space_golf::{
+    golf_ball::PointMass,
+    gravity::{
+        step_gravity, GravityCache, GravityMode,
+        GravitySettings, Integrator, Precision, SimBody, G,
+    },
+};
+
+const PERIODS: f32 = 2.0;
+/// The figure eight's period with unit masses and `G` of 1.
+const PERIOD: f32 = 6.32591;
+/// World units per unit of the standard solution.
+const LENGTH: f32 = 100.0;
+const DT: f32 = 1.0 / 60.0;
+/// relative to the sum of every body's momentum
+const MOMENTUM_TOLERANCE: f32 = 1e-3;
+/// relative to the starting energy
+const ENERGY_TOLERANCE: f32 = 1e-2;
+
+/// Each body has a mass of one, so gravity's force and its
+/// acceleration are the same and `G` is the whole of each
+/// body's `mu`.
+fn figure_eight() -> Vec<SimBody> {
+    // seconds per unit of time in the standard solution,
+    // with `G` scaled in
+    let time = (LENGTH.powi(3) / G).sqrt();
+    let speed = LENGTH / time;
+    // the published digits, more than an f32 holds
+    let position =
+        DVec2::new(0.97000436, -0.24308753).as_vec2();
+    let velocity =
+        DVec2::new(-0.93240737, -0.86473146).as_vec2();
+    let body = |position: Vec2, velocity: Vec2| SimBody {
+        position: position * LENGTH,
+        velocity: velocity * speed,
+        mass: 1.0,
+        point_mass: PointMass::HasGravity { mass: 1.0 },
+        golf_ball: false,
+    };
+    vec![
+        body(position, -velocity / 2.0),
+        body(-position, -velocity / 2.0),
+        body(Vec2::ZERO, velocity),
+    ]
+}
+
+fn momentum(bodies: &[SimBody]) -> Vec2 {
+    bodies.iter().fold(Vec2::ZERO, |total, body| {
+        total + body.velocity * body.mass
+    })
+}
+
+fn energy(bodies: &[SimBody]) -> f32 {
+    let kinetic: f32 = bodies
+        .iter()
+        .map(|body| {
+            0.5 * body.mass * body.velocity.length_squared()
+        })
+        .sum();
+    let mut potential = 0.0;
+    for (i, a) in bodies.iter().enumerate() {
+        for b in bodies.iter().skip(i + 1) {
+            // gravity is applied as a force, so each pair's
+            // potential is `mu / r` rather than
+            // `mu * mass / r`
+            potential -= b.point_mass.mu()
+                / a.position.distance(b.position);
+        }
+    }
+    kinetic + potential
+}
+
+#[test]
+fn figure_eight_keeps_momentum_and_energy() {
+    let steps =
+        (PERIODS * PERIOD * (LENGTH.powi(3) / G).sqrt()
+            / DT) as usize;
+
+    for integrator in
+        [Integrator::SemiImplicitEuler, Integrator::Verlet]
+    {
+        let mut bodies = figure_eight();
+        let mut particle_set = ParticleSet::new();
+        let mut cache = GravityCache::default();
+        let start = energy(&bodies);
+        let scale: f32 = bodies
+            .iter()
+            .map(|body| {
+                (body.velocity * body.mass).length()
+            })
+            .sum();
+        let mut worst_momentum: f32 = 0.0;
+        let mut worst_energy: f32 = 0.0;
+        for _ in 0..steps {
+            step_gravity(
+                &mut particle_set,
+                &mut cache,
+                &mut bodies,
+                DT,
+                integrator,
+                GravityMode::All,
+                &GravitySettings::default(),
+                Precision::F32,
+            );
+            worst_momentum = worst_momentum
+                .max(momentum(&bodies).length() / scale);
+            worst_energy = worst_energy.max(
+                ((energy(&bodies) - start) / start).abs(),
+            );
+        }
+        assert!(
+            worst_momentum <= MOMENTUM_TOLERANCE,
+            "{:?} let momentum build up to {:.2e}",
+            integrator,
+            worst_momentum
+        );
+        assert!(
+            worst_energy <= ENERGY_TOLERANCE,
+            "{:?} let energy drift by {:.4}%",
+            integrator,
+            worst_energy * 100.0
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Exports a known trail, a ball swinging half way around
+//! a planet, and checks the SVG made it to disk.
+use bevy::prelude::*;
+use space_golf::{
+    prediction::Obstacle, trail_export::export_trail,
+};
+use std::{f32::consts::PI, fs};
+
+#[test]
+fn exported_trail_draws_the_path_and_planets() {
+    let planets = [Obstacle {
+        center: Vec2::ZERO,
+        radius: 50.0,
+        shape: None,
+    }];
+    let points: Vec<Vec2> = (0..=32)
+        .map(|i| {
+            let angle = i as f32 / 32.0 * PI;
+            Vec2::new(angle.cos(), angle.sin()) * 120.0
+        })
+        .collect();
+
+    let path = std::env::temp_dir()
+        .join("space-golf-test")
+        .join("trail.svg");
+    export_trail(&path, &points, &planets)
+        .expect("couldn't write the trail");
+
+    let svg = fs::read_to_string(&path)
+        .expect("the trail wasn't written");
+    assert!(!svg.is_empty());
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("<polyline"));
+    assert_eq!(svg.matches("<circle").count(), 1);
+    let _ = fs::remove_file(&path);
+}
@@ -0,0 +1,103 @@
+//! Fires a very fast ball from `spawn_ball` at a small
+//! planet, headless through rapier, once without
+//! `GolfBallSettings::ccd` and once with it. The ball
+//! covers several times the planet's width each step, so
+//! without CCD it jumps clean over the planet, and with
+//! CCD it has to hit it and stay on the near side.
+use bevy::{
+    asset::AssetPlugin, hierarchy::HierarchyPlugin,
+    prelude::*, transform::TransformPlugin,
+};
+use bevy_rapier2d::prelude::*;
+use space_golf::{
+    golf_ball::{
+        spawn_ball, BallAssets, GolfBall, GolfBallSettings,
+    },
+    PIXELS_PER_METER,
+};
+
+const DT: f32 = 1.0 / 60.0;
+const STEPS: usize = 30;
+const PLANET_RADIUS: f32 = 5.0;
+const START: Vec2 = Vec2::new(-300.0, 0.0);
+/// over 300 pixels a step
+const SPEED: f32 = 20000.0;
+
+type Physics = RapierPhysicsPlugin<NoUserData>;
+
+/// whether the ball is launched with CCD
+struct UseCcd(bool);
+
+fn launch(
+    mut commands: Commands,
+    use_ccd: Res<UseCcd>,
+    mut ball_assets: ResMut<BallAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands
+        .spawn_bundle(TransformBundle::default())
+        .insert(RigidBody::Fixed)
+        .insert(Collider::ball(PLANET_RADIUS));
+    spawn_ball(
+        &mut commands,
+        &mut ball_assets,
+        &mut meshes,
+        &mut materials,
+        START.extend(0.0),
+        Vec2::X * SPEED,
+        Color::WHITE,
+        &GolfBallSettings {
+            ccd: use_ccd.0,
+            ..default()
+        },
+    );
+}
+
+/// Where the ball ends up along the x axis.
+fn fire(ccd: bool) -> f32 {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .init_resource::<BallAssets>()
+        .insert_resource(UseCcd(ccd))
+        .insert_resource(RapierConfiguration {
+            gravity: Vec2::ZERO,
+            timestep_mode: TimestepMode::Fixed {
+                dt: DT,
+                substeps: 1,
+            },
+            ..default()
+        })
+        .add_plugin(Physics::pixels_per_meter(
+            PIXELS_PER_METER,
+        ))
+        .add_startup_system(launch);
+    for _ in 0..STEPS {
+        app.update();
+    }
+    let mut balls = app
+        .world
+        .query_filtered::<&Transform, With<GolfBall>>();
+    balls.single(&app.world).translation.x
+}
+
+#[test]
+fn ccd_stops_a_fast_ball_tunnelling() {
+    let without = fire(false);
+    assert!(
+        without > 0.0,
+        "the ball hit the planet without CCD, so it's too \
+         slow to show anything"
+    );
+
+    let with = fire(true);
+    assert!(
+        with < 0.0,
+        "the ball tunnelled through the planet with CCD"
+    );
+}
@@ -0,0 +1,78 @@
+//! Turns typed angles and powers into launch velocities
+//! the way the "Launch" window does: angles go counter
+//! clockwise from +x, power over the maximum is clamped to
+//! it, and anything that isn't a usable number is turned
+//! down with the matching `LaunchInputError`.
+use bevy::prelude::*;
+use space_golf::typed_launch::{
+    typed_velocity, LaunchInputError,
+};
+
+const MAX_POWER: f32 = 600.0;
+
+fn assert_close(velocity: Vec2, expected: Vec2) {
+    assert!(
+        velocity.abs_diff_eq(expected, 1e-3),
+        "got {:?} instead of {:?}",
+        velocity,
+        expected
+    );
+}
+
+fn launch(
+    angle: &str,
+    power: &str,
+) -> Result<Vec2, LaunchInputError> {
+    typed_velocity(angle, power, MAX_POWER)
+}
+
+#[test]
+fn typed_shots_become_velocities() {
+    assert_close(
+        launch("0", "100").unwrap(),
+        Vec2::new(100.0, 0.0),
+    );
+    assert_close(
+        launch(" 90 ", "250.5").unwrap(),
+        Vec2::new(0.0, 250.5),
+    );
+    assert_close(
+        launch("-135", "10").unwrap(),
+        Vec2::new(-1.0, -1.0).normalize() * 10.0,
+    );
+    assert_close(launch("180", "0").unwrap(), Vec2::ZERO);
+    assert_close(
+        launch("45", "9000").unwrap(),
+        Vec2::new(1.0, 1.0).normalize() * MAX_POWER,
+    );
+}
+
+#[test]
+fn bad_input_is_turned_down() {
+    assert_eq!(
+        launch("north", "100"),
+        Err(LaunchInputError::InvalidAngle(
+            "north".to_string()
+        ))
+    );
+    assert_eq!(
+        launch("inf", "100"),
+        Err(LaunchInputError::InvalidAngle(
+            "inf".to_string()
+        ))
+    );
+    assert_eq!(
+        launch("30", ""),
+        Err(LaunchInputError::InvalidPower(String::new()))
+    );
+    assert_eq!(
+        launch("30", "NaN"),
+        Err(LaunchInputError::InvalidPower(
+            "NaN".to_string()
+        ))
+    );
+    assert_eq!(
+        launch("30", "-5"),
+        Err(LaunchInputError::NegativePower(-5.0))
+    );
+}
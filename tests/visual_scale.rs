@@ -0,0 +1,98 @@
+//! Spawns a planet drawn bigger than it really is and
+//! checks `DensityPlugin` leaves it that way: the collider
+//! keeps the planet's true radius and the mesh keeps
+//! `visual_scale` on top of it, on the frame it's spawned
+//! and after its density changes.
+use bevy::{
+    asset::AssetPlugin, prelude::*, sprite::Mesh2dHandle,
+};
+use bevy_rapier2d::prelude::*;
+use space_golf::{
+    density::{mesh_radius, Density, DensityPlugin},
+    planet_spec::{spawn_planet, Planet, PlanetSpec},
+};
+
+const VISUAL_SCALE: f32 = 1.5;
+
+fn spec() -> PlanetSpec {
+    PlanetSpec {
+        visual_scale: VISUAL_SCALE,
+        ..default()
+    }
+}
+
+fn spawn(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    spawn_planet(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &spec(),
+    );
+}
+
+/// The planet's collider radius and the radius its mesh is
+/// drawn at.
+fn radii(app: &mut App) -> (f32, f32) {
+    let mut planets = app.world.query_filtered::<(
+        &Collider,
+        &Mesh2dHandle,
+    ), With<Planet>>();
+    let (collider, mesh) = planets.single(&app.world);
+    let meshes = app.world.resource::<Assets<Mesh>>();
+    (
+        collider.as_ball().unwrap().radius(),
+        mesh_radius(meshes.get(&mesh.0).unwrap()).unwrap(),
+    )
+}
+
+#[test]
+fn resizing_keeps_the_visual_scale() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<ColorMaterial>()
+        .add_plugin(DensityPlugin)
+        .add_startup_system(spawn);
+    app.update();
+
+    let radius = spec().radius();
+    let (collider, drawn) = radii(&mut app);
+    assert!(
+        (collider - radius).abs() < 1e-3,
+        "the collider is {} across instead of {}",
+        collider,
+        radius
+    );
+    assert!(
+        (drawn - radius * VISUAL_SCALE).abs() < 1e-2,
+        "the mesh is {} across instead of {}",
+        drawn,
+        radius * VISUAL_SCALE
+    );
+
+    // a quarter of the density takes twice the radius
+    let mut densities = app
+        .world
+        .query_filtered::<&mut Density, With<Planet>>();
+    densities.single_mut(&mut app.world).0 /= 4.0;
+    app.update();
+
+    let (collider, drawn) = radii(&mut app);
+    assert!(
+        (collider - radius * 2.0).abs() < 1e-2,
+        "the collider is {} across instead of {}",
+        collider,
+        radius * 2.0
+    );
+    assert!(
+        (drawn - radius * 2.0 * VISUAL_SCALE).abs() < 1e-2,
+        "the mesh is {} across instead of {}",
+        drawn,
+        radius * 2.0 * VISUAL_SCALE
+    );
+}
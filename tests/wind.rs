@@ -0,0 +1,99 @@
+//! A custom `ForceModifier`: a wind that gusts back and
+//! forth over time, followed by a second modifier that
+//! caps the total force on each ball. The cap is added
+//! after the wind, so it sees the wind's force in
+//! `BallState::force` and can take it back off.
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use space_golf::{
+    forces::{
+        AddForceModifier, BallState, ForceModifier,
+        ForceModifierPlugin,
+    },
+    golf_ball::GolfBall,
+    sim_state::PhysicsActive,
+};
+
+/// Blows along +x, as an acceleration that swings between
+/// `strength` and `-strength` every `period` seconds.
+struct GustingWind {
+    strength: f32,
+    period: f32,
+    /// the acceleration this frame
+    current: f32,
+}
+
+impl ForceModifier for GustingWind {
+    fn prepare(&mut self, world: &mut World) {
+        let seconds = world
+            .resource::<Time>()
+            .seconds_since_startup()
+            as f32;
+        self.current = self.strength
+            * (seconds / self.period
+                * std::f32::consts::TAU)
+                .sin();
+    }
+
+    fn force(&self, ball: &BallState) -> Vec2 {
+        Vec2::X * self.current * ball.mass
+    }
+}
+
+/// Keeps the force on a ball under `max`, whatever added
+/// it.
+struct ForceCap {
+    max: f32,
+}
+
+impl ForceModifier for ForceCap {
+    fn force(&self, ball: &BallState) -> Vec2 {
+        ball.force.clamp_length_max(self.max) - ball.force
+    }
+}
+
+#[test]
+fn a_capped_wind_stays_under_the_cap() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<PhysicsActive>()
+        .add_plugin(ForceModifierPlugin)
+        .add_force_modifier(GustingWind {
+            strength: 50.0,
+            period: 0.5,
+            current: 0.0,
+        })
+        .add_force_modifier(ForceCap { max: 1000.0 });
+
+    let mass = 100.0;
+    let ball = app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .insert(GolfBall)
+        .insert(Velocity::zero())
+        .insert(ColliderMassProperties::Mass(mass))
+        .insert(ExternalForce::default())
+        .id();
+
+    for _ in 0..10 {
+        // gravity would normally start each frame's force
+        // over, so do the same here
+        app.world
+            .entity_mut(ball)
+            .insert(ExternalForce::default());
+        app.update();
+        let force = app
+            .world
+            .get::<ExternalForce>(ball)
+            .unwrap()
+            .force;
+        assert!(force.y == 0.0);
+        // the wind alone would reach 5000
+        assert!(
+            force.length() <= 1000.0 + 1e-3,
+            "the cap didn't hold: {}",
+            force
+        );
+    }
+}